@@ -1,8 +1,11 @@
 // Attachment file operations
+use std::collections::HashSet;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tauri::{AppHandle, Manager};
-use crate::models::Attachment;
+use log::warn;
+use crate::models::{Attachment, AttachmentPolicy, FileType};
+use super::file_system::load_topic;
 
 /// Get attachments directory path
 fn get_attachments_dir(app: &AppHandle) -> Result<PathBuf, String> {
@@ -12,19 +15,50 @@ fn get_attachments_dir(app: &AppHandle) -> Result<PathBuf, String> {
     Ok(app_data.join("attachments"))
 }
 
-/// Save attachment file
+/// Save attachment file, enforcing the type and size policy from settings.
+/// Returns the attachment as stored: its AppData-relative `file_path`, and its `file_size`
+/// after any metadata was stripped.
 #[tauri::command]
 pub async fn save_attachment(
     app: AppHandle,
     attachment: Attachment,
     file_data: Vec<u8>
-) -> Result<String, String> {
+) -> Result<Attachment, String> {
+    let attachments_dir = get_attachments_dir(&app)?;
+    let (policy, strip_metadata) = attachments_dir
+        .parent()
+        .and_then(|app_data| super::settings::load_settings(&app_data.join("settings.json")).ok())
+        .map(|settings| (settings.attachment_policy, settings.strip_image_metadata))
+        .unwrap_or_else(|| (AttachmentPolicy::default(), true));
+
+    store_attachment(&attachments_dir, &attachment, &file_data, &policy, strip_metadata)
+}
+
+/// Validate an attachment against `policy` and write it to `attachments_dir`; returns the stored attachment.
+/// With `strip_metadata`, EXIF/GPS and similar metadata is removed from JPEG, PNG and WebP images first.
+fn store_attachment(
+    attachments_dir: &Path,
+    attachment: &Attachment,
+    file_data: &[u8],
+    policy: &AttachmentPolicy,
+    strip_metadata: bool,
+) -> Result<Attachment, String> {
     attachment.validate()?;
+    attachment.check_policy(policy, file_data.len() as u64)?;
 
-    let attachments_dir = get_attachments_dir(&app)?;
+    let stripped = if strip_metadata && Attachment::detect_file_type(&attachment.filename) == FileType::Image {
+        let stripped = super::image_metadata::strip_image_metadata(file_data);
+        if stripped.is_none() {
+            warn!("Saving {} with its metadata: unsupported or unreadable image format", attachment.filename);
+        }
+        stripped
+    } else {
+        None
+    };
+    let file_data = stripped.as_deref().unwrap_or(file_data);
 
     // Ensure attachments directory exists
-    fs::create_dir_all(&attachments_dir)
+    fs::create_dir_all(attachments_dir)
         .map_err(|e| format!("Failed to create attachments directory: {}", e))?;
 
     let file_path = attachments_dir.join(&attachment.filename);
@@ -33,8 +67,11 @@ pub async fn save_attachment(
     fs::write(&file_path, file_data)
         .map_err(|e| format!("Failed to write attachment file: {}", e))?;
 
-    // Return relative path
-    Ok(format!("attachments/{}", attachment.filename))
+    Ok(Attachment {
+        file_path: format!("attachments/{}", attachment.filename),
+        file_size: file_data.len() as u64,
+        ..attachment.clone()
+    })
 }
 
 /// Read attachment file
@@ -73,3 +110,253 @@ pub async fn delete_attachment(app: AppHandle, file_path: String) -> Result<(),
 
     Ok(())
 }
+
+/// Normalize an attachment reference to an AppData-relative path with '/' separators
+fn normalize_reference(app_data: &Path, file_path: &str) -> String {
+    let path = Path::new(file_path);
+    let relative = path.strip_prefix(app_data).unwrap_or(path);
+    relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().to_string())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Topic directories whose topics keep their attachments alive. Trashed topics count too,
+/// since they can still be restored by hand.
+const TOPIC_DIRS: &[&str] = &["Agents", "AgentGroups", ".trash/Agents", ".trash/AgentGroups"];
+
+/// Attachment files (and thumbnails) referenced by any stored or trashed topic.
+/// Fails if a topic can't be read, so nothing it references is mistaken for an orphan.
+fn referenced_attachments(app_data: &Path) -> Result<HashSet<String>, String> {
+    let mut referenced = HashSet::new();
+
+    for dir in TOPIC_DIRS {
+        let dir = app_data.join(dir);
+        if !dir.exists() {
+            continue;
+        }
+
+        let entries = fs::read_dir(&dir)
+            .map_err(|e| format!("Failed to read directory: {}", e))?;
+        for entry in entries {
+            let path = entry.map_err(|e| format!("Failed to read entry: {}", e))?.path();
+            if path.extension().and_then(|s| s.to_str()) != Some("json") {
+                continue;
+            }
+
+            let topic = load_topic(&path)?;
+            for attachment in topic.messages.iter().flat_map(|m| &m.attachments) {
+                referenced.insert(normalize_reference(app_data, &attachment.file_path));
+                if let Some(thumbnail) = &attachment.thumbnail {
+                    referenced.insert(normalize_reference(app_data, thumbnail));
+                }
+            }
+        }
+    }
+
+    Ok(referenced)
+}
+
+/// Files under `dir`, as AppData-relative paths
+fn collect_attachment_files(app_data: &Path, dir: &Path, files: &mut Vec<String>) -> Result<(), String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read directory: {}", e))?;
+
+    for entry in entries {
+        let path = entry.map_err(|e| format!("Failed to read entry: {}", e))?.path();
+        if path.is_dir() {
+            collect_attachment_files(app_data, &path, files)?;
+        } else {
+            files.push(normalize_reference(app_data, &path.to_string_lossy()));
+        }
+    }
+
+    Ok(())
+}
+
+/// Files in `attachments/` that no topic references, sorted
+fn orphaned_attachments(app_data: &Path) -> Result<Vec<String>, String> {
+    let attachments_dir = app_data.join("attachments");
+    if !attachments_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let referenced = referenced_attachments(app_data)?;
+    let mut files = Vec::new();
+    collect_attachment_files(app_data, &attachments_dir, &mut files)?;
+    files.retain(|file| !referenced.contains(file));
+    files.sort();
+
+    Ok(files)
+}
+
+/// Delete orphaned attachments (only list them when `dry_run`), returning their paths
+fn remove_orphaned_attachments(app_data: &Path, dry_run: bool) -> Result<Vec<String>, String> {
+    let orphans = orphaned_attachments(app_data)?;
+
+    if !dry_run {
+        for orphan in &orphans {
+            fs::remove_file(app_data.join(orphan))
+                .map_err(|e| format!("Failed to delete attachment file {}: {}", orphan, e))?;
+        }
+    }
+
+    Ok(orphans)
+}
+
+/// List attachment files no longer referenced by any conversation
+#[tauri::command]
+pub async fn find_orphaned_attachments(app: AppHandle) -> Result<Vec<String>, String> {
+    let attachments_dir = get_attachments_dir(&app)?;
+    let app_data = attachments_dir.parent().unwrap_or(&attachments_dir);
+    orphaned_attachments(app_data)
+}
+
+/// Delete unreferenced attachment files. Defaults to a dry run that only lists them.
+#[tauri::command]
+pub async fn cleanup_orphaned_attachments(app: AppHandle, dry_run: Option<bool>) -> Result<Vec<String>, String> {
+    let attachments_dir = get_attachments_dir(&app)?;
+    let app_data = attachments_dir.parent().unwrap_or(&attachments_dir);
+    remove_orphaned_attachments(app_data, dry_run.unwrap_or(true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::FileType;
+
+    fn attachment(filename: &str, file_type: FileType, file_size: u64) -> Attachment {
+        Attachment {
+            id: "att-1".to_string(),
+            filename: filename.to_string(),
+            file_path: format!("attachments/{}", filename),
+            file_type,
+            file_size,
+            created_at: "2025-01-01T00:00:00Z".to_string(),
+            thumbnail: None,
+        }
+    }
+
+    #[test]
+    fn test_attachment_policy() {
+        let attachments_dir = std::env::temp_dir().join(format!("vcp_attachment_policy_test_{}", uuid::Uuid::new_v4())).join("attachments");
+        let policy = AttachmentPolicy {
+            allowed_types: vec![FileType::Image, FileType::Pdf],
+            max_size_bytes: 64,
+            max_size_by_type: std::collections::HashMap::from([(FileType::Image, 16)]),
+        };
+
+        let error = store_attachment(&attachments_dir, &attachment("big.png", FileType::Image, 32), &[0; 32], &policy, true).unwrap_err();
+        assert!(error.contains("too large") && error.contains("16 bytes"), "{}", error);
+
+        let error = store_attachment(&attachments_dir, &attachment("clip.mp4", FileType::Video, 8), &[0; 8], &policy, true).unwrap_err();
+        assert!(error.contains("not allowed"), "{}", error);
+
+        // The filename decides the type, whatever the caller claims
+        let error = store_attachment(&attachments_dir, &attachment("clip.mp4", FileType::Pdf, 8), &[0; 8], &policy, true).unwrap_err();
+        assert!(error.contains("Video"), "{}", error);
+        let error = store_attachment(&attachments_dir, &attachment("big.jpg", FileType::Pdf, 32), &[0; 32], &policy, true).unwrap_err();
+        assert!(error.contains("too large"), "{}", error);
+
+        // Declared size must match the data
+        let error = store_attachment(&attachments_dir, &attachment("doc.pdf", FileType::Pdf, 10), &[0; 8], &policy, true).unwrap_err();
+        assert!(error.contains("does not match"), "{}", error);
+        assert!(!attachments_dir.exists());
+
+        // Types without their own cap use the overall one
+        let saved = store_attachment(&attachments_dir, &attachment("doc.pdf", FileType::Pdf, 40), &[0; 40], &policy, true).unwrap();
+        assert_eq!(saved.file_path, "attachments/doc.pdf");
+        assert_eq!(fs::read(attachments_dir.join("doc.pdf")).unwrap().len(), 40);
+
+        let _ = fs::remove_dir_all(attachments_dir.parent().unwrap());
+    }
+
+    #[test]
+    fn test_image_metadata_stripped_on_save() {
+        let attachments_dir = std::env::temp_dir().join(format!("vcp_attachment_exif_test_{}", uuid::Uuid::new_v4())).join("attachments");
+        let exif = b"Exif\0\0MM\0*GPSLatitude=48.8584";
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+        jpeg.extend_from_slice(&((exif.len() + 2) as u16).to_be_bytes());
+        jpeg.extend_from_slice(exif);
+        jpeg.extend_from_slice(&[0xFF, 0xDA, 0x00, 0x08, 1, 1, 0, 0, 0x3F, 0, 0x12, 0x34, 0xFF, 0xD9]);
+        let policy = AttachmentPolicy::default();
+
+        let photo = attachment("photo.jpg", FileType::Image, jpeg.len() as u64);
+        let stored = store_attachment(&attachments_dir, &photo, &jpeg, &policy, true).unwrap();
+        let saved = fs::read(attachments_dir.join("photo.jpg")).unwrap();
+        assert_eq!(saved, vec![0xFF, 0xD8, 0xFF, 0xDA, 0x00, 0x08, 1, 1, 0, 0, 0x3F, 0, 0x12, 0x34, 0xFF, 0xD9]);
+        // The returned size is that of the stripped file
+        assert_eq!(stored.file_size, saved.len() as u64);
+
+        // Disabled in settings: saved as received
+        let original = attachment("original.jpg", FileType::Image, jpeg.len() as u64);
+        let stored = store_attachment(&attachments_dir, &original, &jpeg, &policy, false).unwrap();
+        assert_eq!(fs::read(attachments_dir.join("original.jpg")).unwrap(), jpeg);
+        assert_eq!(stored.file_size, jpeg.len() as u64);
+
+        let _ = fs::remove_dir_all(attachments_dir.parent().unwrap());
+    }
+
+    #[test]
+    fn test_orphaned_attachments() {
+        let app_data = std::env::temp_dir().join(format!("vcp_attachment_gc_test_{}", uuid::Uuid::new_v4()));
+        let attachments_dir = app_data.join("attachments");
+        fs::create_dir_all(attachments_dir.join("thumbs")).unwrap();
+        for file in ["used.png", "thumbs/used.png", "orphan.pdf", "trashed.pdf"] {
+            fs::write(attachments_dir.join(file), b"data").unwrap();
+        }
+
+        let topic = serde_json::json!({
+            "id": "topic-1",
+            "owner_id": "agent-1",
+            "owner_type": "agent",
+            "title": "Topic",
+            "messages": [{
+                "id": "m1",
+                "sender": "user",
+                "content": "see attached",
+                "attachments": [{
+                    "id": "att-1",
+                    "filename": "used.png",
+                    "file_path": "attachments/used.png",
+                    "file_type": "image",
+                    "file_size": 4,
+                    "created_at": "2025-01-01T00:00:00Z",
+                    "thumbnail": "attachments/thumbs/used.png"
+                }],
+                "timestamp": "2025-01-01T00:00:00Z",
+                "is_streaming": false
+            }],
+            "created_at": "2025-01-01T00:00:00Z",
+            "updated_at": "2025-01-01T00:00:00Z"
+        });
+        fs::create_dir_all(app_data.join("Agents")).unwrap();
+        fs::write(app_data.join("Agents").join("topic-1.json"), topic.to_string()).unwrap();
+
+        // A trashed topic still holds on to its attachments
+        let mut trashed = topic.clone();
+        trashed["id"] = serde_json::json!("topic-2");
+        trashed["messages"][0]["attachments"][0]["file_path"] = serde_json::json!("attachments/trashed.pdf");
+        fs::create_dir_all(app_data.join(".trash").join("Agents")).unwrap();
+        fs::write(app_data.join(".trash").join("Agents").join("topic-2.json"), trashed.to_string()).unwrap();
+
+        assert_eq!(orphaned_attachments(&app_data).unwrap(), vec!["attachments/orphan.pdf"]);
+
+        // Dry run only reports
+        assert_eq!(remove_orphaned_attachments(&app_data, true).unwrap().len(), 1);
+        assert!(attachments_dir.join("orphan.pdf").exists());
+
+        remove_orphaned_attachments(&app_data, false).unwrap();
+        assert!(!attachments_dir.join("orphan.pdf").exists());
+        assert!(attachments_dir.join("used.png").exists());
+        assert!(attachments_dir.join("thumbs/used.png").exists());
+        assert!(attachments_dir.join("trashed.pdf").exists());
+
+        // An unreadable topic aborts the scan instead of orphaning its attachments
+        fs::write(app_data.join("Agents").join("broken.json"), "{").unwrap();
+        assert!(orphaned_attachments(&app_data).is_err());
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+}
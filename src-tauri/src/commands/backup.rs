@@ -0,0 +1,265 @@
+// Full AppData backup and restore as a single ZIP archive
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+
+/// Archive entry describing the backup itself
+pub const BACKUP_MANIFEST: &str = "backup-manifest.json";
+
+/// Directories (at any depth) that are never backed up
+const SKIPPED_DIRS: &[&str] = &[".trash"];
+
+/// Transient files left behind by atomic writes and file locks
+const SKIPPED_FILES: &[&str] = &[".settings.lock"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub app_version: String,
+    pub created_at: String,
+    pub file_count: usize,
+}
+
+fn get_app_data(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path().resolve("AppData", tauri::path::BaseDirectory::AppData)
+        .map_err(|e| format!("Failed to resolve AppData: {}", e))
+}
+
+fn is_transient(path: &Path) -> bool {
+    let name = path.file_name().map(|n| n.to_string_lossy().to_string()).unwrap_or_default();
+    SKIPPED_FILES.contains(&name.as_str()) || name.ends_with(".tmp")
+}
+
+/// Relative paths (with '/' separators) of every file to back up, skipping `exclude`
+fn collect_backup_files(root: &Path, dir: &Path, exclude: &Path, files: &mut Vec<String>) -> Result<(), String> {
+    let entries = fs::read_dir(dir)
+        .map_err(|e| format!("Failed to read directory {}: {}", dir.display(), e))?;
+
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path == exclude {
+            continue;
+        }
+
+        if path.is_dir() {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if !SKIPPED_DIRS.contains(&name.as_str()) {
+                collect_backup_files(root, &path, exclude, files)?;
+            }
+        } else if !is_transient(&path) {
+            if let Ok(relative) = path.strip_prefix(root) {
+                let parts: Vec<String> = relative
+                    .components()
+                    .map(|c| c.as_os_str().to_string_lossy().to_string())
+                    .collect();
+                files.push(parts.join("/"));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Zip the whole AppData tree into `output_path`, returning the archive path
+fn create_backup_archive(app_data: &Path, output_path: &Path, app_version: &str) -> Result<String, String> {
+    let mut files = Vec::new();
+    if app_data.is_dir() {
+        collect_backup_files(app_data, app_data, output_path, &mut files)?;
+    }
+    files.sort();
+
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create backup directory: {}", e))?;
+    }
+
+    let file = fs::File::create(output_path)
+        .map_err(|e| format!("Failed to create backup archive: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = zip::write::FileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+
+    let manifest = BackupManifest {
+        app_version: app_version.to_string(),
+        created_at: chrono::Utc::now().to_rfc3339(),
+        file_count: files.len(),
+    };
+    let manifest_json = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("Failed to serialize backup manifest: {}", e))?;
+
+    zip.start_file(BACKUP_MANIFEST, options)
+        .and_then(|_| zip.write_all(manifest_json.as_bytes()).map_err(Into::into))
+        .map_err(|e| format!("Failed to write backup manifest: {}", e))?;
+
+    for relative in &files {
+        let content = fs::read(app_data.join(relative))
+            .map_err(|e| format!("Failed to read {}: {}", relative, e))?;
+        zip.start_file(relative.as_str(), options)
+            .and_then(|_| zip.write_all(&content).map_err(Into::into))
+            .map_err(|e| format!("Failed to add {} to backup: {}", relative, e))?;
+    }
+
+    zip.finish()
+        .map_err(|e| format!("Failed to finalize backup archive: {}", e))?;
+
+    Ok(output_path.to_string_lossy().to_string())
+}
+
+/// Extract a backup archive into AppData.
+/// Entries escaping the target directory (Zip-Slip) abort the restore before anything is written.
+fn restore_backup_archive(app_data: &Path, archive_path: &Path, overwrite: bool) -> Result<BackupManifest, String> {
+    let file = fs::File::open(archive_path)
+        .map_err(|e| format!("Failed to open backup archive: {}", e))?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| format!("Invalid backup archive: {}", e))?;
+
+    let manifest: BackupManifest = {
+        let mut entry = archive.by_name(BACKUP_MANIFEST)
+            .map_err(|_| format!("Backup archive is missing {}", BACKUP_MANIFEST))?;
+        let mut content = String::new();
+        entry.read_to_string(&mut content)
+            .map_err(|e| format!("Failed to read backup manifest: {}", e))?;
+        serde_json::from_str(&content)
+            .map_err(|e| format!("Invalid backup manifest: {}", e))?
+    };
+
+    // Validate every entry name up front so a malicious archive leaves AppData untouched
+    let mut targets = Vec::with_capacity(archive.len());
+    for i in 0..archive.len() {
+        let entry = archive.by_index(i)
+            .map_err(|e| format!("Failed to read backup entry: {}", e))?;
+        let relative = entry.enclosed_name()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| format!("Unsafe path in backup archive: {}", entry.name()))?;
+        targets.push((relative, entry.is_dir()));
+    }
+
+    let is_empty = fs::read_dir(app_data)
+        .map(|mut entries| entries.next().is_none())
+        .unwrap_or(true);
+    if !is_empty && !overwrite {
+        return Err("AppData is not empty; restore with overwrite=true to replace existing files".to_string());
+    }
+
+    fs::create_dir_all(app_data)
+        .map_err(|e| format!("Failed to create AppData directory: {}", e))?;
+
+    for (i, (relative, is_dir)) in targets.into_iter().enumerate() {
+        if relative == Path::new(BACKUP_MANIFEST) {
+            continue;
+        }
+
+        let target = app_data.join(&relative);
+        if is_dir {
+            fs::create_dir_all(&target)
+                .map_err(|e| format!("Failed to create {}: {}", target.display(), e))?;
+            continue;
+        }
+
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Failed to create {}: {}", parent.display(), e))?;
+        }
+
+        let mut entry = archive.by_index(i)
+            .map_err(|e| format!("Failed to read backup entry: {}", e))?;
+        let mut content = Vec::new();
+        entry.read_to_end(&mut content)
+            .map_err(|e| format!("Failed to extract {}: {}", relative.display(), e))?;
+        fs::write(&target, content)
+            .map_err(|e| format!("Failed to write {}: {}", target.display(), e))?;
+    }
+
+    Ok(manifest)
+}
+
+/// Back up the entire AppData directory into a ZIP archive at `output_path`
+#[tauri::command]
+pub async fn create_backup(app: AppHandle, output_path: String) -> Result<String, String> {
+    let app_data = get_app_data(&app)?;
+    let app_version = app.package_info().version.to_string();
+    create_backup_archive(&app_data, Path::new(&output_path), &app_version)
+}
+
+/// Restore AppData from a backup archive; a non-empty AppData requires `overwrite`
+#[tauri::command]
+pub async fn restore_backup(app: AppHandle, archive_path: String, overwrite: bool) -> Result<BackupManifest, String> {
+    let app_data = get_app_data(&app)?;
+    let result = restore_backup_archive(&app_data, Path::new(&archive_path), overwrite);
+    // A restore that failed partway may still have replaced topics
+    super::search::invalidate_index(&app_data);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        std::env::temp_dir().join(format!("vcp_backup_test_{}_{}", name, uuid::Uuid::new_v4()))
+    }
+
+    #[test]
+    fn test_backup_round_trip() {
+        let source = temp_dir("source");
+        fs::create_dir_all(source.join("Agents").join("agent-1")).unwrap();
+        fs::create_dir_all(source.join("attachments")).unwrap();
+        fs::create_dir_all(source.join(".trash")).unwrap();
+        fs::write(source.join("settings.json"), r#"{"theme":"dark"}"#).unwrap();
+        fs::write(source.join("Agents").join("agent-1").join("topic-1.json"), "{}").unwrap();
+        fs::write(source.join("attachments").join("photo.png"), [0u8, 1, 2, 3]).unwrap();
+        fs::write(source.join(".trash").join("deleted.json"), "{}").unwrap();
+        fs::write(source.join("settings.json.tmp"), "partial").unwrap();
+        fs::write(source.join(".settings.lock"), "").unwrap();
+
+        let archive = temp_dir("archive").join("backup.zip");
+        create_backup_archive(&source, &archive, "1.2.3").unwrap();
+
+        let target = temp_dir("target");
+        let manifest = restore_backup_archive(&target, &archive, false).unwrap();
+        assert_eq!(manifest.app_version, "1.2.3");
+        assert_eq!(manifest.file_count, 3);
+
+        assert_eq!(fs::read_to_string(target.join("settings.json")).unwrap(), r#"{"theme":"dark"}"#);
+        assert_eq!(fs::read_to_string(target.join("Agents").join("agent-1").join("topic-1.json")).unwrap(), "{}");
+        assert_eq!(fs::read(target.join("attachments").join("photo.png")).unwrap(), vec![0u8, 1, 2, 3]);
+        assert!(!target.join(".trash").exists());
+        assert!(!target.join("settings.json.tmp").exists());
+        assert!(!target.join(".settings.lock").exists());
+        assert!(!target.join(BACKUP_MANIFEST).exists());
+
+        // Restoring over existing data needs explicit consent
+        fs::write(target.join("settings.json"), r#"{"theme":"light"}"#).unwrap();
+        assert!(restore_backup_archive(&target, &archive, false).is_err());
+        restore_backup_archive(&target, &archive, true).unwrap();
+        assert_eq!(fs::read_to_string(target.join("settings.json")).unwrap(), r#"{"theme":"dark"}"#);
+
+        let _ = fs::remove_dir_all(&source);
+        let _ = fs::remove_dir_all(archive.parent().unwrap());
+        let _ = fs::remove_dir_all(&target);
+    }
+
+    #[test]
+    fn test_restore_rejects_zip_slip() {
+        let dir = temp_dir("slip");
+        fs::create_dir_all(&dir).unwrap();
+        let archive = dir.join("evil.zip");
+
+        let mut zip = zip::ZipWriter::new(fs::File::create(&archive).unwrap());
+        let options = zip::write::FileOptions::default();
+        zip.start_file(BACKUP_MANIFEST, options).unwrap();
+        zip.write_all(br#"{"app_version":"1.0.0","created_at":"2024-01-01T00:00:00Z","file_count":1}"#).unwrap();
+        zip.start_file("../escaped.txt", options).unwrap();
+        zip.write_all(b"pwned").unwrap();
+        zip.finish().unwrap();
+
+        let target = dir.join("AppData");
+        let err = restore_backup_archive(&target, &archive, false).unwrap_err();
+        assert!(err.contains("Unsafe path"));
+        assert!(!dir.join("escaped.txt").exists());
+        assert!(!target.exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
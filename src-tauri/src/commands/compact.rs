@@ -0,0 +1,332 @@
+// Housekeeping over the AppData tree
+//
+// Removes what accumulates without being data: temp files left by interrupted atomic writes,
+// trash past its retention, audit logs past theirs, and directories left empty by all of that.
+// Plugin directories belong to the plugins and are never touched.
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Manager};
+use crate::plugin::audit_logger::AUDIT_LOG_RETENTION_DAYS;
+use super::file_system::run_blocking;
+
+/// Temp files younger than this may belong to a write in progress
+const TEMP_FILE_GRACE: Duration = Duration::from_secs(10 * 60);
+
+/// Directories written with `write_json_atomic`, where its temp files can be left behind (relative to AppData)
+const TEMP_FILE_DIRS: &[&str] = &[
+    "",
+    "Agents",
+    "AgentGroups",
+    "UserData",
+    "UserData/groups",
+    "Canvasmodules",
+    ".search-index",
+];
+
+/// Plugin code and plugin-owned data (relative to AppData)
+const PLUGIN_DIRS: &[&str] = &["plugins", "plugin-data"];
+
+/// Directories the app expects to exist, kept even when empty (relative to AppData)
+const REQUIRED_DIRS: &[&str] = &[
+    "Agents",
+    "AgentGroups",
+    "UserData",
+    "UserData/groups",
+    "Canvasmodules",
+    "attachments",
+    "plugins",
+    "plugin-data",
+    "audit-logs",
+    ".trash",
+    ".search-index",
+];
+
+/// What `compact_data` removed (or, for a dry run, would remove)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CompactReport {
+    pub temp_files_removed: usize,
+    pub trash_items_removed: usize,
+    pub audit_logs_removed: usize,
+    pub empty_dirs_removed: usize,
+    pub bytes_reclaimed: u64,
+    /// AppData-relative paths of everything removed, with '/' separators
+    pub removed: Vec<String>,
+    pub dry_run: bool,
+}
+
+fn get_app_data(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path().resolve("AppData", tauri::path::BaseDirectory::AppData)
+        .map_err(|e| format!("Failed to resolve AppData: {}", e))
+}
+
+/// Leftover of `write_json_atomic`: a `.<name>.<uuid>.tmp` file directly in one of the data directories
+fn is_temp_file(app_data: &Path, path: &Path) -> bool {
+    let name = path.file_name().and_then(|n| n.to_str()).unwrap_or_default();
+    let in_data_dir = path.parent()
+        .and_then(|dir| dir.strip_prefix(app_data).ok())
+        .and_then(|dir| dir.to_str())
+        .is_some_and(|dir| TEMP_FILE_DIRS.contains(&dir.replace('\\', "/").as_str()));
+    in_data_dir && super::io::is_atomic_temp_name(name)
+}
+
+/// Audit logs are named by day (`YYYY-MM-DD.jsonl`)
+fn audit_log_date(path: &Path) -> Option<chrono::NaiveDate> {
+    let stem = path.file_stem()?.to_str()?;
+    chrono::NaiveDate::parse_from_str(stem, "%Y-%m-%d").ok()
+}
+
+fn is_older_than(metadata: &fs::Metadata, age: Duration) -> bool {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .is_some_and(|elapsed| elapsed > age)
+}
+
+/// Regular files under `dir`, outside the `skipped` directories (symlinks are not followed)
+fn walk_files(dir: &Path, skipped: &[PathBuf], files: &mut Vec<(PathBuf, fs::Metadata)>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for path in entries.flatten().map(|entry| entry.path()) {
+        let Ok(metadata) = fs::symlink_metadata(&path) else {
+            continue;
+        };
+        if metadata.is_dir() {
+            if !skipped.contains(&path) {
+                walk_files(&path, skipped, files);
+            }
+        } else if metadata.is_file() {
+            files.push((path, metadata));
+        }
+    }
+}
+
+struct Compactor<'a> {
+    app_data: &'a Path,
+    dry_run: bool,
+    /// Plugin directories, left alone entirely
+    skipped: Vec<PathBuf>,
+    removed: HashSet<PathBuf>,
+    report: CompactReport,
+}
+
+impl Compactor<'_> {
+    fn relative(&self, path: &Path) -> String {
+        path.strip_prefix(self.app_data)
+            .unwrap_or(path)
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect::<Vec<_>>()
+            .join("/")
+    }
+
+    fn remove_file(&mut self, path: &Path, size: u64) -> Result<(), String> {
+        if !self.dry_run {
+            fs::remove_file(path)
+                .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+        }
+        self.report.bytes_reclaimed += size;
+        self.report.removed.push(self.relative(path));
+        self.removed.insert(path.to_path_buf());
+        Ok(())
+    }
+
+    /// Remove `dir`'s empty subdirectories bottom-up; returns whether `dir` itself is (or would be) empty
+    fn remove_empty_dirs(&mut self, dir: &Path) -> Result<bool, String> {
+        let entries = fs::read_dir(dir)
+            .map_err(|e| format!("Failed to read directory {}: {}", dir.display(), e))?;
+
+        let mut empty = true;
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if self.removed.contains(&path) {
+                continue;
+            }
+            if self.skipped.contains(&path) {
+                empty = false;
+                continue;
+            }
+            let is_dir = fs::symlink_metadata(&path).map(|m| m.is_dir()).unwrap_or(false);
+            if !is_dir || !self.remove_empty_dirs(&path)? {
+                empty = false;
+                continue;
+            }
+
+            if REQUIRED_DIRS.contains(&self.relative(&path).as_str()) {
+                empty = false;
+                continue;
+            }
+            if !self.dry_run {
+                fs::remove_dir(&path)
+                    .map_err(|e| format!("Failed to remove {}: {}", path.display(), e))?;
+            }
+            self.report.empty_dirs_removed += 1;
+            self.report.removed.push(self.relative(&path));
+            self.removed.insert(path);
+        }
+
+        Ok(empty)
+    }
+}
+
+/// Clean up the AppData tree; `trash_retention` is how long trashed items are kept.
+/// `plugins_dir` is the plugins directory when settings relocate it.
+fn compact(app_data: &Path, trash_retention: Duration, plugins_dir: Option<&Path>, dry_run: bool) -> Result<CompactReport, String> {
+    let mut skipped: Vec<PathBuf> = PLUGIN_DIRS.iter().map(|dir| app_data.join(dir)).collect();
+    skipped.extend(plugins_dir.map(Path::to_path_buf));
+
+    let mut compactor = Compactor {
+        app_data,
+        dry_run,
+        skipped,
+        removed: HashSet::new(),
+        report: CompactReport { dry_run, ..CompactReport::default() },
+    };
+    if !app_data.is_dir() {
+        return Ok(compactor.report);
+    }
+
+    let mut files = Vec::new();
+    walk_files(app_data, &compactor.skipped, &mut files);
+
+    let trash_dir = app_data.join(".trash");
+    let audit_dir = app_data.join("audit-logs");
+    let audit_cutoff = (chrono::Utc::now() - chrono::Duration::days(AUDIT_LOG_RETENTION_DAYS)).date_naive();
+
+    for (path, metadata) in files {
+        if is_temp_file(app_data, &path) {
+            if is_older_than(&metadata, TEMP_FILE_GRACE) {
+                compactor.remove_file(&path, metadata.len())?;
+                compactor.report.temp_files_removed += 1;
+            }
+        } else if path.starts_with(&trash_dir) {
+            if is_older_than(&metadata, trash_retention) {
+                compactor.remove_file(&path, metadata.len())?;
+                compactor.report.trash_items_removed += 1;
+            }
+        } else if path.starts_with(&audit_dir) && audit_log_date(&path).is_some_and(|date| date < audit_cutoff) {
+            compactor.remove_file(&path, metadata.len())?;
+            compactor.report.audit_logs_removed += 1;
+        }
+    }
+
+    compactor.remove_empty_dirs(app_data)?;
+    compactor.report.removed.sort();
+    Ok(compactor.report)
+}
+
+/// Remove stray temp files, expired trash and audit logs, and empty directories from AppData.
+/// `dry_run` reports what would be removed without touching anything.
+#[tauri::command]
+pub async fn compact_data(app: AppHandle, dry_run: Option<bool>) -> Result<CompactReport, String> {
+    let app_data = get_app_data(&app)?;
+    let settings = super::settings::load_settings(&app_data.join("settings.json")).ok();
+    let retention_days = settings.as_ref()
+        .map(|settings| settings.trash_retention_days)
+        .unwrap_or(crate::models::settings::DEFAULT_TRASH_RETENTION_DAYS);
+    let trash_retention = Duration::from_secs(u64::from(retention_days) * 24 * 60 * 60);
+    let plugins_dir = settings
+        .and_then(|settings| settings.plugins_directory)
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from);
+
+    run_blocking("compact_data", move || {
+        compact(&app_data, trash_retention, plugins_dir.as_deref(), dry_run.unwrap_or(false))
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    const TEMP_ID: &str = "0f3a77aa0f3a77aa0f3a77aa0f3a77aa";
+
+    fn write_aged(path: &Path, content: &str, age: Duration) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, content).unwrap();
+        let file = fs::File::options().write(true).open(path).unwrap();
+        file.set_modified(SystemTime::now() - age).unwrap();
+    }
+
+    fn create_test_tree() -> PathBuf {
+        let app_data = std::env::temp_dir().join(format!("vcp_compact_test_{}", uuid::Uuid::new_v4()));
+        // Real data
+        write_aged(&app_data.join("Agents").join("topic-1.json"), "{}", DAY * 400);
+        write_aged(&app_data.join("UserData").join("agent-1.json"), "{}", DAY * 400);
+        write_aged(&app_data.join("settings.json"), "{}", DAY * 400);
+        // Leftovers of interrupted writes; the fresh one may still be in use
+        write_aged(&app_data.join("Agents").join(format!(".topic-1.json.{}.tmp", TEMP_ID)), "partial", DAY);
+        write_aged(&app_data.join(format!(".settings.json.{}.tmp", TEMP_ID)), "partial", DAY);
+        write_aged(&app_data.join("UserData").join(format!(".agent-1.json.{}.tmp", TEMP_ID)), "partial", DAY);
+        write_aged(&app_data.join("Agents").join(format!(".topic-2.json.{}.tmp", TEMP_ID)), "partial", Duration::ZERO);
+        // Files that only look temporary, and plugin files, are not ours to remove
+        write_aged(&app_data.join("attachments").join("notes.tmp"), "data", DAY);
+        write_aged(&app_data.join("plugins").join("cache").join(format!(".state.json.{}.tmp", TEMP_ID)), "data", DAY);
+        fs::create_dir_all(app_data.join("plugins").join("my-plugin").join("assets")).unwrap();
+        fs::create_dir_all(app_data.join("plugin-data").join("my-plugin")).unwrap();
+        fs::create_dir_all(app_data.join("custom-plugins").join("other-plugin")).unwrap();
+        // Trash and audit logs
+        write_aged(&app_data.join(".trash").join("Agents").join("old.json"), "{}", DAY * 60);
+        write_aged(&app_data.join(".trash").join("Agents").join("recent.json"), "{}", DAY);
+        write_aged(&app_data.join("audit-logs").join("2000-01-01.jsonl"), "{}", DAY);
+        let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+        write_aged(&app_data.join("audit-logs").join(format!("{}.jsonl", today)), "{}", Duration::ZERO);
+        // Empty directories, one of them required
+        fs::create_dir_all(app_data.join("attachments").join("thumbs").join("old")).unwrap();
+        fs::create_dir_all(app_data.join("Canvasmodules")).unwrap();
+        app_data
+    }
+
+    #[test]
+    fn test_compact_removes_leftovers_only() {
+        let app_data = create_test_tree();
+
+        let report = compact(&app_data, DAY * 30, Some(&app_data.join("custom-plugins")), false).unwrap();
+        assert_eq!(report.temp_files_removed, 3);
+        assert_eq!(report.trash_items_removed, 1);
+        assert_eq!(report.audit_logs_removed, 1);
+        assert_eq!(report.empty_dirs_removed, 2);
+        assert_eq!(report.bytes_reclaimed, 7 * 3 + 2 + 2);
+        assert!(report.removed.contains(&"attachments/thumbs".to_string()));
+
+        let fresh_temp = format!("Agents/.topic-2.json.{}.tmp", TEMP_ID);
+        let plugin_temp = format!("plugins/cache/.state.json.{}.tmp", TEMP_ID);
+        for kept in ["Agents/topic-1.json", "UserData/agent-1.json", "settings.json", &fresh_temp, ".trash/Agents/recent.json", "Canvasmodules", "attachments/notes.tmp", &plugin_temp, "plugins/my-plugin/assets", "plugin-data/my-plugin", "custom-plugins/other-plugin"] {
+            assert!(app_data.join(kept).exists(), "{} was removed", kept);
+        }
+        let settings_temp = format!(".settings.json.{}.tmp", TEMP_ID);
+        for removed in [settings_temp.as_str(), ".trash/Agents/old.json", "audit-logs/2000-01-01.jsonl", "attachments/thumbs"] {
+            assert!(!app_data.join(removed).exists(), "{} was kept", removed);
+        }
+
+        // Nothing left to do
+        let again = compact(&app_data, DAY * 30, Some(&app_data.join("custom-plugins")), false).unwrap();
+        assert!(again.removed.is_empty());
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_compact_dry_run() {
+        let app_data = create_test_tree();
+
+        let preview = compact(&app_data, DAY * 30, None, true).unwrap();
+        assert!(preview.dry_run);
+        assert_eq!(preview.temp_files_removed, 3);
+        // The unconfigured custom-plugins directory counts as ordinary empty directories here
+        assert_eq!(preview.empty_dirs_removed, 4);
+        assert!(app_data.join(format!(".settings.json.{}.tmp", TEMP_ID)).exists());
+        assert!(app_data.join("attachments").join("thumbs").join("old").exists());
+
+        let report = compact(&app_data, DAY * 30, None, false).unwrap();
+        assert_eq!(report.removed, preview.removed);
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+}
@@ -0,0 +1,134 @@
+// Typed errors for the command layer
+//
+// Commands fail with a `CommandError`, whose `code` is stable so the frontend can branch on it
+// instead of matching (English) message text. Helpers that still return `String` errors map to
+// `GENERIC_ERROR_CODE` until they are converted.
+use serde::{Deserialize, Serialize};
+
+/// Code of errors that haven't been given a kind yet
+pub const GENERIC_ERROR_CODE: &str = "ERROR";
+
+/// What went wrong in a command
+#[derive(Debug, thiserror::Error)]
+pub enum AppError {
+    #[error("{0}")]
+    NotFound(String),
+    /// Invalid input, or stored data that doesn't pass validation
+    #[error("{0}")]
+    Validation(String),
+    #[error("{0}")]
+    Io(String),
+    /// The stored copy changed since the client read it
+    #[error("{0}")]
+    Conflict(String),
+    /// The data isn't in the state the operation needs (e.g. a message that is still streaming)
+    #[error("{0}")]
+    InvalidState(String),
+    #[error("{0}")]
+    PermissionDenied(String),
+}
+
+impl AppError {
+    /// Stable error code for this variant
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::NotFound(_) => "NOT_FOUND",
+            AppError::Validation(_) => "VALIDATION",
+            AppError::Io(_) => "IO_ERROR",
+            AppError::Conflict(_) => "CONFLICT",
+            AppError::InvalidState(_) => "INVALID_STATE",
+            AppError::PermissionDenied(_) => "PERMISSION_DENIED",
+        }
+    }
+}
+
+impl From<std::io::Error> for AppError {
+    fn from(error: std::io::Error) -> Self {
+        match error.kind() {
+            std::io::ErrorKind::NotFound => AppError::NotFound(error.to_string()),
+            std::io::ErrorKind::PermissionDenied => AppError::PermissionDenied(error.to_string()),
+            _ => AppError::Io(error.to_string()),
+        }
+    }
+}
+
+/// Lets converted helpers be called from code that still returns `String` errors
+impl From<AppError> for String {
+    fn from(error: AppError) -> Self {
+        error.to_string()
+    }
+}
+
+/// Serializable error returned by commands
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandError {
+    pub code: String,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+}
+
+impl std::fmt::Display for CommandError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.message)
+    }
+}
+
+impl From<AppError> for CommandError {
+    fn from(error: AppError) -> Self {
+        Self {
+            code: error.code().to_string(),
+            message: error.to_string(),
+            details: None,
+        }
+    }
+}
+
+impl From<String> for CommandError {
+    fn from(message: String) -> Self {
+        Self {
+            code: GENERIC_ERROR_CODE.to_string(),
+            message,
+            details: None,
+        }
+    }
+}
+
+impl From<std::io::Error> for CommandError {
+    fn from(error: std::io::Error) -> Self {
+        AppError::from(error).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_mapping() {
+        let error = CommandError::from(AppError::NotFound("Topic not found: t1".to_string()));
+        assert_eq!(error.code, "NOT_FOUND");
+        assert_eq!(error.message, "Topic not found: t1");
+        assert_eq!(
+            serde_json::to_value(&error).unwrap(),
+            serde_json::json!({ "code": "NOT_FOUND", "message": "Topic not found: t1" })
+        );
+
+        assert_eq!(CommandError::from(AppError::Conflict("Conflict: Agent a".to_string())).code, "CONFLICT");
+        assert_eq!(CommandError::from(AppError::Validation("bad".to_string())).code, "VALIDATION");
+        assert_eq!(CommandError::from(AppError::InvalidState("streaming".to_string())).code, "INVALID_STATE");
+
+        // Untyped errors keep their message under the generic code
+        let error = CommandError::from("Failed to read topic".to_string());
+        assert_eq!(error.code, GENERIC_ERROR_CODE);
+        assert_eq!(error.to_string(), "Failed to read topic");
+
+        let missing = std::io::Error::new(std::io::ErrorKind::NotFound, "no such file");
+        assert_eq!(CommandError::from(missing).code, "NOT_FOUND");
+        let denied = std::io::Error::new(std::io::ErrorKind::PermissionDenied, "read-only");
+        assert_eq!(CommandError::from(denied).code, "PERMISSION_DENIED");
+        assert_eq!(CommandError::from(std::io::Error::other("disk full")).code, "IO_ERROR");
+
+        assert_eq!(String::from(AppError::Io("disk full".to_string())), "disk full");
+    }
+}
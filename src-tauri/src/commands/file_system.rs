@@ -1,195 +1,1264 @@
 // File system operations for conversations, agents, and groups
-use std::fs;
-use std::path::PathBuf;
+//
+// Topics are stored as `<topic_id>.json`. Messages added with `append_message` go to a
+// `<topic_id>.messages.jsonl` log next to it (one message per line), so sending a message
+// doesn't rewrite the whole conversation. Streaming replies are logged the same way, as
+// small delta and end-of-stream lines. The log is folded back into the JSON file on the
+// next full write, or once it grows to `MESSAGE_LOG_COMPACT_THRESHOLD` lines (checked when
+// a message is added or a stream ends, never in the middle of one). Line counts and the
+// messages still streaming are kept in memory, so a delta doesn't re-read the log.
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+use std::time::Duration;
+use log::warn;
 use tauri::{AppHandle, Manager};
-use crate::models::{Topic, Agent, Group};
+use super::error::{AppError, CommandError};
+use super::io::write_json_atomic;
+use crate::models::{Topic, Agent, Group, Message, MessageEdit, MessageLimits, MessageMetadata, OwnerType, TokenEstimate, ConversationImport, TopicStats, TopicFilter, TopicBulkDelete, ActivityItem, ActivityKind};
+
+/// Appended messages kept in the log before it is folded into the topic JSON
+const MESSAGE_LOG_COMPACT_THRESHOLD: usize = 200;
+
+/// Previous versions kept in a message's edit history; older ones are dropped first
+const MAX_EDIT_HISTORY: usize = 20;
+
+/// Serializes topic writes, appends and log compactions. It guards no data, so a panic
+/// while it was held leaves nothing to repair and a poisoned lock is simply taken over.
+static TOPIC_LOCK: Mutex<()> = Mutex::new(());
+
+/// What is known about each message log; only used while holding `TOPIC_LOCK`
+static LOG_STATES: Mutex<Option<HashMap<PathBuf, LogState>>> = Mutex::new(None);
+
+/// Serializes the conflict check and write of agent and group files (poisoning is ignored, as for `TOPIC_LOCK`)
+static USER_DATA_LOCK: Mutex<()> = Mutex::new(());
+
+/// Longest a command may wait on disk IO before reporting an error instead of hanging
+const FS_COMMAND_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Run a command's blocking file IO on the blocking thread pool, so a slow disk or network
+/// mount doesn't stall the async runtime, and give up after `FS_COMMAND_TIMEOUT`. For reads
+/// and other operations that are harmless to repeat; writes go through `run_blocking_write`.
+pub(crate) async fn run_blocking<T, E, F>(operation: &str, f: F) -> Result<T, E>
+where
+    T: Send + 'static,
+    E: From<AppError> + Send + 'static,
+    F: FnOnce() -> Result<T, E> + Send + 'static,
+{
+    run_blocking_with_timeout(operation, None, FS_COMMAND_TIMEOUT, f).await
+}
+
+/// `run_blocking` for an operation that changes `resource` (a topic, agent, ... id). If it
+/// times out while running, the same operation on that resource is refused until it finishes.
+pub(crate) async fn run_blocking_write<T, E, F>(operation: &str, resource: &str, f: F) -> Result<T, E>
+where
+    T: Send + 'static,
+    E: From<AppError> + Send + 'static,
+    F: FnOnce() -> Result<T, E> + Send + 'static,
+{
+    run_blocking_with_timeout(operation, Some(resource), FS_COMMAND_TIMEOUT, f).await
+}
+
+/// Writes that timed out while already running and are still finishing in the background,
+/// as operation and resource (one entry per abandoned run)
+static ABANDONED_OPERATIONS: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+// Progress of a `run_blocking` call, shared between the caller and the blocking thread
+const OPERATION_PENDING: u8 = 0;
+const OPERATION_RUNNING: u8 = 1;
+const OPERATION_ABANDONED: u8 = 2;
+const OPERATION_CANCELLED: u8 = 3;
+const OPERATION_DONE: u8 = 4;
+
+fn is_abandoned(operation: &str, resource: &str) -> bool {
+    ABANDONED_OPERATIONS.lock().unwrap_or_else(PoisonError::into_inner)
+        .iter()
+        .any(|(name, id)| name == operation && id == resource)
+}
+
+fn set_abandoned(operation: &str, resource: &str, abandoned: bool) {
+    let mut operations = ABANDONED_OPERATIONS.lock().unwrap_or_else(PoisonError::into_inner);
+    if abandoned {
+        operations.push((operation.to_string(), resource.to_string()));
+    } else if let Some(index) = operations.iter().position(|(name, id)| name == operation && id == resource) {
+        operations.remove(index);
+    }
+}
+
+/// Move `progress` from one state to another, if it is still in `from`
+fn advance(progress: &AtomicU8, from: u8, to: u8) -> bool {
+    progress.compare_exchange(from, to, Ordering::AcqRel, Ordering::Acquire).is_ok()
+}
+
+/// `run_blocking` with an explicit timeout. Blocking IO can't be interrupted, so on timeout an
+/// operation that hasn't started yet is cancelled, and one that has is left to finish in the
+/// background. For a write (`resource` set), new calls of the same operation on the same resource
+/// are refused until it does, so retrying after a timeout never performs the write twice.
+async fn run_blocking_with_timeout<T, E, F>(operation: &str, resource: Option<&str>, timeout: Duration, f: F) -> Result<T, E>
+where
+    T: Send + 'static,
+    E: From<AppError> + Send + 'static,
+    F: FnOnce() -> Result<T, E> + Send + 'static,
+{
+    if let Some(resource) = resource.filter(|resource| is_abandoned(operation, resource)) {
+        return Err(AppError::Io(format!(
+            "{} for {} is still finishing an earlier attempt that timed out; try again shortly",
+            operation, resource
+        )).into());
+    }
+
+    let progress = Arc::new(AtomicU8::new(OPERATION_PENDING));
+    let task_progress = Arc::clone(&progress);
+    let task_operation = operation.to_string();
+    let task_resource = resource.map(str::to_string);
+    let mut handle = tokio::task::spawn_blocking(move || {
+        if !advance(&task_progress, OPERATION_PENDING, OPERATION_RUNNING) {
+            return Err(AppError::Io(format!("{} was cancelled", task_operation)).into());
+        }
+        let result = f();
+        if task_progress.swap(OPERATION_DONE, Ordering::AcqRel) == OPERATION_ABANDONED {
+            if let Some(resource) = &task_resource {
+                set_abandoned(&task_operation, resource, false);
+            }
+        }
+        result
+    });
+
+    let result = match tokio::time::timeout(timeout, &mut handle).await {
+        Ok(result) => result,
+        Err(_) if advance(&progress, OPERATION_PENDING, OPERATION_CANCELLED) => {
+            return Err(timed_out(operation, timeout).into());
+        }
+        Err(_) => {
+            // Registered before it is marked abandoned, so the thread always finds the entry to remove
+            if let Some(resource) = resource {
+                set_abandoned(operation, resource, true);
+            }
+            if advance(&progress, OPERATION_RUNNING, OPERATION_ABANDONED) {
+                return Err(timed_out(operation, timeout).into());
+            }
+            // It finished just now after all
+            if let Some(resource) = resource {
+                set_abandoned(operation, resource, false);
+            }
+            handle.await
+        }
+    };
+    result.unwrap_or_else(|e| Err(AppError::Io(format!("{} failed: {}", operation, e)).into()))
+}
+
+fn timed_out(operation: &str, timeout: Duration) -> AppError {
+    AppError::Io(format!(
+        "{} timed out after {:?}; the data folder may be on a slow or unreachable drive",
+        operation, timeout
+    ))
+}
 
 /// Get AppData directory path
-fn get_app_data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+fn get_app_data_dir(app: &AppHandle) -> Result<PathBuf, AppError> {
     app.path().resolve("AppData", tauri::path::BaseDirectory::AppData)
-        .map_err(|e| format!("Failed to get app data directory: {}", e))
+        .map_err(|e| AppError::Io(format!("Failed to get app data directory: {}", e)))
 }
 
-/// Read conversation (topic) from file
-#[tauri::command]
-pub async fn read_conversation(app: AppHandle, topic_id: String) -> Result<Topic, String> {
-    let app_data = get_app_data_dir(&app)?;
+/// Directory holding topics of the given owner type ("agent" or "group")
+fn topic_dir(app_data: &Path, owner_type: &str) -> Result<PathBuf, AppError> {
+    match owner_type {
+        "agent" => Ok(app_data.join("Agents")),
+        "group" => Ok(app_data.join("AgentGroups")),
+        _ => Err(AppError::Validation("Invalid owner_type: must be 'agent' or 'group'".to_string())),
+    }
+}
+
+/// Append-only message log of a topic file
+fn message_log_path(topic_path: &Path) -> PathBuf {
+    topic_path.with_extension("messages.jsonl")
+}
+
+/// Update to a streaming message, logged as `{"delta": {...}}` or `{"end": {...}}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum StreamUpdate {
+    /// Text appended to the message content
+    Delta { message_id: String, delta: String },
+    /// The message is complete
+    End { message_id: String, metadata: Option<MessageMetadata> },
+}
 
-    // Try agent topics first
-    let agent_path = app_data.join("Agents").join(format!("{}.json", topic_id));
-    if agent_path.exists() {
-        let content = fs::read_to_string(&agent_path)
-            .map_err(|e| format!("Failed to read agent topic: {}", e))?;
-        let topic: Topic = serde_json::from_str(&content)
-            .map_err(|e| format!("Failed to parse agent topic JSON: {}", e))?;
-        return Ok(topic);
+impl StreamUpdate {
+    fn message_id(&self) -> &str {
+        match self {
+            StreamUpdate::Delta { message_id, .. } | StreamUpdate::End { message_id, .. } => message_id,
+        }
     }
 
-    // Try group topics
-    let group_path = app_data.join("AgentGroups").join(format!("{}.json", topic_id));
-    if group_path.exists() {
-        let content = fs::read_to_string(&group_path)
-            .map_err(|e| format!("Failed to read group topic: {}", e))?;
-        let topic: Topic = serde_json::from_str(&content)
-            .map_err(|e| format!("Failed to parse group topic JSON: {}", e))?;
-        return Ok(topic);
+    /// Apply to the latest message with the update's id; false if there is none
+    fn apply(&self, messages: &mut [Message]) -> bool {
+        let Some(message) = messages.iter_mut().rev().find(|m| m.id == self.message_id()) else {
+            return false;
+        };
+        match self {
+            StreamUpdate::Delta { delta, .. } => message.content.push_str(delta),
+            StreamUpdate::End { metadata, .. } => {
+                message.is_streaming = false;
+                if metadata.is_some() {
+                    message.metadata = metadata.clone();
+                }
+            }
+        }
+        true
     }
+}
 
-    Err(format!("Topic not found: {}", topic_id))
+/// A line of the message log
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+enum LogEntry {
+    Message(Message),
+    Stream(StreamUpdate),
 }
 
-/// Write conversation (topic) to file
-#[tauri::command]
-pub async fn write_conversation(app: AppHandle, topic: Topic) -> Result<(), String> {
-    topic.validate()?;
+/// Read the entries of a topic's log (a torn line from an interrupted write is skipped)
+fn read_message_log(log_path: &Path) -> Result<Vec<LogEntry>, AppError> {
+    if !log_path.exists() {
+        return Ok(Vec::new());
+    }
 
-    let app_data = get_app_data_dir(&app)?;
+    let content = fs::read_to_string(log_path)
+        .map_err(|e| AppError::Io(format!("Failed to read message log: {}", e)))?;
+
+    let mut entries = Vec::new();
+    for line in content.lines().filter(|line| !line.trim().is_empty()) {
+        match serde_json::from_str::<LogEntry>(line) {
+            Ok(entry) => entries.push(entry),
+            Err(e) => warn!("Skipping unreadable line in {}: {}", log_path.display(), e),
+        }
+    }
+
+    Ok(entries)
+}
+
+/// Load a topic file together with any messages appended to its log
+pub(crate) fn load_topic(topic_path: &Path) -> Result<Topic, AppError> {
+    let content = fs::read_to_string(topic_path)
+        .map_err(|e| AppError::Io(format!("Failed to read topic: {}", e)))?;
+    let mut topic: Topic = serde_json::from_str(&content)
+        .map_err(|e| AppError::Validation(format!("Failed to parse topic JSON: {}", e)))?;
+
+    let log_path = message_log_path(topic_path);
+    for entry in read_message_log(&log_path)? {
+        match entry {
+            LogEntry::Message(message) => {
+                // The topic file isn't rewritten on append, so the newest message dates the topic
+                if message.timestamp > topic.updated_at {
+                    topic.updated_at = message.timestamp.clone();
+                }
+                topic.messages.push(message);
+            }
+            LogEntry::Stream(update) => {
+                if !update.apply(&mut topic.messages) {
+                    warn!("Skipping update to unknown message {} in {}", update.message_id(), log_path.display());
+                }
+            }
+        }
+    }
+
+    Ok(topic)
+}
+
+/// Write the complete topic atomically and drop its message log (now folded in)
+fn save_topic(topic_path: &Path, topic: &Topic) -> Result<(), AppError> {
+    write_json_atomic(topic_path, topic).map_err(AppError::Io)?;
+
+    let log_path = message_log_path(topic_path);
+    if log_path.exists() {
+        fs::remove_file(&log_path)
+            .map_err(|e| AppError::Io(format!("Failed to remove message log: {}", e)))?;
+    }
+
+    Ok(())
+}
+
+/// Whether a non-empty log lacks a trailing newline
+fn log_ends_mid_line(log: &mut fs::File) -> std::io::Result<bool> {
+    if log.metadata()?.len() == 0 {
+        return Ok(false);
+    }
+
+    let mut last = [0u8; 1];
+    log.seek(SeekFrom::End(-1))?;
+    log.read_exact(&mut last)?;
+    Ok(last[0] != b'\n')
+}
+
+/// Append one message to a topic without rewriting its existing messages
+fn append_message_to_topic(topic_path: &Path, message: &Message) -> Result<(), AppError> {
+    message.validate().map_err(AppError::Validation)?;
+
+    let _guard = TOPIC_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+
+    if !topic_path.exists() {
+        return Err(AppError::NotFound(format!("Topic not found: {}", topic_path.display())));
+    }
+
+    let mut states = log_states();
+    let state = log_state(&mut states, topic_path)?;
+    append_log_entry(topic_path, &LogEntry::Message(message.clone()), state)
+}
+
+/// A topic's message log as of its last append, so appends don't have to read it
+#[derive(Debug, Default)]
+struct LogState {
+    /// Size of the log file then; any other size means it changed elsewhere (e.g. was folded in)
+    len: u64,
+    /// Lines in the log
+    lines: usize,
+    /// Messages known to be streaming, with their content length so far
+    streaming: HashMap<String, usize>,
+}
+
+impl LogState {
+    /// Read the log once to recover its state
+    fn read(log_path: &Path, len: u64) -> Result<Self, AppError> {
+        let entries = read_message_log(log_path)?;
+        let lines = entries.len();
+
+        let mut logged = Vec::new();
+        for entry in entries {
+            match entry {
+                LogEntry::Message(message) => logged.push(message),
+                // Updates to messages stored in the topic file don't apply here
+                LogEntry::Stream(update) => {
+                    update.apply(&mut logged);
+                }
+            }
+        }
+        let streaming = logged
+            .into_iter()
+            .filter(|m| m.is_streaming)
+            .map(|m| (m.id, m.content.len()))
+            .collect();
+
+        Ok(Self { len, lines, streaming })
+    }
+
+    /// Account for a line just written to the log
+    fn record(&mut self, entry: &LogEntry, len: u64) {
+        self.len = len;
+        self.lines += 1;
+        match entry {
+            LogEntry::Message(message) if message.is_streaming => {
+                self.streaming.insert(message.id.clone(), message.content.len());
+            }
+            LogEntry::Message(_) => {}
+            LogEntry::Stream(StreamUpdate::Delta { message_id, delta }) => {
+                if let Some(content_len) = self.streaming.get_mut(message_id) {
+                    *content_len += delta.len();
+                }
+            }
+            LogEntry::Stream(StreamUpdate::End { message_id, .. }) => {
+                self.streaming.remove(message_id);
+            }
+        }
+    }
+}
+
+/// The log state cache. It only holds what can be re-read from disk, so a panic
+/// while it was held just drops it.
+fn log_states() -> MutexGuard<'static, Option<HashMap<PathBuf, LogState>>> {
+    LOG_STATES.lock().unwrap_or_else(|poisoned| {
+        let mut states = poisoned.into_inner();
+        *states = None;
+        states
+    })
+}
+
+/// The state of a topic's log, re-read if the log changed since it was cached.
+/// The caller holds `TOPIC_LOCK`.
+fn log_state<'a>(states: &'a mut Option<HashMap<PathBuf, LogState>>, topic_path: &Path) -> Result<&'a mut LogState, AppError> {
+    let log_path = message_log_path(topic_path);
+    let len = fs::metadata(&log_path).map(|m| m.len()).unwrap_or(0);
+
+    let states = states.get_or_insert_with(HashMap::new);
+    if states.get(&log_path).map_or(true, |state| state.len != len) {
+        let state = LogState::read(&log_path, len)?;
+        states.insert(log_path.clone(), state);
+    }
+    Ok(states.get_mut(&log_path).expect("log state was just inserted"))
+}
+
+/// Write one line to a topic's log, folding the log into the topic once it is long.
+/// The caller holds `TOPIC_LOCK` and passes the log's current `state`.
+fn append_log_entry(topic_path: &Path, entry: &LogEntry, state: &mut LogState) -> Result<(), AppError> {
+    let log_path = message_log_path(topic_path);
+    let line = serde_json::to_string(entry)
+        .map_err(|e| AppError::Validation(format!("Failed to serialize message: {}", e)))?;
+
+    // One write per line, so a crash leaves at most one torn (skipped) line
+    let mut log = OpenOptions::new()
+        .create(true)
+        .read(true)
+        .append(true)
+        .open(&log_path)
+        .map_err(|e| AppError::Io(format!("Failed to open message log: {}", e)))?;
+
+    // Start on a fresh line if a previous append was cut off
+    let mut record = format!("{}\n", line);
+    if log_ends_mid_line(&mut log).map_err(|e| AppError::Io(format!("Failed to read message log: {}", e)))? {
+        record.insert(0, '\n');
+    }
+
+    log.write_all(record.as_bytes())
+        .map_err(|e| AppError::Io(format!("Failed to append message: {}", e)))?;
+    let len = log.metadata().map(|m| m.len()).unwrap_or(0);
+    drop(log);
+    state.record(entry, len);
+
+    // Fold a long log back into the topic JSON to keep reads fast, but not for every delta of a stream
+    let is_delta = matches!(entry, LogEntry::Stream(StreamUpdate::Delta { .. }));
+    if !is_delta && state.lines >= MESSAGE_LOG_COMPACT_THRESHOLD {
+        let topic = load_topic(topic_path)?;
+        save_topic(topic_path, &topic)?;
+        // Messages still streaming now live in the topic file and are looked up there
+        *state = LogState::default();
+    }
+
+    Ok(())
+}
+
+/// Current state of a message. Only the log is read when the message was appended since the
+/// last compaction; otherwise the whole topic is loaded.
+fn find_message(topic_path: &Path, message_id: &str) -> Result<Message, AppError> {
+    let mut logged = Vec::new();
+    for entry in read_message_log(&message_log_path(topic_path))? {
+        match entry {
+            LogEntry::Message(message) => logged.push(message),
+            // Updates to messages stored in the topic file don't apply here
+            LogEntry::Stream(update) => {
+                update.apply(&mut logged);
+            }
+        }
+    }
+
+    let in_log = logged.iter().any(|m| m.id == message_id);
+    let messages = if in_log { logged } else { load_topic(topic_path)?.messages };
+
+    messages
+        .into_iter()
+        .rev()
+        .find(|m| m.id == message_id)
+        .ok_or_else(|| AppError::NotFound(format!("Message not found: {}", message_id)))
+}
+
+/// Append text to a streaming message by logging the delta (the topic is not rewritten).
+/// The message is looked up on disk only for the first delta after the log state was lost.
+fn append_stream_delta(topic_path: &Path, message_id: &str, delta: &str) -> Result<(), AppError> {
+    let _guard = TOPIC_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+
+    if !topic_path.exists() {
+        return Err(AppError::NotFound(format!("Topic not found: {}", topic_path.display())));
+    }
+
+    let mut states = log_states();
+    let state = log_state(&mut states, topic_path)?;
+    let content_len = match state.streaming.get(message_id) {
+        Some(content_len) => *content_len,
+        None => {
+            let message = find_message(topic_path, message_id)?;
+            if !message.is_streaming {
+                return Err(AppError::InvalidState(format!("Message {} is not streaming", message_id)));
+            }
+            state.streaming.insert(message.id, message.content.len());
+            message.content.len()
+        }
+    };
+    let limit = MessageLimits::default().max_content_len;
+    if content_len + delta.len() > limit {
+        return Err(AppError::Validation(format!("Message content exceeds {} bytes", limit)));
+    }
+
+    append_log_entry(topic_path, &LogEntry::Stream(StreamUpdate::Delta {
+        message_id: message_id.to_string(),
+        delta: delta.to_string(),
+    }), state)
+}
+
+/// Mark a streaming message complete and attach its metadata; returns the final message
+fn finalize_stream(topic_path: &Path, message_id: &str, metadata: Option<MessageMetadata>) -> Result<Message, AppError> {
+    let _guard = TOPIC_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+
+    if !topic_path.exists() {
+        return Err(AppError::NotFound(format!("Topic not found: {}", topic_path.display())));
+    }
+
+    let mut message = find_message(topic_path, message_id)?;
+    if !message.is_streaming {
+        return Err(AppError::InvalidState(format!("Message {} is not streaming", message_id)));
+    }
+
+    let update = StreamUpdate::End { message_id: message_id.to_string(), metadata };
+    update.apply(std::slice::from_mut(&mut message));
+    message.validate().map_err(AppError::Validation)?;
+
+    let mut states = log_states();
+    let state = log_state(&mut states, topic_path)?;
+    append_log_entry(topic_path, &LogEntry::Stream(update), state)?;
+    Ok(message)
+}
+
+/// Replace a finished message's content, keeping the old content in its edit history if asked
+/// (up to `MAX_EDIT_HISTORY` versions). The topic (with its log folded in) is rewritten; returns it
+/// with the edited message.
+fn edit_topic_message(topic_path: &Path, message_id: &str, new_content: &str, keep_history: bool) -> Result<Topic, AppError> {
+    if new_content.trim().is_empty() {
+        return Err(AppError::Validation("Message content is required".to_string()));
+    }
+    let max_content_len = MessageLimits::default().max_content_len;
+    if new_content.len() > max_content_len {
+        return Err(AppError::Validation(format!(
+            "Message content exceeds {} bytes ({} bytes)",
+            max_content_len, new_content.len()
+        )));
+    }
+
+    let _guard = TOPIC_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+
+    if !topic_path.exists() {
+        return Err(AppError::NotFound(format!("Topic not found: {}", topic_path.display())));
+    }
+
+    let mut topic = load_topic(topic_path)?;
+    let message = topic.messages
+        .iter_mut()
+        .find(|m| m.id == message_id)
+        .ok_or_else(|| AppError::NotFound(format!("Message not found: {}", message_id)))?;
+    if message.is_streaming {
+        return Err(AppError::InvalidState(format!("Message {} is still streaming", message_id)));
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let previous = std::mem::replace(&mut message.content, new_content.to_string());
+    // An edit that changes nothing leaves no history entry
+    if keep_history && previous != new_content {
+        let history = message.metadata
+            .get_or_insert_with(MessageMetadata::default)
+            .edit_history
+            .get_or_insert_with(Vec::new);
+        history.push(MessageEdit { content: previous, edited_at: now.clone() });
+        if history.len() > MAX_EDIT_HISTORY {
+            history.drain(..history.len() - MAX_EDIT_HISTORY);
+        }
+    }
+    message.validate().map_err(AppError::Validation)?;
+
+    topic.updated_at = now;
+    save_topic(topic_path, &topic)?;
+    Ok(topic)
+}
+
+/// Remove one finished message from a topic, keeping the order of the rest; returns the updated topic.
+/// Attachments of the removed message are left for `cleanup_orphaned_attachments`.
+fn delete_topic_message(topic_path: &Path, message_id: &str) -> Result<Topic, AppError> {
+    let _guard = TOPIC_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+
+    if !topic_path.exists() {
+        return Err(AppError::NotFound(format!("Topic not found: {}", topic_path.display())));
+    }
+
+    let mut topic = load_topic(topic_path)?;
+    let index = topic.messages
+        .iter()
+        .position(|m| m.id == message_id)
+        .ok_or_else(|| AppError::NotFound(format!("Message not found: {}", message_id)))?;
+    // Deltas still arriving would recreate it, and finalizing it would fail
+    if topic.messages[index].is_streaming {
+        return Err(AppError::InvalidState(format!("Message {} is still streaming", message_id)));
+    }
+    topic.messages.remove(index);
+
+    topic.updated_at = chrono::Utc::now().to_rfc3339();
+    save_topic(topic_path, &topic)?;
+    Ok(topic)
+}
+
+/// Start a new topic with the messages of `topic_path` up to and including `at_message_id`.
+/// The source topic is left as it is; the fork links back to it through `forked_from`.
+fn fork_topic(topic_path: &Path, at_message_id: &str, new_title: &str) -> Result<Topic, AppError> {
+    let _guard = TOPIC_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+
+    if !topic_path.exists() {
+        return Err(AppError::NotFound(format!("Topic not found: {}", topic_path.display())));
+    }
+
+    let source = load_topic(topic_path)?;
+    let index = source.messages
+        .iter()
+        .position(|m| m.id == at_message_id)
+        .ok_or_else(|| AppError::NotFound(format!("Message not found: {}", at_message_id)))?;
+    if source.messages[index].is_streaming {
+        return Err(AppError::InvalidState(format!("Message {} is still streaming", at_message_id)));
+    }
+
+    let now = chrono::Utc::now().to_rfc3339();
+    let mut messages = source.messages;
+    messages.truncate(index + 1);
+    let fork = Topic {
+        id: uuid::Uuid::new_v4().to_string(),
+        owner_id: source.owner_id,
+        owner_type: source.owner_type,
+        title: new_title.to_string(),
+        messages,
+        created_at: now.clone(),
+        updated_at: now,
+        pinned: false,
+        forked_from: Some(source.id),
+    };
+    fork.validate().map_err(AppError::Validation)?;
+
+    save_topic(&topic_path.with_file_name(format!("{}.json", fork.id)), &fork)?;
+    Ok(fork)
+}
+
+/// Whether timestamp `a` is later than `b` (RFC 3339, falling back to string order)
+pub(crate) fn is_later(a: &str, b: &str) -> bool {
+    match (chrono::DateTime::parse_from_rfc3339(a), chrono::DateTime::parse_from_rfc3339(b)) {
+        (Ok(a), Ok(b)) => a > b,
+        _ => a > b,
+    }
+}
+
+/// Total order on timestamps for sorting: unparseable ones sort before any RFC 3339 one,
+/// and equal instants fall back to string order
+fn timestamp_order(a: &str, b: &str) -> std::cmp::Ordering {
+    let parse = |timestamp: &str| chrono::DateTime::parse_from_rfc3339(timestamp).ok();
+    parse(a).cmp(&parse(b)).then_with(|| a.cmp(b))
+}
+
+/// Optimistic concurrency check before overwriting a stored agent, group or topic.
+/// Fails with a "Conflict: ..." error when the stored copy was updated after the
+/// `expected_updated_at` the client last read, unless `force` is set.
+fn check_write_conflict(
+    kind: &str,
+    id: &str,
+    stored_updated_at: Option<&str>,
+    expected_updated_at: Option<&str>,
+    force: bool,
+) -> Result<(), AppError> {
+    if force {
+        return Ok(());
+    }
+
+    match (stored_updated_at, expected_updated_at) {
+        (Some(stored), Some(expected)) if is_later(stored, expected) => Err(AppError::Conflict(format!(
+            "Conflict: {} {} was modified at {} (expected {})",
+            kind, id, stored, expected
+        ))),
+        _ => Ok(()),
+    }
+}
+
+/// `updated_at` of a stored agent or group file, if it exists and has one
+fn stored_updated_at(file_path: &Path) -> Option<String> {
+    let content = fs::read_to_string(file_path).ok()?;
+    let value: serde_json::Value = serde_json::from_str(&content).ok()?;
+    value
+        .get("updated_at")
+        .and_then(|updated_at| updated_at.as_str())
+        .filter(|updated_at| !updated_at.is_empty())
+        .map(str::to_string)
+}
+
+/// Write a topic under its owner's directory, checking for a concurrent edit first
+fn write_topic_file(app_data: &Path, topic: &Topic, expected_updated_at: Option<&str>, force: bool) -> Result<(), AppError> {
+    topic.validate().map_err(AppError::Validation)?;
 
     // Determine directory based on owner_type
     let dir = match topic.owner_type {
-        crate::models::OwnerType::Agent => app_data.join("Agents"),
-        crate::models::OwnerType::Group => app_data.join("AgentGroups"),
+        OwnerType::Agent => app_data.join("Agents"),
+        OwnerType::Group => app_data.join("AgentGroups"),
     };
 
     // Ensure directory exists
     fs::create_dir_all(&dir)
-        .map_err(|e| format!("Failed to create directory: {}", e))?;
+        .map_err(|e| AppError::Io(format!("Failed to create directory: {}", e)))?;
 
     let file_path = dir.join(format!("{}.json", topic.id));
-    let json = serde_json::to_string_pretty(&topic)
-        .map_err(|e| format!("Failed to serialize topic: {}", e))?;
 
-    fs::write(&file_path, json)
-        .map_err(|e| format!("Failed to write topic file: {}", e))?;
+    let _guard = TOPIC_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    // Appended messages also date the topic, so compare against the fully loaded one
+    let stored = if file_path.exists() {
+        load_topic(&file_path).ok().map(|stored| stored.updated_at)
+    } else {
+        None
+    };
+    check_write_conflict("Topic", &topic.id, stored.as_deref(), expected_updated_at, force)?;
 
+    save_topic(&file_path, topic)?;
+    super::search::on_topic_written(app_data, topic);
     Ok(())
 }
 
+/// Write an agent to `UserData/`, checking for a concurrent edit first.
+/// `updated_at` is stamped here rather than trusted from the client; the stored copy is returned.
+fn write_agent_file(app_data: &Path, agent: &Agent, expected_updated_at: Option<&str>, force: bool) -> Result<Agent, AppError> {
+    // Check the model against the configured allow-list (if any)
+    let known_models = super::settings::load_settings(&app_data.join("settings.json"))
+        .map(|settings| settings.known_models)
+        .unwrap_or_default();
+    agent.validate_with_models(&known_models).map_err(AppError::Validation)?;
+    for warning in agent.warnings() {
+        warn!("{}", warning);
+    }
+
+    let dir = app_data.join("UserData");
+
+    fs::create_dir_all(&dir)
+        .map_err(|e| AppError::Io(format!("Failed to create directory: {}", e)))?;
+
+    let file_path = dir.join(format!("{}.json", agent.id));
+
+    let _guard = USER_DATA_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    check_write_conflict("Agent", &agent.id, stored_updated_at(&file_path).as_deref(), expected_updated_at, force)?;
+
+    let mut stored = agent.clone();
+    stored.updated_at = chrono::Utc::now().to_rfc3339();
+    write_json_atomic(&file_path, &stored).map_err(AppError::Io)?;
+    Ok(stored)
+}
+
+/// Write a group to `UserData/groups/`, checking for a concurrent edit first.
+/// `updated_at` is stamped here rather than trusted from the client; the stored copy is returned.
+fn write_group_file(app_data: &Path, group: &Group, expected_updated_at: Option<&str>, force: bool) -> Result<Group, AppError> {
+    group.validate().map_err(AppError::Validation)?;
+
+    let dir = app_data.join("UserData").join("groups");
+
+    fs::create_dir_all(&dir)
+        .map_err(|e| AppError::Io(format!("Failed to create directory: {}", e)))?;
+
+    let file_path = dir.join(format!("{}.json", group.id));
+
+    let _guard = USER_DATA_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    check_write_conflict("Group", &group.id, stored_updated_at(&file_path).as_deref(), expected_updated_at, force)?;
+
+    let mut stored = group.clone();
+    stored.updated_at = chrono::Utc::now().to_rfc3339();
+    write_json_atomic(&file_path, &stored).map_err(AppError::Io)?;
+    Ok(stored)
+}
+
+/// Import a topic exported elsewhere under a new owner.
+/// The topic and its messages get fresh ids so no existing file is overwritten.
+fn import_topic(
+    app_data: &Path,
+    json: serde_json::Value,
+    target_owner_id: &str,
+    target_owner_type: &str,
+) -> Result<ConversationImport, AppError> {
+    let dir = topic_dir(app_data, target_owner_type)?;
+    let mut topic: Topic = serde_json::from_value(json)
+        .map_err(|e| AppError::Validation(format!("Failed to parse topic JSON: {}", e)))?;
+
+    topic.id = uuid::Uuid::new_v4().to_string();
+    topic.owner_id = target_owner_id.to_string();
+    topic.owner_type = match target_owner_type {
+        "agent" => OwnerType::Agent,
+        _ => OwnerType::Group,
+    };
+    for message in &mut topic.messages {
+        message.id = uuid::Uuid::new_v4().to_string();
+    }
+
+    topic.validate().map_err(AppError::Validation)?;
+    for message in &topic.messages {
+        message.validate().map_err(AppError::Validation)?;
+    }
+
+    // Attachment files aren't part of the export; report the ones missing here
+    let mut warnings = Vec::new();
+    for attachment in topic.messages.iter().flat_map(|m| &m.attachments) {
+        let path = Path::new(&attachment.file_path);
+        if path.is_relative() && !app_data.join(path).exists() {
+            warnings.push(format!("Attachment not found: {}", attachment.file_path));
+        }
+    }
+
+    fs::create_dir_all(&dir)
+        .map_err(|e| AppError::Io(format!("Failed to create directory: {}", e)))?;
+
+    let _guard = TOPIC_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    save_topic(&dir.join(format!("{}.json", topic.id)), &topic)?;
+    super::search::on_topic_written(app_data, &topic);
+
+    Ok(ConversationImport {
+        topic_id: topic.id,
+        warnings,
+    })
+}
+
+/// Read conversation (topic) from file
+#[tauri::command]
+pub async fn read_conversation(app: AppHandle, topic_id: String) -> Result<Topic, CommandError> {
+    let app_data = get_app_data_dir(&app)?;
+    run_blocking("read_conversation", move || {
+        // Try agent topics first, then group topics
+        for dir in ["Agents", "AgentGroups"] {
+            let path = app_data.join(dir).join(format!("{}.json", topic_id));
+            if path.exists() {
+                return load_topic(&path).map_err(CommandError::from);
+            }
+        }
+
+        Err(AppError::NotFound(format!("Topic not found: {}", topic_id)).into())
+    })
+    .await
+}
+
+/// Write conversation (topic) to file.
+/// With `expected_updated_at`, a topic changed since the client read it is rejected as a conflict unless `force` is set.
+#[tauri::command]
+pub async fn write_conversation(
+    app: AppHandle,
+    topic: Topic,
+    expected_updated_at: Option<String>,
+    force: Option<bool>,
+) -> Result<(), CommandError> {
+    let app_data = get_app_data_dir(&app)?;
+    run_blocking_write("write_conversation", &topic.id.clone(), move || write_topic_file(&app_data, &topic, expected_updated_at.as_deref(), force.unwrap_or(false)).map_err(CommandError::from)).await
+}
+
+/// Append a single message to an existing topic (existing messages are not rewritten)
+#[tauri::command]
+pub async fn append_message(app: AppHandle, topic_id: String, owner_type: String, message: Message) -> Result<(), CommandError> {
+    let app_data = get_app_data_dir(&app)?;
+    run_blocking_write("append_message", &topic_id.clone(), move || {
+        let topic_path = topic_dir(&app_data, &owner_type)?.join(format!("{}.json", topic_id));
+
+        if !topic_path.exists() {
+            return Err(AppError::NotFound(format!("Topic not found: {}", topic_id)).into());
+        }
+
+        append_message_to_topic(&topic_path, &message)?;
+
+        // `topic_dir` accepted the owner type, so it is "agent" or "group"
+        let owner = if owner_type == "group" { OwnerType::Group } else { OwnerType::Agent };
+        super::search::on_message_appended(&app_data, &topic_id, &owner, &message);
+        Ok(())
+    })
+    .await
+}
+
+/// Append a chunk of a reply to a streaming message (logged as a delta; the topic is not rewritten)
+#[tauri::command]
+pub async fn append_to_streaming_message(
+    app: AppHandle,
+    topic_id: String,
+    owner_type: String,
+    message_id: String,
+    delta: String,
+) -> Result<(), CommandError> {
+    let app_data = get_app_data_dir(&app)?;
+    run_blocking_write("append_to_streaming_message", &topic_id.clone(), move || {
+        let topic_path = topic_dir(&app_data, &owner_type)?.join(format!("{}.json", topic_id));
+
+        if !topic_path.exists() {
+            return Err(AppError::NotFound(format!("Topic not found: {}", topic_id)).into());
+        }
+
+        append_stream_delta(&topic_path, &message_id, &delta).map_err(CommandError::from)
+    })
+    .await
+}
+
+/// Mark a streaming message complete and attach its metadata; returns the final message
+#[tauri::command]
+pub async fn finalize_streaming_message(
+    app: AppHandle,
+    topic_id: String,
+    message_id: String,
+    metadata: Option<MessageMetadata>,
+) -> Result<Message, CommandError> {
+    let app_data = get_app_data_dir(&app)?;
+    run_blocking_write("finalize_streaming_message", &topic_id.clone(), move || {
+        for (dir, owner) in [("Agents", OwnerType::Agent), ("AgentGroups", OwnerType::Group)] {
+            let topic_path = app_data.join(dir).join(format!("{}.json", topic_id));
+            if topic_path.exists() {
+                let message = finalize_stream(&topic_path, &message_id, metadata)?;
+                // Only now is the whole reply there to index
+                super::search::on_message_appended(&app_data, &topic_id, &owner, &message);
+                return Ok(message);
+            }
+        }
+
+        Err(AppError::NotFound(format!("Topic not found: {}", topic_id)).into())
+    })
+    .await
+}
+
+/// Replace the content of a message; with `keep_history` the old content is kept in its metadata
+#[tauri::command]
+pub async fn edit_message(
+    app: AppHandle,
+    topic_id: String,
+    owner_type: String,
+    message_id: String,
+    new_content: String,
+    keep_history: Option<bool>,
+) -> Result<Message, CommandError> {
+    let app_data = get_app_data_dir(&app)?;
+    run_blocking_write("edit_message", &topic_id.clone(), move || {
+        let topic_path = topic_dir(&app_data, &owner_type)?.join(format!("{}.json", topic_id));
+
+        if !topic_path.exists() {
+            return Err(AppError::NotFound(format!("Topic not found: {}", topic_id)).into());
+        }
+
+        let topic = edit_topic_message(&topic_path, &message_id, &new_content, keep_history.unwrap_or(false))?;
+        super::search::on_topic_written(&app_data, &topic);
+        topic.messages
+            .into_iter()
+            .find(|m| m.id == message_id)
+            .ok_or_else(|| AppError::NotFound(format!("Message not found: {}", message_id)).into())
+    })
+    .await
+}
+
+/// Remove a single message from a topic. Its attachment files stay until the next orphan cleanup.
+#[tauri::command]
+pub async fn delete_message(app: AppHandle, topic_id: String, owner_type: String, message_id: String) -> Result<(), CommandError> {
+    let app_data = get_app_data_dir(&app)?;
+    run_blocking_write("delete_message", &topic_id.clone(), move || {
+        let topic_path = topic_dir(&app_data, &owner_type)?.join(format!("{}.json", topic_id));
+
+        if !topic_path.exists() {
+            return Err(AppError::NotFound(format!("Topic not found: {}", topic_id)).into());
+        }
+
+        let topic = delete_topic_message(&topic_path, &message_id)?;
+        super::search::on_topic_written(&app_data, &topic);
+        Ok(())
+    })
+    .await
+}
+
+/// Fork a conversation at a message: a new topic with the messages up to and including
+/// `at_message_id`. Returns the new topic's id.
+#[tauri::command]
+pub async fn fork_conversation(
+    app: AppHandle,
+    topic_id: String,
+    owner_type: String,
+    at_message_id: String,
+    new_title: String,
+) -> Result<String, CommandError> {
+    let app_data = get_app_data_dir(&app)?;
+    run_blocking_write("fork_conversation", &topic_id.clone(), move || {
+        let topic_path = topic_dir(&app_data, &owner_type)?.join(format!("{}.json", topic_id));
+
+        if !topic_path.exists() {
+            return Err(AppError::NotFound(format!("Topic not found: {}", topic_id)).into());
+        }
+
+        let fork = fork_topic(&topic_path, &at_message_id, &new_title)?;
+        super::search::on_topic_written(&app_data, &fork);
+        Ok(fork.id)
+    })
+    .await
+}
+
+/// Import a conversation exported as JSON into the given agent or group
+#[tauri::command]
+pub async fn import_conversation(
+    app: AppHandle,
+    json: serde_json::Value,
+    target_owner_id: String,
+    target_owner_type: String,
+) -> Result<ConversationImport, CommandError> {
+    let app_data = get_app_data_dir(&app)?;
+    run_blocking_write("import_conversation", &target_owner_id.clone(), move || import_topic(&app_data, json, &target_owner_id, &target_owner_type).map_err(CommandError::from)).await
+}
+
 /// Delete conversation (topic) file
 #[tauri::command]
-pub async fn delete_conversation(app: AppHandle, topic_id: String, owner_type: String) -> Result<(), String> {
+pub async fn delete_conversation(app: AppHandle, topic_id: String, owner_type: String) -> Result<(), CommandError> {
     let app_data = get_app_data_dir(&app)?;
+    run_blocking_write("delete_conversation", &topic_id.clone(), move || {
+        let dir = topic_dir(&app_data, &owner_type)?;
 
-    let dir = match owner_type.as_str() {
-        "agent" => app_data.join("Agents"),
-        "group" => app_data.join("AgentGroups"),
-        _ => return Err("Invalid owner_type: must be 'agent' or 'group'".to_string()),
-    };
+        let file_path = dir.join(format!("{}.json", topic_id));
+
+        if !file_path.exists() {
+            return Err(AppError::NotFound(format!("Topic not found: {}", topic_id)).into());
+        }
+
+        fs::remove_file(&file_path)
+            .map_err(|e| AppError::Io(format!("Failed to delete topic file: {}", e)))?;
+
+        let log_path = message_log_path(&file_path);
+        if log_path.exists() {
+            fs::remove_file(&log_path)
+                .map_err(|e| AppError::Io(format!("Failed to delete message log: {}", e)))?;
+        }
+
+        super::search::on_topic_deleted(&app_data, &topic_id);
+        Ok(())
+    })
+    .await
+}
+
+/// List all topics for a specific owner
+#[tauri::command]
+pub async fn list_topics(app: AppHandle, owner_id: String, owner_type: String) -> Result<Vec<Topic>, CommandError> {
+    let app_data = get_app_data_dir(&app)?;
+    run_blocking("list_topics", move || load_owner_topics(&app_data, &owner_id, &owner_type).map_err(CommandError::from)).await
+}
+
+/// Topics of one owner: pinned first, then most recently updated
+fn load_owner_topics(app_data: &Path, owner_id: &str, owner_type: &str) -> Result<Vec<Topic>, AppError> {
+    let dir = topic_dir(app_data, owner_type)?;
+
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let entries = fs::read_dir(&dir)
+        .map_err(|e| AppError::Io(format!("Failed to read directory: {}", e)))?;
 
-    let file_path = dir.join(format!("{}.json", topic_id));
+    let mut topics = Vec::new();
 
-    if !file_path.exists() {
-        return Err(format!("Topic not found: {}", topic_id));
+    for entry in entries {
+        let entry = entry.map_err(|e| AppError::Io(format!("Failed to read entry: {}", e)))?;
+        let path = entry.path();
+
+        if path.extension().and_then(|s| s.to_str()) == Some("json") {
+            if let Ok(topic) = load_topic(&path) {
+                if topic.owner_id == owner_id {
+                    topics.push(topic);
+                }
+            }
+        }
     }
 
-    fs::remove_file(&file_path)
-        .map_err(|e| format!("Failed to delete topic file: {}", e))?;
+    // Pinned first, then by updated_at (most recent first)
+    topics.sort_by(|a, b| b.pinned.cmp(&a.pinned).then_with(|| b.updated_at.cmp(&a.updated_at)));
+
+    Ok(topics)
+}
+
+/// Move a topic file and its message log into `AppData/.trash/<dir>/`, where they can be restored by hand
+fn move_topic_to_trash(app_data: &Path, topic_path: &Path) -> Result<(), AppError> {
+    let dir_name = topic_path.parent().and_then(|dir| dir.file_name()).unwrap_or_default();
+    let trash_dir = app_data.join(".trash").join(dir_name);
+    fs::create_dir_all(&trash_dir)
+        .map_err(|e| AppError::Io(format!("Failed to create trash directory: {}", e)))?;
+
+    // An earlier topic with the same id may already be in the trash
+    let stem = topic_path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+    let mut trashed = trash_dir.join(format!("{}.json", stem));
+    if trashed.exists() {
+        trashed = trash_dir.join(format!("{}.{}.json", stem, chrono::Utc::now().timestamp_millis()));
+    }
+
+    fs::rename(topic_path, &trashed)
+        .map_err(|e| AppError::Io(format!("Failed to move topic to trash: {}", e)))?;
+    mark_trashed_now(&trashed);
+
+    let log_path = message_log_path(topic_path);
+    if log_path.exists() {
+        let trashed_log = message_log_path(&trashed);
+        fs::rename(&log_path, &trashed_log)
+            .map_err(|e| AppError::Io(format!("Failed to move message log to trash: {}", e)))?;
+        mark_trashed_now(&trashed_log);
+    }
 
     Ok(())
 }
 
-/// List all topics for a specific owner
+/// Trash retention counts from the move, not from the last edit, so a trashed file's mtime is reset
+fn mark_trashed_now(path: &Path) {
+    if let Err(e) = fs::File::options().write(true).open(path).and_then(|file| file.set_modified(std::time::SystemTime::now())) {
+        warn!("Failed to timestamp trashed file {}: {}", path.display(), e);
+    }
+}
+
+/// Move an owner's topics selected by `filter` to the trash (or only list them with `dry_run`).
+/// Pinned topics are kept unless the filter sets `include_pinned`.
+fn delete_matching_topics(
+    app_data: &Path,
+    owner_id: &str,
+    owner_type: &str,
+    filter: &TopicFilter,
+    dry_run: bool,
+) -> Result<TopicBulkDelete, AppError> {
+    filter.validate().map_err(AppError::Validation)?;
+
+    let dir = topic_dir(app_data, owner_type)?;
+
+    // Select under the lock, so a topic written meanwhile (e.g. pinned or given new messages)
+    // is judged by its current contents
+    let _guard = TOPIC_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    let topic_ids: Vec<String> = load_owner_topics(app_data, owner_id, owner_type)?
+        .into_iter()
+        .filter(|topic| filter.matches(topic))
+        .map(|topic| topic.id)
+        .collect();
+
+    if !dry_run {
+        for topic_id in &topic_ids {
+            move_topic_to_trash(app_data, &dir.join(format!("{}.json", topic_id)))?;
+            super::search::on_topic_deleted(app_data, topic_id);
+        }
+    }
+
+    Ok(TopicBulkDelete {
+        count: topic_ids.len(),
+        topic_ids,
+        dry_run,
+    })
+}
+
+/// Move an owner's topics matching a filter to the trash; `dry_run` only reports what would be deleted
 #[tauri::command]
-pub async fn list_topics(app: AppHandle, owner_id: String, owner_type: String) -> Result<Vec<Topic>, String> {
+pub async fn delete_topics_matching(
+    app: AppHandle,
+    owner_id: String,
+    owner_type: String,
+    filter: TopicFilter,
+    dry_run: Option<bool>,
+) -> Result<TopicBulkDelete, CommandError> {
     let app_data = get_app_data_dir(&app)?;
+    run_blocking_write("delete_topics_matching", &owner_id.clone(), move || {
+        delete_matching_topics(&app_data, &owner_id, &owner_type, &filter, dry_run.unwrap_or(false)).map_err(CommandError::from)
+    })
+    .await
+}
 
-    let dir = match owner_type.as_str() {
-        "agent" => app_data.join("Agents"),
-        "group" => app_data.join("AgentGroups"),
-        _ => return Err("Invalid owner_type: must be 'agent' or 'group'".to_string()),
-    };
+/// Pin or unpin a stored topic (rewritten atomically, `updated_at` unchanged)
+fn set_pinned(app_data: &Path, topic_id: &str, owner_type: &str, pinned: bool) -> Result<(), AppError> {
+    let topic_path = topic_dir(app_data, owner_type)?.join(format!("{}.json", topic_id));
 
-    if !dir.exists() {
-        return Ok(Vec::new());
+    let _guard = TOPIC_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+    if !topic_path.exists() {
+        return Err(AppError::NotFound(format!("Topic not found: {}", topic_id)));
     }
 
-    let entries = fs::read_dir(&dir)
-        .map_err(|e| format!("Failed to read directory: {}", e))?;
-
-    let mut topics = Vec::new();
+    let mut topic = load_topic(&topic_path)?;
+    topic.pinned = pinned;
+    save_topic(&topic_path, &topic)
+}
 
-    for entry in entries {
-        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-        let path = entry.path();
+/// Pin a topic to the top of its owner's list (or unpin it)
+#[tauri::command]
+pub async fn set_topic_pinned(app: AppHandle, topic_id: String, owner_type: String, pinned: bool) -> Result<(), CommandError> {
+    let app_data = get_app_data_dir(&app)?;
+    run_blocking_write("set_topic_pinned", &topic_id.clone(), move || set_pinned(&app_data, &topic_id, &owner_type, pinned).map_err(CommandError::from)).await
+}
 
-        if path.extension().and_then(|s| s.to_str()) == Some("json") {
-            let content = fs::read_to_string(&path)
-                .map_err(|e| format!("Failed to read file: {}", e))?;
+/// Resolve the context token limit for a topic's owner.
+/// Group topics use the largest limit among member agents that can be loaded.
+fn resolve_context_token_limit(app_data: &Path, topic: &Topic) -> Option<u32> {
+    let load_agent = |agent_id: &str| -> Option<Agent> {
+        let path = app_data.join("UserData").join(format!("{}.json", agent_id));
+        let content = fs::read_to_string(path).ok()?;
+        serde_json::from_str(&content).ok()
+    };
 
-            if let Ok(topic) = serde_json::from_str::<Topic>(&content) {
-                if topic.owner_id == owner_id {
-                    topics.push(topic);
-                }
-            }
+    match topic.owner_type {
+        OwnerType::Agent => load_agent(&topic.owner_id).map(|agent| agent.context_token_limit),
+        OwnerType::Group => {
+            let path = app_data.join("UserData").join("groups").join(format!("{}.json", topic.owner_id));
+            let content = fs::read_to_string(path).ok()?;
+            let group: Group = serde_json::from_str(&content).ok()?;
+
+            group.agent_ids
+                .iter()
+                .filter_map(|agent_id| load_agent(agent_id))
+                .map(|agent| agent.context_token_limit)
+                .max()
         }
     }
+}
+
+/// Estimate token usage of a topic relative to its owner's context_token_limit
+#[tauri::command]
+pub async fn estimate_topic_tokens(app: AppHandle, topic_id: String) -> Result<TokenEstimate, CommandError> {
+    let app_data = get_app_data_dir(&app)?;
+    let topic = read_conversation(app, topic_id).await?;
 
-    // Sort by updated_at (most recent first)
-    topics.sort_by(|a, b| b.updated_at.cmp(&a.updated_at));
+    run_blocking("estimate_topic_tokens", move || {
+        let token_limit = resolve_context_token_limit(&app_data, &topic);
+        Ok(topic.estimate_tokens(token_limit))
+    })
+    .await
+}
 
-    Ok(topics)
+/// Message statistics of a topic (counts, characters, tool calls, time span)
+#[tauri::command]
+pub async fn get_topic_stats(app: AppHandle, topic_id: String) -> Result<TopicStats, CommandError> {
+    let topic = read_conversation(app, topic_id).await?;
+    Ok(topic.stats())
 }
 
 /// Read agent from file
 #[tauri::command]
-pub async fn read_agent(app: AppHandle, agent_id: String) -> Result<Agent, String> {
+pub async fn read_agent(app: AppHandle, agent_id: String) -> Result<Agent, CommandError> {
     let app_data = get_app_data_dir(&app)?;
-    let file_path = app_data.join("UserData").join(format!("{}.json", agent_id));
+    run_blocking("read_agent", move || {
+        let file_path = app_data.join("UserData").join(format!("{}.json", agent_id));
 
-    if !file_path.exists() {
-        return Err(format!("Agent not found: {}", agent_id));
-    }
+        if !file_path.exists() {
+            return Err(AppError::NotFound(format!("Agent not found: {}", agent_id)).into());
+        }
 
-    let content = fs::read_to_string(&file_path)
-        .map_err(|e| format!("Failed to read agent file: {}", e))?;
+        let content = fs::read_to_string(&file_path)
+            .map_err(|e| AppError::Io(format!("Failed to read agent file: {}", e)))?;
 
-    let agent: Agent = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse agent JSON: {}", e))?;
+        let agent: Agent = serde_json::from_str(&content)
+            .map_err(|e| AppError::Validation(format!("Failed to parse agent JSON: {}", e)))?;
 
-    Ok(agent)
+        Ok(agent)
+    })
+    .await
 }
 
-/// Write agent to file
+/// Write agent to file.
+/// With `expected_updated_at`, an agent changed since the client read it is rejected as a conflict unless `force` is set.
+/// Returns the stored copy with its server-stamped `updated_at`.
 #[tauri::command]
-pub async fn write_agent(app: AppHandle, agent: Agent) -> Result<(), String> {
-    agent.validate()?;
-
+pub async fn write_agent(
+    app: AppHandle,
+    agent: Agent,
+    expected_updated_at: Option<String>,
+    force: Option<bool>,
+) -> Result<Agent, CommandError> {
     let app_data = get_app_data_dir(&app)?;
-    let dir = app_data.join("UserData");
-
-    fs::create_dir_all(&dir)
-        .map_err(|e| format!("Failed to create directory: {}", e))?;
-
-    let file_path = dir.join(format!("{}.json", agent.id));
-    let json = serde_json::to_string_pretty(&agent)
-        .map_err(|e| format!("Failed to serialize agent: {}", e))?;
-
-    fs::write(&file_path, json)
-        .map_err(|e| format!("Failed to write agent file: {}", e))?;
-
-    Ok(())
+    run_blocking_write("write_agent", &agent.id.clone(), move || write_agent_file(&app_data, &agent, expected_updated_at.as_deref(), force.unwrap_or(false)).map_err(CommandError::from)).await
 }
 
 /// Delete agent file
 #[tauri::command]
-pub async fn delete_agent(app: AppHandle, agent_id: String) -> Result<(), String> {
+pub async fn delete_agent(app: AppHandle, agent_id: String) -> Result<(), CommandError> {
     let app_data = get_app_data_dir(&app)?;
-    let file_path = app_data.join("UserData").join(format!("{}.json", agent_id));
+    run_blocking_write("delete_agent", &agent_id.clone(), move || {
+        let file_path = app_data.join("UserData").join(format!("{}.json", agent_id));
 
-    if !file_path.exists() {
-        return Err(format!("Agent not found: {}", agent_id));
-    }
+        if !file_path.exists() {
+            return Err(AppError::NotFound(format!("Agent not found: {}", agent_id)).into());
+        }
 
-    fs::remove_file(&file_path)
-        .map_err(|e| format!("Failed to delete agent file: {}", e))?;
+        fs::remove_file(&file_path)
+            .map_err(|e| AppError::Io(format!("Failed to delete agent file: {}", e)))?;
 
-    Ok(())
+        Ok(())
+    })
+    .await
 }
 
-/// List all agents
-#[tauri::command]
-pub async fn list_agents(app: AppHandle) -> Result<Vec<Agent>, String> {
-    let app_data = get_app_data_dir(&app)?;
+/// Load every agent in `UserData/`, most recently created first (unreadable files are skipped)
+fn load_agents(app_data: &Path) -> Result<Vec<Agent>, AppError> {
     let dir = app_data.join("UserData");
 
     if !dir.exists() {
@@ -197,17 +1266,17 @@ pub async fn list_agents(app: AppHandle) -> Result<Vec<Agent>, String> {
     }
 
     let entries = fs::read_dir(&dir)
-        .map_err(|e| format!("Failed to read directory: {}", e))?;
+        .map_err(|e| AppError::Io(format!("Failed to read directory: {}", e)))?;
 
     let mut agents = Vec::new();
 
     for entry in entries {
-        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
+        let entry = entry.map_err(|e| AppError::Io(format!("Failed to read entry: {}", e)))?;
         let path = entry.path();
 
         if path.extension().and_then(|s| s.to_str()) == Some("json") {
             let content = fs::read_to_string(&path)
-                .map_err(|e| format!("Failed to read file: {}", e))?;
+                .map_err(|e| AppError::Io(format!("Failed to read file: {}", e)))?;
 
             if let Ok(agent) = serde_json::from_str::<Agent>(&content) {
                 agents.push(agent);
@@ -221,192 +1290,1060 @@ pub async fn list_agents(app: AppHandle) -> Result<Vec<Agent>, String> {
     Ok(agents)
 }
 
-/// Read group from file
-#[tauri::command]
-pub async fn read_group(app: AppHandle, group_id: String) -> Result<Group, String> {
-    let app_data = get_app_data_dir(&app)?;
-    let file_path = app_data.join("UserData").join("groups").join(format!("{}.json", group_id));
-
-    if !file_path.exists() {
-        return Err(format!("Group not found: {}", group_id));
-    }
+/// Agents carrying `tag`
+fn agents_with_tag(app_data: &Path, tag: &str) -> Result<Vec<Agent>, AppError> {
+    let mut agents = load_agents(app_data)?;
+    agents.retain(|agent| agent.tags.iter().any(|t| t == tag));
+    Ok(agents)
+}
 
-    let content = fs::read_to_string(&file_path)
-        .map_err(|e| format!("Failed to read group file: {}", e))?;
+/// Distinct tags across all agents, sorted
+fn all_agent_tags(app_data: &Path) -> Result<Vec<String>, AppError> {
+    let tags: std::collections::BTreeSet<String> = load_agents(app_data)?
+        .into_iter()
+        .flat_map(|agent| agent.tags)
+        .collect();
+    Ok(tags.into_iter().collect())
+}
 
-    let group: Group = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse group JSON: {}", e))?;
+/// List all agents
+#[tauri::command]
+pub async fn list_agents(app: AppHandle) -> Result<Vec<Agent>, CommandError> {
+    let app_data = get_app_data_dir(&app)?;
+    run_blocking("list_agents", move || load_agents(&app_data).map_err(CommandError::from)).await
+}
 
-    Ok(group)
+/// List the agents carrying a tag
+#[tauri::command]
+pub async fn list_agents_by_tag(app: AppHandle, tag: String) -> Result<Vec<Agent>, CommandError> {
+    let app_data = get_app_data_dir(&app)?;
+    run_blocking("list_agents_by_tag", move || agents_with_tag(&app_data, &tag).map_err(CommandError::from)).await
 }
 
-/// Write group to file
+/// List every tag used by any agent
 #[tauri::command]
-pub async fn write_group(app: AppHandle, group: Group) -> Result<(), String> {
-    group.validate()?;
+pub async fn list_all_tags(app: AppHandle) -> Result<Vec<String>, CommandError> {
+    let app_data = get_app_data_dir(&app)?;
+    run_blocking("list_all_tags", move || all_agent_tags(&app_data).map_err(CommandError::from)).await
+}
 
+/// Read group from file
+#[tauri::command]
+pub async fn read_group(app: AppHandle, group_id: String) -> Result<Group, CommandError> {
     let app_data = get_app_data_dir(&app)?;
-    let dir = app_data.join("UserData").join("groups");
+    run_blocking("read_group", move || {
+        let file_path = app_data.join("UserData").join("groups").join(format!("{}.json", group_id));
 
-    fs::create_dir_all(&dir)
-        .map_err(|e| format!("Failed to create directory: {}", e))?;
+        if !file_path.exists() {
+            return Err(AppError::NotFound(format!("Group not found: {}", group_id)).into());
+        }
 
-    let file_path = dir.join(format!("{}.json", group.id));
-    let json = serde_json::to_string_pretty(&group)
-        .map_err(|e| format!("Failed to serialize group: {}", e))?;
+        let content = fs::read_to_string(&file_path)
+            .map_err(|e| AppError::Io(format!("Failed to read group file: {}", e)))?;
 
-    fs::write(&file_path, json)
-        .map_err(|e| format!("Failed to write group file: {}", e))?;
+        let group: Group = serde_json::from_str(&content)
+            .map_err(|e| AppError::Validation(format!("Failed to parse group JSON: {}", e)))?;
 
-    Ok(())
+        Ok(group)
+    })
+    .await
+}
+
+/// Write group to file.
+/// With `expected_updated_at`, a group changed since the client read it is rejected as a conflict unless `force` is set.
+/// Returns the stored copy with its server-stamped `updated_at`.
+#[tauri::command]
+pub async fn write_group(
+    app: AppHandle,
+    group: Group,
+    expected_updated_at: Option<String>,
+    force: Option<bool>,
+) -> Result<Group, CommandError> {
+    let app_data = get_app_data_dir(&app)?;
+    run_blocking_write("write_group", &group.id.clone(), move || write_group_file(&app_data, &group, expected_updated_at.as_deref(), force.unwrap_or(false)).map_err(CommandError::from)).await
 }
 
 /// Delete group file
 #[tauri::command]
-pub async fn delete_group(app: AppHandle, group_id: String) -> Result<(), String> {
+pub async fn delete_group(app: AppHandle, group_id: String) -> Result<(), CommandError> {
     let app_data = get_app_data_dir(&app)?;
-    let file_path = app_data.join("UserData").join("groups").join(format!("{}.json", group_id));
+    run_blocking_write("delete_group", &group_id.clone(), move || {
+        let file_path = app_data.join("UserData").join("groups").join(format!("{}.json", group_id));
 
-    if !file_path.exists() {
-        return Err(format!("Group not found: {}", group_id));
-    }
+        if !file_path.exists() {
+            return Err(AppError::NotFound(format!("Group not found: {}", group_id)).into());
+        }
 
-    fs::remove_file(&file_path)
-        .map_err(|e| format!("Failed to delete group file: {}", e))?;
+        fs::remove_file(&file_path)
+            .map_err(|e| AppError::Io(format!("Failed to delete group file: {}", e)))?;
 
-    Ok(())
+        Ok(())
+    })
+    .await
 }
 
 /// List all groups
 #[tauri::command]
-pub async fn list_groups(app: AppHandle) -> Result<Vec<Group>, String> {
+pub async fn list_groups(app: AppHandle) -> Result<Vec<Group>, CommandError> {
     let app_data = get_app_data_dir(&app)?;
-    let dir = app_data.join("UserData").join("groups");
+    run_blocking("list_groups", move || {
+        let dir = app_data.join("UserData").join("groups");
 
-    if !dir.exists() {
-        return Ok(Vec::new());
-    }
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
 
-    let entries = fs::read_dir(&dir)
-        .map_err(|e| format!("Failed to read directory: {}", e))?;
+        let entries = fs::read_dir(&dir)
+            .map_err(|e| AppError::Io(format!("Failed to read directory: {}", e)))?;
 
-    let mut groups = Vec::new();
+        let mut groups = Vec::new();
 
-    for entry in entries {
-        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-        let path = entry.path();
+        for entry in entries {
+            let entry = entry.map_err(|e| AppError::Io(format!("Failed to read entry: {}", e)))?;
+            let path = entry.path();
 
-        if path.extension().and_then(|s| s.to_str()) == Some("json") {
-            let content = fs::read_to_string(&path)
-                .map_err(|e| format!("Failed to read file: {}", e))?;
+            if path.extension().and_then(|s| s.to_str()) == Some("json") {
+                let content = fs::read_to_string(&path)
+                    .map_err(|e| AppError::Io(format!("Failed to read file: {}", e)))?;
+
+                if let Ok(group) = serde_json::from_str::<Group>(&content) {
+                    groups.push(group);
+                }
+            }
+        }
+
+        // Sort by created_at (most recent first)
+        groups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+        Ok(groups)
+    })
+    .await
+}
+
+/// Fields of a topic file needed for the activity list
+#[derive(serde::Deserialize)]
+struct TopicSummary {
+    id: String,
+    owner_id: String,
+    title: String,
+    updated_at: String,
+}
+
+/// Fields of an agent or group file needed for the activity list
+#[derive(serde::Deserialize)]
+struct CreatedSummary {
+    id: String,
+    name: String,
+    created_at: String,
+}
+
+/// Only the timestamp of a logged message
+#[derive(serde::Deserialize)]
+struct LoggedTimestamp {
+    timestamp: String,
+}
+
+/// JSON files directly inside `dir` deserialized as `T` (missing directory and unparseable files are skipped)
+fn read_summaries<T: serde::de::DeserializeOwned>(dir: &Path) -> Vec<(PathBuf, T)> {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.extension().and_then(|s| s.to_str()) == Some("json"))
+        .filter_map(|path| {
+            let content = fs::read_to_string(&path).ok()?;
+            let summary = serde_json::from_str(&content).ok()?;
+            Some((path, summary))
+        })
+        .collect()
+}
 
-            if let Ok(group) = serde_json::from_str::<Group>(&content) {
-                groups.push(group);
+/// Recently updated topics merged with recently created agents and groups, newest first
+fn recent_activity(app_data: &Path, limit: usize) -> Vec<ActivityItem> {
+    let mut items = Vec::new();
+
+    for dir in [app_data.join("Agents"), app_data.join("AgentGroups")] {
+        for (path, topic) in read_summaries::<TopicSummary>(&dir) {
+            // Appended messages don't touch the topic file, so the newest one dates the topic
+            let mut updated_at = topic.updated_at;
+            if let Ok(log) = fs::read_to_string(message_log_path(&path)) {
+                for logged in log.lines().filter_map(|line| serde_json::from_str::<LoggedTimestamp>(line).ok()) {
+                    if logged.timestamp > updated_at {
+                        updated_at = logged.timestamp;
+                    }
+                }
             }
+
+            items.push(ActivityItem {
+                kind: ActivityKind::Topic,
+                id: topic.id,
+                title: topic.title,
+                owner_id: Some(topic.owner_id),
+                timestamp: updated_at,
+            });
         }
     }
 
-    // Sort by created_at (most recent first)
-    groups.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+    let created = [
+        (app_data.join("UserData"), ActivityKind::Agent),
+        (app_data.join("UserData").join("groups"), ActivityKind::Group),
+    ];
+    for (dir, kind) in created {
+        for (_, summary) in read_summaries::<CreatedSummary>(&dir) {
+            items.push(ActivityItem {
+                kind,
+                id: summary.id,
+                title: summary.name,
+                owner_id: None,
+                timestamp: summary.created_at,
+            });
+        }
+    }
 
-    Ok(groups)
+    // Newest first; the id keeps entries with equal timestamps in a stable order
+    items.sort_by(|a, b| timestamp_order(&b.timestamp, &a.timestamp).then_with(|| a.id.cmp(&b.id)));
+    items.truncate(limit);
+
+    items
+}
+
+/// Unified recent activity list for the home screen
+#[tauri::command]
+pub async fn get_recent_activity(app: AppHandle, limit: usize) -> Result<Vec<ActivityItem>, CommandError> {
+    let app_data = get_app_data_dir(&app)?;
+    run_blocking("get_recent_activity", move || Ok(recent_activity(&app_data, limit))).await
 }
 
 /// Read canvas from file (CORE-044)
 #[tauri::command]
-pub async fn read_canvas(app: AppHandle, canvas_id: String) -> Result<serde_json::Value, String> {
+pub async fn read_canvas(app: AppHandle, canvas_id: String) -> Result<serde_json::Value, CommandError> {
     let app_data = get_app_data_dir(&app)?;
-    let file_path = app_data.join("Canvasmodules").join(format!("{}.json", canvas_id));
+    run_blocking("read_canvas", move || {
+        let file_path = app_data.join("Canvasmodules").join(format!("{}.json", canvas_id));
 
-    if !file_path.exists() {
-        return Err(format!("Canvas not found: {}", canvas_id));
-    }
+        if !file_path.exists() {
+            return Err(AppError::NotFound(format!("Canvas not found: {}", canvas_id)).into());
+        }
 
-    let content = fs::read_to_string(&file_path)
-        .map_err(|e| format!("Failed to read canvas file: {}", e))?;
+        let content = fs::read_to_string(&file_path)
+            .map_err(|e| AppError::Io(format!("Failed to read canvas file: {}", e)))?;
 
-    let canvas: serde_json::Value = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse canvas JSON: {}", e))?;
+        let canvas: serde_json::Value = serde_json::from_str(&content)
+            .map_err(|e| AppError::Validation(format!("Failed to parse canvas JSON: {}", e)))?;
 
-    Ok(canvas)
+        Ok(canvas)
+    })
+    .await
 }
 
 /// Write canvas to file (CORE-044)
 #[tauri::command]
-pub async fn write_canvas(app: AppHandle, canvas: serde_json::Value) -> Result<(), String> {
+pub async fn write_canvas(app: AppHandle, canvas: serde_json::Value) -> Result<(), CommandError> {
     // Extract canvas_id from the JSON
     let canvas_id = canvas.get("id")
         .and_then(|v| v.as_str())
-        .ok_or_else(|| "Canvas must have an 'id' field".to_string())?;
+        .ok_or_else(|| AppError::Validation("Canvas must have an 'id' field".to_string()))?
+        .to_string();
 
     let app_data = get_app_data_dir(&app)?;
-    let dir = app_data.join("Canvasmodules");
-
-    // Ensure directory exists
-    fs::create_dir_all(&dir)
-        .map_err(|e| format!("Failed to create directory: {}", e))?;
+    run_blocking_write("write_canvas", &canvas_id.clone(), move || {
+        let dir = app_data.join("Canvasmodules");
 
-    let file_path = dir.join(format!("{}.json", canvas_id));
-    let json = serde_json::to_string_pretty(&canvas)
-        .map_err(|e| format!("Failed to serialize canvas: {}", e))?;
+        // Ensure directory exists
+        fs::create_dir_all(&dir)
+            .map_err(|e| AppError::Io(format!("Failed to create directory: {}", e)))?;
 
-    fs::write(&file_path, json)
-        .map_err(|e| format!("Failed to write canvas file: {}", e))?;
-
-    Ok(())
+        write_json_atomic(&dir.join(format!("{}.json", canvas_id)), &canvas).map_err(AppError::Io)?;
+        Ok(())
+    })
+    .await
 }
 
 /// Delete canvas file (CORE-044)
 #[tauri::command]
-pub async fn delete_canvas(app: AppHandle, canvas_id: String) -> Result<(), String> {
+pub async fn delete_canvas(app: AppHandle, canvas_id: String) -> Result<(), CommandError> {
     let app_data = get_app_data_dir(&app)?;
-    let file_path = app_data.join("Canvasmodules").join(format!("{}.json", canvas_id));
+    run_blocking_write("delete_canvas", &canvas_id.clone(), move || {
+        let file_path = app_data.join("Canvasmodules").join(format!("{}.json", canvas_id));
 
-    if !file_path.exists() {
-        return Err(format!("Canvas not found: {}", canvas_id));
-    }
+        if !file_path.exists() {
+            return Err(AppError::NotFound(format!("Canvas not found: {}", canvas_id)).into());
+        }
 
-    fs::remove_file(&file_path)
-        .map_err(|e| format!("Failed to delete canvas file: {}", e))?;
+        fs::remove_file(&file_path)
+            .map_err(|e| AppError::Io(format!("Failed to delete canvas file: {}", e)))?;
 
-    Ok(())
+        Ok(())
+    })
+    .await
 }
 
 /// List all canvas files (CORE-044)
 #[tauri::command]
-pub async fn list_canvases(app: AppHandle) -> Result<Vec<serde_json::Value>, String> {
+pub async fn list_canvases(app: AppHandle) -> Result<Vec<serde_json::Value>, CommandError> {
     let app_data = get_app_data_dir(&app)?;
-    let dir = app_data.join("Canvasmodules");
+    run_blocking("list_canvases", move || {
+        let dir = app_data.join("Canvasmodules");
 
-    if !dir.exists() {
-        return Ok(Vec::new());
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let entries = fs::read_dir(&dir)
+            .map_err(|e| AppError::Io(format!("Failed to read directory: {}", e)))?;
+
+        let mut canvases = Vec::new();
+
+        for entry in entries {
+            let entry = entry.map_err(|e| AppError::Io(format!("Failed to read entry: {}", e)))?;
+            let path = entry.path();
+
+            if path.extension().and_then(|s| s.to_str()) == Some("json") {
+                let content = fs::read_to_string(&path)
+                    .map_err(|e| AppError::Io(format!("Failed to read file: {}", e)))?;
+
+                if let Ok(canvas) = serde_json::from_str::<serde_json::Value>(&content) {
+                    canvases.push(canvas);
+                }
+            }
+        }
+
+        // Sort by modifiedAt (most recent first)
+        canvases.sort_by(|a, b| {
+            let a_time = a.get("modifiedAt").and_then(|v| v.as_str()).unwrap_or("");
+            let b_time = b.get("modifiedAt").and_then(|v| v.as_str()).unwrap_or("");
+            b_time.cmp(a_time)
+        });
+
+        Ok(canvases)
+    })
+    .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::CollaborationMode;
+
+    #[test]
+    fn test_blocking_io_timeout() {
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap();
+
+        let slow: Result<(), CommandError> = runtime.block_on(run_blocking_with_timeout("read_conversation", None, Duration::from_millis(20), || {
+            std::thread::sleep(Duration::from_millis(200));
+            Ok(())
+        }));
+        let error = slow.unwrap_err();
+        assert_eq!(error.code, "IO_ERROR");
+        assert!(error.message.contains("read_conversation timed out"), "{}", error);
+
+        let fast: Result<i32, String> = runtime.block_on(run_blocking_with_timeout("list_topics", None, Duration::from_secs(5), || Ok(3)));
+        assert_eq!(fast, Ok(3));
+
+        let failed: Result<(), String> = runtime.block_on(run_blocking("read_agent", || Err("Agent not found: a".to_string())));
+        assert_eq!(failed, Err("Agent not found: a".to_string()));
     }
 
-    let entries = fs::read_dir(&dir)
-        .map_err(|e| format!("Failed to read directory: {}", e))?;
+    #[test]
+    fn test_timed_out_operation_is_not_run_twice() {
+        let runtime = tokio::runtime::Builder::new_current_thread().enable_time().build().unwrap();
+        let runs = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let write = |runs: &Arc<std::sync::atomic::AtomicUsize>| {
+            let runs = Arc::clone(runs);
+            move || -> Result<(), CommandError> {
+                runs.fetch_add(1, Ordering::SeqCst);
+                std::thread::sleep(Duration::from_millis(200));
+                Ok(())
+            }
+        };
 
-    let mut canvases = Vec::new();
+        let first = runtime.block_on(run_blocking_with_timeout("test_append", Some("topic_a"), Duration::from_millis(20), write(&runs)));
+        assert!(first.unwrap_err().message.contains("timed out"));
 
-    for entry in entries {
-        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
-        let path = entry.path();
+        // The first attempt is still running, so a retry is refused instead of appending again
+        let retry = runtime.block_on(run_blocking_with_timeout("test_append", Some("topic_a"), Duration::from_secs(5), write(&runs)));
+        assert!(retry.unwrap_err().message.contains("still finishing"));
+        assert_eq!(runs.load(Ordering::SeqCst), 1);
 
-        if path.extension().and_then(|s| s.to_str()) == Some("json") {
-            let content = fs::read_to_string(&path)
-                .map_err(|e| format!("Failed to read file: {}", e))?;
+        // Other topics, and reads of the same one, are not held up by it
+        let other = runtime.block_on(run_blocking_with_timeout("test_append", Some("topic_b"), Duration::from_secs(5), || Ok::<_, CommandError>(())));
+        assert!(other.is_ok());
+        let read = runtime.block_on(run_blocking_with_timeout("test_append", None, Duration::from_secs(5), || Ok::<_, CommandError>(())));
+        assert!(read.is_ok());
+
+        std::thread::sleep(Duration::from_millis(400));
+        runtime.block_on(run_blocking_with_timeout("test_append", Some("topic_a"), Duration::from_secs(5), write(&runs))).unwrap();
+        assert_eq!(runs.load(Ordering::SeqCst), 2);
+    }
+
+    fn write_test_agent(app_data: &Path, id: &str, context_token_limit: u32) {
+        let agent = Agent {
+            id: id.to_string(),
+            name: id.to_string(),
+            avatar: "avatar.png".to_string(),
+            system_prompt: String::new(),
+            model: "gpt-4".to_string(),
+            temperature: 0.7,
+            context_token_limit,
+            max_output_tokens: 1000,
+            created_at: "2025-01-01T00:00:00Z".to_string(),
+            updated_at: "2025-01-01T00:00:00Z".to_string(),
+            tags: Vec::new(),
+        };
+        let dir = app_data.join("UserData");
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join(format!("{}.json", id)), serde_json::to_string(&agent).unwrap()).unwrap();
+    }
+
+    fn create_test_topic(owner_id: &str, owner_type: OwnerType) -> Topic {
+        Topic {
+            id: "topic-1".to_string(),
+            owner_id: owner_id.to_string(),
+            owner_type,
+            title: "Test Topic".to_string(),
+            messages: Vec::new(),
+            created_at: "2025-01-01T00:00:00Z".to_string(),
+            updated_at: "2025-01-01T00:00:00Z".to_string(),
+            pinned: false,
+            forked_from: None,
+        }
+    }
+
+    #[test]
+    fn test_resolve_context_token_limit() {
+        let app_data = std::env::temp_dir().join(format!("vcp_fs_cmd_test_{}", uuid::Uuid::new_v4()));
+        write_test_agent(&app_data, "agent-a", 4000);
+        write_test_agent(&app_data, "agent-b", 8000);
+
+        let group = Group {
+            id: "group-1".to_string(),
+            name: "Group".to_string(),
+            avatar: "avatar.png".to_string(),
+            agent_ids: vec!["agent-a".to_string(), "agent-b".to_string(), "missing".to_string()],
+            collaboration_mode: CollaborationMode::Sequential,
+            turn_count: 1,
+            speaking_rules: String::new(),
+            created_at: "2025-01-01T00:00:00Z".to_string(),
+            updated_at: "2025-01-01T00:00:00Z".to_string(),
+        };
+        let groups_dir = app_data.join("UserData").join("groups");
+        fs::create_dir_all(&groups_dir).unwrap();
+        fs::write(groups_dir.join("group-1.json"), serde_json::to_string(&group).unwrap()).unwrap();
+
+        let agent_topic = create_test_topic("agent-a", OwnerType::Agent);
+        assert_eq!(resolve_context_token_limit(&app_data, &agent_topic), Some(4000));
+
+        let group_topic = create_test_topic("group-1", OwnerType::Group);
+        assert_eq!(resolve_context_token_limit(&app_data, &group_topic), Some(8000));
+
+        let orphan_topic = create_test_topic("missing", OwnerType::Agent);
+        assert_eq!(resolve_context_token_limit(&app_data, &orphan_topic), None);
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+
+    fn test_message(id: &str, timestamp: &str) -> Message {
+        Message {
+            id: id.to_string(),
+            sender: crate::models::MessageSender::User,
+            sender_id: None,
+            sender_name: None,
+            content: format!("message {}", id),
+            attachments: Vec::new(),
+            timestamp: timestamp.to_string(),
+            is_streaming: false,
+            metadata: None,
+        }
+    }
+
+    fn write_test_topic(app_data: &Path) -> PathBuf {
+        let dir = app_data.join("Agents");
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("topic-1.json");
+        let mut topic = create_test_topic("agent-a", OwnerType::Agent);
+        topic.messages.push(test_message("m0", "2025-01-01T00:00:00Z"));
+        fs::write(&path, serde_json::to_string(&topic).unwrap()).unwrap();
+        path
+    }
+
+    #[test]
+    fn test_append_message_preserves_order() {
+        let app_data = std::env::temp_dir().join(format!("vcp_fs_append_test_{}", uuid::Uuid::new_v4()));
+        let topic_path = write_test_topic(&app_data);
+        let original = fs::read_to_string(&topic_path).unwrap();
+
+        append_message_to_topic(&topic_path, &test_message("m1", "2025-01-02T00:00:00Z")).unwrap();
+        append_message_to_topic(&topic_path, &test_message("m2", "2025-01-03T00:00:00Z")).unwrap();
+
+        // The topic file itself is untouched; messages went to the log
+        assert_eq!(fs::read_to_string(&topic_path).unwrap(), original);
+
+        let topic = load_topic(&topic_path).unwrap();
+        let ids: Vec<&str> = topic.messages.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, vec!["m0", "m1", "m2"]);
+        assert_eq!(topic.updated_at, "2025-01-03T00:00:00Z");
+
+        // A full write folds the log into the topic file
+        save_topic(&topic_path, &topic).unwrap();
+        assert!(!message_log_path(&topic_path).exists());
+        assert_eq!(load_topic(&topic_path).unwrap().messages.len(), 3);
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_append_message_validates_and_compacts() {
+        let app_data = std::env::temp_dir().join(format!("vcp_fs_compact_test_{}", uuid::Uuid::new_v4()));
+        let topic_path = write_test_topic(&app_data);
+
+        assert!(matches!(append_message_to_topic(&topic_path, &test_message("", "2025-01-02T00:00:00Z")), Err(AppError::Validation(_))));
+        assert!(matches!(append_message_to_topic(&app_data.join("Agents").join("missing.json"), &test_message("m1", "2025-01-02T00:00:00Z")), Err(AppError::NotFound(_))));
+
+        for i in 0..MESSAGE_LOG_COMPACT_THRESHOLD {
+            append_message_to_topic(&topic_path, &test_message(&format!("m{}", i + 1), "2025-01-02T00:00:00Z")).unwrap();
+        }
+
+        // Reaching the threshold folds the log back into the topic file
+        assert!(!message_log_path(&topic_path).exists());
+        let topic = load_topic(&topic_path).unwrap();
+        assert_eq!(topic.messages.len(), MESSAGE_LOG_COMPACT_THRESHOLD + 1);
+        assert_eq!(topic.messages.last().unwrap().id, format!("m{}", MESSAGE_LOG_COMPACT_THRESHOLD));
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_torn_log_line_is_skipped() {
+        let app_data = std::env::temp_dir().join(format!("vcp_fs_torn_test_{}", uuid::Uuid::new_v4()));
+        let topic_path = write_test_topic(&app_data);
+
+        append_message_to_topic(&topic_path, &test_message("m1", "2025-01-02T00:00:00Z")).unwrap();
+        let mut log = OpenOptions::new().append(true).open(message_log_path(&topic_path)).unwrap();
+        log.write_all(b"{\"id\":\"m2\",\"sen").unwrap();
+
+        assert_eq!(load_topic(&topic_path).unwrap().messages.len(), 2);
+
+        // The next append starts on its own line
+        append_message_to_topic(&topic_path, &test_message("m3", "2025-01-03T00:00:00Z")).unwrap();
+        let ids: Vec<String> = load_topic(&topic_path).unwrap().messages.into_iter().map(|m| m.id).collect();
+        assert_eq!(ids, vec!["m0", "m1", "m3"]);
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+
+    fn streaming_message(id: &str) -> Message {
+        Message {
+            sender: crate::models::MessageSender::Agent,
+            content: String::new(),
+            is_streaming: true,
+            ..test_message(id, "2025-01-02T00:00:00Z")
+        }
+    }
+
+    #[test]
+    fn test_streaming_deltas_are_appended() {
+        let app_data = std::env::temp_dir().join(format!("vcp_fs_stream_test_{}", uuid::Uuid::new_v4()));
+        let topic_path = write_test_topic(&app_data);
+        let original = fs::read_to_string(&topic_path).unwrap();
+
+        append_message_to_topic(&topic_path, &streaming_message("m1")).unwrap();
+        for delta in ["Hel", "lo, ", "world"] {
+            append_stream_delta(&topic_path, "m1", delta).unwrap();
+        }
+
+        // Deltas only go to the log
+        assert_eq!(fs::read_to_string(&topic_path).unwrap(), original);
+        let topic = load_topic(&topic_path).unwrap();
+        assert_eq!(topic.messages[1].content, "Hello, world");
+        assert!(topic.messages[1].is_streaming);
+
+        assert!(matches!(append_stream_delta(&topic_path, "missing", "x"), Err(AppError::NotFound(e)) if e.contains("Message not found")));
+        // m0 is a finished message in the topic file
+        assert!(matches!(append_stream_delta(&topic_path, "m0", "x"), Err(AppError::InvalidState(e)) if e.contains("not streaming")));
+
+        // Deltas keep working once the log is folded into the topic file
+        save_topic(&topic_path, &topic).unwrap();
+        append_stream_delta(&topic_path, "m1", "!").unwrap();
+        assert_eq!(load_topic(&topic_path).unwrap().messages[1].content, "Hello, world!");
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_long_stream_is_folded_in_when_it_ends() {
+        let app_data = std::env::temp_dir().join(format!("vcp_fs_long_stream_test_{}", uuid::Uuid::new_v4()));
+        let topic_path = write_test_topic(&app_data);
+        let original = fs::read_to_string(&topic_path).unwrap();
+
+        append_message_to_topic(&topic_path, &streaming_message("m1")).unwrap();
+        for _ in 0..MESSAGE_LOG_COMPACT_THRESHOLD + 50 {
+            append_stream_delta(&topic_path, "m1", "ab").unwrap();
+        }
 
-            if let Ok(canvas) = serde_json::from_str::<serde_json::Value>(&content) {
-                canvases.push(canvas);
+        // No rewrite of the topic in the middle of the stream
+        assert_eq!(fs::read_to_string(&topic_path).unwrap(), original);
+
+        // The size limit still counts every delta
+        let limit = MessageLimits::default().max_content_len;
+        let too_long = "x".repeat(limit - 2 * (MESSAGE_LOG_COMPACT_THRESHOLD + 50) + 1);
+        assert!(matches!(append_stream_delta(&topic_path, "m1", &too_long), Err(AppError::Validation(e)) if e.contains("exceeds")));
+
+        let message = finalize_stream(&topic_path, "m1", None).unwrap();
+        assert_eq!(message.content.len(), 2 * (MESSAGE_LOG_COMPACT_THRESHOLD + 50));
+        assert!(!message_log_path(&topic_path).exists());
+        let stored = load_topic(&topic_path).unwrap().messages.pop().unwrap();
+        assert_eq!(stored.content, message.content);
+        assert!(!stored.is_streaming);
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_finalize_streaming_message() {
+        let app_data = std::env::temp_dir().join(format!("vcp_fs_finalize_test_{}", uuid::Uuid::new_v4()));
+        let topic_path = write_test_topic(&app_data);
+
+        append_message_to_topic(&topic_path, &streaming_message("empty")).unwrap();
+        // Nothing arrived: an agent reply without text or tool calls is invalid
+        assert!(finalize_stream(&topic_path, "empty", None).is_err());
+
+        append_message_to_topic(&topic_path, &streaming_message("m1")).unwrap();
+        append_stream_delta(&topic_path, "m1", "Done.").unwrap();
+        let metadata = MessageMetadata {
+            tokens: Some(2),
+            model_used: Some("gpt-4".to_string()),
+            latency_ms: Some(850),
+            tool_calls: None,
+            edit_history: None,
+        };
+        let message = finalize_stream(&topic_path, "m1", Some(metadata)).unwrap();
+        assert!(!message.is_streaming);
+        assert_eq!(message.content, "Done.");
+
+        let stored = load_topic(&topic_path).unwrap().messages.pop().unwrap();
+        assert_eq!(stored.id, "m1");
+        assert!(!stored.is_streaming);
+        assert_eq!(stored.content, "Done.");
+        assert_eq!(stored.metadata.unwrap().model_used.as_deref(), Some("gpt-4"));
+
+        assert!(matches!(append_stream_delta(&topic_path, "m1", "more"), Err(AppError::InvalidState(e)) if e.contains("not streaming")));
+        assert!(matches!(finalize_stream(&topic_path, "m1", None), Err(AppError::InvalidState(_))));
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_edit_message() {
+        let app_data = std::env::temp_dir().join(format!("vcp_fs_edit_test_{}", uuid::Uuid::new_v4()));
+        let topic_path = write_test_topic(&app_data);
+        append_message_to_topic(&topic_path, &test_message("m1", "2025-01-02T00:00:00Z")).unwrap();
+        append_message_to_topic(&topic_path, &test_message("m2", "2025-01-03T00:00:00Z")).unwrap();
+
+        edit_topic_message(&topic_path, "m1", "First draft", false).unwrap();
+        let topic = edit_topic_message(&topic_path, "m1", "Second draft", true).unwrap();
+        assert!(is_later(&topic.updated_at, "2025-01-03T00:00:00Z"));
+
+        let stored = load_topic(&topic_path).unwrap();
+        let ids: Vec<&str> = stored.messages.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, vec!["m0", "m1", "m2"]);
+        assert_eq!(stored.messages[1].content, "Second draft");
+        // Only the edit that asked for it was recorded
+        let history = stored.messages[1].metadata.as_ref().unwrap().edit_history.as_ref().unwrap();
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].content, "First draft");
+
+        // Unchanged content adds no history entry, and the history is capped
+        edit_topic_message(&topic_path, "m1", "Second draft", true).unwrap();
+        for i in 0..MAX_EDIT_HISTORY + 5 {
+            edit_topic_message(&topic_path, "m1", &format!("Draft {}", i), true).unwrap();
+        }
+        let stored = load_topic(&topic_path).unwrap();
+        let history = stored.messages[1].metadata.as_ref().unwrap().edit_history.as_ref().unwrap();
+        assert_eq!(history.len(), MAX_EDIT_HISTORY);
+        assert_eq!(history[0].content, "Draft 4");
+
+        // The edited content is validated
+        assert!(matches!(edit_topic_message(&topic_path, "m2", "", true), Err(AppError::Validation(e)) if e.contains("content is required")));
+        assert!(matches!(edit_topic_message(&topic_path, "m2", "  \n", true), Err(AppError::Validation(e)) if e.contains("content is required")));
+        let oversized = "x".repeat(MessageLimits::default().max_content_len + 1);
+        assert!(matches!(edit_topic_message(&topic_path, "m2", &oversized, true), Err(AppError::Validation(e)) if e.contains("exceeds")));
+        assert_eq!(load_topic(&topic_path).unwrap().messages[2].content, "message m2");
+        assert!(matches!(edit_topic_message(&topic_path, "missing", "x", false), Err(AppError::NotFound(e)) if e.contains("Message not found")));
+
+        append_message_to_topic(&topic_path, &streaming_message("m3")).unwrap();
+        assert!(matches!(edit_topic_message(&topic_path, "m3", "x", false), Err(AppError::InvalidState(e)) if e.contains("still streaming")));
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_delete_message_preserves_order() {
+        let app_data = std::env::temp_dir().join(format!("vcp_fs_delete_message_test_{}", uuid::Uuid::new_v4()));
+        let topic_path = write_test_topic(&app_data);
+        for (id, timestamp) in [("m1", "2025-01-02T00:00:00Z"), ("m2", "2025-01-03T00:00:00Z"), ("m3", "2025-01-04T00:00:00Z")] {
+            append_message_to_topic(&topic_path, &test_message(id, timestamp)).unwrap();
+        }
+
+        let topic = delete_topic_message(&topic_path, "m2").unwrap();
+        assert!(is_later(&topic.updated_at, "2025-01-04T00:00:00Z"));
+        // The log was folded in, so the message is gone for good
+        assert!(!message_log_path(&topic_path).exists());
+
+        let ids: Vec<String> = load_topic(&topic_path).unwrap().messages.into_iter().map(|m| m.id).collect();
+        assert_eq!(ids, vec!["m0", "m1", "m3"]);
+
+        delete_topic_message(&topic_path, "m0").unwrap();
+        let ids: Vec<String> = load_topic(&topic_path).unwrap().messages.into_iter().map(|m| m.id).collect();
+        assert_eq!(ids, vec!["m1", "m3"]);
+
+        assert!(matches!(delete_topic_message(&topic_path, "m2"), Err(AppError::NotFound(e)) if e.contains("Message not found")));
+
+        append_message_to_topic(&topic_path, &streaming_message("m4")).unwrap();
+        assert!(matches!(delete_topic_message(&topic_path, "m4"), Err(AppError::InvalidState(e)) if e.contains("still streaming")));
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_fork_conversation() {
+        let app_data = std::env::temp_dir().join(format!("vcp_fs_fork_test_{}", uuid::Uuid::new_v4()));
+        let topic_path = write_test_topic(&app_data);
+        for (id, timestamp) in [("m1", "2025-01-02T00:00:00Z"), ("m2", "2025-01-03T00:00:00Z"), ("m3", "2025-01-04T00:00:00Z")] {
+            append_message_to_topic(&topic_path, &test_message(id, timestamp)).unwrap();
+        }
+        let source = load_topic(&topic_path).unwrap();
+
+        let fork = fork_topic(&topic_path, "m1", "Alternate ending").unwrap();
+        assert_ne!(fork.id, source.id);
+        assert_eq!(fork.forked_from.as_deref(), Some("topic-1"));
+        assert_eq!(fork.title, "Alternate ending");
+        assert!(is_later(&fork.created_at, &source.updated_at));
+
+        let stored = load_topic(&topic_path.with_file_name(format!("{}.json", fork.id))).unwrap();
+        let ids: Vec<&str> = stored.messages.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, vec!["m0", "m1"]);
+        assert_eq!(stored.owner_id, "agent-a");
+
+        // The source is untouched
+        let after = load_topic(&topic_path).unwrap();
+        assert_eq!(after.messages.len(), 4);
+        assert_eq!(after.updated_at, source.updated_at);
+        assert!(after.forked_from.is_none());
+
+        // Forking at the last message copies everything; forks get distinct ids
+        let full = fork_topic(&topic_path, "m3", "Copy").unwrap();
+        assert_eq!(full.messages.len(), 4);
+        assert_ne!(full.id, fork.id);
+
+        assert!(matches!(fork_topic(&topic_path, "missing", "Fork"), Err(AppError::NotFound(e)) if e.contains("Message not found")));
+        assert!(matches!(fork_topic(&topic_path, "m1", ""), Err(AppError::Validation(e)) if e.contains("title")));
+        assert!(fork_topic(&topic_path, "m1", &"x".repeat(101)).is_err());
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_import_remaps_ids_and_rewrites_owner() {
+        let app_data = std::env::temp_dir().join(format!("vcp_fs_import_test_{}", uuid::Uuid::new_v4()));
+        let existing = write_test_topic(&app_data);
+
+        let mut exported = create_test_topic("agent-a", OwnerType::Agent);
+        exported.messages.push(test_message("m0", "2025-01-01T00:00:00Z"));
+        exported.messages.push(test_message("m1", "2025-01-02T00:00:00Z"));
+
+        let imported = import_topic(&app_data, serde_json::to_value(&exported).unwrap(), "group-9", "group").unwrap();
+        assert_ne!(imported.topic_id, "topic-1");
+        assert!(imported.warnings.is_empty());
+
+        // The existing topic with the same id is left alone
+        assert_eq!(load_topic(&existing).unwrap().messages.len(), 1);
+
+        let topic = load_topic(&app_data.join("AgentGroups").join(format!("{}.json", imported.topic_id))).unwrap();
+        assert_eq!(topic.id, imported.topic_id);
+        assert_eq!(topic.owner_id, "group-9");
+        assert!(matches!(topic.owner_type, OwnerType::Group));
+        assert_eq!(topic.messages.len(), 2);
+        assert!(topic.messages.iter().all(|m| m.id != "m0" && m.id != "m1"));
+        assert_ne!(topic.messages[0].id, topic.messages[1].id);
+        assert_eq!(topic.messages[1].content, "message m1");
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_import_validates_and_reports_missing_attachments() {
+        let app_data = std::env::temp_dir().join(format!("vcp_fs_import_attach_test_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(app_data.join("attachments")).unwrap();
+        fs::write(app_data.join("attachments").join("present.png"), b"png").unwrap();
+
+        let attachment = |file_path: &str| crate::models::Attachment {
+            id: file_path.to_string(),
+            filename: file_path.to_string(),
+            file_path: file_path.to_string(),
+            file_type: crate::models::FileType::Image,
+            file_size: 3,
+            created_at: "2025-01-01T00:00:00Z".to_string(),
+            thumbnail: None,
+        };
+        let mut exported = create_test_topic("agent-a", OwnerType::Agent);
+        let mut message = test_message("m0", "2025-01-01T00:00:00Z");
+        message.attachments = vec![attachment("attachments/present.png"), attachment("attachments/missing.png")];
+        exported.messages.push(message);
+
+        let imported = import_topic(&app_data, serde_json::to_value(&exported).unwrap(), "agent-b", "agent").unwrap();
+        assert_eq!(imported.warnings, vec!["Attachment not found: attachments/missing.png".to_string()]);
+
+        let mut invalid = serde_json::to_value(&exported).unwrap();
+        invalid["title"] = serde_json::json!("");
+        assert!(import_topic(&app_data, invalid, "agent-b", "agent").is_err());
+        assert!(import_topic(&app_data, serde_json::json!({"title": "x"}), "agent-b", "agent").is_err());
+        assert!(import_topic(&app_data, serde_json::to_value(&exported).unwrap(), "agent-b", "team").is_err());
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_stale_write_is_rejected_unless_forced() {
+        let app_data = std::env::temp_dir().join(format!("vcp_fs_conflict_test_{}", uuid::Uuid::new_v4()));
+        write_test_agent(&app_data, "agent-a", 4000);
+        let agent_path = app_data.join("UserData").join("agent-a.json");
+        let read: Agent = serde_json::from_str(&fs::read_to_string(&agent_path).unwrap()).unwrap();
+
+        // Window A saves its edit first
+        let mut first = read.clone();
+        first.name = "Edited in A".to_string();
+        let saved = write_agent_file(&app_data, &first, Some(&read.updated_at), false).unwrap();
+        // The timestamp comes from the server, not the client copy
+        assert!(is_later(&saved.updated_at, &read.updated_at));
+        assert_eq!(stored_updated_at(&agent_path), Some(saved.updated_at.clone()));
+
+        // Window B still holds the original copy
+        let mut second = read.clone();
+        second.name = "Edited in B".to_string();
+        second.updated_at = "2099-01-01T00:00:00Z".to_string();
+        let error = write_agent_file(&app_data, &second, Some(&read.updated_at), false).unwrap_err();
+        assert!(matches!(&error, AppError::Conflict(message) if message.starts_with("Conflict: Agent agent-a")));
+        assert_eq!(CommandError::from(error).code, "CONFLICT");
+
+        write_agent_file(&app_data, &second, Some(&read.updated_at), true).unwrap();
+        let stored: Agent = serde_json::from_str(&fs::read_to_string(&agent_path).unwrap()).unwrap();
+        assert_eq!(stored.name, "Edited in B");
+        assert_ne!(stored.updated_at, second.updated_at);
+
+        // Topics also count appended messages as modifications
+        let topic_path = write_test_topic(&app_data);
+        append_message_to_topic(&topic_path, &test_message("m1", "2025-01-05T00:00:00Z")).unwrap();
+        let stale = create_test_topic("agent-a", OwnerType::Agent);
+        assert!(write_topic_file(&app_data, &stale, Some("2025-01-01T00:00:00Z"), false).is_err());
+        assert!(write_topic_file(&app_data, &stale, Some("2025-01-05T00:00:00Z"), false).is_ok());
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_list_agents_by_tag() {
+        let app_data = std::env::temp_dir().join(format!("vcp_fs_tags_test_{}", uuid::Uuid::new_v4()));
+        write_test_agent(&app_data, "agent-a", 4000);
+        write_test_agent(&app_data, "agent-b", 4000);
+        write_test_agent(&app_data, "agent-c", 4000);
+
+        for (id, tags) in [("agent-a", vec!["coding", "work"]), ("agent-b", vec!["writing"])] {
+            let path = app_data.join("UserData").join(format!("{}.json", id));
+            let mut agent: Agent = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+            agent.tags = tags.into_iter().map(str::to_string).collect();
+            fs::write(&path, serde_json::to_string(&agent).unwrap()).unwrap();
+        }
+
+        let coding: Vec<String> = agents_with_tag(&app_data, "coding").unwrap().into_iter().map(|a| a.id).collect();
+        assert_eq!(coding, vec!["agent-a".to_string()]);
+        assert!(agents_with_tag(&app_data, "missing").unwrap().is_empty());
+        assert_eq!(all_agent_tags(&app_data).unwrap(), vec!["coding", "work", "writing"]);
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_pinned_topics_sort_first() {
+        let app_data = std::env::temp_dir().join(format!("vcp_fs_pin_test_{}", uuid::Uuid::new_v4()));
+        for (id, updated_at) in [("old", "2025-01-01T00:00:00Z"), ("new", "2025-03-01T00:00:00Z"), ("mid", "2025-02-01T00:00:00Z")] {
+            let mut topic = create_test_topic("agent-a", OwnerType::Agent);
+            topic.id = id.to_string();
+            topic.updated_at = updated_at.to_string();
+            write_topic_file(&app_data, &topic, None, false).unwrap();
+        }
+
+        set_pinned(&app_data, "old", "agent", true).unwrap();
+        let ids: Vec<String> = load_owner_topics(&app_data, "agent-a", "agent").unwrap().into_iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec!["old", "new", "mid"]);
+
+        // The flag round-trips and pinning doesn't touch updated_at
+        let stored = load_topic(&app_data.join("Agents").join("old.json")).unwrap();
+        assert!(stored.pinned);
+        assert_eq!(stored.updated_at, "2025-01-01T00:00:00Z");
+
+        set_pinned(&app_data, "old", "agent", false).unwrap();
+        let ids: Vec<String> = load_owner_topics(&app_data, "agent-a", "agent").unwrap().into_iter().map(|t| t.id).collect();
+        assert_eq!(ids, vec!["new", "mid", "old"]);
+        assert!(matches!(set_pinned(&app_data, "missing", "agent", true), Err(AppError::NotFound(_))));
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_recent_activity_merges_by_time() {
+        let app_data = std::env::temp_dir().join(format!("vcp_fs_activity_test_{}", uuid::Uuid::new_v4()));
+
+        write_test_agent(&app_data, "agent-a", 4096);
+        let agent_path = app_data.join("UserData").join("agent-a.json");
+        let mut agent: Agent = serde_json::from_str(&fs::read_to_string(&agent_path).unwrap()).unwrap();
+        agent.created_at = "2025-02-01T00:00:00Z".to_string();
+        fs::write(&agent_path, serde_json::to_string(&agent).unwrap()).unwrap();
+
+        let group = Group {
+            id: "group-1".to_string(),
+            name: "Group".to_string(),
+            avatar: "avatar.png".to_string(),
+            agent_ids: vec!["agent-a".to_string(), "agent-b".to_string()],
+            collaboration_mode: CollaborationMode::Sequential,
+            turn_count: 1,
+            speaking_rules: String::new(),
+            created_at: "2025-04-01T00:00:00Z".to_string(),
+            updated_at: "2025-04-01T00:00:00Z".to_string(),
+        };
+        fs::create_dir_all(app_data.join("UserData").join("groups")).unwrap();
+        write_json_atomic(&app_data.join("UserData").join("groups").join("group-1.json"), &group).unwrap();
+
+        for (id, updated_at) in [("topic-old", "2025-01-01T00:00:00Z"), ("topic-new", "2025-03-01T00:00:00Z")] {
+            let mut topic = create_test_topic("agent-a", OwnerType::Agent);
+            topic.id = id.to_string();
+            topic.updated_at = updated_at.to_string();
+            write_topic_file(&app_data, &topic, None, false).unwrap();
+        }
+        // A message appended later moves the old topic to the top
+        append_message_to_topic(
+            &app_data.join("Agents").join("topic-old.json"),
+            &test_message("msg-1", "2025-05-01T00:00:00Z"),
+        ).unwrap();
+
+        fs::write(app_data.join("UserData").join("broken.json"), "{").unwrap();
+
+        let items = recent_activity(&app_data, 10);
+        let order: Vec<(ActivityKind, &str)> = items.iter().map(|item| (item.kind, item.id.as_str())).collect();
+        assert_eq!(order, vec![
+            (ActivityKind::Topic, "topic-old"),
+            (ActivityKind::Group, "group-1"),
+            (ActivityKind::Topic, "topic-new"),
+            (ActivityKind::Agent, "agent-a"),
+        ]);
+        assert_eq!(items[0].owner_id.as_deref(), Some("agent-a"));
+        assert_eq!(items[1].title, "Group");
+
+        assert_eq!(recent_activity(&app_data, 2).len(), 2);
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_timestamp_order_is_total() {
+        use std::cmp::Ordering;
+
+        // Mixing parseable and unparseable timestamps must not break transitivity
+        let mut timestamps = vec!["2025-01-02T00:00:00Z", "garbage", "2025-01-01T00:00:00+00:00", "2025-01-01T01:00:00+01:00", "2025-01-03T00:00:00Z"];
+        timestamps.sort_by(|a, b| timestamp_order(a, b));
+        assert_eq!(timestamps, vec!["garbage", "2025-01-01T00:00:00+00:00", "2025-01-01T01:00:00+01:00", "2025-01-02T00:00:00Z", "2025-01-03T00:00:00Z"]);
+
+        assert_eq!(timestamp_order("2025-01-01T00:00:00Z", "2025-01-01T00:00:00Z"), Ordering::Equal);
+        assert_eq!(timestamp_order("garbage", "2020-01-01T00:00:00Z"), Ordering::Less);
+    }
+
+    fn write_bulk_topics(app_data: &Path) {
+        let dir = app_data.join("Agents");
+        fs::create_dir_all(&dir).unwrap();
+        let topics = [
+            ("old-empty", "Scratch", "2024-01-01T00:00:00Z", 0),
+            ("old-chat", "Trip planning", "2024-02-01T00:00:00Z", 1),
+            ("new-empty", "Untitled scratch", "2025-06-01T00:00:00Z", 0),
+            ("new-chat", "Release notes", "2025-06-02T00:00:00Z", 2),
+        ];
+        for (id, title, updated_at, messages) in topics {
+            let mut topic = create_test_topic("agent-a", OwnerType::Agent);
+            topic.id = id.to_string();
+            topic.title = title.to_string();
+            topic.updated_at = updated_at.to_string();
+            for i in 0..messages {
+                topic.messages.push(test_message(&format!("{}-m{}", id, i), "2024-01-01T00:00:00Z"));
             }
+            fs::write(dir.join(format!("{}.json", id)), serde_json::to_string(&topic).unwrap()).unwrap();
         }
     }
 
-    // Sort by modifiedAt (most recent first)
-    canvases.sort_by(|a, b| {
-        let a_time = a.get("modifiedAt").and_then(|v| v.as_str()).unwrap_or("");
-        let b_time = b.get("modifiedAt").and_then(|v| v.as_str()).unwrap_or("");
-        b_time.cmp(a_time)
-    });
+    fn selected(app_data: &Path, filter: TopicFilter, dry_run: bool) -> Vec<String> {
+        let mut ids = delete_matching_topics(app_data, "agent-a", "agent", &filter, dry_run).unwrap().topic_ids;
+        ids.sort();
+        ids
+    }
+
+    #[test]
+    fn test_bulk_delete_filters() {
+        let app_data = std::env::temp_dir().join(format!("vcp_bulk_delete_test_{}", uuid::Uuid::new_v4()));
+        write_bulk_topics(&app_data);
+
+        let older = TopicFilter { older_than: Some("2025-01-01T00:00:00Z".to_string()), ..TopicFilter::default() };
+        assert_eq!(selected(&app_data, older, true), vec!["old-chat", "old-empty"]);
 
-    Ok(canvases)
+        let empty = TopicFilter { empty: true, ..TopicFilter::default() };
+        assert_eq!(selected(&app_data, empty, true), vec!["new-empty", "old-empty"]);
+
+        let titled = TopicFilter { title_contains: Some("SCRATCH".to_string()), ..TopicFilter::default() };
+        assert_eq!(selected(&app_data, titled, true), vec!["new-empty", "old-empty"]);
+
+        // Criteria combine
+        let old_and_empty = TopicFilter { older_than: Some("2025-01-01T00:00:00Z".to_string()), empty: true, ..TopicFilter::default() };
+        assert_eq!(selected(&app_data, old_and_empty, true), vec!["old-empty"]);
+
+        // A filter without criteria is refused rather than deleting everything
+        assert!(matches!(delete_matching_topics(&app_data, "agent-a", "agent", &TopicFilter::default(), true), Err(AppError::Validation(_))));
+
+        // Pinned topics are only selected when asked for
+        set_pinned(&app_data, "old-empty", "agent", true).unwrap();
+        let older = TopicFilter { older_than: Some("2025-01-01T00:00:00Z".to_string()), ..TopicFilter::default() };
+        assert_eq!(selected(&app_data, older.clone(), true), vec!["old-chat"]);
+        let with_pinned = TopicFilter { include_pinned: true, ..older };
+        assert_eq!(selected(&app_data, with_pinned, true), vec!["old-chat", "old-empty"]);
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_bulk_delete_dry_run_and_trash() {
+        let app_data = std::env::temp_dir().join(format!("vcp_bulk_delete_trash_test_{}", uuid::Uuid::new_v4()));
+        write_bulk_topics(&app_data);
+        let log_path = message_log_path(&app_data.join("Agents").join("old-chat.json"));
+        fs::write(&log_path, "").unwrap();
+        let a_year_ago = std::time::SystemTime::now() - Duration::from_secs(365 * 24 * 60 * 60);
+        fs::File::options().write(true).open(&log_path).unwrap().set_modified(a_year_ago).unwrap();
+        let older = TopicFilter { older_than: Some("2025-01-01T00:00:00Z".to_string()), ..TopicFilter::default() };
+
+        let preview = delete_matching_topics(&app_data, "agent-a", "agent", &older, true).unwrap();
+        assert!(preview.dry_run);
+        assert_eq!(preview.count, 2);
+        assert!(app_data.join("Agents").join("old-chat.json").exists());
+
+        let deleted = delete_matching_topics(&app_data, "agent-a", "agent", &older, false).unwrap();
+        assert_eq!(deleted.count, 2);
+        assert!(!app_data.join("Agents").join("old-chat.json").exists());
+        assert!(app_data.join(".trash").join("Agents").join("old-chat.json").exists());
+        // Both files start their trash retention now
+        let trashed_log = app_data.join(".trash").join("Agents").join("old-chat.messages.jsonl");
+        assert!(trashed_log.metadata().unwrap().modified().unwrap() > a_year_ago + Duration::from_secs(24 * 60 * 60));
+        assert_eq!(load_owner_topics(&app_data, "agent-a", "agent").unwrap().len(), 2);
+
+        // Nothing left to match
+        assert_eq!(delete_matching_topics(&app_data, "agent-a", "agent", &older, false).unwrap().count, 0);
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
 }
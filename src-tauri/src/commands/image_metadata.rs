@@ -0,0 +1,373 @@
+// Metadata stripping for image attachments
+//
+// Removes EXIF (including GPS), XMP, IPTC and text metadata from JPEG, PNG and WebP files by
+// dropping the segments/chunks that carry it; `img-parts` reads and writes the containers.
+// Pixel data is copied byte for byte, never re-encoded, so the image itself can't be degraded.
+// Colour profiles are kept, and so is the EXIF orientation tag: it is written back as a minimal
+// EXIF block holding nothing else, so photos taken sideways still display upright.
+//
+// WebP chunks are edited directly instead of through `ImageEXIF::set_exif`, which rebuilds the
+// VP8X header from scratch and would lose the alpha and animation flags.
+use img_parts::jpeg::{markers, Jpeg, JpegSegment};
+use img_parts::png::{Png, PngChunk};
+use img_parts::riff::{RiffChunk, RiffContent};
+use img_parts::webp::{WebP, CHUNK_EXIF, CHUNK_VP8X, CHUNK_XMP};
+use img_parts::Bytes;
+
+/// The image without its metadata, or `None` if the format isn't supported or the file doesn't parse
+pub fn strip_image_metadata(data: &[u8]) -> Option<Vec<u8>> {
+    let bytes = Bytes::copy_from_slice(data);
+    let stripped = if data.starts_with(&[0xFF, 0xD8]) {
+        strip_jpeg(Jpeg::from_bytes(bytes).ok()?)?
+    } else if data.starts_with(PNG_SIGNATURE) {
+        strip_png(Png::from_bytes(bytes).ok()?)?
+    } else if data.len() >= 12 && &data[..4] == b"RIFF" && &data[8..12] == b"WEBP" {
+        strip_webp(WebP::from_bytes(bytes).ok()?)
+    } else {
+        return None;
+    };
+    Some(stripped.to_vec())
+}
+
+/// EXIF tag holding the image orientation
+const ORIENTATION_TAG: u16 = 0x0112;
+
+/// Header in front of the TIFF structure in JPEG APP1 segments (and some WebP EXIF chunks)
+const EXIF_HEADER: &[u8] = b"Exif\0\0";
+
+/// The orientation (1-8) from IFD0 of a TIFF-structured EXIF block
+fn exif_orientation(tiff: &[u8]) -> Option<u16> {
+    let big_endian = match tiff.get(..2)? {
+        b"MM" => true,
+        b"II" => false,
+        _ => return None,
+    };
+    // Offsets come from the file, so they are bounds-checked without ever overflowing
+    let u16_at = |pos: usize| -> Option<u16> {
+        let bytes: [u8; 2] = tiff.get(pos..pos.checked_add(2)?)?.try_into().ok()?;
+        Some(if big_endian { u16::from_be_bytes(bytes) } else { u16::from_le_bytes(bytes) })
+    };
+    let u32_at = |pos: usize| -> Option<u32> {
+        let bytes: [u8; 4] = tiff.get(pos..pos.checked_add(4)?)?.try_into().ok()?;
+        Some(if big_endian { u32::from_be_bytes(bytes) } else { u32::from_le_bytes(bytes) })
+    };
+
+    if u16_at(2)? != 42 {
+        return None;
+    }
+    let ifd = usize::try_from(u32_at(4)?).ok()?;
+    let count = u16_at(ifd)? as usize;
+
+    (0..count)
+        .map_while(|i| ifd.checked_add(2 + i * 12))
+        .take_while(|&entry| entry < tiff.len())
+        .find(|&entry| u16_at(entry) == Some(ORIENTATION_TAG))
+        .and_then(|entry| u16_at(entry + 8))
+        .filter(|orientation| (1..=8).contains(orientation))
+}
+
+/// A big-endian TIFF structure whose IFD0 holds only the orientation tag
+fn minimal_exif(orientation: u16) -> Vec<u8> {
+    let mut tiff = b"MM\0*".to_vec();
+    tiff.extend_from_slice(&8u32.to_be_bytes());
+    tiff.extend_from_slice(&1u16.to_be_bytes());
+    // Tag, type SHORT, count 1, value padded to four bytes
+    tiff.extend_from_slice(&ORIENTATION_TAG.to_be_bytes());
+    tiff.extend_from_slice(&3u16.to_be_bytes());
+    tiff.extend_from_slice(&1u32.to_be_bytes());
+    tiff.extend_from_slice(&orientation.to_be_bytes());
+    tiff.extend_from_slice(&[0, 0]);
+    // No next IFD
+    tiff.extend_from_slice(&0u32.to_be_bytes());
+    tiff
+}
+
+/// The minimal replacement for an EXIF block, if it had an orientation
+fn orientation_only(exif: &[u8]) -> Option<Vec<u8>> {
+    let tiff = exif.strip_prefix(EXIF_HEADER).unwrap_or(exif);
+    exif_orientation(tiff).map(minimal_exif)
+}
+
+/// APP1 (EXIF, XMP), APP13 (Photoshop/IPTC) and COM segments; APP0 (JFIF), APP2 (ICC) and APP14 (Adobe) stay
+fn is_jpeg_metadata_marker(marker: u8) -> bool {
+    matches!(marker, markers::APP1 | markers::APP13 | markers::COM)
+}
+
+/// Segments after the start of scan are part of the entropy-coded data and copied as-is
+fn strip_jpeg(mut jpeg: Jpeg) -> Option<Bytes> {
+    let segments = std::mem::take(jpeg.segments_mut())
+        .into_iter()
+        .filter_map(|segment| match segment.marker() {
+            markers::APP1 => segment.contents()
+                .strip_prefix(EXIF_HEADER)
+                .and_then(orientation_only)
+                .map(|tiff| JpegSegment::new_with_contents(markers::APP1, [EXIF_HEADER, &tiff].concat().into())),
+            marker if is_jpeg_metadata_marker(marker) => None,
+            _ => Some(segment),
+        })
+        .collect();
+    *jpeg.segments_mut() = segments;
+
+    // The encoder leaves out the scan data behind a start of scan without a header, which no
+    // decodable JPEG has; such files are left alone rather than cut short
+    let expected_len = jpeg.len();
+    let encoded = jpeg.encoder().bytes();
+    (encoded.len() == expected_len).then_some(encoded)
+}
+
+const PNG_SIGNATURE: &[u8] = &[0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A];
+
+const PNG_EXIF: [u8; 4] = *b"eXIf";
+const PNG_IEND: [u8; 4] = *b"IEND";
+
+/// Chunks holding EXIF, free text (which is where XMP lives in PNG) and the modification time
+const PNG_METADATA_CHUNKS: &[[u8; 4]] = &[PNG_EXIF, *b"tEXt", *b"zTXt", *b"iTXt", *b"tIME"];
+
+/// `None` for a PNG cut off before its IEND chunk
+fn strip_png(mut png: Png) -> Option<Bytes> {
+    if png.chunks().last().map(PngChunk::kind) != Some(PNG_IEND) {
+        return None;
+    }
+
+    let chunks = std::mem::take(png.chunks_mut())
+        .into_iter()
+        .filter_map(|chunk| match chunk.kind() {
+            PNG_EXIF => orientation_only(chunk.contents()).map(|tiff| PngChunk::new(PNG_EXIF, tiff.into())),
+            kind if PNG_METADATA_CHUNKS.contains(&kind) => None,
+            _ => Some(chunk),
+        })
+        .collect();
+    *png.chunks_mut() = chunks;
+    Some(png.encoder().bytes())
+}
+
+/// VP8X feature flags announcing EXIF and XMP chunks
+const WEBP_EXIF_FLAG: u8 = 0x08;
+const WEBP_XMP_FLAG: u8 = 0x04;
+
+fn strip_webp(mut webp: WebP) -> Bytes {
+    let mut kept_exif = false;
+    let chunks = std::mem::take(webp.chunks_mut())
+        .into_iter()
+        .filter_map(|chunk| match chunk.id() {
+            CHUNK_EXIF => {
+                let tiff = orientation_only(chunk.content().data()?)?;
+                kept_exif = true;
+                Some(RiffChunk::new(CHUNK_EXIF, RiffContent::Data(tiff.into())))
+            }
+            CHUNK_XMP => None,
+            _ => Some(chunk),
+        })
+        .collect();
+    *webp.chunks_mut() = chunks;
+
+    // Only the metadata flags change; alpha, animation and the canvas size stay as they were
+    let vp8x = webp.chunks_mut().iter_mut().find(|chunk| chunk.id() == CHUNK_VP8X);
+    if let Some(vp8x) = vp8x {
+        if let Some(mut header) = vp8x.content().data().filter(|data| !data.is_empty()).map(|data| data.to_vec()) {
+            header[0] &= !(WEBP_EXIF_FLAG | WEBP_XMP_FLAG);
+            if kept_exif {
+                header[0] |= WEBP_EXIF_FLAG;
+            }
+            *vp8x = RiffChunk::new(CHUNK_VP8X, RiffContent::Data(header.into()));
+        }
+    }
+
+    webp.encoder().bytes()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// JFIF header, an EXIF segment with GPS data, a quantization table and a tiny scan
+    fn jpeg_with_exif() -> Vec<u8> {
+        let mut jpeg = vec![0xFF, 0xD8];
+        jpeg.extend_from_slice(&[0xFF, 0xE0, 0x00, 0x10, b'J', b'F', b'I', b'F', 0, 1, 1, 0, 0, 1, 0, 1, 0, 0]);
+        let exif = b"Exif\0\0MM\0*GPSLatitude=48.8584";
+        jpeg.extend_from_slice(&[0xFF, 0xE1]);
+        jpeg.extend_from_slice(&((exif.len() + 2) as u16).to_be_bytes());
+        jpeg.extend_from_slice(exif);
+        jpeg.extend_from_slice(&[0xFF, 0xDB, 0x00, 0x04, 0x00, 0x01]);
+        jpeg.extend_from_slice(&[0xFF, 0xDA, 0x00, 0x08, 1, 1, 0, 0, 0x3F, 0, 0x12, 0x34, 0xFF, 0x00, 0x56, 0xFF, 0xD9]);
+        jpeg
+    }
+
+    fn png_chunk(kind: &[u8; 4], data: &[u8]) -> Vec<u8> {
+        PngChunk::new(*kind, Bytes::copy_from_slice(data)).encoder().bytes().to_vec()
+    }
+
+    fn contains(haystack: &[u8], needle: &[u8]) -> bool {
+        haystack.windows(needle.len()).any(|window| window == needle)
+    }
+
+    #[test]
+    fn test_strip_jpeg_exif() {
+        let jpeg = jpeg_with_exif();
+        let stripped = strip_image_metadata(&jpeg).unwrap();
+
+        assert!(!contains(&stripped, b"Exif"));
+        assert!(!contains(&stripped, b"GPS"));
+        assert!(contains(&stripped, b"JFIF"));
+        // Everything from the start of scan on is untouched
+        assert!(stripped.ends_with(&[0xFF, 0xDA, 0x00, 0x08, 1, 1, 0, 0, 0x3F, 0, 0x12, 0x34, 0xFF, 0x00, 0x56, 0xFF, 0xD9]));
+        assert_eq!(stripped.len(), jpeg.len() - 4 - b"Exif\0\0MM\0*GPSLatitude=48.8584".len());
+
+        // Already clean: unchanged
+        assert_eq!(strip_image_metadata(&stripped).unwrap(), stripped);
+        // Truncated: not touched
+        assert!(strip_image_metadata(&jpeg[..10]).is_none());
+    }
+
+    #[test]
+    fn test_strip_png_and_webp_metadata() {
+        let mut png = PNG_SIGNATURE.to_vec();
+        png.extend(png_chunk(b"IHDR", &[0; 13]));
+        png.extend(png_chunk(b"tEXt", b"Comment\0shot on a phone"));
+        png.extend(png_chunk(b"eXIf", b"MM\0*GPS"));
+        png.extend(png_chunk(b"IDAT", &[1, 2, 3]));
+        png.extend(png_chunk(b"IEND", &[]));
+
+        let stripped = strip_image_metadata(&png).unwrap();
+        assert!(!contains(&stripped, b"tEXt") && !contains(&stripped, b"eXIf"));
+        assert!(contains(&stripped, b"IHDR") && contains(&stripped, b"IDAT") && contains(&stripped, b"IEND"));
+
+        let mut webp = b"RIFF\0\0\0\0WEBP".to_vec();
+        webp.extend_from_slice(b"VP8X");
+        webp.extend_from_slice(&10u32.to_le_bytes());
+        webp.extend_from_slice(&[WEBP_EXIF_FLAG, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        webp.extend_from_slice(b"VP8L");
+        webp.extend_from_slice(&3u32.to_le_bytes());
+        webp.extend_from_slice(&[1, 2, 3, 0]);
+        webp.extend_from_slice(b"EXIF");
+        webp.extend_from_slice(&5u32.to_le_bytes());
+        webp.extend_from_slice(b"GPS!!\0");
+        let riff_size = (webp.len() - 8) as u32;
+        webp[4..8].copy_from_slice(&riff_size.to_le_bytes());
+
+        let stripped = strip_image_metadata(&webp).unwrap();
+        assert!(!contains(&stripped, b"EXIF") && !contains(&stripped, b"GPS"));
+        assert_eq!(stripped[20] & WEBP_EXIF_FLAG, 0);
+        assert_eq!(u32::from_le_bytes(stripped[4..8].try_into().unwrap()) as usize, stripped.len() - 8);
+
+        assert!(strip_image_metadata(b"GIF89a").is_none());
+    }
+
+    /// Little-endian EXIF with a GPS IFD pointer and orientation 6 (rotate 90° clockwise)
+    fn exif_with_orientation() -> Vec<u8> {
+        let mut tiff = b"II*\0".to_vec();
+        tiff.extend_from_slice(&8u32.to_le_bytes());
+        tiff.extend_from_slice(&2u16.to_le_bytes());
+        tiff.extend_from_slice(&[0x12, 0x01, 3, 0, 1, 0, 0, 0, 6, 0, 0, 0]);
+        tiff.extend_from_slice(&[0x25, 0x88, 4, 0, 1, 0, 0, 0, 38, 0, 0, 0]);
+        tiff.extend_from_slice(&0u32.to_le_bytes());
+        tiff.extend_from_slice(b"GPSLatitude=48.8584");
+        tiff
+    }
+
+    #[test]
+    fn test_orientation_is_kept() {
+        let tiff = exif_with_orientation();
+        assert_eq!(exif_orientation(&tiff), Some(6));
+        assert_eq!(exif_orientation(&minimal_exif(6)), Some(6));
+
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+        jpeg.extend_from_slice(&((2 + EXIF_HEADER.len() + tiff.len()) as u16).to_be_bytes());
+        jpeg.extend_from_slice(EXIF_HEADER);
+        jpeg.extend_from_slice(&tiff);
+        jpeg.extend_from_slice(&[0xFF, 0xDA, 0x00, 0x08, 1, 1, 0, 0, 0x3F, 0, 0x12, 0x34, 0xFF, 0xD9]);
+
+        let stripped = strip_image_metadata(&jpeg).unwrap();
+        assert!(!contains(&stripped, b"GPS"));
+        let app1_end = 4 + u16::from_be_bytes([stripped[4], stripped[5]]) as usize;
+        assert_eq!(&stripped[..4], &[0xFF, 0xD8, 0xFF, 0xE1]);
+        assert_eq!(exif_orientation(&stripped[6 + EXIF_HEADER.len()..app1_end]), Some(6));
+        assert!(stripped.ends_with(&[0xFF, 0xDA, 0x00, 0x08, 1, 1, 0, 0, 0x3F, 0, 0x12, 0x34, 0xFF, 0xD9]));
+
+        let mut png = PNG_SIGNATURE.to_vec();
+        png.extend(png_chunk(b"IHDR", &[0; 13]));
+        png.extend(png_chunk(b"eXIf", &tiff));
+        png.extend(png_chunk(b"IEND", &[]));
+        let stripped = strip_image_metadata(&png).unwrap();
+        assert!(!contains(&stripped, b"GPS"));
+        assert!(contains(&stripped, &png_chunk(b"eXIf", &minimal_exif(6))));
+        // The well-known CRC of an empty IEND chunk
+        assert!(stripped.ends_with(&[0xAE, 0x42, 0x60, 0x82]));
+
+        let mut webp = b"RIFF\0\0\0\0WEBP".to_vec();
+        webp.extend_from_slice(b"VP8X");
+        webp.extend_from_slice(&10u32.to_le_bytes());
+        webp.extend_from_slice(&[WEBP_EXIF_FLAG | WEBP_XMP_FLAG, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
+        webp.extend_from_slice(b"EXIF");
+        webp.extend_from_slice(&(tiff.len() as u32).to_le_bytes());
+        webp.extend_from_slice(&tiff);
+        if tiff.len() % 2 == 1 {
+            webp.push(0);
+        }
+        let riff_size = (webp.len() - 8) as u32;
+        webp[4..8].copy_from_slice(&riff_size.to_le_bytes());
+        let stripped = strip_image_metadata(&webp).unwrap();
+        assert!(!contains(&stripped, b"GPS"));
+        assert_eq!(stripped[20], WEBP_EXIF_FLAG);
+        assert_eq!(exif_orientation(&stripped[38..]), Some(6));
+    }
+
+    #[test]
+    fn test_malformed_exif() {
+        let tiff = |ifd_offset: u32, rest: &[u8]| {
+            let mut tiff = b"II*\0".to_vec();
+            tiff.extend_from_slice(&ifd_offset.to_le_bytes());
+            tiff.extend_from_slice(rest);
+            tiff
+        };
+
+        // IFD offset far past the end, entry count larger than the data, orientation out of range
+        assert_eq!(exif_orientation(&tiff(u32::MAX, &[])), None);
+        assert_eq!(exif_orientation(&tiff(8, &[0xFF, 0xFF, 0x12, 0x01, 3, 0])), None);
+        assert_eq!(exif_orientation(&tiff(8, &[1, 0, 0x12, 0x01, 3, 0, 1, 0, 0, 0, 9, 0, 0, 0])), None);
+        assert_eq!(exif_orientation(b"II*"), None);
+
+        // A JPEG whose EXIF can't be read still has it removed, with nothing put back
+        let exif = [EXIF_HEADER, &tiff(u32::MAX, b"GPS")].concat();
+        let mut jpeg = vec![0xFF, 0xD8, 0xFF, 0xE1];
+        jpeg.extend_from_slice(&((exif.len() + 2) as u16).to_be_bytes());
+        jpeg.extend_from_slice(&exif);
+        jpeg.extend_from_slice(&[0xFF, 0xDA, 0x00, 0x08, 1, 1, 0, 0, 0x3F, 0, 0x12, 0x34, 0xFF, 0xD9]);
+        let stripped = strip_image_metadata(&jpeg).unwrap();
+        assert_eq!(&stripped[..4], &[0xFF, 0xD8, 0xFF, 0xDA]);
+    }
+
+    #[test]
+    fn test_malformed_containers_are_left_alone() {
+        // JPEG: segment running past the end, length below its own size, start of scan without a header
+        assert!(strip_image_metadata(&[0xFF, 0xD8, 0xFF, 0xE1, 0xFF, 0xFF, b'E', b'x']).is_none());
+        assert!(strip_image_metadata(&[0xFF, 0xD8, 0xFF, 0xE1, 0x00, 0x01, 0xFF, 0xD9]).is_none());
+        assert!(strip_image_metadata(&[0xFF, 0xD8, 0xFF, 0xDA, 0x00, 0x02, 0x12, 0x34, 0xFF, 0xD9]).is_none());
+
+        // PNG: truncated chunk, wrong CRC, no IEND
+        let mut png = PNG_SIGNATURE.to_vec();
+        png.extend(png_chunk(b"IHDR", &[0; 13]));
+        let mut truncated = png.clone();
+        truncated.extend_from_slice(&1000u32.to_be_bytes());
+        truncated.extend_from_slice(b"tEXtComment");
+        assert!(strip_image_metadata(&truncated).is_none());
+        let mut bad_crc = png.clone();
+        bad_crc.extend(png_chunk(b"tEXt", b"Comment\0GPS"));
+        let crc_pos = bad_crc.len() - 1;
+        bad_crc[crc_pos] ^= 0xFF;
+        bad_crc.extend(png_chunk(b"IEND", &[]));
+        assert!(strip_image_metadata(&bad_crc).is_none());
+        assert!(strip_image_metadata(&png).is_none());
+
+        // WebP: chunk larger than the RIFF payload, RIFF size past the end of the file
+        let mut webp = b"RIFF\0\0\0\0WEBP".to_vec();
+        webp.extend_from_slice(b"EXIF");
+        webp.extend_from_slice(&u32::MAX.to_le_bytes());
+        webp.extend_from_slice(b"GPS!");
+        let riff_size = (webp.len() - 8) as u32;
+        webp[4..8].copy_from_slice(&riff_size.to_le_bytes());
+        assert!(strip_image_metadata(&webp).is_none());
+        webp[4..8].copy_from_slice(&u32::MAX.to_le_bytes());
+        assert!(strip_image_metadata(&webp).is_none());
+    }
+}
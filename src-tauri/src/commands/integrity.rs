@@ -0,0 +1,366 @@
+// Data integrity verification and repair for the Tauri AppData directory
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Manager};
+use crate::models::{Agent, GlobalSettings, Group, Topic};
+
+/// Directory (inside AppData) where unrepairable files are moved
+const QUARANTINE_DIR: &str = ".corrupt";
+
+/// Which model a data file is expected to deserialize into
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DataFileKind {
+    Agent,
+    Group,
+    Topic,
+    Settings,
+}
+
+/// A data file that could not be parsed or failed validation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BrokenFile {
+    pub file_path: String,
+    pub kind: DataFileKind,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IntegrityReport {
+    pub valid_files: Vec<String>,
+    pub broken_files: Vec<BrokenFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RepairAction {
+    /// Missing fields were filled with defaults
+    FillDefaults,
+    /// The file was moved into `.corrupt/`
+    Quarantine,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepairedFile {
+    pub file_path: String,
+    pub action: RepairAction,
+    pub detail: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepairReport {
+    pub dry_run: bool,
+    pub repairs: Vec<RepairedFile>,
+}
+
+/// List every data file in AppData together with its expected model
+fn data_files(app_data: &Path) -> Result<Vec<(PathBuf, DataFileKind)>, String> {
+    let mut files = Vec::new();
+
+    let settings_path = app_data.join("settings.json");
+    if settings_path.is_file() {
+        files.push((settings_path, DataFileKind::Settings));
+    }
+
+    let dirs = [
+        (app_data.join("UserData"), DataFileKind::Agent),
+        (app_data.join("UserData").join("groups"), DataFileKind::Group),
+        (app_data.join("Agents"), DataFileKind::Topic),
+        (app_data.join("AgentGroups"), DataFileKind::Topic),
+    ];
+
+    for (dir, kind) in dirs {
+        if !dir.is_dir() {
+            continue;
+        }
+
+        let entries = fs::read_dir(&dir)
+            .map_err(|e| format!("Failed to read directory {}: {}", dir.display(), e))?;
+
+        for entry in entries {
+            let path = entry.map_err(|e| format!("Failed to read entry: {}", e))?.path();
+            if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("json") {
+                files.push((path, kind));
+            }
+        }
+    }
+
+    files.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(files)
+}
+
+/// Deserialize a JSON value into its model and run the model's validation
+fn check_value(kind: DataFileKind, value: &serde_json::Value) -> Result<(), String> {
+    fn parse<T: serde::de::DeserializeOwned>(value: &serde_json::Value) -> Result<T, String> {
+        serde_json::from_value(value.clone()).map_err(|e| e.to_string())
+    }
+
+    match kind {
+        DataFileKind::Agent => parse::<Agent>(value)?.validate(),
+        DataFileKind::Group => parse::<Group>(value)?.validate(),
+        DataFileKind::Topic => parse::<Topic>(value)?.validate(),
+        DataFileKind::Settings => parse::<GlobalSettings>(value)?.validate(),
+    }
+}
+
+/// Read and parse a data file as JSON
+fn read_json(path: &Path) -> Result<serde_json::Value, String> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read file: {}", e))?;
+
+    serde_json::from_str(&content).map_err(|e| format!("Invalid JSON: {}", e))
+}
+
+/// Defaults for fields that can be safely regenerated when missing
+fn default_fields(kind: DataFileKind, path: &Path) -> serde_json::Value {
+    let id = path.file_stem().and_then(|s| s.to_str()).unwrap_or_default();
+    let now = chrono::Utc::now().to_rfc3339();
+
+    match kind {
+        DataFileKind::Agent => serde_json::json!({
+            "id": id,
+            "system_prompt": "",
+            "temperature": 0.7,
+            "context_token_limit": 4096,
+            "max_output_tokens": 2048,
+            "created_at": now,
+        }),
+        DataFileKind::Group => serde_json::json!({
+            "id": id,
+            "collaboration_mode": "sequential",
+            "turn_count": 3,
+            "speaking_rules": "",
+            "created_at": now,
+        }),
+        DataFileKind::Topic => serde_json::json!({
+            "id": id,
+            "messages": [],
+            "created_at": now,
+            "updated_at": now,
+        }),
+        DataFileKind::Settings => serde_json::to_value(GlobalSettings::default())
+            .unwrap_or_else(|_| serde_json::json!({})),
+    }
+}
+
+/// Fill missing fields of a JSON object with defaults.
+/// Returns the names of the top-level fields that were added.
+fn fill_defaults(kind: DataFileKind, path: &Path, value: &mut serde_json::Value) -> Vec<String> {
+    let Some(object) = value.as_object() else {
+        return Vec::new();
+    };
+
+    let mut merged = default_fields(kind, path);
+    let filled = merged
+        .as_object()
+        .map(|defaults| {
+            defaults
+                .keys()
+                .filter(|key| !object.contains_key(*key))
+                .cloned()
+                .collect()
+        })
+        .unwrap_or_default();
+
+    // Existing values win over defaults, including nested settings sections
+    super::settings::merge_json(&mut merged, value);
+    *value = merged;
+
+    filled
+}
+
+/// Walk AppData and check every data file against its model
+fn verify(app_data: &Path) -> Result<IntegrityReport, String> {
+    let mut report = IntegrityReport {
+        valid_files: Vec::new(),
+        broken_files: Vec::new(),
+    };
+
+    for (path, kind) in data_files(app_data)? {
+        let file_path = path.to_string_lossy().to_string();
+        match read_json(&path).and_then(|value| check_value(kind, &value)) {
+            Ok(()) => report.valid_files.push(file_path),
+            Err(reason) => report.broken_files.push(BrokenFile { file_path, kind, reason }),
+        }
+    }
+
+    Ok(report)
+}
+
+/// Repair broken data files: fill missing fields where that makes them valid,
+/// otherwise move them into `.corrupt/`. Nothing is changed when `dry_run` is set.
+fn repair(app_data: &Path, dry_run: bool) -> Result<RepairReport, String> {
+    let mut repairs = Vec::new();
+
+    for broken in verify(app_data)?.broken_files {
+        let path = PathBuf::from(&broken.file_path);
+
+        let fixed = read_json(&path).ok().and_then(|mut value| {
+            let filled = fill_defaults(broken.kind, &path, &mut value);
+            if filled.is_empty() || check_value(broken.kind, &value).is_err() {
+                None
+            } else {
+                Some((value, filled))
+            }
+        });
+
+        let repaired = match fixed {
+            Some((value, filled)) => {
+                if !dry_run {
+                    super::io::write_json_atomic(&path, &value)?;
+                }
+
+                RepairedFile {
+                    file_path: broken.file_path,
+                    action: RepairAction::FillDefaults,
+                    detail: format!("Filled missing fields: {}", filled.join(", ")),
+                }
+            }
+            None => {
+                let relative = path.strip_prefix(app_data).unwrap_or(&path);
+                let target = app_data.join(QUARANTINE_DIR).join(relative);
+
+                if !dry_run {
+                    if let Some(parent) = target.parent() {
+                        fs::create_dir_all(parent)
+                            .map_err(|e| format!("Failed to create quarantine directory: {}", e))?;
+                    }
+                    fs::rename(&path, &target)
+                        .map_err(|e| format!("Failed to quarantine {}: {}", path.display(), e))?;
+                }
+
+                RepairedFile {
+                    file_path: broken.file_path,
+                    action: RepairAction::Quarantine,
+                    detail: format!("Moved to {} ({})", target.display(), broken.reason),
+                }
+            }
+        };
+
+        repairs.push(repaired);
+    }
+
+    Ok(RepairReport { dry_run, repairs })
+}
+
+/// Check every agent, group, topic and settings file in AppData
+#[tauri::command]
+pub async fn verify_data_integrity(app: AppHandle) -> Result<IntegrityReport, String> {
+    let app_data = app.path().resolve("AppData", tauri::path::BaseDirectory::AppData)
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    verify(&app_data)
+}
+
+/// Repair broken data files. Defaults to a dry run that only reports what would change.
+#[tauri::command]
+pub async fn repair_data(app: AppHandle, dry_run: Option<bool>) -> Result<RepairReport, String> {
+    let app_data = app.path().resolve("AppData", tauri::path::BaseDirectory::AppData)
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    let dry_run = dry_run.unwrap_or(true);
+    let report = repair(&app_data, dry_run)?;
+    if !dry_run && !report.repairs.is_empty() {
+        super::search::invalidate_index(&app_data);
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// AppData with a valid agent, an agent missing defaultable fields,
+    /// a corrupt topic and valid settings
+    fn create_mixed_app_data() -> PathBuf {
+        let app_data = std::env::temp_dir().join(format!("vcp_integrity_test_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(app_data.join("UserData")).unwrap();
+        fs::create_dir_all(app_data.join("Agents")).unwrap();
+
+        fs::write(app_data.join("UserData").join("agent-ok.json"), serde_json::json!({
+            "id": "agent-ok",
+            "name": "Nova",
+            "avatar": "nova.png",
+            "system_prompt": "You are helpful",
+            "model": "gpt-4",
+            "temperature": 0.5,
+            "context_token_limit": 8000,
+            "max_output_tokens": 1000,
+            "created_at": "2025-01-01T00:00:00Z",
+        }).to_string()).unwrap();
+
+        fs::write(app_data.join("UserData").join("agent-partial.json"), serde_json::json!({
+            "name": "Partial",
+            "avatar": "partial.png",
+            "model": "gpt-4",
+        }).to_string()).unwrap();
+
+        fs::write(app_data.join("Agents").join("topic-broken.json"), "{ \"id\": \"topic-broken\", \"mess").unwrap();
+
+        fs::write(
+            app_data.join("settings.json"),
+            serde_json::to_string(&GlobalSettings::default()).unwrap(),
+        ).unwrap();
+
+        app_data
+    }
+
+    #[test]
+    fn test_verify_mixed_directory() {
+        let app_data = create_mixed_app_data();
+
+        let report = verify(&app_data).unwrap();
+        assert_eq!(report.valid_files.len(), 2);
+        assert_eq!(report.broken_files.len(), 2);
+        assert!(report.broken_files.iter().any(|f| f.kind == DataFileKind::Agent && f.file_path.ends_with("agent-partial.json")));
+        assert!(report.broken_files.iter().any(|f| f.kind == DataFileKind::Topic && f.reason.contains("Invalid JSON")));
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_repair_dry_run_changes_nothing() {
+        let app_data = create_mixed_app_data();
+        let partial = app_data.join("UserData").join("agent-partial.json");
+        let before = fs::read_to_string(&partial).unwrap();
+
+        let report = repair(&app_data, true).unwrap();
+        assert!(report.dry_run);
+        assert_eq!(report.repairs.len(), 2);
+
+        assert_eq!(fs::read_to_string(&partial).unwrap(), before);
+        assert!(app_data.join("Agents").join("topic-broken.json").exists());
+        assert!(!app_data.join(QUARANTINE_DIR).exists());
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_repair_fills_defaults_and_quarantines() {
+        let app_data = create_mixed_app_data();
+
+        let report = repair(&app_data, false).unwrap();
+        let action_for = |name: &str| {
+            report.repairs.iter().find(|r| r.file_path.ends_with(name)).map(|r| r.action.clone())
+        };
+        assert!(matches!(action_for("agent-partial.json"), Some(RepairAction::FillDefaults)));
+        assert!(matches!(action_for("topic-broken.json"), Some(RepairAction::Quarantine)));
+
+        let agent: Agent = serde_json::from_str(
+            &fs::read_to_string(app_data.join("UserData").join("agent-partial.json")).unwrap()
+        ).unwrap();
+        assert_eq!(agent.id, "agent-partial");
+        assert_eq!(agent.name, "Partial");
+        assert_eq!(agent.context_token_limit, 4096);
+
+        assert!(!app_data.join("Agents").join("topic-broken.json").exists());
+        assert!(app_data.join(QUARANTINE_DIR).join("Agents").join("topic-broken.json").exists());
+
+        let after = verify(&app_data).unwrap();
+        assert!(after.broken_files.is_empty());
+        assert_eq!(after.valid_files.len(), 3);
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+}
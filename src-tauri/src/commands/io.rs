@@ -0,0 +1,90 @@
+// File IO helpers shared by the commands
+use serde::Serialize;
+use std::fs::{self, File};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// Serialize `value` as pretty JSON and replace `path` atomically.
+/// The JSON goes to a uniquely named temp file next to `path`, is flushed to disk and then
+/// renamed over it, so a crash or failed write leaves the old file or the new one, never half of each.
+pub fn write_json_atomic<T: Serialize + ?Sized>(path: &Path, value: &T) -> Result<(), String> {
+    write_json_atomic_with(path, value, |_| Ok(()))
+}
+
+/// `write_json_atomic` with a hook run on the finished temp file just before the rename
+fn write_json_atomic_with<T, F>(path: &Path, value: &T, before_rename: F) -> Result<(), String>
+where
+    T: Serialize + ?Sized,
+    F: FnOnce(&Path) -> io::Result<()>,
+{
+    let json = serde_json::to_string_pretty(value)
+        .map_err(|e| format!("Failed to serialize {}: {}", path.display(), e))?;
+
+    let temp_path = temp_path_for(path);
+    let written = File::create(&temp_path)
+        .and_then(|mut file| {
+            file.write_all(json.as_bytes())?;
+            file.sync_all()
+        })
+        .and_then(|_| before_rename(&temp_path));
+    if let Err(e) = written {
+        let _ = fs::remove_file(&temp_path);
+        return Err(format!("Failed to write {}: {}", path.display(), e));
+    }
+
+    fs::rename(&temp_path, path).map_err(|e| {
+        let _ = fs::remove_file(&temp_path);
+        format!("Failed to replace {}: {}", path.display(), e)
+    })
+}
+
+/// `.<name>.<uuid>.tmp` in the same directory (a rename across filesystems wouldn't be atomic)
+fn temp_path_for(path: &Path) -> PathBuf {
+    let name = path.file_name().and_then(|name| name.to_str()).unwrap_or("data");
+    path.with_file_name(format!(".{}.{}.tmp", name, uuid::Uuid::new_v4().simple()))
+}
+
+/// Whether `file_name` is one `temp_path_for` produces
+pub fn is_atomic_temp_name(file_name: &str) -> bool {
+    let Some(rest) = file_name.strip_prefix('.').and_then(|rest| rest.strip_suffix(".tmp")) else {
+        return false;
+    };
+    match rest.rsplit_once('.') {
+        Some((name, id)) => !name.is_empty() && id.len() == 32 && id.bytes().all(|b| b.is_ascii_hexdigit()),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_interrupted_write_keeps_original() {
+        let dir = std::env::temp_dir().join(format!("vcp_io_test_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("agent.json");
+
+        write_json_atomic(&path, &json!({ "name": "before" })).unwrap();
+
+        let result = write_json_atomic_with(&path, &json!({ "name": "after" }), |_| {
+            Err(io::Error::other("simulated crash"))
+        });
+        assert!(result.unwrap_err().contains("simulated crash"));
+
+        let stored: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(stored["name"], "before");
+        // No temp file is left behind
+        assert_eq!(fs::read_dir(&dir).unwrap().count(), 1);
+        assert!(is_atomic_temp_name(temp_path_for(&path).file_name().unwrap().to_str().unwrap()));
+        assert!(!is_atomic_temp_name(".agent.json.tmp"));
+        assert!(!is_atomic_temp_name("agent.json.tmp"));
+
+        write_json_atomic(&path, &json!({ "name": "after" })).unwrap();
+        let stored: serde_json::Value = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(stored["name"], "after");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
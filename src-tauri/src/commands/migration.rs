@@ -6,12 +6,39 @@
  * - Validate JSON schemas
  * - Copy directory structure with progress tracking
  * - Non-destructive migration with backup
+ * - Resumable copy: an interrupted migration picks up where it left off
+ * - Cancellable: a cancelled migration restores the original data
+ * - Selective: only chosen data categories are copied; a later run can add the rest
+ * - Verified: the copy is compared with the backup before the migration is recorded
  */
 
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::fs;
 use std::path::{Path, PathBuf};
-use tauri::{AppHandle, Manager};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tauri::{AppHandle, Emitter, Manager};
+
+/// Event emitted with a `MigrationProgress` payload for every processed file
+pub const MIGRATION_PROGRESS_EVENT: &str = "migration://progress";
+
+/// `MigrationProgress::status` of the final event of a cancelled migration
+pub const MIGRATION_CANCELLED_STATUS: &str = "cancelled";
+
+/// Top-level data directories that can be migrated on their own
+pub const MIGRATION_CATEGORIES: &[&str] = &[
+    "Agents",
+    "AgentGroups",
+    "UserData",
+    "Canvasmodules",
+    "attachments",
+    "plugins",
+    "plugin-data",
+];
+
+/// Cancel token of the running migration, if any
+static ACTIVE_MIGRATION: Mutex<Option<Arc<AtomicBool>>> = Mutex::new(None);
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MigrationProgress {
@@ -28,26 +55,23 @@ pub struct MigrationStatus {
     pub tauri_path: String,
     pub backup_path: Option<String>,
     pub migration_date: Option<String>,
+    /// Categories migrated so far; `None` once everything was migrated
+    pub migrated_categories: Option<Vec<String>>,
 }
 
 /**
- * US5-025: Detect Electron AppData location (Windows, macOS, Linux)
+ * Platform-specific Electron AppData location, whether or not it exists
  */
-fn detect_electron_appdata() -> Result<Option<PathBuf>, String> {
-    // Determine platform-specific Electron AppData path
+fn electron_appdata_path() -> Option<PathBuf> {
     let electron_dir_name = "VCPChat";
 
     #[cfg(target_os = "windows")]
     {
         // Windows: %APPDATA%/VCPChat/AppData
         if let Ok(appdata) = std::env::var("APPDATA") {
-            let path = PathBuf::from(appdata)
+            return Some(PathBuf::from(appdata)
                 .join(electron_dir_name)
-                .join("AppData");
-
-            if path.exists() {
-                return Ok(Some(path));
-            }
+                .join("AppData"));
         }
     }
 
@@ -55,15 +79,11 @@ fn detect_electron_appdata() -> Result<Option<PathBuf>, String> {
     {
         // macOS: ~/Library/Application Support/VCPChat/AppData
         if let Some(home) = dirs::home_dir() {
-            let path = home
+            return Some(home
                 .join("Library")
                 .join("Application Support")
                 .join(electron_dir_name)
-                .join("AppData");
-
-            if path.exists() {
-                return Ok(Some(path));
-            }
+                .join("AppData"));
         }
     }
 
@@ -71,18 +91,101 @@ fn detect_electron_appdata() -> Result<Option<PathBuf>, String> {
     {
         // Linux: ~/.config/VCPChat/AppData
         if let Some(home) = dirs::home_dir() {
-            let path = home
+            return Some(home
                 .join(".config")
                 .join(electron_dir_name)
-                .join("AppData");
-
-            if path.exists() {
-                return Ok(Some(path));
-            }
+                .join("AppData"));
         }
     }
 
-    Ok(None)
+    None
+}
+
+/// A file that failed validation during migration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationWarning {
+    pub file_path: String,
+    pub reason: String,
+}
+
+/// A copied file that doesn't match its source
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationMismatch {
+    pub file_path: String,
+    pub reason: String,
+}
+
+/// Outcome of a successful migration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationResult {
+    pub message: String,
+    pub warnings: Vec<MigrationWarning>,
+}
+
+/**
+ * US5-025: Detect Electron AppData location (Windows, macOS, Linux)
+ */
+fn detect_electron_appdata() -> Result<Option<PathBuf>, String> {
+    Ok(electron_appdata_path().filter(|path| path.exists()))
+}
+
+/**
+ * Check that a user-supplied directory looks like VCPChat data
+ * (it must contain an `Agents` or `UserData` subdirectory)
+ */
+fn validate_source_dir(path: &Path) -> Result<(), String> {
+    if !path.is_dir() {
+        return Err(format!("Migration source {} does not exist or is not a directory", path.display()));
+    }
+
+    if !path.join("Agents").is_dir() && !path.join("UserData").is_dir() {
+        return Err(format!(
+            "{} is not a VCPChat data directory (expected an Agents or UserData folder)",
+            path.display()
+        ));
+    }
+
+    Ok(())
+}
+
+/**
+ * Backup location for the Electron AppData directory
+ */
+fn backup_path_for(electron_path: &Path) -> PathBuf {
+    electron_path.with_file_name("VCPChat_backup")
+}
+
+/**
+ * Metadata file next to a backup directory (`VCPChat_backup.json`)
+ */
+fn backup_metadata_path(backup_path: &Path) -> PathBuf {
+    backup_path.with_extension("json")
+}
+
+/**
+ * Record where a backup was taken from, so it can be restored there later
+ */
+fn write_backup_metadata(backup_path: &Path, source_path: &Path) -> Result<(), String> {
+    let metadata = serde_json::json!({
+        "created_at": chrono::Utc::now().to_rfc3339(),
+        "source_path": source_path.to_string_lossy(),
+    });
+    fs::write(backup_metadata_path(backup_path), serde_json::to_string_pretty(&metadata).unwrap())
+        .map_err(|e| format!("Failed to write backup metadata: {}", e))
+}
+
+/**
+ * Source path recorded when the backup was taken
+ */
+fn read_backup_source(backup_path: &Path) -> Result<PathBuf, String> {
+    let metadata_path = backup_metadata_path(backup_path);
+    let metadata = fs::read_to_string(&metadata_path)
+        .map_err(|e| format!("Failed to read backup metadata {}: {}", metadata_path.display(), e))?;
+    let info: serde_json::Value = serde_json::from_str(&metadata)
+        .map_err(|e| format!("Invalid backup metadata: {}", e))?;
+
+    info.get("source_path").and_then(|v| v.as_str()).map(PathBuf::from)
+        .ok_or_else(|| "Backup metadata is missing source_path".to_string())
 }
 
 /**
@@ -140,25 +243,267 @@ fn validate_json_file(file_path: &Path) -> Result<(), String> {
     Ok(())
 }
 
+/**
+ * Read the Electron source and backup paths recorded in a migration marker
+ */
+fn read_marker_paths(marker_path: &Path) -> Result<(PathBuf, PathBuf), String> {
+    let marker = fs::read_to_string(marker_path)
+        .map_err(|e| format!("Failed to read migration marker: {}", e))?;
+    let info: serde_json::Value = serde_json::from_str(&marker)
+        .map_err(|e| format!("Invalid migration marker: {}", e))?;
+
+    let electron_path = info.get("electron_path").and_then(|v| v.as_str()).map(PathBuf::from)
+        .ok_or_else(|| "Migration marker is missing electron_path".to_string())?;
+    let backup_path = info.get("backup_path").and_then(|v| v.as_str()).map(PathBuf::from)
+        .ok_or_else(|| "Migration marker is missing backup_path".to_string())?;
+
+    Ok((electron_path, backup_path))
+}
+
+/**
+ * Categories recorded in a migration marker (`None`: all of them)
+ */
+fn read_marker_categories(marker_path: &Path) -> Result<Option<Vec<String>>, String> {
+    let marker = fs::read_to_string(marker_path)
+        .map_err(|e| format!("Failed to read migration marker: {}", e))?;
+    let info: serde_json::Value = serde_json::from_str(&marker)
+        .map_err(|e| format!("Invalid migration marker: {}", e))?;
+
+    match info.get("categories") {
+        None | Some(serde_json::Value::Null) => Ok(None),
+        Some(categories) => serde_json::from_value(categories.clone())
+            .map(Some)
+            .map_err(|e| format!("Invalid categories in migration marker: {}", e)),
+    }
+}
+
+/**
+ * Check requested category names against `MIGRATION_CATEGORIES` and drop duplicates
+ */
+fn validate_categories(categories: Option<Vec<String>>) -> Result<Option<Vec<String>>, String> {
+    let Some(categories) = categories else {
+        return Ok(None);
+    };
+
+    let mut selected: Vec<String> = Vec::new();
+    for category in categories {
+        if !MIGRATION_CATEGORIES.contains(&category.as_str()) {
+            return Err(format!(
+                "Unknown migration category: {} (expected one of: {})",
+                category,
+                MIGRATION_CATEGORIES.join(", ")
+            ));
+        }
+        if !selected.contains(&category) {
+            selected.push(category);
+        }
+    }
+
+    if selected.is_empty() {
+        return Err("No migration categories selected".to_string());
+    }
+    Ok(Some(selected))
+}
+
+/**
+ * Which top-level entries of the source a migration run copies
+ */
+struct MigrationScope {
+    /// Directories to copy (`None`: all of them)
+    categories: Option<Vec<String>>,
+    /// Directories already migrated by an earlier run
+    skip: Vec<String>,
+    /// Loose files next to the directories (settings); copied by the first run only
+    include_files: bool,
+}
+
+impl MigrationScope {
+    fn includes(&self, name: &str, is_dir: bool) -> bool {
+        if !is_dir {
+            return self.include_files;
+        }
+        !self.skip.iter().any(|done| done == name)
+            && self.categories.as_ref().map_or(true, |selected| selected.iter().any(|c| c == name))
+    }
+
+    /// Top-level entries of `source` in scope, with whether each is a directory
+    fn entries(&self, source: &Path) -> Result<Vec<(PathBuf, bool)>, String> {
+        let entries = fs::read_dir(source)
+            .map_err(|e| format!("Failed to read directory {}: {}", source.display(), e))?;
+
+        let mut selected = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
+            let file_type = entry.file_type()
+                .map_err(|e| format!("Failed to get file type: {}", e))?;
+            let name = entry.file_name().to_string_lossy().to_string();
+
+            if (file_type.is_dir() || file_type.is_file()) && self.includes(&name, file_type.is_dir()) {
+                selected.push((entry.path(), file_type.is_dir()));
+            }
+        }
+        selected.sort();
+        Ok(selected)
+    }
+}
+
+/**
+ * Outcome of rolling back a migration
+ */
+#[derive(Debug)]
+struct RolledBack {
+    /// Where the Electron data was restored to
+    electron_path: PathBuf,
+    /// Where the Tauri data of a completed migration was moved, as it may hold newer data
+    set_aside_path: Option<PathBuf>,
+}
+
+/**
+ * Sibling of the Tauri AppData directory that a rolled back completed migration is moved to
+ */
+fn set_aside_path_for(tauri_path: &Path) -> PathBuf {
+    let name = tauri_path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    tauri_path.with_file_name(format!(
+        "{}_rolled_back_{}",
+        name,
+        chrono::Utc::now().format("%Y%m%d%H%M%S")
+    ))
+}
+
+/**
+ * Undo a migration recorded in the Tauri AppData directory: move the backup
+ * back to the Electron location and remove the Tauri copy.
+ *
+ * A partial copy (`.migrating`) is deleted. After a completed migration (`.migrated`)
+ * the app may have written new data, so the Tauri directory is moved aside instead.
+ */
+fn rollback(tauri_path: &Path) -> Result<RolledBack, String> {
+    let marker_path = [tauri_path.join(".migrating"), tauri_path.join(".migrated")]
+        .into_iter()
+        .find(|path| path.exists())
+        .ok_or_else(|| "No migration found to roll back".to_string())?;
+    let completed = marker_path.ends_with(".migrated");
+
+    let (electron_path, backup_path) = read_marker_paths(&marker_path)?;
+
+    if !backup_path.is_dir() {
+        return Err(format!("No backup found at {}. Cannot roll back.", backup_path.display()));
+    }
+    if electron_path.exists() {
+        return Err(format!("Electron data already exists at {}. Cannot roll back.", electron_path.display()));
+    }
+
+    // Restore the original data first so nothing is lost if cleanup fails
+    fs::rename(&backup_path, &electron_path)
+        .map_err(|e| format!("Failed to restore backup: {}", e))?;
+    let _ = fs::remove_file(backup_metadata_path(&backup_path));
+
+    if completed {
+        let set_aside_path = set_aside_path_for(tauri_path);
+        fs::rename(tauri_path, &set_aside_path)
+            .map_err(|e| format!("Restored backup, but failed to move migrated data aside: {}", e))?;
+
+        return Ok(RolledBack { electron_path, set_aside_path: Some(set_aside_path) });
+    }
+
+    fs::remove_dir_all(tauri_path)
+        .map_err(|e| format!("Restored backup, but failed to remove migrated data: {}", e))?;
+
+    Ok(RolledBack { electron_path, set_aside_path: None })
+}
+
+/**
+ * Move a `VCPChat_backup` directory back to the location recorded when it was taken
+ */
+fn restore_backup_dir(backup_path: &Path) -> Result<PathBuf, String> {
+    if !backup_path.is_dir() {
+        return Err(format!("Backup not found at {}", backup_path.display()));
+    }
+
+    let electron_path = read_backup_source(backup_path)?;
+    if electron_path.exists() {
+        return Err(format!("Electron data already exists at {}. Remove it before restoring.", electron_path.display()));
+    }
+
+    fs::rename(backup_path, &electron_path)
+        .map_err(|e| format!("Failed to restore backup: {}", e))?;
+    let _ = fs::remove_file(backup_metadata_path(backup_path));
+
+    Ok(electron_path)
+}
+
+/**
+ * A destination file with the same size as its source was already copied
+ * by an earlier, interrupted run and can be skipped
+ */
+fn is_already_copied(src: &Path, dst: &Path) -> bool {
+    match (fs::metadata(src), fs::metadata(dst)) {
+        (Ok(src_meta), Ok(dst_meta)) => dst_meta.is_file() && src_meta.len() == dst_meta.len(),
+        _ => false,
+    }
+}
+
+/**
+ * State of one migration copy: progress reporting, cancellation, and what it created
+ */
+struct CopyRun<'a> {
+    progress_callback: &'a dyn Fn(MigrationProgress),
+    cancel: &'a AtomicBool,
+    total_files: u64,
+    copied_files: u64,
+    /// Files and directories that didn't exist before this run, in creation order
+    created: Vec<PathBuf>,
+    /// Leave files already at the destination alone: they are live data, not an earlier partial copy
+    keep_existing: bool,
+    /// Destination files left alone under `keep_existing`
+    kept: Vec<PathBuf>,
+}
+
+impl<'a> CopyRun<'a> {
+    fn new(progress_callback: &'a dyn Fn(MigrationProgress), cancel: &'a AtomicBool, total_files: u64) -> Self {
+        Self { progress_callback, cancel, total_files, copied_files: 0, created: Vec::new(), keep_existing: false, kept: Vec::new() }
+    }
+
+    fn check_cancelled(&self) -> Result<(), String> {
+        if self.cancel.load(Ordering::SeqCst) {
+            return Err("Migration cancelled".to_string());
+        }
+        Ok(())
+    }
+
+    /**
+     * Remove everything this run created, newest first so directories are empty by then
+     */
+    fn remove_created(&self) {
+        for path in self.created.iter().rev() {
+            let removed = if path.is_dir() { fs::remove_dir(path) } else { fs::remove_file(path) };
+            if let Err(e) = removed {
+                eprintln!("Failed to remove partial copy {}: {}", path.display(), e);
+            }
+        }
+    }
+}
+
 /**
  * US5-027: Recursive directory copy with progress tracking
+ * Files already present at the destination with a matching size are skipped.
+ * The run's `cancel` flag is checked before every file; once set, the copy stops with an error.
  */
-fn copy_dir_recursive(
-    src: &Path,
-    dst: &Path,
-    progress_callback: &dyn Fn(MigrationProgress),
-    total_files: &mut u64,
-    copied_files: &mut u64,
-) -> Result<(), String> {
+fn copy_dir_recursive(src: &Path, dst: &Path, run: &mut CopyRun) -> Result<(), String> {
     // Create destination directory
-    fs::create_dir_all(dst)
-        .map_err(|e| format!("Failed to create directory {}: {}", dst.display(), e))?;
+    if !dst.exists() {
+        fs::create_dir_all(dst)
+            .map_err(|e| format!("Failed to create directory {}: {}", dst.display(), e))?;
+        run.created.push(dst.to_path_buf());
+    }
 
     // Read source directory
     let entries = fs::read_dir(src)
         .map_err(|e| format!("Failed to read directory {}: {}", src.display(), e))?;
 
     for entry in entries {
+        run.check_cancelled()?;
+
         let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
         let file_type = entry.file_type()
             .map_err(|e| format!("Failed to get file type: {}", e))?;
@@ -168,35 +513,201 @@ fn copy_dir_recursive(
 
         if file_type.is_dir() {
             // Recursively copy subdirectory
-            copy_dir_recursive(&src_path, &dst_path, progress_callback, total_files, copied_files)?;
+            copy_dir_recursive(&src_path, &dst_path, run)?;
         } else if file_type.is_file() {
-            // Validate JSON files before copying
-            if src_path.extension().and_then(|s| s.to_str()) == Some("json") {
-                if let Err(e) = validate_json_file(&src_path) {
-                    eprintln!("Warning: JSON validation failed for {}: {}", src_path.display(), e);
-                    // Continue anyway - migration should be tolerant
-                }
+            copy_file(&src_path, &dst_path, run)?;
+        }
+    }
+
+    Ok(())
+}
+
+/**
+ * Copy a single file (unless already copied, or kept under `keep_existing`) and report progress
+ */
+fn copy_file(src_path: &Path, dst_path: &Path, run: &mut CopyRun) -> Result<(), String> {
+    if run.keep_existing && dst_path.exists() {
+        run.kept.push(dst_path.to_path_buf());
+    } else if !is_already_copied(src_path, dst_path) {
+        let existed = dst_path.exists();
+        fs::copy(src_path, dst_path)
+            .map_err(|e| format!("Failed to copy {} to {}: {}", src_path.display(), dst_path.display(), e))?;
+        if !existed {
+            run.created.push(dst_path.to_path_buf());
+        }
+    }
+
+    run.copied_files += 1;
+
+    // Report progress
+    (run.progress_callback)(MigrationProgress {
+        total_files: run.total_files,
+        copied_files: run.copied_files,
+        current_file: src_path.file_name().unwrap_or_default().to_string_lossy().to_string(),
+        status: format!("Copying ({}/{})", run.copied_files, run.total_files),
+    });
+
+    Ok(())
+}
+
+/**
+ * Copy the top-level entries selected by a `MigrationScope` into `dst`
+ */
+fn copy_scoped(entries: &[(PathBuf, bool)], dst: &Path, run: &mut CopyRun) -> Result<(), String> {
+    fs::create_dir_all(dst)
+        .map_err(|e| format!("Failed to create directory {}: {}", dst.display(), e))?;
+
+    for (src_path, is_dir) in entries {
+        run.check_cancelled()?;
+
+        let dst_path = dst.join(src_path.file_name().unwrap_or_default());
+        if *is_dir {
+            copy_dir_recursive(src_path, &dst_path, run)?;
+        } else {
+            copy_file(src_path, &dst_path, run)?;
+        }
+    }
+
+    Ok(())
+}
+
+/**
+ * Validate every JSON file under a directory, collecting failures as warnings
+ */
+fn collect_validation_warnings(path: &Path, warnings: &mut Vec<MigrationWarning>) -> Result<(), String> {
+    let entries = fs::read_dir(path)
+        .map_err(|e| format!("Failed to read directory {}: {}", path.display(), e))?;
+
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
+        let file_type = entry.file_type()
+            .map_err(|e| format!("Failed to get file type: {}", e))?;
+        let entry_path = entry.path();
+
+        if file_type.is_dir() {
+            collect_validation_warnings(&entry_path, warnings)?;
+        } else if file_type.is_file()
+            && entry_path.extension().and_then(|s| s.to_str()) == Some("json")
+        {
+            if let Err(reason) = validate_json_file(&entry_path) {
+                warnings.push(MigrationWarning {
+                    file_path: entry_path.to_string_lossy().to_string(),
+                    reason,
+                });
             }
+        }
+    }
+
+    Ok(())
+}
 
-            // Copy file
-            fs::copy(&src_path, &dst_path)
-                .map_err(|e| format!("Failed to copy {} to {}: {}", src_path.display(), dst_path.display(), e))?;
+/**
+ * SHA-256 of a file's contents
+ */
+fn file_sha256(path: &Path) -> Result<Vec<u8>, String> {
+    let mut file = fs::File::open(path)
+        .map_err(|e| format!("Failed to open {}: {}", path.display(), e))?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)
+        .map_err(|e| format!("Failed to read {}: {}", path.display(), e))?;
+    Ok(hasher.finalize().to_vec())
+}
 
-            *copied_files += 1;
+/**
+ * Why the copy `dst` doesn't match `src`, if it doesn't
+ */
+fn verify_file(src: &Path, dst: &Path, compare_hashes: bool) -> Result<Option<String>, String> {
+    let src_meta = fs::metadata(src)
+        .map_err(|e| format!("Failed to read {}: {}", src.display(), e))?;
+    let dst_meta = match fs::metadata(dst) {
+        Ok(meta) if meta.is_file() => meta,
+        _ => return Ok(Some("missing from destination".to_string())),
+    };
 
-            // Report progress
-            progress_callback(MigrationProgress {
-                total_files: *total_files,
-                copied_files: *copied_files,
-                current_file: entry.file_name().to_string_lossy().to_string(),
-                status: format!("Copying ({}/{})", copied_files, total_files),
-            });
+    if src_meta.len() != dst_meta.len() {
+        return Ok(Some(format!(
+            "size differs: source {} bytes, destination {} bytes",
+            src_meta.len(),
+            dst_meta.len()
+        )));
+    }
+    if compare_hashes && file_sha256(src)? != file_sha256(dst)? {
+        return Ok(Some("content differs (SHA-256 mismatch)".to_string()));
+    }
+
+    Ok(None)
+}
+
+/**
+ * Compare every file under `src` with its copy under `dst`
+ */
+fn verify_dir(src: &Path, dst: &Path, compare_hashes: bool, mismatches: &mut Vec<MigrationMismatch>) -> Result<(), String> {
+    let entries = fs::read_dir(src)
+        .map_err(|e| format!("Failed to read directory {}: {}", src.display(), e))?;
+
+    for entry in entries {
+        let entry = entry.map_err(|e| format!("Failed to read entry: {}", e))?;
+        let file_type = entry.file_type()
+            .map_err(|e| format!("Failed to get file type: {}", e))?;
+        let dst_path = dst.join(entry.file_name());
+
+        if file_type.is_dir() {
+            verify_dir(&entry.path(), &dst_path, compare_hashes, mismatches)?;
+        } else if file_type.is_file() {
+            if let Some(reason) = verify_file(&entry.path(), &dst_path, compare_hashes)? {
+                mismatches.push(MigrationMismatch { file_path: dst_path.to_string_lossy().to_string(), reason });
+            }
         }
     }
 
     Ok(())
 }
 
+/**
+ * Post-copy verification of the entries selected by a `MigrationScope`
+ *
+ * Every source file must exist in `dst` with the same size (and, with `compare_hashes`,
+ * the same SHA-256). With `expected_files`, the destination must also hold exactly that
+ * many files under the copied entries.
+ */
+fn verify_copy(
+    entries: &[(PathBuf, bool)],
+    dst: &Path,
+    compare_hashes: bool,
+    expected_files: Option<u64>,
+) -> Result<Vec<MigrationMismatch>, String> {
+    let mut mismatches = Vec::new();
+    let mut destination_files = 0u64;
+
+    for (src_path, is_dir) in entries {
+        let dst_path = dst.join(src_path.file_name().unwrap_or_default());
+        if *is_dir {
+            verify_dir(src_path, &dst_path, compare_hashes, &mut mismatches)?;
+            if dst_path.is_dir() {
+                destination_files += count_files(&dst_path)?;
+            }
+        } else {
+            if let Some(reason) = verify_file(src_path, &dst_path, compare_hashes)? {
+                mismatches.push(MigrationMismatch { file_path: dst_path.to_string_lossy().to_string(), reason });
+            }
+            if dst_path.is_file() {
+                destination_files += 1;
+            }
+        }
+    }
+
+    if let Some(expected) = expected_files {
+        if destination_files != expected {
+            mismatches.push(MigrationMismatch {
+                file_path: dst.to_string_lossy().to_string(),
+                reason: format!("file count differs: source {}, destination {}", expected, destination_files),
+            });
+        }
+    }
+
+    Ok(mismatches)
+}
+
 /**
  * Count total files for progress tracking
  */
@@ -222,75 +733,257 @@ fn count_files(path: &Path) -> Result<u64, String> {
 }
 
 /**
- * US5-024: Implement migrate_from_electron Tauri command
+ * Migrate Electron data into `tauri_path`
+ *
+ * If a previous run was interrupted (the `.migrating` marker exists), the copy
+ * resumes from the backup and skips files that were already copied. A resumed
+ * run keeps the categories of the interrupted one.
+ *
+ * `categories` restricts the copy to those top-level directories (`None`: everything).
+ * The whole source is backed up either way, and the marker records what was copied:
+ * once a selective migration completed, running again copies further categories
+ * (or, with `None`, all remaining data) from the backup.
+ *
+ * JSON files that fail validation are reported as warnings. In `strict` mode any
+ * warning aborts the migration and the backup is restored to its original location.
+ *
+ * After the copy, the destination is verified against the backup (file count and sizes,
+ * plus SHA-256 with `verify_hashes`). On any mismatch the migration is not recorded:
+ * the mismatched copies are deleted and the error lists the discrepancies, so running
+ * the migration again resumes and copies those files anew.
+ *
+ * Setting `cancel` stops the copy at the next file; the backup is moved back to its
+ * original location and the partial copy is removed, as if the migration never ran.
+ * Cancelling a run that adds categories removes the files and directories it created;
+ * the marker is left as it was, so the same categories can be migrated again.
+ *
+ * A run that adds categories never overwrites a file already in the destination, since
+ * the app may have written it since the first run; each one that differs is reported as a warning.
  */
-#[tauri::command]
-pub async fn migrate_from_electron(
-    app_handle: AppHandle,
-) -> Result<String, String> {
-    // Detect Electron AppData location
-    let electron_path = match detect_electron_appdata()? {
-        Some(path) => path,
-        None => return Err("Electron VCPChat data not found. No migration needed.".to_string()),
+fn run_migration(
+    tauri_path: &Path,
+    source_override: Option<String>,
+    strict: bool,
+    categories: Option<Vec<String>>,
+    verify_hashes: bool,
+    progress_callback: &dyn Fn(MigrationProgress),
+    cancel: &AtomicBool,
+) -> Result<MigrationResult, String> {
+    let categories = validate_categories(categories)?;
+
+    let migrated_marker = tauri_path.join(".migrated");
+    let in_progress_marker = tauri_path.join(".migrating");
+
+    // Categories an earlier selective migration already copied
+    let previous_categories = if migrated_marker.exists() {
+        match read_marker_categories(&migrated_marker)? {
+            Some(done) => Some(done),
+            None => return Err("Data already migrated. Migration can only run once.".to_string()),
+        }
+    } else {
+        None
     };
 
-    // Get Tauri AppData directory
-    let tauri_path = app_handle
-        .path().resolve("AppData", tauri::path::BaseDirectory::AppData)
-        .map_err(|e| format!("Failed to get Tauri AppData directory: {}", e))?;
+    let (electron_path, backup_path, scope) = if let Some(done) = &previous_categories {
+        // Add categories to a selective migration, copying from its backup
+        if let Some(requested) = &categories {
+            if requested.iter().all(|category| done.contains(category)) {
+                return Err(format!("Already migrated: {}", requested.join(", ")));
+            }
+        }
 
-    // Check if already migrated
-    let migrated_marker = tauri_path.join(".migrated");
-    if migrated_marker.exists() {
-        return Err("Data already migrated. Migration can only run once.".to_string());
-    }
+        let (electron_path, backup_path) = read_marker_paths(&migrated_marker)?;
+        if !backup_path.is_dir() {
+            return Err(format!("No backup found at {}. Cannot migrate further data.", backup_path.display()));
+        }
 
-    // Check if destination already has data
-    if tauri_path.exists() && fs::read_dir(&tauri_path).map_or(false, |mut d| d.next().is_some()) {
-        return Err("Destination directory already contains data. Manual migration required.".to_string());
-    }
+        println!("Adding to previous migration from {}", backup_path.display());
+        let scope = MigrationScope { categories: categories.clone(), skip: done.clone(), include_files: false };
+        (electron_path, backup_path, scope)
+    } else if in_progress_marker.exists() {
+        // Resume an interrupted migration
+        let (electron_path, backup_path) = read_marker_paths(&in_progress_marker)?;
+        let categories = read_marker_categories(&in_progress_marker)?;
 
-    // Create backup
-    let backup_path = electron_path.with_file_name("VCPChat_backup");
-    if backup_path.exists() {
-        return Err(format!("Backup already exists at {}. Remove it before migrating.", backup_path.display()));
-    }
+        println!("Resuming interrupted migration from {}", backup_path.display());
+        (electron_path, backup_path, MigrationScope { categories, skip: Vec::new(), include_files: true })
+    } else {
+        // Use the requested source, or detect the Electron AppData location
+        let electron_path = match source_override {
+            Some(source) => {
+                let path = PathBuf::from(source);
+                validate_source_dir(&path)?;
+                path
+            }
+            None => match detect_electron_appdata()? {
+                Some(path) => path,
+                None => return Err("Electron VCPChat data not found. No migration needed.".to_string()),
+            },
+        };
+
+        // Check if destination already has data
+        if tauri_path.exists() && fs::read_dir(tauri_path).is_ok_and(|mut d| d.next().is_some()) {
+            return Err("Destination directory already contains data. Manual migration required.".to_string());
+        }
+
+        let backup_path = backup_path_for(&electron_path);
+        if backup_path.exists() {
+            return Err(format!("Backup already exists at {}. Remove it before migrating.", backup_path.display()));
+        }
+
+        // Record the migration so an interrupted run can be resumed
+        fs::create_dir_all(tauri_path)
+            .map_err(|e| format!("Failed to create Tauri AppData directory: {}", e))?;
+        let marker = serde_json::json!({
+            "started_at": chrono::Utc::now().to_rfc3339(),
+            "electron_path": electron_path.to_string_lossy(),
+            "backup_path": backup_path.to_string_lossy(),
+            "categories": categories,
+        });
+        fs::write(&in_progress_marker, serde_json::to_string_pretty(&marker).unwrap())
+            .map_err(|e| format!("Failed to create migration marker: {}", e))?;
+
+        let scope = MigrationScope { categories: categories.clone(), skip: Vec::new(), include_files: true };
+        (electron_path, backup_path, scope)
+    };
+    let is_first_run = previous_categories.is_none();
 
     println!("Migrating data from Electron to Tauri...");
     println!("Source: {}", electron_path.display());
     println!("Destination: {}", tauri_path.display());
 
-    // Count total files
-    let total_files = count_files(&electron_path)?;
-    let mut copied_files = 0u64;
+    // Create backup (rename original) unless an earlier run already did
+    if !backup_path.exists() {
+        fs::rename(&electron_path, &backup_path)
+            .map_err(|e| format!("Failed to create backup: {}", e))?;
+        write_backup_metadata(&backup_path, &electron_path)?;
+
+        println!("Created backup at: {}", backup_path.display());
+    }
+
+    let entries = scope.entries(&backup_path)?;
+
+    // Validate JSON files before copying
+    let mut warnings = Vec::new();
+    for (path, is_dir) in &entries {
+        if *is_dir {
+            collect_validation_warnings(path, &mut warnings)?;
+        } else if path.extension().and_then(|s| s.to_str()) == Some("json") {
+            if let Err(reason) = validate_json_file(path) {
+                warnings.push(MigrationWarning { file_path: path.to_string_lossy().to_string(), reason });
+            }
+        }
+    }
 
+    for warning in &warnings {
+        eprintln!("Warning: JSON validation failed for {}: {}", warning.file_path, warning.reason);
+    }
+
+    if strict && !warnings.is_empty() {
+        if is_first_run {
+            rollback(tauri_path)?;
+        }
+        return Err(format!(
+            "Migration aborted: {} file(s) failed validation. {} First failure: {}: {}",
+            warnings.len(),
+            if is_first_run { "Original data restored." } else { "Nothing was copied." },
+            warnings[0].file_path,
+            warnings[0].reason
+        ));
+    }
+
+    // Count total files
+    let mut total_files = 0u64;
+    for (path, is_dir) in &entries {
+        total_files += if *is_dir { count_files(path)? } else { 1 };
+    }
     println!("Found {} files to migrate", total_files);
 
-    // Create backup (rename original)
-    fs::rename(&electron_path, &backup_path)
-        .map_err(|e| format!("Failed to create backup: {}", e))?;
+    let mut run = CopyRun::new(progress_callback, cancel, total_files);
+    // After the first run the destination is in use: never copy over what the app wrote there
+    run.keep_existing = !is_first_run;
+    let copied = copy_scoped(&entries, tauri_path, &mut run);
+    let copied_files = run.copied_files;
 
-    println!("Created backup at: {}", backup_path.display());
+    if cancel.load(Ordering::SeqCst) {
+        if is_first_run {
+            rollback(tauri_path)?;
+        } else {
+            // Leave the destination as the earlier runs left it
+            run.remove_created();
+        }
 
-    // Copy data with progress tracking
-    let progress_callback = |progress: MigrationProgress| {
-        println!("[Migration] {} - {}", progress.status, progress.current_file);
-    };
+        progress_callback(MigrationProgress {
+            total_files,
+            copied_files,
+            current_file: String::new(),
+            status: MIGRATION_CANCELLED_STATUS.to_string(),
+        });
+        return Err(if is_first_run {
+            format!("Migration cancelled. Original data restored to: {}", electron_path.display())
+        } else {
+            "Migration cancelled. Files copied by this run were removed; previously migrated data was kept.".to_string()
+        });
+    }
+    copied?;
+
+    // Verify the copy before recording the migration. Files already in the destination
+    // before an added-categories run may legitimately add to the count.
+    let expected_files = if is_first_run { Some(total_files) } else { None };
+    let mut mismatches = verify_copy(&entries, tauri_path, verify_hashes, expected_files)?;
+    // Kept files are expected to differ, and must never be deleted as bad copies
+    mismatches.retain(|mismatch| !run.kept.iter().any(|kept| kept.as_path() == Path::new(&mismatch.file_path)));
+    for kept in &run.kept {
+        let src_path = backup_path.join(kept.strip_prefix(tauri_path).unwrap_or(kept));
+        if !is_already_copied(&src_path, kept) {
+            warnings.push(MigrationWarning {
+                file_path: kept.to_string_lossy().to_string(),
+                reason: "Already exists in the Tauri data; kept it instead of the Electron copy".to_string(),
+            });
+        }
+    }
+    if !mismatches.is_empty() {
+        for mismatch in &mismatches {
+            eprintln!("Verification failed for {}: {}", mismatch.file_path, mismatch.reason);
+            let path = Path::new(&mismatch.file_path);
+            if path.is_file() {
+                let _ = fs::remove_file(path);
+            }
+        }
+
+        let details: Vec<String> = mismatches
+            .iter()
+            .take(10)
+            .map(|mismatch| format!("{}: {}", mismatch.file_path, mismatch.reason))
+            .collect();
+        return Err(format!(
+            "Migration verification failed: {} mismatch(es). Run the migration again to recopy them. {}{}",
+            mismatches.len(),
+            details.join("; "),
+            if mismatches.len() > details.len() { "; ..." } else { "" }
+        ));
+    }
 
-    copy_dir_recursive(
-        &backup_path,
-        &tauri_path,
-        &progress_callback,
-        &mut total_files.clone(),
-        &mut copied_files,
-    )?;
+    // Everything copied so far; `None` once no category is left out
+    let migrated_categories = match (previous_categories, scope.categories) {
+        (Some(mut done), Some(added)) => {
+            for category in added {
+                if !done.contains(&category) {
+                    done.push(category);
+                }
+            }
+            Some(done)
+        }
+        (_, selected) => selected,
+    };
 
-    // Create migration marker
+    // Create migration marker only after the copy fully completed
     let migration_info = serde_json::json!({
         "migrated_at": chrono::Utc::now().to_rfc3339(),
         "electron_path": electron_path.to_string_lossy(),
         "backup_path": backup_path.to_string_lossy(),
         "total_files": total_files,
+        "categories": migrated_categories,
     });
 
     fs::write(
@@ -299,13 +992,96 @@ pub async fn migrate_from_electron(
     )
     .map_err(|e| format!("Failed to create migration marker: {}", e))?;
 
+    let _ = fs::remove_file(&in_progress_marker);
+
     println!("Migration complete! {} files copied.", copied_files);
 
-    Ok(format!(
-        "Successfully migrated {} files from Electron to Tauri. Backup saved at: {}",
-        copied_files,
-        backup_path.display()
-    ))
+    Ok(MigrationResult {
+        message: format!(
+            "Successfully migrated {} files from Electron to Tauri. Backup saved at: {}",
+            copied_files,
+            backup_path.display()
+        ),
+        warnings,
+    })
+}
+
+/**
+ * US5-024: Implement migrate_from_electron Tauri command
+ *
+ * Progress is emitted as `migration://progress` events.
+ *
+ * `source_override` migrates from a user-chosen directory (portable builds, forks)
+ * instead of the auto-detected Electron AppData location. `strict` aborts the
+ * migration if any JSON file fails validation (default: tolerant). `categories`
+ * limits the migration to some of `MIGRATION_CATEGORIES`; run it again to add others.
+ * `verify_hashes` also compares file contents when verifying the copy (default: sizes only).
+ *
+ * The migration can be stopped with `cancel_migration`; the last progress event of
+ * a cancelled migration has the status `cancelled`.
+ */
+#[tauri::command]
+pub async fn migrate_from_electron(
+    app_handle: AppHandle,
+    source_override: Option<String>,
+    strict: Option<bool>,
+    categories: Option<Vec<String>>,
+    verify_hashes: Option<bool>,
+) -> Result<MigrationResult, String> {
+    // Get Tauri AppData directory
+    let tauri_path = app_handle
+        .path().resolve("AppData", tauri::path::BaseDirectory::AppData)
+        .map_err(|e| format!("Failed to get Tauri AppData directory: {}", e))?;
+
+    let cancel = {
+        let mut active = ACTIVE_MIGRATION.lock().map_err(|e| format!("Migration lock poisoned: {}", e))?;
+        if active.is_some() {
+            return Err("A migration is already running".to_string());
+        }
+        let cancel = Arc::new(AtomicBool::new(false));
+        *active = Some(cancel.clone());
+        cancel
+    };
+
+    // Copy on a blocking thread so `cancel_migration` can run meanwhile
+    let result = tokio::task::spawn_blocking(move || {
+        // Report progress to the frontend
+        let progress_callback = |progress: MigrationProgress| {
+            println!("[Migration] {} - {}", progress.status, progress.current_file);
+            if let Err(e) = app_handle.emit(MIGRATION_PROGRESS_EVENT, progress) {
+                eprintln!("Failed to emit migration progress: {}", e);
+            }
+        };
+
+        let result = run_migration(&tauri_path, source_override, strict.unwrap_or(false), categories, verify_hashes.unwrap_or(false), &progress_callback, &cancel);
+        // Even a failed or cancelled run may have copied topics in
+        super::search::invalidate_index(&tauri_path);
+        result
+    })
+    .await
+    .map_err(|e| format!("Migration failed: {}", e));
+
+    if let Ok(mut active) = ACTIVE_MIGRATION.lock() {
+        *active = None;
+    }
+
+    result?
+}
+
+/**
+ * Ask the running migration to stop. Returns `false` if no migration is running.
+ */
+#[tauri::command]
+pub fn cancel_migration() -> Result<bool, String> {
+    let active = ACTIVE_MIGRATION.lock().map_err(|e| format!("Migration lock poisoned: {}", e))?;
+
+    match active.as_ref() {
+        Some(cancel) => {
+            cancel.store(true, Ordering::SeqCst);
+            Ok(true)
+        }
+        None => Ok(false),
+    }
 }
 
 /**
@@ -319,6 +1095,13 @@ pub async fn check_migration_status(
         .path().resolve("AppData", tauri::path::BaseDirectory::AppData)
         .map_err(|e| format!("Failed to get Tauri AppData directory: {}", e))?;
 
+    migration_status(&tauri_path)
+}
+
+/**
+ * Migration status of a Tauri AppData directory
+ */
+pub(crate) fn migration_status(tauri_path: &Path) -> Result<MigrationStatus, String> {
     let migrated_marker = tauri_path.join(".migrated");
 
     if migrated_marker.exists() {
@@ -335,6 +1118,7 @@ pub async fn check_migration_status(
             tauri_path: tauri_path.to_string_lossy().to_string(),
             backup_path: info.get("backup_path").and_then(|v| v.as_str()).map(String::from),
             migration_date: info.get("migrated_at").and_then(|v| v.as_str()).map(String::from),
+            migrated_categories: read_marker_categories(&migrated_marker)?,
         })
     } else {
         // Check if Electron data exists
@@ -346,6 +1130,496 @@ pub async fn check_migration_status(
             tauri_path: tauri_path.to_string_lossy().to_string(),
             backup_path: None,
             migration_date: None,
+            migrated_categories: None,
         })
     }
 }
+
+/**
+ * Roll back a failed or unwanted migration, restoring the Electron data
+ * from its backup. A partial Tauri copy is removed; after a completed
+ * migration the Tauri AppData is moved aside so newer data isn't lost.
+ */
+#[tauri::command]
+pub async fn rollback_migration(
+    app_handle: AppHandle,
+) -> Result<String, String> {
+    let tauri_path = app_handle
+        .path().resolve("AppData", tauri::path::BaseDirectory::AppData)
+        .map_err(|e| format!("Failed to get Tauri AppData directory: {}", e))?;
+
+    let rolled_back = rollback(&tauri_path)?;
+
+    Ok(match rolled_back.set_aside_path {
+        Some(set_aside_path) => format!(
+            "Migration rolled back. Electron data restored to: {}. Data written since the migration was moved to: {}",
+            rolled_back.electron_path.display(),
+            set_aside_path.display()
+        ),
+        None => format!("Migration rolled back. Electron data restored to: {}", rolled_back.electron_path.display()),
+    })
+}
+
+/**
+ * Manually restore Electron data from a backup directory
+ */
+#[tauri::command]
+pub async fn restore_from_backup(backup_path: String) -> Result<String, String> {
+    let electron_path = restore_backup_dir(Path::new(&backup_path))?;
+
+    Ok(format!("Backup restored to: {}", electron_path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("vcp_migration_{}_{}", name, uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_copy_skips_files_already_copied() {
+        let src = temp_dir("src");
+        let dst = temp_dir("dst");
+
+        fs::write(src.join("same.txt"), "source").unwrap();
+        fs::write(src.join("partial.txt"), "complete contents").unwrap();
+        fs::write(src.join("new.txt"), "new").unwrap();
+
+        // Simulate an interrupted run: one file fully copied (same size, marked by
+        // different bytes so we can tell it was skipped), one truncated
+        fs::write(dst.join("same.txt"), "SOURCE").unwrap();
+        fs::write(dst.join("partial.txt"), "compl").unwrap();
+
+        let cancel = AtomicBool::new(false);
+        let mut run = CopyRun::new(&|_| {}, &cancel, 3);
+        copy_dir_recursive(&src, &dst, &mut run).unwrap();
+
+        assert_eq!(fs::read_to_string(dst.join("same.txt")).unwrap(), "SOURCE");
+        assert_eq!(fs::read_to_string(dst.join("partial.txt")).unwrap(), "complete contents");
+        assert_eq!(fs::read_to_string(dst.join("new.txt")).unwrap(), "new");
+        assert_eq!(run.copied_files, 3);
+        // Only the missing file counts as created by the run
+        assert_eq!(run.created, vec![dst.join("new.txt")]);
+
+        let _ = fs::remove_dir_all(&src);
+        let _ = fs::remove_dir_all(&dst);
+    }
+
+    #[test]
+    fn test_copied_count_matches_counted_total() {
+        let src = temp_dir("src");
+        let dst = temp_dir("dst");
+
+        fs::create_dir_all(src.join("Agents").join("agent-1")).unwrap();
+        fs::create_dir_all(src.join("UserData")).unwrap();
+        fs::write(src.join("settings.json"), "{}").unwrap();
+        fs::write(src.join("Agents").join("agent-1").join("config.json"), "{}").unwrap();
+        fs::write(src.join("UserData").join("notes.txt"), "notes").unwrap();
+
+        let total_files = count_files(&src).unwrap();
+        assert_eq!(total_files, 3);
+
+        let statuses = std::cell::RefCell::new(Vec::new());
+        let cancel = AtomicBool::new(false);
+        let progress_callback = |progress: MigrationProgress| {
+            assert_eq!(progress.total_files, total_files);
+            statuses.borrow_mut().push(progress.status);
+        };
+        let mut run = CopyRun::new(&progress_callback, &cancel, total_files);
+        copy_dir_recursive(&src, &dst, &mut run).unwrap();
+
+        assert_eq!(run.copied_files, total_files);
+        assert_eq!(statuses.borrow().last().unwrap(), "Copying (3/3)");
+
+        let _ = fs::remove_dir_all(&src);
+        let _ = fs::remove_dir_all(&dst);
+    }
+
+    /// Lay out an Electron data dir and a migration that failed after the backup rename
+    fn simulate_failed_migration(root: &Path) -> (PathBuf, PathBuf, PathBuf) {
+        let electron_path = root.join("VCPChat").join("AppData");
+        let backup_path = backup_path_for(&electron_path);
+        let tauri_path = root.join("tauri");
+
+        fs::create_dir_all(electron_path.join("Agents")).unwrap();
+        fs::write(electron_path.join("settings.json"), "{}").unwrap();
+        fs::write(electron_path.join("Agents").join("agent.json"), "{}").unwrap();
+
+        fs::create_dir_all(&tauri_path).unwrap();
+        let marker = serde_json::json!({
+            "electron_path": electron_path.to_string_lossy(),
+            "backup_path": backup_path.to_string_lossy(),
+        });
+        fs::write(tauri_path.join(".migrating"), marker.to_string()).unwrap();
+        fs::rename(&electron_path, &backup_path).unwrap();
+        write_backup_metadata(&backup_path, &electron_path).unwrap();
+
+        // Only part of the data made it across before the failure
+        fs::write(tauri_path.join("settings.json"), "{}").unwrap();
+
+        (electron_path, backup_path, tauri_path)
+    }
+
+    #[test]
+    fn test_rollback_after_failed_copy() {
+        let root = temp_dir("rollback");
+        let (electron_path, backup_path, tauri_path) = simulate_failed_migration(&root);
+
+        let restored = rollback(&tauri_path).unwrap();
+
+        assert_eq!(restored.electron_path, electron_path);
+        assert!(restored.set_aside_path.is_none());
+        assert!(electron_path.join("Agents").join("agent.json").exists());
+        assert!(!backup_path.exists());
+        assert!(!tauri_path.exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_rollback_after_completed_migration_keeps_new_data() {
+        let root = temp_dir("rollback_completed");
+        let (electron_path, _, tauri_path) = simulate_failed_migration(&root);
+        fs::rename(tauri_path.join(".migrating"), tauri_path.join(".migrated")).unwrap();
+        // Written by the app after the migration
+        fs::write(tauri_path.join("new-topic.json"), "{}").unwrap();
+
+        let restored = rollback(&tauri_path).unwrap();
+
+        assert!(electron_path.join("Agents").join("agent.json").exists());
+        assert!(!tauri_path.exists());
+        let set_aside_path = restored.set_aside_path.unwrap();
+        assert!(set_aside_path.join("new-topic.json").exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_rollback_requires_backup() {
+        let root = temp_dir("rollback_no_backup");
+        let (_, backup_path, tauri_path) = simulate_failed_migration(&root);
+        fs::remove_dir_all(&backup_path).unwrap();
+
+        assert!(rollback(&tauri_path).is_err());
+        // Nothing was deleted
+        assert!(tauri_path.join("settings.json").exists());
+
+        assert!(rollback(&root.join("never_migrated")).is_err());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_restore_from_backup() {
+        let root = temp_dir("restore");
+        let (electron_path, backup_path, _) = simulate_failed_migration(&root);
+
+        assert_eq!(restore_backup_dir(&backup_path).unwrap(), electron_path);
+        assert!(electron_path.join("settings.json").exists());
+        assert!(restore_backup_dir(&backup_path).is_err());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_restore_from_backup_uses_recorded_source() {
+        let root = temp_dir("restore_override");
+        let source = root.join("portable").join("data");
+        fs::create_dir_all(source.join("Agents")).unwrap();
+        fs::write(source.join("Agents").join("agent.json"), "{}").unwrap();
+        let tauri_path = root.join("tauri");
+
+        run_migration(&tauri_path, Some(source.to_string_lossy().to_string()), false, None, false, &|_| {}, &AtomicBool::new(false)).unwrap();
+        assert!(!source.exists());
+
+        assert_eq!(restore_backup_dir(&backup_path_for(&source)).unwrap(), source);
+        assert!(source.join("Agents").join("agent.json").exists());
+        assert!(!root.join("portable").join("AppData").exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_valid_source_override() {
+        let root = temp_dir("override_valid");
+        fs::create_dir_all(root.join("Agents")).unwrap();
+        assert!(validate_source_dir(&root).is_ok());
+
+        let user_data_only = temp_dir("override_userdata");
+        fs::create_dir_all(user_data_only.join("UserData")).unwrap();
+        assert!(validate_source_dir(&user_data_only).is_ok());
+
+        let _ = fs::remove_dir_all(&root);
+        let _ = fs::remove_dir_all(&user_data_only);
+    }
+
+    #[test]
+    fn test_invalid_source_override() {
+        let root = temp_dir("override_invalid");
+        fs::create_dir_all(root.join("Documents")).unwrap();
+        fs::write(root.join("Agents"), "not a directory").unwrap();
+
+        let err = validate_source_dir(&root).unwrap_err();
+        assert!(err.contains("not a VCPChat data directory"));
+        assert!(validate_source_dir(&root.join("missing")).is_err());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    /// Electron data dir with one valid and one malformed agent file
+    fn create_source_with_malformed_agent(root: &Path) -> PathBuf {
+        let source = root.join("VCPChat").join("AppData");
+        fs::create_dir_all(source.join("Agents")).unwrap();
+        fs::write(source.join("Agents").join("good.json"), serde_json::json!({
+            "id": "good", "name": "Good", "model": "gpt-4", "system_prompt": "hi"
+        }).to_string()).unwrap();
+        fs::write(source.join("Agents").join("broken.json"), "{ \"id\": \"broken\", ").unwrap();
+        source
+    }
+
+    #[test]
+    fn test_lenient_migration_reports_malformed_agent() {
+        let root = temp_dir("lenient");
+        let source = create_source_with_malformed_agent(&root);
+        let tauri_path = root.join("tauri");
+
+        let result = run_migration(&tauri_path, Some(source.to_string_lossy().to_string()), false, None, false, &|_| {}, &AtomicBool::new(false)).unwrap();
+
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.warnings[0].file_path.ends_with("broken.json"));
+        assert!(!result.warnings[0].reason.is_empty());
+        // The malformed file is still copied
+        assert!(tauri_path.join("Agents").join("broken.json").exists());
+        assert!(tauri_path.join(".migrated").exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_migration_after_plugin_startup() {
+        let root = temp_dir("plugin_startup");
+        let source = create_source_with_malformed_agent(&root);
+        let tauri_path = root.join("tauri");
+
+        // What app setup does before the migration wizard can run
+        let plugin_manager = crate::plugin::plugin_manager::PluginManager::with_auto_approve(tauri_path.clone(), false);
+        plugin_manager.apply_settings(&crate::models::GlobalSettings::default());
+        plugin_manager.discover_installed_plugins().unwrap();
+        plugin_manager.activate_startup_plugins().unwrap();
+
+        let result = run_migration(&tauri_path, Some(source.to_string_lossy().to_string()), false, None, false, &|_| {}, &AtomicBool::new(false)).unwrap();
+        assert!(tauri_path.join("Agents").join("good.json").exists());
+        assert_eq!(result.warnings.len(), 1);
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_strict_migration_aborts_and_restores_backup() {
+        let root = temp_dir("strict");
+        let source = create_source_with_malformed_agent(&root);
+        let tauri_path = root.join("tauri");
+
+        let err = run_migration(&tauri_path, Some(source.to_string_lossy().to_string()), true, None, false, &|_| {}, &AtomicBool::new(false)).unwrap_err();
+
+        assert!(err.contains("broken.json"));
+        assert!(source.join("Agents").join("broken.json").exists());
+        assert!(!backup_path_for(&source).exists());
+        assert!(!tauri_path.join(".migrated").exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_cancel_midway_restores_source() {
+        let root = temp_dir("cancel");
+        let source = root.join("VCPChat").join("AppData");
+        fs::create_dir_all(source.join("Agents")).unwrap();
+        for i in 0..5 {
+            fs::write(source.join("Agents").join(format!("agent-{}.json", i)), "{}").unwrap();
+        }
+        let tauri_path = root.join("tauri");
+
+        // Cancel once two files are across
+        let cancel = AtomicBool::new(false);
+        let statuses = std::cell::RefCell::new(Vec::new());
+        let err = run_migration(&tauri_path, Some(source.to_string_lossy().to_string()), false, None, false, &|progress| {
+            if progress.copied_files == 2 {
+                cancel.store(true, Ordering::SeqCst);
+            }
+            statuses.borrow_mut().push(progress.status);
+        }, &cancel).unwrap_err();
+
+        assert!(err.contains("cancelled"));
+        assert_eq!(statuses.borrow().len(), 3);
+        assert_eq!(statuses.borrow().last().unwrap(), MIGRATION_CANCELLED_STATUS);
+        assert_eq!(fs::read_dir(source.join("Agents")).unwrap().count(), 5);
+        assert!(!backup_path_for(&source).exists());
+        assert!(!tauri_path.exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    /// Electron data dir with agents, user data, a plugin and loose settings
+    fn create_source_with_categories(root: &Path) -> PathBuf {
+        let source = root.join("VCPChat").join("AppData");
+        fs::create_dir_all(source.join("Agents")).unwrap();
+        fs::create_dir_all(source.join("UserData")).unwrap();
+        fs::create_dir_all(source.join("plugins").join("weather")).unwrap();
+        fs::write(source.join("Agents").join("good.json"), serde_json::json!({
+            "id": "good", "name": "Good", "model": "gpt-4", "system_prompt": "hi"
+        }).to_string()).unwrap();
+        fs::write(source.join("UserData").join("notes.txt"), "notes").unwrap();
+        fs::write(source.join("plugins").join("weather").join("plugin.js"), "//").unwrap();
+        fs::write(source.join("settings.json"), "{}").unwrap();
+        source
+    }
+
+    #[test]
+    fn test_single_category_migration() {
+        let root = temp_dir("category");
+        let source = create_source_with_categories(&root);
+        let tauri_path = root.join("tauri");
+        let source_arg = Some(source.to_string_lossy().to_string());
+
+        let err = run_migration(&tauri_path, source_arg.clone(), false, Some(vec!["Themes".to_string()]), false, &|_| {}, &AtomicBool::new(false)).unwrap_err();
+        assert!(err.contains("Unknown migration category: Themes"));
+        assert!(source.exists());
+
+        run_migration(&tauri_path, source_arg, false, Some(vec!["Agents".to_string()]), false, &|_| {}, &AtomicBool::new(false)).unwrap();
+
+        assert!(tauri_path.join("Agents").join("good.json").exists());
+        assert!(tauri_path.join("settings.json").exists());
+        assert!(!tauri_path.join("UserData").exists());
+        assert!(!tauri_path.join("plugins").exists());
+        // The whole source is backed up
+        assert!(backup_path_for(&source).join("plugins").join("weather").join("plugin.js").exists());
+        assert_eq!(read_marker_categories(&tauri_path.join(".migrated")).unwrap(), Some(vec!["Agents".to_string()]));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_rerun_adds_categories() {
+        let root = temp_dir("category_rerun");
+        let source = create_source_with_categories(&root);
+        let tauri_path = root.join("tauri");
+        let agents = || Some(vec!["Agents".to_string()]);
+
+        run_migration(&tauri_path, Some(source.to_string_lossy().to_string()), false, agents(), false, &|_| {}, &AtomicBool::new(false)).unwrap();
+        // Changed in the Tauri app since the first run; must not be overwritten
+        fs::write(tauri_path.join("settings.json"), "{\"theme\": \"dark\"}").unwrap();
+
+        assert!(run_migration(&tauri_path, None, false, agents(), false, &|_| {}, &AtomicBool::new(false)).unwrap_err().contains("Already migrated"));
+
+        run_migration(&tauri_path, None, false, Some(vec!["UserData".to_string()]), false, &|_| {}, &AtomicBool::new(false)).unwrap();
+        assert!(tauri_path.join("UserData").join("notes.txt").exists());
+        assert!(!tauri_path.join("plugins").exists());
+        assert_eq!(
+            read_marker_categories(&tauri_path.join(".migrated")).unwrap(),
+            Some(vec!["Agents".to_string(), "UserData".to_string()])
+        );
+
+        // No categories: everything that's left
+        run_migration(&tauri_path, None, false, None, false, &|_| {}, &AtomicBool::new(false)).unwrap();
+        assert!(tauri_path.join("plugins").join("weather").join("plugin.js").exists());
+        assert_eq!(fs::read_to_string(tauri_path.join("settings.json")).unwrap(), "{\"theme\": \"dark\"}");
+        assert_eq!(read_marker_categories(&tauri_path.join(".migrated")).unwrap(), None);
+        assert!(run_migration(&tauri_path, None, false, None, false, &|_| {}, &AtomicBool::new(false)).is_err());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_cancelled_rerun_removes_its_copies() {
+        let root = temp_dir("category_cancel");
+        let source = create_source_with_categories(&root);
+        let tauri_path = root.join("tauri");
+
+        run_migration(&tauri_path, Some(source.to_string_lossy().to_string()), false, Some(vec!["Agents".to_string()]), false, &|_| {}, &AtomicBool::new(false)).unwrap();
+        fs::write(backup_path_for(&source).join("UserData").join("more.txt"), "more").unwrap();
+
+        // Cancel once the first UserData file is across
+        let cancel = AtomicBool::new(false);
+        let err = run_migration(&tauri_path, None, false, Some(vec!["UserData".to_string()]), false, &|progress| {
+            if progress.copied_files == 1 {
+                cancel.store(true, Ordering::SeqCst);
+            }
+        }, &cancel).unwrap_err();
+
+        assert!(err.contains("cancelled"));
+        assert!(!tauri_path.join("UserData").exists());
+        assert!(tauri_path.join("Agents").join("good.json").exists());
+        assert_eq!(read_marker_categories(&tauri_path.join(".migrated")).unwrap(), Some(vec!["Agents".to_string()]));
+
+        // The same category can be migrated again
+        run_migration(&tauri_path, None, false, Some(vec!["UserData".to_string()]), false, &|_| {}, &AtomicBool::new(false)).unwrap();
+        assert!(tauri_path.join("UserData").join("more.txt").exists());
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_rerun_keeps_existing_files() {
+        let root = temp_dir("category_conflict");
+        let source = create_source_with_categories(&root);
+        let tauri_path = root.join("tauri");
+
+        run_migration(&tauri_path, Some(source.to_string_lossy().to_string()), false, Some(vec!["Agents".to_string()]), false, &|_| {}, &AtomicBool::new(false)).unwrap();
+        // Written by the Tauri app before the UserData category is added
+        fs::create_dir_all(tauri_path.join("UserData")).unwrap();
+        fs::write(tauri_path.join("UserData").join("notes.txt"), "newer notes").unwrap();
+        fs::write(backup_path_for(&source).join("UserData").join("more.txt"), "more").unwrap();
+
+        let result = run_migration(&tauri_path, None, false, Some(vec!["UserData".to_string()]), false, &|_| {}, &AtomicBool::new(false)).unwrap();
+
+        assert_eq!(fs::read_to_string(tauri_path.join("UserData").join("notes.txt")).unwrap(), "newer notes");
+        assert!(tauri_path.join("UserData").join("more.txt").exists());
+        assert_eq!(result.warnings.len(), 1);
+        assert!(result.warnings[0].file_path.ends_with("notes.txt"));
+        assert_eq!(
+            read_marker_categories(&tauri_path.join(".migrated")).unwrap(),
+            Some(vec!["Agents".to_string(), "UserData".to_string()])
+        );
+
+        let _ = fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_verification_catches_size_mismatch() {
+        let root = temp_dir("verify");
+        let source = create_source_with_categories(&root);
+        let tauri_path = root.join("tauri");
+
+        // Corrupt one copy right after it is written
+        let err = run_migration(&tauri_path, Some(source.to_string_lossy().to_string()), false, None, false, &|progress| {
+            if progress.current_file == "notes.txt" {
+                fs::write(tauri_path.join("UserData").join("notes.txt"), "truncated?").unwrap();
+            }
+        }, &AtomicBool::new(false)).unwrap_err();
+
+        assert!(err.contains("verification failed"));
+        assert!(err.contains("notes.txt: size differs"));
+        assert!(!tauri_path.join(".migrated").exists());
+        assert!(!tauri_path.join("UserData").join("notes.txt").exists());
+
+        // Running again resumes and recopies the bad file
+        run_migration(&tauri_path, None, false, None, false, &|_| {}, &AtomicBool::new(false)).unwrap();
+        assert_eq!(fs::read_to_string(tauri_path.join("UserData").join("notes.txt")).unwrap(), "notes");
+        assert!(tauri_path.join(".migrated").exists());
+
+        // Same size, different bytes: only caught by hashing
+        fs::write(tauri_path.join("UserData").join("notes.txt"), "NOTES").unwrap();
+        let entries = vec![(backup_path_for(&source).join("UserData"), true)];
+        assert!(verify_copy(&entries, &tauri_path, false, Some(1)).unwrap().is_empty());
+        let mismatches = verify_copy(&entries, &tauri_path, true, Some(1)).unwrap();
+        assert_eq!(mismatches.len(), 1);
+        assert!(mismatches[0].reason.contains("SHA-256"));
+
+        let _ = fs::remove_dir_all(&root);
+    }
+}
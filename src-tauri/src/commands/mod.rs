@@ -5,6 +5,15 @@ pub mod window;
 pub mod attachments;
 pub mod migration;
 pub mod utils;
+pub mod notifications;
+pub mod integrity;
+pub mod plugins;
+pub mod backup;
+pub mod io;
+pub mod error;
+pub mod search;
+pub mod image_metadata;
+pub mod compact;
 
 pub use file_system::*;
 pub use settings::*;
@@ -12,3 +21,10 @@ pub use window::*;
 pub use attachments::*;
 pub use migration::*;
 pub use utils::*;
+pub use notifications::*;
+pub use integrity::*;
+pub use plugins::*;
+pub use backup::*;
+pub use search::*;
+pub use compact::*;
+pub use error::*;
@@ -0,0 +1,286 @@
+// Notification store commands
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use log::warn;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_notification::NotificationExt;
+use crate::models::{GlobalSettings, Notification, NotificationType};
+
+/// Maximum number of notifications kept on disk (oldest are dropped first)
+const MAX_STORED_NOTIFICATIONS: usize = 500;
+
+/// Serializes read-modify-write cycles on the notifications file
+static NOTIFICATIONS_LOCK: Mutex<()> = Mutex::new(());
+
+/// Get notifications file path
+fn get_notifications_path(app: &AppHandle) -> Result<PathBuf, String> {
+    let app_data = app.path().resolve("AppData", tauri::path::BaseDirectory::AppData)
+        .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+    Ok(app_data.join("notifications.json"))
+}
+
+/// Load stored notifications (empty if the file doesn't exist)
+fn load_notifications(path: &Path) -> Result<Vec<Notification>, String> {
+    if !path.exists() {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(path)
+        .map_err(|e| format!("Failed to read notifications file: {}", e))?;
+
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse notifications JSON: {}", e))
+}
+
+/// Sort newest first, cap the stored count and persist atomically
+fn save_notifications(path: &Path, notifications: &mut Vec<Notification>) -> Result<(), String> {
+    notifications.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    notifications.truncate(MAX_STORED_NOTIFICATIONS);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create notifications directory: {}", e))?;
+    }
+
+    super::io::write_json_atomic(path, notifications)
+}
+
+/// Load, modify and save the notification list under the store lock
+fn modify_notifications<T>(
+    path: &Path,
+    modify: impl FnOnce(&mut Vec<Notification>) -> Result<T, String>,
+) -> Result<T, String> {
+    let _guard = NOTIFICATIONS_LOCK.lock().map_err(|_| "Notifications lock poisoned".to_string())?;
+
+    let mut notifications = load_notifications(path)?;
+    let result = modify(&mut notifications)?;
+    save_notifications(path, &mut notifications)?;
+
+    Ok(result)
+}
+
+/// Create a notification with a generated id and timestamp and store it
+fn insert_notification(
+    path: &Path,
+    notification_type: NotificationType,
+    title: String,
+    content: String,
+) -> Result<Notification, String> {
+    let notification = Notification {
+        id: uuid::Uuid::new_v4().to_string(),
+        r#type: notification_type,
+        title,
+        content,
+        timestamp: chrono::Utc::now().to_rfc3339(),
+        read_status: false,
+    };
+    notification.validate()?;
+
+    modify_notifications(path, |notifications| {
+        notifications.push(notification.clone());
+        Ok(())
+    })?;
+
+    Ok(notification)
+}
+
+/// List stored notifications, most recent first
+fn query_notifications(path: &Path, unread_only: bool) -> Result<Vec<Notification>, String> {
+    let mut notifications = load_notifications(path)?;
+    if unread_only {
+        notifications.retain(|n| !n.read_status);
+    }
+    notifications.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    Ok(notifications)
+}
+
+/// Mark one notification (or all when `id` is `None`) as read
+fn mark_read(path: &Path, id: Option<&str>) -> Result<(), String> {
+    modify_notifications(path, |notifications| {
+        match id {
+            Some(id) => {
+                let notification = notifications
+                    .iter_mut()
+                    .find(|n| n.id == id)
+                    .ok_or_else(|| format!("Notification not found: {}", id))?;
+                notification.read_status = true;
+            }
+            None => {
+                for notification in notifications.iter_mut() {
+                    notification.read_status = true;
+                }
+            }
+        }
+        Ok(())
+    })
+}
+
+/// Whether a notification should also raise a native OS popup.
+/// Only alerts and errors do, and only when enabled and not in do-not-disturb mode.
+fn should_show_os_notification(settings: &GlobalSettings, notification_type: &NotificationType) -> bool {
+    settings.notifications_enabled
+        && !settings.do_not_disturb
+        && matches!(notification_type, NotificationType::SystemAlert | NotificationType::Error)
+}
+
+/// Add a notification to the store, raising an OS notification when appropriate.
+/// Returns the created notification with its generated id and timestamp.
+#[tauri::command]
+pub async fn add_notification(
+    app: AppHandle,
+    notification_type: NotificationType,
+    title: String,
+    content: String,
+) -> Result<Notification, String> {
+    let path = get_notifications_path(&app)?;
+    let notification = insert_notification(&path, notification_type, title, content)?;
+
+    // The notification is already stored; failing to read the settings or to show
+    // the popup should not fail the command
+    let settings_path = path.with_file_name("settings.json");
+    let settings = match super::settings::load_settings(&settings_path) {
+        Ok(settings) => settings,
+        Err(e) => {
+            warn!("Not showing OS notification, failed to load settings: {}", e);
+            return Ok(notification);
+        }
+    };
+
+    if should_show_os_notification(&settings, &notification.r#type) {
+        if let Err(e) = app.notification()
+            .builder()
+            .title(&notification.title)
+            .body(&notification.content)
+            .show()
+        {
+            warn!("Failed to show OS notification: {}", e);
+        }
+    }
+
+    Ok(notification)
+}
+
+/// List notifications, optionally only unread ones
+#[tauri::command]
+pub async fn list_notifications(app: AppHandle, unread_only: bool) -> Result<Vec<Notification>, String> {
+    let path = get_notifications_path(&app)?;
+    query_notifications(&path, unread_only)
+}
+
+/// Mark a single notification as read
+#[tauri::command]
+pub async fn mark_notification_read(app: AppHandle, id: String) -> Result<(), String> {
+    let path = get_notifications_path(&app)?;
+    mark_read(&path, Some(&id))
+}
+
+/// Mark every notification as read
+#[tauri::command]
+pub async fn mark_all_read(app: AppHandle) -> Result<(), String> {
+    let path = get_notifications_path(&app)?;
+    mark_read(&path, None)
+}
+
+/// Delete a notification
+#[tauri::command]
+pub async fn delete_notification(app: AppHandle, id: String) -> Result<(), String> {
+    let path = get_notifications_path(&app)?;
+    modify_notifications(&path, |notifications| {
+        let before = notifications.len();
+        notifications.retain(|n| n.id != id);
+        if notifications.len() == before {
+            return Err(format!("Notification not found: {}", id));
+        }
+        Ok(())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_notifications_path() -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("vcp_notifications_test_{}", uuid::Uuid::new_v4()))
+            .join("notifications.json")
+    }
+
+    #[test]
+    fn test_add_list_mark_read() {
+        let path = temp_notifications_path();
+
+        let first = insert_notification(&path, NotificationType::SystemAlert, "First".to_string(), "a".to_string()).unwrap();
+        let second = insert_notification(&path, NotificationType::Error, "Second".to_string(), "b".to_string()).unwrap();
+
+        let all = query_notifications(&path, false).unwrap();
+        assert_eq!(all.len(), 2);
+        // Most recent first
+        assert_eq!(all[0].id, second.id);
+        assert!(all.iter().all(|n| !n.read_status));
+
+        // Mark one read
+        mark_read(&path, Some(&first.id)).unwrap();
+        let unread = query_notifications(&path, true).unwrap();
+        assert_eq!(unread.len(), 1);
+        assert_eq!(unread[0].id, second.id);
+
+        // Mark all read
+        mark_read(&path, None).unwrap();
+        assert!(query_notifications(&path, true).unwrap().is_empty());
+
+        assert!(mark_read(&path, Some("missing")).is_err());
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_stored_count_is_capped() {
+        let path = temp_notifications_path();
+
+        modify_notifications(&path, |notifications| {
+            for i in 0..MAX_STORED_NOTIFICATIONS + 10 {
+                notifications.push(Notification {
+                    id: format!("n-{}", i),
+                    r#type: NotificationType::PluginComplete,
+                    title: "Title".to_string(),
+                    content: String::new(),
+                    timestamp: format!("2025-01-01T00:{:02}:{:02}Z", i / 60, i % 60),
+                    read_status: false,
+                });
+            }
+            Ok(())
+        }).unwrap();
+
+        let stored = query_notifications(&path, false).unwrap();
+        assert_eq!(stored.len(), MAX_STORED_NOTIFICATIONS);
+        // The oldest ones were dropped
+        assert!(stored.iter().all(|n| n.id != "n-0"));
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_invalid_notification_rejected() {
+        let path = temp_notifications_path();
+        let result = insert_notification(&path, NotificationType::Error, String::new(), "body".to_string());
+        assert!(result.is_err());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_should_show_os_notification() {
+        let mut settings = GlobalSettings::default();
+        assert!(should_show_os_notification(&settings, &NotificationType::SystemAlert));
+        assert!(should_show_os_notification(&settings, &NotificationType::Error));
+        assert!(!should_show_os_notification(&settings, &NotificationType::PluginComplete));
+
+        settings.do_not_disturb = true;
+        assert!(!should_show_os_notification(&settings, &NotificationType::Error));
+
+        settings.do_not_disturb = false;
+        settings.notifications_enabled = false;
+        assert!(!should_show_os_notification(&settings, &NotificationType::Error));
+    }
+}
@@ -0,0 +1,291 @@
+// Plugin system commands
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Manager, State};
+use crate::plugin::{PluginErrorDto, PluginRestartPolicy, PluginState};
+use crate::plugin::health_check::HealthStatus;
+use crate::plugin::keybinding_registry::{KeybindingConflict, RegisteredKeybinding};
+use crate::plugin::manifest_parser::{ConfigurationProperty, ViewLocation};
+use crate::plugin::network_proxy::RateLimitStatus;
+use crate::plugin::permission_manager::PermissionImport;
+use crate::plugin::resource_limiter::{ResourceLimits, ResourceUsage};
+use crate::plugin::view_registry::ContributedView;
+use crate::plugin::plugin_manager::{DependencyGraph, PluginManager, UninstallPreview};
+use crate::plugin::update_check::UpdateInfo;
+use crate::plugin::PluginError;
+
+/// Event emitted with a `PluginStateChange` payload when a plugin changes state on its own
+pub const PLUGIN_STATE_EVENT: &str = "plugin://state-changed";
+
+/// How often running plugins are health-checked in the background
+const HEALTH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PluginStateChange {
+    pub plugin_id: String,
+    pub from: PluginState,
+    pub to: PluginState,
+}
+
+/// Forward plugin state changes to the frontend, and periodically health-check running plugins,
+/// stop the ones over their resource caps and restart crashed ones.
+/// Must be called after the `PluginManager` is managed.
+pub fn start_plugin_health_monitor(app: &AppHandle) {
+    let handle = app.clone();
+    app.state::<PluginManager>().on_state_change(Box::new(move |plugin_id, from, to| {
+        let change = PluginStateChange { plugin_id: plugin_id.to_string(), from, to };
+        if let Err(e) = handle.emit(PLUGIN_STATE_EVENT, change) {
+            eprintln!("Failed to emit plugin state change: {}", e);
+        }
+    }));
+
+    let handle = app.clone();
+    std::thread::spawn(move || loop {
+        std::thread::sleep(HEALTH_CHECK_INTERVAL);
+        let plugin_manager = handle.state::<PluginManager>();
+        plugin_manager.enforce_resource_limits();
+        plugin_manager.check_running_plugins();
+        plugin_manager.restart_crashed_plugins();
+    });
+}
+
+/// Ping a running plugin; an unresponsive plugin is marked `Crashed`
+#[tauri::command]
+pub fn check_plugin_health(
+    plugin_manager: State<'_, PluginManager>,
+    plugin_id: String,
+) -> Result<HealthStatus, PluginErrorDto> {
+    plugin_manager
+        .health_check(&plugin_id)
+        .map_err(PluginErrorDto::from)
+}
+
+/// Current memory/CPU usage of a plugin's process
+#[tauri::command]
+pub fn get_plugin_resource_usage(
+    plugin_manager: State<'_, PluginManager>,
+    plugin_id: String,
+) -> Result<ResourceUsage, PluginErrorDto> {
+    plugin_manager
+        .get_plugin_resource_usage(&plugin_id)
+        .map_err(PluginErrorDto::from)
+}
+
+/// Set a plugin's resource caps; a plugin going over one is marked `Crashed`.
+/// Fails for caps this platform can't enforce.
+#[tauri::command]
+pub fn set_plugin_resource_limits(
+    plugin_manager: State<'_, PluginManager>,
+    plugin_id: String,
+    limits: ResourceLimits,
+) -> Result<(), PluginErrorDto> {
+    plugin_manager
+        .set_resource_limits(&plugin_id, limits)
+        .map_err(PluginErrorDto::from)
+}
+
+/// What uninstalling a plugin would remove (files, stored data, permissions, contributions)
+#[tauri::command]
+pub fn preview_plugin_uninstall(
+    plugin_manager: State<'_, PluginManager>,
+    plugin_id: String,
+) -> Result<UninstallPreview, PluginErrorDto> {
+    plugin_manager
+        .preview_uninstall(&plugin_id)
+        .map_err(PluginErrorDto::from)
+}
+
+/// Uninstall a plugin; its stored data is kept unless `keep_data` is false
+#[tauri::command]
+pub fn uninstall_plugin(
+    plugin_manager: State<'_, PluginManager>,
+    plugin_id: String,
+    keep_data: Option<bool>,
+) -> Result<(), PluginErrorDto> {
+    plugin_manager
+        .uninstall_plugin(&plugin_id, keep_data.unwrap_or(true))
+        .map_err(PluginErrorDto::from)
+}
+
+/// Installed plugins and their dependencies (including cycles), for troubleshooting activation order
+#[tauri::command]
+pub fn get_plugin_dependency_graph(plugin_manager: State<'_, PluginManager>) -> DependencyGraph {
+    plugin_manager.dependency_graph()
+}
+
+/// Check whether a plugin's installed files still match what was installed
+/// (false means the plugin was modified on disk)
+#[tauri::command]
+pub fn verify_plugin_integrity(
+    plugin_manager: State<'_, PluginManager>,
+    plugin_id: String,
+) -> Result<bool, PluginErrorDto> {
+    plugin_manager
+        .verify_plugin_integrity(&plugin_id)
+        .map_err(PluginErrorDto::from)
+}
+
+/// Choose whether a crashed plugin is restarted automatically ("never", "on-crash", "always")
+#[tauri::command]
+pub fn set_plugin_restart_policy(
+    plugin_manager: State<'_, PluginManager>,
+    plugin_id: String,
+    policy: PluginRestartPolicy,
+) -> Result<(), PluginErrorDto> {
+    plugin_manager
+        .set_restart_policy(&plugin_id, policy)
+        .map_err(PluginErrorDto::from)
+}
+
+/// Activate plugins (and their dependencies) in dependency order, rolling back on failure.
+/// Returns the plugins that are running afterwards.
+#[tauri::command]
+pub fn activate_plugins(
+    plugin_manager: State<'_, PluginManager>,
+    plugin_ids: Vec<String>,
+) -> Result<Vec<String>, PluginErrorDto> {
+    plugin_manager
+        .activate_plugins(&plugin_ids)
+        .map_err(PluginErrorDto::from)
+}
+
+/// Whether the app started in safe mode (plugins not activated automatically), for the UI banner
+#[tauri::command]
+pub fn is_safe_mode(plugin_manager: State<'_, PluginManager>) -> bool {
+    plugin_manager.is_safe_mode()
+}
+
+/// Views contributed by running plugins at a UI location ("sidebar", "panel" or "editor")
+#[tauri::command]
+pub fn list_plugin_views(
+    plugin_manager: State<'_, PluginManager>,
+    location: ViewLocation,
+) -> Vec<ContributedView> {
+    plugin_manager.list_views(location)
+}
+
+/// Settings a plugin declares, for rendering its settings form
+#[tauri::command]
+pub fn get_plugin_config_schema(
+    plugin_manager: State<'_, PluginManager>,
+    plugin_id: String,
+) -> Result<Vec<ConfigurationProperty>, PluginErrorDto> {
+    plugin_manager
+        .get_config_schema(&plugin_id)
+        .map_err(PluginErrorDto::from)
+}
+
+/// Current values of a plugin's declared settings (defaults for unset ones)
+#[tauri::command]
+pub fn get_plugin_config(
+    plugin_manager: State<'_, PluginManager>,
+    plugin_id: String,
+) -> Result<serde_json::Map<String, serde_json::Value>, PluginErrorDto> {
+    plugin_manager
+        .get_plugin_config(&plugin_id)
+        .map_err(PluginErrorDto::from)
+}
+
+/// Set one of a plugin's declared settings; a `null` value resets it to its default
+#[tauri::command]
+pub fn set_plugin_config(
+    plugin_manager: State<'_, PluginManager>,
+    plugin_id: String,
+    key: String,
+    value: Option<serde_json::Value>,
+) -> Result<(), PluginErrorDto> {
+    plugin_manager
+        .set_plugin_config(&plugin_id, &key, value)
+        .map_err(PluginErrorDto::from)
+}
+
+/// How much of a plugin's network request budget is left
+#[tauri::command]
+pub fn get_plugin_rate_limit(
+    plugin_manager: State<'_, PluginManager>,
+    plugin_id: String,
+) -> Result<RateLimitStatus, PluginErrorDto> {
+    plugin_manager
+        .rate_limit_status(&plugin_id)
+        .map_err(PluginErrorDto::from)
+}
+
+/// Change how many network requests per minute a plugin may make (until the app restarts)
+#[tauri::command]
+pub fn set_plugin_rate_limit(
+    plugin_manager: State<'_, PluginManager>,
+    plugin_id: String,
+    requests_per_minute: u32,
+) -> Result<(), PluginErrorDto> {
+    plugin_manager
+        .set_rate_limit(&plugin_id, requests_per_minute)
+        .map_err(PluginErrorDto::from)
+}
+
+/// Icon image declared by a plugin's manifest
+#[tauri::command]
+pub fn get_plugin_icon(
+    plugin_manager: State<'_, PluginManager>,
+    plugin_id: String,
+) -> Result<Vec<u8>, PluginErrorDto> {
+    plugin_manager
+        .get_plugin_icon(&plugin_id)
+        .map_err(PluginErrorDto::from)
+}
+
+/// Installed plugins with a newer version in the registry set in `plugin_registry_url` (nothing is installed).
+/// Runs off the main thread since it waits on the network.
+#[tauri::command]
+pub async fn check_plugin_updates(app: AppHandle) -> Result<Vec<UpdateInfo>, PluginErrorDto> {
+    tokio::task::spawn_blocking(move || app.state::<PluginManager>().check_plugin_updates())
+        .await
+        .map_err(|e| PluginErrorDto::from(PluginError::IoError(std::io::Error::other(e.to_string()))))?
+        .map_err(PluginErrorDto::from)
+}
+
+/// All granted plugin permissions, for moving them to another install
+#[tauri::command]
+pub fn export_plugin_permissions(plugin_manager: State<'_, PluginManager>) -> serde_json::Value {
+    plugin_manager.export_permissions()
+}
+
+/// Restore permissions from `export_plugin_permissions` for installed plugins, merged with or
+/// replacing the current grants. `confirmed` must be set once the user has approved the import.
+#[tauri::command]
+pub fn import_plugin_permissions(
+    plugin_manager: State<'_, PluginManager>,
+    value: serde_json::Value,
+    merge: bool,
+    confirmed: bool,
+) -> Result<PermissionImport, PluginErrorDto> {
+    if !confirmed {
+        return Err(PluginErrorDto::from(PluginError::PermissionDenied(
+            "Importing plugin permissions requires confirmation".to_string(),
+        )));
+    }
+    plugin_manager
+        .import_permissions(value, merge)
+        .map_err(PluginErrorDto::from)
+}
+
+/// Keybindings contributed by running plugins that are in effect
+#[tauri::command]
+pub fn list_plugin_keybindings(plugin_manager: State<'_, PluginManager>) -> Vec<RegisteredKeybinding> {
+    plugin_manager.list_keybindings()
+}
+
+/// Plugin keybindings that were not applied because the key was already bound
+#[tauri::command]
+pub fn list_keybinding_conflicts(plugin_manager: State<'_, PluginManager>) -> Vec<KeybindingConflict> {
+    plugin_manager.keybinding_conflicts()
+}
+
+/// Command a plugin bound to `key` in the `context` (`when` clause), if any
+#[tauri::command]
+pub fn resolve_plugin_key(
+    plugin_manager: State<'_, PluginManager>,
+    key: String,
+    context: Option<String>,
+) -> Option<String> {
+    plugin_manager.resolve_key(&key, context.as_deref())
+}
@@ -0,0 +1,582 @@
+// Conversation search backed by an on-disk inverted index
+//
+// `AppData/.search-index/index.json` maps each token of message content to the topics
+// containing it. Writes, appends and deletes keep it up to date; a query looks up candidate
+// topics there and only reads those files to confirm and locate the matches.
+// Appended messages go to `journal.jsonl` next to it, which is folded into the index once it grows.
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, PoisonError};
+use log::warn;
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Manager};
+use crate::models::{Message, OwnerType, SearchHit, SearchOptions, Topic};
+use super::file_system::{is_later, load_topic, run_blocking};
+use super::io::write_json_atomic;
+
+/// Hits returned when the caller doesn't pass a limit
+const DEFAULT_SEARCH_LIMIT: usize = 50;
+
+/// Characters of context on each side of a match in a snippet
+const SNIPPET_CONTEXT_CHARS: usize = 40;
+
+/// Compiled size cap for search patterns, so a pathological regex fails instead of exhausting memory
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+/// Journal size at which appended tokens are folded into the index file
+const MAX_JOURNAL_BYTES: u64 = 256 * 1024;
+
+/// Serializes read-modify-write cycles on the index file
+static INDEX_LOCK: Mutex<()> = Mutex::new(());
+
+/// Get AppData directory path
+fn get_app_data_dir(app: &AppHandle) -> Result<PathBuf, String> {
+    app.path().resolve("AppData", tauri::path::BaseDirectory::AppData)
+        .map_err(|e| format!("Failed to get app data directory: {}", e))
+}
+
+fn index_dir(app_data: &Path) -> PathBuf {
+    app_data.join(".search-index")
+}
+
+fn index_path(app_data: &Path) -> PathBuf {
+    index_dir(app_data).join("index.json")
+}
+
+fn journal_path(app_data: &Path) -> PathBuf {
+    index_dir(app_data).join("journal.jsonl")
+}
+
+fn topic_path(app_data: &Path, owner_type: &OwnerType, topic_id: &str) -> PathBuf {
+    let dir = match owner_type {
+        OwnerType::Agent => "Agents",
+        OwnerType::Group => "AgentGroups",
+    };
+    app_data.join(dir).join(format!("{}.json", topic_id))
+}
+
+/// Ideographic and kana/hangul characters aren't separated by spaces, so each one is its own token
+fn is_cjk(c: char) -> bool {
+    matches!(c,
+        '\u{3040}'..='\u{30ff}'
+        | '\u{3400}'..='\u{4dbf}'
+        | '\u{4e00}'..='\u{9fff}'
+        | '\u{ac00}'..='\u{d7af}'
+        | '\u{f900}'..='\u{faff}'
+    )
+}
+
+/// Lowercased words of `text`, with CJK characters split out individually
+fn tokenize(text: &str) -> BTreeSet<String> {
+    let mut tokens = BTreeSet::new();
+    let mut word = String::new();
+
+    for c in text.chars() {
+        if is_cjk(c) {
+            if !word.is_empty() {
+                tokens.insert(std::mem::take(&mut word));
+            }
+            tokens.insert(c.to_string());
+        } else if c.is_alphanumeric() {
+            word.extend(c.to_lowercase());
+        } else if !word.is_empty() {
+            tokens.insert(std::mem::take(&mut word));
+        }
+    }
+    if !word.is_empty() {
+        tokens.insert(word);
+    }
+
+    tokens
+}
+
+/// A topic as recorded in the index
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct IndexedTopic {
+    owner_type: OwnerType,
+    /// Tokens this topic is listed under, so it can be removed again
+    tokens: BTreeSet<String>,
+}
+
+/// Tokens of an appended message, as one line of the journal
+#[derive(Debug, Serialize, Deserialize)]
+struct JournalEntry {
+    topic_id: String,
+    owner_type: OwnerType,
+    tokens: BTreeSet<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct SearchIndex {
+    /// Token -> IDs of the topics whose messages contain it
+    tokens: BTreeMap<String, BTreeSet<String>>,
+    topics: BTreeMap<String, IndexedTopic>,
+}
+
+impl SearchIndex {
+    fn load(path: &Path) -> Result<Option<Self>, String> {
+        if !path.exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read search index: {}", e))?;
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| format!("Failed to parse search index: {}", e))
+    }
+
+    fn add_tokens(&mut self, topic_id: &str, owner_type: &OwnerType, tokens: BTreeSet<String>) {
+        for token in &tokens {
+            self.tokens.entry(token.clone()).or_default().insert(topic_id.to_string());
+        }
+        self.topics
+            .entry(topic_id.to_string())
+            .or_insert_with(|| IndexedTopic { owner_type: owner_type.clone(), tokens: BTreeSet::new() })
+            .tokens
+            .extend(tokens);
+    }
+
+    fn remove_topic(&mut self, topic_id: &str) {
+        let Some(indexed) = self.topics.remove(topic_id) else {
+            return;
+        };
+        for token in indexed.tokens {
+            if let Some(topic_ids) = self.tokens.get_mut(&token) {
+                topic_ids.remove(topic_id);
+                if topic_ids.is_empty() {
+                    self.tokens.remove(&token);
+                }
+            }
+        }
+    }
+
+    fn add_topic(&mut self, topic: &Topic) {
+        self.remove_topic(&topic.id);
+        let tokens = topic.messages.iter().flat_map(|m| tokenize(&m.content)).collect();
+        self.add_tokens(&topic.id, &topic.owner_type, tokens);
+    }
+
+    /// Topics containing every query token (as a whole token or a token prefix)
+    fn candidates(&self, query_tokens: &BTreeSet<String>) -> Vec<(String, OwnerType)> {
+        let mut matching: Option<BTreeSet<String>> = None;
+
+        for query_token in query_tokens {
+            let topic_ids: BTreeSet<String> = self.tokens
+                .range(query_token.clone()..)
+                .take_while(|(token, _)| token.starts_with(query_token.as_str()))
+                .flat_map(|(_, topic_ids)| topic_ids.iter().cloned())
+                .collect();
+            matching = Some(match matching {
+                Some(previous) => previous.intersection(&topic_ids).cloned().collect(),
+                None => topic_ids,
+            });
+        }
+
+        matching
+            .unwrap_or_default()
+            .into_iter()
+            .filter_map(|topic_id| {
+                let owner_type = self.topics.get(&topic_id)?.owner_type.clone();
+                Some((topic_id, owner_type))
+            })
+            .collect()
+    }
+}
+
+/// The index file with the journal applied on top.
+/// A journal line cut short by a crash is skipped; `rebuild_search_index` recovers its tokens.
+fn load_index(app_data: &Path) -> Result<Option<SearchIndex>, String> {
+    let Some(mut index) = SearchIndex::load(&index_path(app_data))? else {
+        return Ok(None);
+    };
+
+    if let Ok(journal) = fs::read_to_string(journal_path(app_data)) {
+        for line in journal.lines().filter(|line| !line.trim().is_empty()) {
+            match serde_json::from_str::<JournalEntry>(line) {
+                Ok(entry) => index.add_tokens(&entry.topic_id, &entry.owner_type, entry.tokens),
+                Err(e) => warn!("Skipping unreadable search journal entry: {}", e),
+            }
+        }
+    }
+
+    Ok(Some(index))
+}
+
+/// Write the index file and drop the journal it now includes
+fn save_index(app_data: &Path, index: &SearchIndex) -> Result<(), String> {
+    write_json_atomic(&index_path(app_data), index)?;
+
+    match fs::remove_file(journal_path(app_data)) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(format!("Failed to clear search journal: {}", e)),
+        _ => Ok(()),
+    }
+}
+
+/// Load the index, change it and save it back.
+/// Until the index is built (or while it is unreadable) there is nothing to keep up to date.
+fn update_index(app_data: &Path, update: impl FnOnce(&mut SearchIndex)) -> Result<(), String> {
+    let _guard = INDEX_LOCK.lock().map_err(|_| "Search index lock poisoned".to_string())?;
+
+    let Some(mut index) = load_index(app_data).unwrap_or(None) else {
+        // No index yet: it is built in full by the first search or `rebuild_search_index`
+        return Ok(());
+    };
+    update(&mut index);
+
+    save_index(app_data, &index)
+}
+
+/// Record an appended message's tokens in the journal, folding it into the index once it is large
+fn append_to_journal(app_data: &Path, entry: &JournalEntry) -> Result<(), String> {
+    let _guard = INDEX_LOCK.lock().map_err(|_| "Search index lock poisoned".to_string())?;
+
+    if !index_path(app_data).exists() {
+        return Ok(());
+    }
+
+    let mut line = serde_json::to_string(entry)
+        .map_err(|e| format!("Failed to serialize search journal entry: {}", e))?;
+    line.push('\n');
+
+    let path = journal_path(app_data);
+    let mut file = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .map_err(|e| format!("Failed to open search journal: {}", e))?;
+    file.write_all(line.as_bytes())
+        .map_err(|e| format!("Failed to write search journal: {}", e))?;
+
+    let size = file.metadata().map(|m| m.len()).unwrap_or(0);
+    if size >= MAX_JOURNAL_BYTES {
+        if let Some(index) = load_index(app_data)? {
+            save_index(app_data, &index)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Drop the index so the next search rebuilds it from the files.
+/// Used after restoring, repairing or migrating data, which replace topic files behind the index's back.
+pub(crate) fn invalidate_index(app_data: &Path) {
+    let _guard = INDEX_LOCK.lock().unwrap_or_else(PoisonError::into_inner);
+
+    if let Err(e) = fs::remove_dir_all(index_dir(app_data)) {
+        if e.kind() != std::io::ErrorKind::NotFound {
+            warn!("Failed to invalidate search index: {}", e);
+        }
+    }
+}
+
+/// Reindex a topic after it was written in full
+pub(crate) fn on_topic_written(app_data: &Path, topic: &Topic) {
+    if let Err(e) = update_index(app_data, |index| index.add_topic(topic)) {
+        warn!("Failed to update search index for topic {}: {}", topic.id, e);
+    }
+}
+
+/// Add an appended message's tokens to its topic
+pub(crate) fn on_message_appended(app_data: &Path, topic_id: &str, owner_type: &OwnerType, message: &Message) {
+    let entry = JournalEntry {
+        topic_id: topic_id.to_string(),
+        owner_type: owner_type.clone(),
+        tokens: tokenize(&message.content),
+    };
+    if let Err(e) = append_to_journal(app_data, &entry) {
+        warn!("Failed to update search index for topic {}: {}", topic_id, e);
+    }
+}
+
+/// Drop a deleted topic from the index
+pub(crate) fn on_topic_deleted(app_data: &Path, topic_id: &str) {
+    if let Err(e) = update_index(app_data, |index| index.remove_topic(topic_id)) {
+        warn!("Failed to update search index for topic {}: {}", topic_id, e);
+    }
+}
+
+/// Build the index from every topic on disk (unreadable topics are skipped); returns the topics indexed
+fn rebuild_index(app_data: &Path) -> Result<usize, String> {
+    let _guard = INDEX_LOCK.lock().map_err(|_| "Search index lock poisoned".to_string())?;
+
+    let mut index = SearchIndex::default();
+    for dir in [app_data.join("Agents"), app_data.join("AgentGroups")] {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        for path in entries.flatten().map(|entry| entry.path()) {
+            if path.extension().and_then(|s| s.to_str()) != Some("json") {
+                continue;
+            }
+            match load_topic(&path) {
+                Ok(topic) => index.add_topic(&topic),
+                Err(e) => warn!("Skipping {} while indexing: {}", path.display(), e),
+            }
+        }
+    }
+
+    fs::create_dir_all(index_dir(app_data))
+        .map_err(|e| format!("Failed to create search index directory: {}", e))?;
+    save_index(app_data, &index)?;
+
+    Ok(index.topics.len())
+}
+
+/// Up to `SNIPPET_CONTEXT_CHARS` characters either side of the match at bytes `start..end`
+fn snippet(content: &str, start: usize, end: usize) -> String {
+    let before: Vec<char> = content[..start].chars().rev().take(SNIPPET_CONTEXT_CHARS).collect();
+    let before: String = before.into_iter().rev().collect();
+    let after: String = content[end..].chars().take(SNIPPET_CONTEXT_CHARS).collect();
+    format!("{}{}{}", before, &content[start..end], after).trim().to_string()
+}
+
+/// The query as a regex: taken as-is with `use_regex`, otherwise matched literally
+fn build_matcher(query: &str, options: &SearchOptions) -> Result<Regex, String> {
+    let pattern = if options.use_regex { query.to_string() } else { regex::escape(query.trim()) };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!options.case_sensitive)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .dfa_size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .map_err(|e| format!("Invalid search pattern: {}", e))
+}
+
+/// Whether a message passes the sender and time range filters (bounds are inclusive)
+fn passes_filters(message: &Message, options: &SearchOptions) -> bool {
+    options.sender.as_ref().map_or(true, |sender| *sender == message.sender)
+        && options.since.as_deref().map_or(true, |since| !is_later(since, &message.timestamp))
+        && options.until.as_deref().map_or(true, |until| !is_later(&message.timestamp, until))
+}
+
+/// Messages matching `query`, newest first
+fn search(app_data: &Path, query: &str, options: &SearchOptions) -> Result<Vec<SearchHit>, String> {
+    if query.trim().is_empty() {
+        return Ok(Vec::new());
+    }
+    let matcher = build_matcher(query, options)?;
+
+    // A literal query narrows the topics down through the index; a pattern could match any topic
+    let query_tokens = tokenize(query);
+    if !options.use_regex && query_tokens.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let index = match load_index(app_data) {
+        Ok(Some(index)) => index,
+        Ok(None) | Err(_) => {
+            rebuild_index(app_data)?;
+            load_index(app_data)?.unwrap_or_default()
+        }
+    };
+    let candidates = if options.use_regex {
+        index.topics.iter().map(|(topic_id, indexed)| (topic_id.clone(), indexed.owner_type.clone())).collect()
+    } else {
+        index.candidates(&query_tokens)
+    };
+
+    let mut hits = Vec::new();
+    for (topic_id, owner_type) in candidates {
+        // The index can be behind the files; confirming against them drops stale candidates
+        let Ok(topic) = load_topic(&topic_path(app_data, &owner_type, &topic_id)) else {
+            continue;
+        };
+        for message in topic.messages.iter().filter(|m| passes_filters(m, options)) {
+            let Some(found) = matcher.find(&message.content) else {
+                continue;
+            };
+            let match_start = message.content[..found.start()].encode_utf16().count();
+            hits.push(SearchHit {
+                topic_id: topic.id.clone(),
+                topic_title: topic.title.clone(),
+                owner_id: topic.owner_id.clone(),
+                owner_type: topic.owner_type.clone(),
+                message_id: message.id.clone(),
+                snippet: snippet(&message.content, found.start(), found.end()),
+                match_start,
+                match_end: match_start + found.as_str().encode_utf16().count(),
+                timestamp: message.timestamp.clone(),
+            });
+        }
+    }
+
+    hits.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    hits.truncate(options.limit.unwrap_or(DEFAULT_SEARCH_LIMIT));
+    Ok(hits)
+}
+
+/// Search message content across all conversations
+#[tauri::command]
+pub async fn search_conversations(app: AppHandle, query: String, options: Option<SearchOptions>) -> Result<Vec<SearchHit>, String> {
+    let app_data = get_app_data_dir(&app)?;
+    let options = options.unwrap_or_default();
+    run_blocking("search_conversations", move || search(&app_data, &query, &options)).await
+}
+
+/// Rebuild the search index from the topic files; returns the number of topics indexed
+#[tauri::command]
+pub async fn rebuild_search_index(app: AppHandle) -> Result<usize, String> {
+    let app_data = get_app_data_dir(&app)?;
+    run_blocking("rebuild_search_index", move || rebuild_index(&app_data)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MessageSender;
+
+    fn message(id: &str, content: &str, timestamp: &str) -> Message {
+        Message {
+            id: id.to_string(),
+            sender: MessageSender::User,
+            sender_id: None,
+            sender_name: None,
+            content: content.to_string(),
+            attachments: Vec::new(),
+            timestamp: timestamp.to_string(),
+            is_streaming: false,
+            metadata: None,
+        }
+    }
+
+    fn limit(limit: usize) -> SearchOptions {
+        SearchOptions { limit: Some(limit), ..SearchOptions::default() }
+    }
+
+    fn save(app_data: &Path, id: &str, messages: Vec<Message>) -> Topic {
+        let topic = Topic {
+            id: id.to_string(),
+            owner_id: "agent-a".to_string(),
+            owner_type: OwnerType::Agent,
+            title: format!("Topic {}", id),
+            messages,
+            created_at: "2025-01-01T00:00:00Z".to_string(),
+            updated_at: "2025-01-01T00:00:00Z".to_string(),
+            pinned: false,
+            forked_from: None,
+        };
+        let path = topic_path(app_data, &OwnerType::Agent, id);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, serde_json::to_string(&topic).unwrap()).unwrap();
+        topic
+    }
+
+    #[test]
+    fn test_tokenize() {
+        let tokens: Vec<String> = tokenize("Hello, World! 你好 rust-lang").into_iter().collect();
+        assert_eq!(tokens, vec!["hello", "lang", "rust", "world", "你", "好"]);
+    }
+
+    #[test]
+    fn test_index_follows_writes() {
+        let app_data = std::env::temp_dir().join(format!("vcp_search_test_{}", uuid::Uuid::new_v4()));
+        save(&app_data, "topic-1", vec![message("m1", "Deploy the server on Friday", "2025-01-01T10:00:00Z")]);
+        assert_eq!(rebuild_index(&app_data).unwrap(), 1);
+
+        // A rewritten topic is reindexed
+        let topic = save(&app_data, "topic-2", vec![message("m2", "The server crashed", "2025-01-02T10:00:00Z")]);
+        on_topic_written(&app_data, &topic);
+        let hits = search(&app_data, "server", &limit(10)).unwrap();
+        assert_eq!(hits.iter().map(|h| h.message_id.as_str()).collect::<Vec<_>>(), vec!["m2", "m1"]);
+
+        // Appended message tokens are added to the topic
+        let appended = message("m3", "Restarted the database", "2025-01-03T10:00:00Z");
+        let mut topic = topic;
+        topic.messages.push(appended.clone());
+        fs::write(topic_path(&app_data, &OwnerType::Agent, "topic-2"), serde_json::to_string(&topic).unwrap()).unwrap();
+        on_message_appended(&app_data, "topic-2", &OwnerType::Agent, &appended);
+        // They go to the journal, leaving the index file as it was
+        assert!(journal_path(&app_data).exists());
+        assert!(!SearchIndex::load(&index_path(&app_data)).unwrap().unwrap().tokens.contains_key("database"));
+        let index = load_index(&app_data).unwrap().unwrap();
+        assert!(index.tokens["database"].contains("topic-2"));
+        assert_eq!(search(&app_data, "database", &limit(10)).unwrap()[0].message_id, "m3");
+
+        // Deleted topics leave the index, which now includes the journal
+        fs::remove_file(topic_path(&app_data, &OwnerType::Agent, "topic-1")).unwrap();
+        on_topic_deleted(&app_data, "topic-1");
+        assert!(!journal_path(&app_data).exists());
+        let index = SearchIndex::load(&index_path(&app_data)).unwrap().unwrap();
+        assert!(!index.topics.contains_key("topic-1"));
+        assert!(!index.tokens.contains_key("friday"));
+        assert!(index.tokens["database"].contains("topic-2"));
+
+        // Invalidating drops the index until the next search rebuilds it
+        invalidate_index(&app_data);
+        assert!(!index_path(&app_data).exists());
+        on_message_appended(&app_data, "topic-2", &OwnerType::Agent, &appended);
+        assert!(!journal_path(&app_data).exists());
+        assert_eq!(search(&app_data, "database", &limit(10)).unwrap().len(), 1);
+        assert!(index_path(&app_data).exists());
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_search_results() {
+        let app_data = std::env::temp_dir().join(format!("vcp_search_query_test_{}", uuid::Uuid::new_v4()));
+        save(&app_data, "topic-1", vec![
+            message("m1", "Rust ownership explained", "2025-01-01T10:00:00Z"),
+            message("m2", "Ownership of the project moved", "2025-01-01T11:00:00Z"),
+        ]);
+        save(&app_data, "topic-2", vec![message("m3", "今天天气很好", "2025-01-02T10:00:00Z")]);
+
+        // The first search builds the missing index
+        let hits = search(&app_data, "rust owner", &limit(10)).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].message_id, "m1");
+        assert_eq!(hits[0].topic_title, "Topic topic-1");
+        assert_eq!(hits[0].snippet, "Rust ownership explained");
+        assert_eq!((hits[0].match_start, hits[0].match_end), (0, 10));
+        assert!(index_path(&app_data).exists());
+
+        assert_eq!(search(&app_data, "OWNERSHIP", &limit(10)).unwrap().len(), 2);
+        assert_eq!(search(&app_data, "ownership", &limit(1)).unwrap()[0].message_id, "m2");
+        assert_eq!(search(&app_data, "天气", &limit(10)).unwrap()[0].message_id, "m3");
+        // Both characters are indexed for topic-2, but not next to each other
+        assert!(search(&app_data, "天好", &limit(10)).unwrap().is_empty());
+        assert!(search(&app_data, "kubernetes", &limit(10)).unwrap().is_empty());
+        assert!(search(&app_data, "  ?! ", &limit(10)).unwrap().is_empty());
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_regex_and_filters() {
+        let app_data = std::env::temp_dir().join(format!("vcp_search_regex_test_{}", uuid::Uuid::new_v4()));
+        let mut reply = message("m2", "Ticket ABC-42 is fixed", "2025-01-02T10:00:00Z");
+        reply.sender = MessageSender::Agent;
+        save(&app_data, "topic-1", vec![
+            message("m1", "Please look at ticket abc-7", "2025-01-01T10:00:00Z"),
+            reply,
+            message("m3", "Also 天气 ABC-100", "2025-01-03T10:00:00Z"),
+        ]);
+
+        let regex = SearchOptions { use_regex: true, case_sensitive: true, ..SearchOptions::default() };
+        let hits = search(&app_data, r"[A-Z]+-\d+", &regex).unwrap();
+        assert_eq!(hits.iter().map(|h| h.message_id.as_str()).collect::<Vec<_>>(), vec!["m3", "m2"]);
+        // Offsets count UTF-16 code units, as the frontend indexes strings
+        assert_eq!((hits[0].match_start, hits[0].match_end), (8, 15));
+
+        let agent_only = SearchOptions { sender: Some(MessageSender::Agent), ..SearchOptions::default() };
+        let hits = search(&app_data, "ticket", &agent_only).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].message_id, "m2");
+
+        let window = SearchOptions {
+            since: Some("2025-01-02T00:00:00Z".to_string()),
+            until: Some("2025-01-02T23:59:59Z".to_string()),
+            ..SearchOptions::default()
+        };
+        assert_eq!(search(&app_data, "abc", &window).unwrap().len(), 1);
+
+        let invalid = SearchOptions { use_regex: true, ..SearchOptions::default() };
+        let error = search(&app_data, "ticket (abc", &invalid).unwrap_err();
+        assert!(error.starts_with("Invalid search pattern"), "{}", error);
+        assert!(search(&app_data, "a{10000}{10000}", &invalid).is_err());
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+}
@@ -1,8 +1,76 @@
 // Settings management commands
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use fs2::FileExt;
 use tauri::{AppHandle, Manager};
 use crate::models::GlobalSettings;
+use crate::plugin::plugin_manager::PluginManager;
+
+/// Serializes read-modify-write cycles on the settings file so concurrent
+/// saves from different parts of the UI cannot clobber each other
+static SETTINGS_LOCK: Mutex<()> = Mutex::new(());
+
+/// Advisory lock file next to settings.json, shared with other app instances
+const SETTINGS_LOCK_FILE: &str = ".settings.lock";
+
+/// How long to wait for another process to release a file lock
+const FILE_LOCK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Exclusive advisory lock on a lock file; released when dropped
+pub(crate) struct FileLock {
+    file: fs::File,
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = FileExt::unlock(&self.file);
+    }
+}
+
+/// Take an exclusive lock on `lock_path`, retrying with backoff until `timeout`
+pub(crate) fn acquire_file_lock(lock_path: &Path, timeout: Duration) -> Result<FileLock, String> {
+    if let Some(parent) = lock_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create lock directory: {}", e))?;
+    }
+
+    let file = fs::OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .write(true)
+        .open(lock_path)
+        .map_err(|e| format!("Failed to open lock file: {}", e))?;
+
+    let started = Instant::now();
+    let mut backoff = Duration::from_millis(10);
+    loop {
+        match file.try_lock_exclusive() {
+            Ok(()) => return Ok(FileLock { file }),
+            Err(e) if e.raw_os_error() != fs2::lock_contended_error().raw_os_error() => {
+                return Err(format!("Failed to lock {}: {}", lock_path.display(), e));
+            }
+            Err(_) if started.elapsed() >= timeout => {
+                return Err(format!(
+                    "{} is locked by another process; try again in a moment",
+                    lock_path.display()
+                ));
+            }
+            Err(_) => {
+                std::thread::sleep(backoff);
+                backoff = (backoff * 2).min(Duration::from_millis(200));
+            }
+        }
+    }
+}
+
+/// Lock the settings file against this and other processes for a read-modify-write
+fn lock_settings(settings_path: &Path) -> Result<(std::sync::MutexGuard<'static, ()>, FileLock), String> {
+    let guard = SETTINGS_LOCK.lock().map_err(|_| "Settings lock poisoned".to_string())?;
+    let file_lock = acquire_file_lock(&settings_path.with_file_name(SETTINGS_LOCK_FILE), FILE_LOCK_TIMEOUT)?;
+    Ok((guard, file_lock))
+}
 
 /// Get settings file path
 fn get_settings_path(app: &AppHandle) -> Result<PathBuf, String> {
@@ -12,25 +80,79 @@ fn get_settings_path(app: &AppHandle) -> Result<PathBuf, String> {
     Ok(app_data.join("settings.json"))
 }
 
-/// Read global settings from file
-#[tauri::command]
-pub async fn read_settings(app: AppHandle) -> Result<GlobalSettings, String> {
-    let settings_path = get_settings_path(&app)?;
-
-    // Return default settings if file doesn't exist
+/// Load settings from disk, falling back to defaults if the file doesn't exist
+pub(crate) fn load_settings(settings_path: &Path) -> Result<GlobalSettings, String> {
     if !settings_path.exists() {
         return Ok(GlobalSettings::default());
     }
 
-    let content = fs::read_to_string(&settings_path)
+    let content = fs::read_to_string(settings_path)
         .map_err(|e| format!("Failed to read settings file: {}", e))?;
 
-    let settings: GlobalSettings = serde_json::from_str(&content)
-        .map_err(|e| format!("Failed to parse settings JSON: {}", e))?;
+    serde_json::from_str(&content)
+        .map_err(|e| format!("Failed to parse settings JSON: {}", e))
+}
+
+/// Persist settings atomically
+fn save_settings(settings_path: &Path, settings: &GlobalSettings) -> Result<(), String> {
+    // Ensure parent directory exists
+    if let Some(parent) = settings_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("Failed to create settings directory: {}", e))?;
+    }
+
+    super::io::write_json_atomic(settings_path, settings)
+}
+
+/// Deep-merge a JSON patch into a target value.
+/// Objects are merged key by key; any other value replaces the target.
+pub(crate) fn merge_json(target: &mut serde_json::Value, patch: &serde_json::Value) {
+    match (target, patch) {
+        (serde_json::Value::Object(target_map), serde_json::Value::Object(patch_map)) => {
+            for (key, patch_value) in patch_map {
+                match target_map.get_mut(key) {
+                    Some(target_value) => merge_json(target_value, patch_value),
+                    None => {
+                        target_map.insert(key.clone(), patch_value.clone());
+                    }
+                }
+            }
+        }
+        (target, patch) => *target = patch.clone(),
+    }
+}
+
+/// Load current settings, merge the patch, validate the merged result and persist it
+fn apply_settings_patch(settings_path: &Path, patch: &serde_json::Value) -> Result<GlobalSettings, String> {
+    if !patch.is_object() {
+        return Err("Settings patch must be a JSON object".to_string());
+    }
+
+    let _lock = lock_settings(settings_path)?;
+
+    let current = load_settings(settings_path)?;
+    let mut merged = serde_json::to_value(&current)
+        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+    merge_json(&mut merged, patch);
+
+    let settings: GlobalSettings = serde_json::from_value(merged)
+        .map_err(|e| format!("Invalid settings patch: {}", e))?;
+    settings.validate()?;
+
+    save_settings(settings_path, &settings)?;
 
     Ok(settings)
 }
 
+/// Read global settings from file
+#[tauri::command]
+pub async fn read_settings(app: AppHandle) -> Result<GlobalSettings, String> {
+    let settings_path = get_settings_path(&app)?;
+
+    // Return default settings if file doesn't exist
+    load_settings(&settings_path)
+}
+
 /// Write global settings to file
 #[tauri::command]
 pub async fn write_settings(app: AppHandle, settings: GlobalSettings) -> Result<(), String> {
@@ -38,17 +160,122 @@ pub async fn write_settings(app: AppHandle, settings: GlobalSettings) -> Result<
 
     let settings_path = get_settings_path(&app)?;
 
-    // Ensure parent directory exists
-    if let Some(parent) = settings_path.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("Failed to create settings directory: {}", e))?;
+    let _lock = lock_settings(&settings_path)?;
+    save_settings(&settings_path, &settings)?;
+    apply_to_plugins(&app, &settings);
+    Ok(())
+}
+
+/// Validate settings without persisting them.
+/// Returns every failing check so the settings form can show all problems at once.
+#[tauri::command]
+pub async fn validate_settings(settings: GlobalSettings) -> Result<(), Vec<String>> {
+    let errors = settings.validation_errors();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
     }
+}
 
-    let json = serde_json::to_string_pretty(&settings)
-        .map_err(|e| format!("Failed to serialize settings: {}", e))?;
+/// Partially update global settings.
+/// Only the fields present in `patch` are changed; everything else keeps its stored value.
+#[tauri::command]
+pub async fn update_settings(app: AppHandle, patch: serde_json::Value) -> Result<GlobalSettings, String> {
+    let settings_path = get_settings_path(&app)?;
+    let settings = apply_settings_patch(&settings_path, &patch)?;
+    apply_to_plugins(&app, &settings);
+    Ok(settings)
+}
 
-    fs::write(&settings_path, json)
-        .map_err(|e| format!("Failed to write settings file: {}", e))?;
+/// Make plugin-related settings (signature policy, proxy) take effect without a restart
+fn apply_to_plugins(app: &AppHandle, settings: &GlobalSettings) {
+    if let Some(plugin_manager) = app.try_state::<PluginManager>() {
+        plugin_manager.apply_settings(settings);
+    }
+}
 
-    Ok(())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_settings_path() -> PathBuf {
+        std::env::temp_dir()
+            .join(format!("vcp_settings_test_{}", uuid::Uuid::new_v4()))
+            .join("settings.json")
+    }
+
+    #[test]
+    fn test_patches_on_different_fields_both_survive() {
+        let path = temp_settings_path();
+
+        apply_settings_patch(&path, &serde_json::json!({ "theme": "claude-dark" })).unwrap();
+        apply_settings_patch(&path, &serde_json::json!({
+            "window_preferences": { "width": 1600 }
+        })).unwrap();
+
+        let settings = load_settings(&path).unwrap();
+        assert_eq!(settings.theme, "claude-dark");
+        assert_eq!(settings.window_preferences.width, 1600);
+        // Sibling fields of a nested patch are preserved
+        assert_eq!(settings.window_preferences.height, 800);
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_patch_validates_merged_result() {
+        let path = temp_settings_path();
+
+        let result = apply_settings_patch(&path, &serde_json::json!({
+            "window_preferences": { "width": 100 }
+        }));
+        assert!(result.is_err());
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_patch_must_be_object() {
+        let path = temp_settings_path();
+        assert!(apply_settings_patch(&path, &serde_json::json!("theme")).is_err());
+    }
+
+    #[test]
+    fn test_writer_waits_for_file_lock() {
+        let path = temp_settings_path();
+        let lock_path = path.with_file_name(SETTINGS_LOCK_FILE);
+
+        // Another process holds the lock while it writes its own change
+        let other_process = acquire_file_lock(&lock_path, Duration::ZERO).unwrap();
+        let writer = {
+            let path = path.clone();
+            std::thread::spawn(move || apply_settings_patch(&path, &serde_json::json!({ "user_name": "Writer" })))
+        };
+
+        std::thread::sleep(Duration::from_millis(200));
+        assert!(!writer.is_finished());
+        let mut settings = GlobalSettings::default();
+        settings.theme = "claude-dark".to_string();
+        save_settings(&path, &settings).unwrap();
+        drop(other_process);
+
+        writer.join().unwrap().unwrap();
+        let settings = load_settings(&path).unwrap();
+        assert_eq!(settings.theme, "claude-dark");
+        assert_eq!(settings.user_name, "Writer");
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_file_lock_timeout() {
+        let path = temp_settings_path();
+        let lock_path = path.with_file_name(SETTINGS_LOCK_FILE);
+
+        let _held = acquire_file_lock(&lock_path, Duration::ZERO).unwrap();
+        let error = acquire_file_lock(&lock_path, Duration::from_millis(50)).err().unwrap();
+        assert!(error.contains("locked by another process"));
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
 }
@@ -4,15 +4,44 @@
  * Provides utility commands for logging, platform detection, and developer tools.
  *
  * US6-019: log_message command for frontend-to-backend logging
+ * log_event command for structured (JSON line) events
+ * get_diagnostics command for support requests
  */
 
-use log::{debug, error, info, warn};
+use log::{log, Level};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tauri::{AppHandle, Manager};
+use super::migration::{migration_status, MigrationStatus};
+use crate::plugin::permission_manager::{canonicalize_app_data, resolve_path};
+
+/**
+ * Environment information for support requests and bug reports.
+ * Never includes settings values such as API keys.
+ */
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Diagnostics {
+  pub os: String,
+  pub os_family: String,
+  pub arch: String,
+  pub app_name: String,
+  pub app_version: String,
+  pub tauri_version: String,
+  pub webview_version: Option<String>,
+  pub debug_build: bool,
+  pub app_data_path: String,
+  pub free_disk_space_bytes: Option<u64>,
+  pub log_file_path: Option<String>,
+  pub migration_status: Option<MigrationStatus>,
+}
 
 /**
  * Log a message from the frontend to the Rust backend logs.
  *
- * This allows frontend console.log to be forwarded to the terminal
- * for unified logging in development mode.
+ * This allows frontend console.log to be forwarded to the application
+ * log file (and the terminal in development mode) for unified logging.
  *
  * @param level - Log level: "debug", "info", "warn", "error"
  * @param message - Message to log
@@ -26,17 +55,260 @@ pub fn log_message(level: String, message: String, source: Option<String>) -> Re
     format!("[Frontend] {}", message)
   };
 
+  log!(parse_level(&level), "{}", formatted_message);
+
+  Ok(())
+}
+
+/**
+ * Parse a frontend log level name, defaulting to info for unknown levels.
+ */
+fn parse_level(level: &str) -> Level {
   match level.to_lowercase().as_str() {
-    "debug" => debug!("{}", formatted_message),
-    "info" => info!("{}", formatted_message),
-    "warn" => warn!("{}", formatted_message),
-    "error" => error!("{}", formatted_message),
-    _ => info!("{}", formatted_message), // Default to info
+    "debug" => Level::Debug,
+    "info" => Level::Info,
+    "warn" => Level::Warn,
+    "error" => Level::Error,
+    _ => Level::Info, // Default to info
+  }
+}
+
+/**
+ * Build the structured JSON line for an event.
+ */
+fn format_event_line(level: Level, event: &str, fields: &HashMap<String, serde_json::Value>) -> String {
+  serde_json::json!({
+    "timestamp": chrono::Utc::now().to_rfc3339(),
+    "level": level.as_str().to_lowercase(),
+    "event": event,
+    "fields": fields,
+  })
+  .to_string()
+}
+
+/**
+ * Log a structured event from the frontend.
+ *
+ * Writes one JSON line (timestamp, level, event, fields) to the log file so
+ * events can be aggregated later, and forwards a readable line to the logs.
+ *
+ * @param level - Log level: "debug", "info", "warn", "error"
+ * @param event - Event name, e.g. "message_sent"
+ * @param fields - Event fields, e.g. { topic_id, tokens, latency_ms }
+ */
+#[tauri::command]
+pub fn log_event(level: String, event: String, fields: HashMap<String, serde_json::Value>) -> Result<(), String> {
+  if event.trim().is_empty() {
+    return Err("Event name is required".to_string());
   }
 
+  let level = parse_level(&level);
+  let line = format_event_line(level, &event, &fields);
+
+  crate::logging::write_raw_line(&line);
+  log!(level, "[Event:{}] {}", event, serde_json::to_string(&fields).unwrap_or_default());
+
   Ok(())
 }
 
+/// Get the AppData/logs directory
+fn get_logs_dir(app: &AppHandle) -> Result<PathBuf, String> {
+  app.path().resolve("logs", tauri::path::BaseDirectory::AppData)
+    .map_err(|e| format!("Failed to get logs directory: {}", e))
+}
+
+/// Open a file or folder with the platform file manager
+pub(crate) fn open_in_file_manager(path: &Path) -> Result<(), String> {
+  #[cfg(target_os = "windows")]
+  let mut command = Command::new("explorer");
+  #[cfg(target_os = "macos")]
+  let mut command = Command::new("open");
+  #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+  let mut command = Command::new("xdg-open");
+
+  command
+    .arg(path)
+    .spawn()
+    .map(|_| ())
+    .map_err(|e| format!("Failed to open {}: {}", path.display(), e))
+}
+
+/**
+ * Resolve a path relative to AppData, rejecting anything that escapes it
+ * (absolute paths, `..` components, or symlinks pointing outside).
+ */
+fn resolve_within_app_data(app_data: &Path, relative_path: &str) -> Result<PathBuf, String> {
+  let relative = Path::new(relative_path);
+  if relative.is_absolute() || relative.components().any(|c| c == std::path::Component::ParentDir) {
+    return Err(format!("Path must stay within AppData: {}", relative_path));
+  }
+
+  let root = canonicalize_app_data(app_data);
+  let resolved = resolve_path(&root.join(relative)).map_err(|e| e.to_string())?;
+  if !resolved.starts_with(&root) {
+    return Err(format!("Path must stay within AppData: {}", relative_path));
+  }
+  if !resolved.exists() {
+    return Err(format!("Path not found: {}", relative_path));
+  }
+
+  Ok(resolved)
+}
+
+/**
+ * Show a file or folder in the platform file manager, selected in its parent folder where supported.
+ */
+fn reveal_path(path: &Path) -> Result<(), String> {
+  #[cfg(target_os = "windows")]
+  let result = Command::new("explorer").arg(format!("/select,{}", path.display())).spawn();
+  #[cfg(target_os = "macos")]
+  let result = Command::new("open").arg("-R").arg(path).spawn();
+  // No common "select" flag on Linux desktops: open the containing folder instead
+  #[cfg(not(any(target_os = "windows", target_os = "macos")))]
+  let result = Command::new("xdg-open")
+    .arg(if path.is_dir() { path } else { path.parent().unwrap_or(path) })
+    .spawn();
+
+  result
+    .map(|_| ())
+    .map_err(|e| format!("Failed to reveal {}: {}", path.display(), e))
+}
+
+/**
+ * Reveal a file or folder inside AppData (e.g. an attachment) in the platform file manager.
+ */
+#[tauri::command]
+pub async fn reveal_in_file_manager(app: AppHandle, relative_path: String) -> Result<(), String> {
+  let app_data = app.path().resolve("AppData", tauri::path::BaseDirectory::AppData)
+    .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+  reveal_path(&resolve_within_app_data(&app_data, &relative_path)?)
+}
+
+/**
+ * Open the AppData root in the platform file manager.
+ */
+#[tauri::command]
+pub async fn open_app_data_folder(app: AppHandle) -> Result<(), String> {
+  let app_data = app.path().resolve("AppData", tauri::path::BaseDirectory::AppData)
+    .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+  open_in_file_manager(&canonicalize_app_data(&app_data))
+}
+
+/**
+ * Get the path of today's log file, so users can attach it to bug reports.
+ */
+#[tauri::command]
+pub async fn get_log_file_path(app: AppHandle) -> Result<String, String> {
+  let path = match crate::logging::current_log_file() {
+    Some(path) => path,
+    None => crate::logging::log_file_path(&get_logs_dir(&app)?, chrono::Local::now().date_naive()),
+  };
+
+  Ok(path.to_string_lossy().to_string())
+}
+
+/**
+ * Open the logs folder in the platform file manager.
+ */
+#[tauri::command]
+pub async fn open_logs_folder(app: AppHandle) -> Result<(), String> {
+  let logs_dir = get_logs_dir(&app)?;
+  std::fs::create_dir_all(&logs_dir)
+    .map_err(|e| format!("Failed to create logs directory: {}", e))?;
+
+  open_in_file_manager(&logs_dir)
+}
+
+/**
+ * Free space (in bytes) available to the current user on the volume containing `path`.
+ */
+#[cfg(unix)]
+#[allow(clippy::unnecessary_cast)]
+fn free_disk_space(path: &Path) -> Option<u64> {
+  use std::ffi::CString;
+  use std::os::unix::ffi::OsStrExt;
+
+  let c_path = CString::new(path.as_os_str().as_bytes()).ok()?;
+  let mut stat: libc::statvfs = unsafe { std::mem::zeroed() };
+
+  // SAFETY: c_path is a valid NUL-terminated string and stat is a valid out pointer
+  if unsafe { libc::statvfs(c_path.as_ptr(), &mut stat) } != 0 {
+    return None;
+  }
+
+  Some(stat.f_bavail as u64 * stat.f_frsize as u64)
+}
+
+/**
+ * Free space (in bytes) available to the current user on the volume containing `path`.
+ */
+#[cfg(windows)]
+fn free_disk_space(path: &Path) -> Option<u64> {
+  use std::os::windows::ffi::OsStrExt;
+  use windows_sys::Win32::Storage::FileSystem::GetDiskFreeSpaceExW;
+
+  let wide: Vec<u16> = path.as_os_str().encode_wide().chain(std::iter::once(0)).collect();
+  let mut free_bytes: u64 = 0;
+
+  // SAFETY: wide is NUL-terminated and free_bytes is a valid out pointer
+  let ok = unsafe { GetDiskFreeSpaceExW(wide.as_ptr(), &mut free_bytes, std::ptr::null_mut(), std::ptr::null_mut()) };
+  if ok == 0 {
+    return None;
+  }
+
+  Some(free_bytes)
+}
+
+#[cfg(not(any(unix, windows)))]
+fn free_disk_space(_path: &Path) -> Option<u64> {
+  None
+}
+
+/**
+ * Collect diagnostics for an app using `app_data` as its data directory.
+ */
+fn collect_diagnostics(app_name: String, app_version: String, app_data: &Path) -> Diagnostics {
+  // The data directory may not exist yet on first launch; measure its volume instead
+  let disk_path = app_data
+    .ancestors()
+    .find(|path| path.exists())
+    .unwrap_or(app_data);
+
+  Diagnostics {
+    os: std::env::consts::OS.to_string(),
+    os_family: std::env::consts::FAMILY.to_string(),
+    arch: std::env::consts::ARCH.to_string(),
+    app_name,
+    app_version,
+    tauri_version: tauri::VERSION.to_string(),
+    webview_version: tauri::webview_version().ok(),
+    debug_build: cfg!(debug_assertions),
+    app_data_path: app_data.to_string_lossy().to_string(),
+    free_disk_space_bytes: free_disk_space(disk_path),
+    log_file_path: crate::logging::current_log_file().map(|p| p.to_string_lossy().to_string()),
+    migration_status: migration_status(app_data).ok(),
+  }
+}
+
+/**
+ * Get platform and app diagnostics for an "About / Support" dialog.
+ */
+#[tauri::command]
+pub async fn get_diagnostics(app: AppHandle) -> Result<Diagnostics, String> {
+  let app_data = app.path().resolve("AppData", tauri::path::BaseDirectory::AppData)
+    .map_err(|e| format!("Failed to get app data directory: {}", e))?;
+
+  let package_info = app.package_info();
+
+  Ok(collect_diagnostics(
+    package_info.name.clone(),
+    package_info.version.to_string(),
+    &app_data,
+  ))
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -70,4 +342,82 @@ mod tests {
     assert!(log_message("error".to_string(), "Error".to_string(), None).is_ok());
     assert!(log_message("unknown".to_string(), "Unknown".to_string(), None).is_ok()); // Defaults to info
   }
+
+  #[test]
+  fn test_parse_level() {
+    assert_eq!(parse_level("WARN"), Level::Warn);
+    assert_eq!(parse_level("error"), Level::Error);
+    assert_eq!(parse_level("verbose"), Level::Info);
+  }
+
+  #[test]
+  fn test_event_line_is_well_formed_json() {
+    let mut fields = HashMap::new();
+    fields.insert("topic_id".to_string(), serde_json::json!("topic-1"));
+    fields.insert("tokens".to_string(), serde_json::json!(128));
+    fields.insert("latency_ms".to_string(), serde_json::json!(42.5));
+
+    let line = format_event_line(parse_level("warn"), "message_sent", &fields);
+    assert!(!line.contains('\n'));
+
+    let value: serde_json::Value = serde_json::from_str(&line).unwrap();
+    assert!(chrono::DateTime::parse_from_rfc3339(value["timestamp"].as_str().unwrap()).is_ok());
+    assert_eq!(value["level"], "warn");
+    assert_eq!(value["event"], "message_sent");
+    assert_eq!(value["fields"]["topic_id"], "topic-1");
+    assert_eq!(value["fields"]["tokens"], 128);
+    assert_eq!(value["fields"]["latency_ms"], 42.5);
+  }
+
+  #[test]
+  fn test_log_event_requires_event_name() {
+    assert!(log_event("info".to_string(), "app_started".to_string(), HashMap::new()).is_ok());
+    assert!(log_event("info".to_string(), "  ".to_string(), HashMap::new()).is_err());
+  }
+
+  #[test]
+  fn test_diagnostics_core_fields() {
+    let app_data = std::env::temp_dir().join(format!("vcp_diagnostics_test_{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(&app_data).unwrap();
+
+    let diagnostics = collect_diagnostics("apexbridge".to_string(), "1.0.0".to_string(), &app_data);
+
+    assert!(!diagnostics.os.is_empty());
+    assert!(!diagnostics.arch.is_empty());
+    assert_eq!(diagnostics.app_name, "apexbridge");
+    assert_eq!(diagnostics.app_version, "1.0.0");
+    assert!(!diagnostics.tauri_version.is_empty());
+    assert_eq!(diagnostics.app_data_path, app_data.to_string_lossy());
+    #[cfg(unix)]
+    assert!(diagnostics.free_disk_space_bytes.is_some());
+
+    let json = serde_json::to_string(&diagnostics).unwrap();
+    assert!(!json.contains("api_key"));
+
+    let _ = std::fs::remove_dir_all(&app_data);
+  }
+
+  #[test]
+  fn test_reveal_path_must_stay_within_app_data() {
+    let app_data = std::env::temp_dir().join(format!("vcp_reveal_test_{}", uuid::Uuid::new_v4()));
+    std::fs::create_dir_all(app_data.join("attachments")).unwrap();
+    std::fs::write(app_data.join("attachments").join("photo.png"), b"png").unwrap();
+
+    let resolved = resolve_within_app_data(&app_data, "attachments/photo.png").unwrap();
+    assert!(resolved.ends_with("attachments/photo.png"));
+    assert!(resolve_within_app_data(&app_data, "").is_ok());
+
+    assert!(resolve_within_app_data(&app_data, "../outside.txt").is_err());
+    assert!(resolve_within_app_data(&app_data, "attachments/../../outside.txt").is_err());
+    assert!(resolve_within_app_data(&app_data, &std::env::temp_dir().to_string_lossy()).is_err());
+    assert!(resolve_within_app_data(&app_data, "attachments/missing.png").is_err());
+
+    #[cfg(unix)]
+    {
+      std::os::unix::fs::symlink(std::env::temp_dir(), app_data.join("escape")).unwrap();
+      assert!(resolve_within_app_data(&app_data, "escape").is_err());
+    }
+
+    let _ = std::fs::remove_dir_all(&app_data);
+  }
 }
@@ -1,4 +1,5 @@
-use log::{debug, info};
+use log::{debug, error, info, warn};
+use tauri::Manager;
 
 // Data models module
 pub mod models;
@@ -9,17 +10,11 @@ pub mod commands;
 // Plugin system module (Phase 1 - P0)
 pub mod plugin;
 
+// Application logging (rotating log files)
+pub mod logging;
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-  // Initialize env_logger for terminal logging in development mode
-  if cfg!(debug_assertions) {
-    env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info"))
-      .format_timestamp_millis()
-      .init();
-    info!("VCPChat Tauri - Development Mode");
-    debug!("Debug logging enabled");
-  }
-
   tauri::Builder::default()
     .plugin(tauri_plugin_fs::init())
     .plugin(tauri_plugin_shell::init())
@@ -31,16 +26,32 @@ pub fn run() {
       // File system commands
       commands::read_conversation,
       commands::write_conversation,
+      commands::append_message,
+      commands::append_to_streaming_message,
+      commands::finalize_streaming_message,
+      commands::edit_message,
+      commands::delete_message,
+      commands::fork_conversation,
+      commands::import_conversation,
       commands::delete_conversation,
+      commands::delete_topics_matching,
       commands::list_topics,
+      commands::set_topic_pinned,
+      commands::estimate_topic_tokens,
+      commands::get_topic_stats,
       commands::read_agent,
       commands::write_agent,
       commands::delete_agent,
       commands::list_agents,
+      commands::list_agents_by_tag,
+      commands::list_all_tags,
       commands::read_group,
       commands::write_group,
       commands::delete_group,
       commands::list_groups,
+      commands::get_recent_activity,
+      commands::search_conversations,
+      commands::rebuild_search_index,
       // Canvas commands (CORE-044)
       commands::read_canvas,
       commands::write_canvas,
@@ -49,6 +60,8 @@ pub fn run() {
       // Settings commands
       commands::read_settings,
       commands::write_settings,
+      commands::update_settings,
+      commands::validate_settings,
       // Window commands
       commands::set_window_always_on_top,
       commands::set_window_transparency,
@@ -59,15 +72,118 @@ pub fn run() {
       commands::save_attachment,
       commands::read_attachment,
       commands::delete_attachment,
+      commands::find_orphaned_attachments,
+      commands::cleanup_orphaned_attachments,
+      // Notification commands
+      commands::add_notification,
+      commands::list_notifications,
+      commands::mark_notification_read,
+      commands::mark_all_read,
+      commands::delete_notification,
       // Migration commands
       commands::migrate_from_electron,
+      commands::cancel_migration,
       commands::check_migration_status,
+      commands::rollback_migration,
+      commands::restore_from_backup,
+      // Data integrity commands
+      commands::verify_data_integrity,
+      commands::repair_data,
+      // Backup commands
+      commands::create_backup,
+      commands::restore_backup,
+      commands::compact_data,
+      // Plugin commands
+      commands::verify_plugin_integrity,
+      commands::preview_plugin_uninstall,
+      commands::uninstall_plugin,
+      commands::get_plugin_dependency_graph,
+      commands::check_plugin_health,
+      commands::get_plugin_resource_usage,
+      commands::set_plugin_resource_limits,
+      commands::set_plugin_restart_policy,
+      commands::activate_plugins,
+      commands::is_safe_mode,
+      commands::list_plugin_views,
+      commands::get_plugin_config_schema,
+      commands::get_plugin_config,
+      commands::set_plugin_config,
+      commands::get_plugin_rate_limit,
+      commands::set_plugin_rate_limit,
+      commands::get_plugin_icon,
+      commands::check_plugin_updates,
+      commands::export_plugin_permissions,
+      commands::import_plugin_permissions,
+      commands::list_plugin_keybindings,
+      commands::list_keybinding_conflicts,
+      commands::resolve_plugin_key,
       // Utility commands
       commands::log_message,
+      commands::log_event,
+      commands::get_log_file_path,
+      commands::open_logs_folder,
+      commands::reveal_in_file_manager,
+      commands::open_app_data_folder,
+      commands::get_diagnostics,
     ])
     .setup(|app| {
+      // Initialize file logging (AppData/logs, echoed to the terminal in development mode)
+      let logs_dir = app.path().resolve("logs", tauri::path::BaseDirectory::AppData)?;
+      if let Err(e) = logging::init(logs_dir) {
+        eprintln!("{}", e);
+      }
+
+      if cfg!(debug_assertions) {
+        info!("VCPChat Tauri - Development Mode");
+        debug!("Debug logging enabled");
+      }
+
       info!("Tauri application setup starting...");
 
+      // Plugin system shares the AppData root with the other data commands
+      let app_data = app.path().resolve("AppData", tauri::path::BaseDirectory::AppData)?;
+      let settings = commands::settings::load_settings(&app_data.join("settings.json")).ok();
+      // ...unless settings relocate the plugins directory
+      let plugins_directory = settings
+        .as_ref()
+        .and_then(|settings| settings.plugins_directory.clone())
+        .filter(|dir| !dir.is_empty());
+      let safe_mode = plugin::plugin_manager::safe_mode_requested(
+        &std::env::args().collect::<Vec<_>>(),
+        std::env::var(plugin::plugin_manager::SAFE_MODE_ENV).ok().as_deref(),
+        &app_data,
+      );
+      let relocated = plugins_directory.is_some();
+      // No permission prompt exists yet, so permissions a plugin requests are denied
+      // (grants come from the default grants in settings or an import)
+      let plugin_manager = match plugins_directory {
+        Some(dir) => plugin::plugin_manager::PluginManager::with_plugins_dir(app_data, dir.into(), false),
+        None => plugin::plugin_manager::PluginManager::with_auto_approve(app_data, false),
+      };
+      // Signature policy and proxy must be in place before any plugin is discovered or installed
+      if let Some(settings) = &settings {
+        plugin_manager.apply_settings(settings);
+      }
+      if safe_mode {
+        warn!("Starting in safe mode: plugins will not be activated automatically");
+        plugin_manager.set_safe_mode(true);
+      }
+      // The default directory is created with the first install; a configured one should work now
+      if relocated {
+        if let Err(e) = plugin_manager.ensure_plugins_dir_writable() {
+          error!("{}", e);
+        }
+      }
+      match plugin_manager.discover_installed_plugins() {
+        Ok(plugin_ids) => info!("Discovered {} installed plugin(s) in {}", plugin_ids.len(), plugin_manager.plugins_dir().display()),
+        Err(e) => warn!("Failed to scan plugins directory: {}", e),
+      }
+      if let Err(e) = plugin_manager.activate_startup_plugins() {
+        warn!("Failed to activate startup plugins: {}", e);
+      }
+      app.manage(plugin_manager);
+      commands::start_plugin_health_monitor(app.handle());
+
       // Log application metadata
       info!("App version: {}", app.package_info().version);
       info!("App name: {}", app.package_info().name);
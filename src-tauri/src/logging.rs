@@ -0,0 +1,233 @@
+// Application logging: daily-rotating log files in AppData/logs
+//
+// Log lines go to `app-YYYY-MM-DD.log` in both debug and release builds, and are
+// also echoed to stderr in debug builds. Only the most recent `MAX_LOG_FILES`
+// files are kept.
+
+use log::{LevelFilter, Log, Metadata, Record};
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+
+/// Number of daily log files kept on disk
+pub const MAX_LOG_FILES: usize = 7;
+
+/// Directory of the installed file logger
+static LOG_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// The installed file logger, for writing raw (structured) lines
+static LOGGER: OnceLock<&'static FileLogger> = OnceLock::new();
+
+/// Path of the log file for a given day
+pub fn log_file_path(log_dir: &Path, date: chrono::NaiveDate) -> PathBuf {
+    log_dir.join(format!("app-{}.log", date.format("%Y-%m-%d")))
+}
+
+/// Log file currently being written, if the file logger is installed
+pub fn current_log_file() -> Option<PathBuf> {
+    LOG_DIR
+        .get()
+        .map(|dir| log_file_path(dir, chrono::Local::now().date_naive()))
+}
+
+/// Append a raw line (e.g. a JSON event) to the current log file.
+/// Does nothing if the file logger is not installed.
+pub fn write_raw_line(line: &str) {
+    if let Some(logger) = LOGGER.get() {
+        logger.write_line(chrono::Local::now().date_naive(), &format!("{}\n", line));
+    }
+}
+
+/// Delete the oldest `app-*.log` files so that at most `keep` remain
+fn prune_old_logs(log_dir: &Path, keep: usize) -> std::io::Result<()> {
+    let mut logs: Vec<PathBuf> = fs::read_dir(log_dir)?
+        .filter_map(|entry| entry.ok().map(|e| e.path()))
+        .filter(|path| {
+            path.file_name()
+                .and_then(|n| n.to_str())
+                .is_some_and(|n| n.starts_with("app-") && n.ends_with(".log"))
+        })
+        .collect();
+
+    // Dated names sort chronologically
+    logs.sort();
+
+    let excess = logs.len().saturating_sub(keep);
+    for path in logs.into_iter().take(excess) {
+        fs::remove_file(path)?;
+    }
+
+    Ok(())
+}
+
+struct OpenLogFile {
+    date: chrono::NaiveDate,
+    file: File,
+}
+
+/// Logger writing to the daily log file (and stderr in debug builds)
+struct FileLogger {
+    log_dir: PathBuf,
+    level: LevelFilter,
+    echo_to_stderr: bool,
+    current: Mutex<Option<OpenLogFile>>,
+}
+
+impl FileLogger {
+    /// Append a line to the log file for `date`, rotating when the day changes
+    fn write_line(&self, date: chrono::NaiveDate, line: &str) {
+        let Ok(mut current) = self.current.lock() else {
+            return;
+        };
+
+        if current.as_ref().map_or(true, |open| open.date != date) {
+            let file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(log_file_path(&self.log_dir, date));
+
+            *current = match file {
+                Ok(file) => {
+                    let _ = prune_old_logs(&self.log_dir, MAX_LOG_FILES);
+                    Some(OpenLogFile { date, file })
+                }
+                Err(e) => {
+                    eprintln!("Failed to open log file: {}", e);
+                    None
+                }
+            };
+        }
+
+        if let Some(open) = current.as_mut() {
+            let _ = open.file.write_all(line.as_bytes());
+        }
+    }
+}
+
+impl Log for FileLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        let now = chrono::Local::now();
+        let line = format!(
+            "{} {:<5} [{}] {}\n",
+            now.format("%Y-%m-%dT%H:%M:%S%.3f"),
+            record.level(),
+            record.target(),
+            record.args()
+        );
+
+        if self.echo_to_stderr {
+            eprint!("{}", line);
+        }
+
+        self.write_line(now.date_naive(), &line);
+    }
+
+    fn flush(&self) {
+        if let Ok(mut current) = self.current.lock() {
+            if let Some(open) = current.as_mut() {
+                let _ = open.file.flush();
+            }
+        }
+    }
+}
+
+/// Install the file logger writing into `log_dir`.
+/// The level defaults to `info` and can be overridden with `RUST_LOG` (e.g. `RUST_LOG=debug`).
+pub fn init(log_dir: PathBuf) -> Result<(), String> {
+    fs::create_dir_all(&log_dir)
+        .map_err(|e| format!("Failed to create log directory: {}", e))?;
+
+    let level = std::env::var("RUST_LOG")
+        .ok()
+        .and_then(|value| value.parse::<LevelFilter>().ok())
+        .unwrap_or(LevelFilter::Info);
+
+    let logger = FileLogger {
+        log_dir: log_dir.clone(),
+        level,
+        echo_to_stderr: cfg!(debug_assertions),
+        current: Mutex::new(None),
+    };
+
+    let logger: &'static FileLogger = Box::leak(Box::new(logger));
+    log::set_logger(logger)
+        .map_err(|e| format!("Failed to install logger: {}", e))?;
+    log::set_max_level(level);
+
+    let _ = LOG_DIR.set(log_dir);
+    let _ = LOGGER.set(logger);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_log_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("vcp_logging_test_{}", uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn test_logger(log_dir: &Path) -> FileLogger {
+        FileLogger {
+            log_dir: log_dir.to_path_buf(),
+            level: LevelFilter::Info,
+            echo_to_stderr: false,
+            current: Mutex::new(None),
+        }
+    }
+
+    #[test]
+    fn test_log_file_path_is_dated() {
+        let date = chrono::NaiveDate::from_ymd_opt(2025, 3, 7).unwrap();
+        assert_eq!(log_file_path(Path::new("logs"), date), Path::new("logs").join("app-2025-03-07.log"));
+    }
+
+    #[test]
+    fn test_rotates_daily() {
+        let dir = temp_log_dir();
+        let logger = test_logger(&dir);
+        let day1 = chrono::NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        let day2 = day1.succ_opt().unwrap();
+
+        logger.write_line(day1, "first\n");
+        logger.write_line(day1, "second\n");
+        logger.write_line(day2, "third\n");
+
+        assert_eq!(fs::read_to_string(log_file_path(&dir, day1)).unwrap(), "first\nsecond\n");
+        assert_eq!(fs::read_to_string(log_file_path(&dir, day2)).unwrap(), "third\n");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_retention_limit() {
+        let dir = temp_log_dir();
+        let logger = test_logger(&dir);
+        let start = chrono::NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+        fs::write(dir.join("notes.txt"), "unrelated").unwrap();
+
+        for offset in 0..(MAX_LOG_FILES as u64 + 3) {
+            logger.write_line(start + chrono::Days::new(offset), "line\n");
+        }
+
+        let logs = fs::read_dir(&dir).unwrap()
+            .filter(|e| e.as_ref().unwrap().file_name().to_string_lossy().ends_with(".log"))
+            .count();
+        assert_eq!(logs, MAX_LOG_FILES);
+        assert!(!log_file_path(&dir, start).exists());
+        assert!(dir.join("notes.txt").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,26 @@
+// Recent activity data model (Rust)
+use serde::{Deserialize, Serialize};
+
+/// What an activity entry refers to
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ActivityKind {
+    /// A topic was updated (`timestamp` is its `updated_at`)
+    Topic,
+    /// An agent was created (`timestamp` is its `created_at`)
+    Agent,
+    /// A group was created (`timestamp` is its `created_at`)
+    Group,
+}
+
+/// One entry of the merged recent activity list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ActivityItem {
+    pub kind: ActivityKind,
+    pub id: String,
+    /// Topic title, or agent/group name
+    pub title: String,
+    /// Owning agent or group of a topic
+    pub owner_id: Option<String>,
+    pub timestamp: String,
+}
@@ -1,6 +1,9 @@
 // Agent data model (Rust)
 use serde::{Deserialize, Serialize};
 
+/// Longest allowed agent tag
+pub const MAX_TAG_LENGTH: usize = 30;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Agent {
     pub id: String,
@@ -12,11 +15,53 @@ pub struct Agent {
     pub context_token_limit: u32,
     pub max_output_tokens: u32,
     pub created_at: String,
+    /// Last modification (ISO 8601); empty for agents saved before it was tracked
+    #[serde(default)]
+    pub updated_at: String,
+    /// Free-form labels for organizing agents (e.g. "coding", "writing")
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+impl Default for Agent {
+    /// A new agent with a fresh id and the same defaults as the agent editor
+    fn default() -> Self {
+        let now = chrono::Utc::now().to_rfc3339();
+        Agent {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: "New Agent".to_string(),
+            avatar: "assets/avatars/default-agent.png".to_string(),
+            system_prompt: String::new(),
+            model: "gpt-4".to_string(),
+            temperature: 0.7,
+            context_token_limit: 4096,
+            max_output_tokens: 2048,
+            created_at: now.clone(),
+            updated_at: now,
+            tags: Vec::new(),
+        }
+    }
+}
+
+/// Highest temperature that still gives coherent output for `model`.
+/// Anthropic models only accept 0.0-1.0; others degrade noticeably above 1.5.
+pub fn temperature_ceiling(model: &str) -> f32 {
+    if model.to_lowercase().starts_with("claude") {
+        1.0
+    } else {
+        1.5
+    }
 }
 
 impl Agent {
     /// Validate Agent data
     pub fn validate(&self) -> Result<(), String> {
+        self.validate_with_models(&[])
+    }
+
+    /// Validate Agent data, checking `model` against an allow-list of known model ids.
+    /// An empty allow-list only requires the model to be non-empty.
+    pub fn validate_with_models(&self, known_models: &[String]) -> Result<(), String> {
         if self.id.is_empty() {
             return Err("Agent ID is required".to_string());
         }
@@ -29,6 +74,12 @@ impl Agent {
         if self.model.is_empty() {
             return Err("Agent model is required".to_string());
         }
+        if !known_models.is_empty() && !known_models.contains(&self.model) {
+            return Err(match suggest_model(&self.model, known_models) {
+                Some(suggestion) => format!("Unknown model '{}', did you mean '{}'?", self.model, suggestion),
+                None => format!("Unknown model '{}'", self.model),
+            });
+        }
         if self.temperature < 0.0 || self.temperature > 2.0 {
             return Err("Agent temperature must be between 0.0 and 2.0".to_string());
         }
@@ -38,6 +89,170 @@ impl Agent {
         if self.max_output_tokens < 1 {
             return Err("Agent max_output_tokens must be positive".to_string());
         }
+        for tag in &self.tags {
+            if tag.trim().is_empty() || tag.chars().count() > MAX_TAG_LENGTH {
+                return Err(format!("Agent tags must be 1-{} characters", MAX_TAG_LENGTH));
+            }
+        }
+        // The reply has to fit in the context window along with the prompt
+        if self.max_output_tokens > self.context_token_limit {
+            return Err(format!(
+                "Agent max_output_tokens ({}) must not exceed context_token_limit ({})",
+                self.max_output_tokens, self.context_token_limit
+            ));
+        }
         Ok(())
     }
+
+    /// Valid but questionable settings worth surfacing to the user
+    pub fn warnings(&self) -> Vec<String> {
+        let mut warnings = Vec::new();
+
+        let ceiling = temperature_ceiling(&self.model);
+        if self.temperature > ceiling {
+            warnings.push(format!(
+                "Agent temperature {} is above the practical ceiling of {} for model '{}'",
+                self.temperature, ceiling, self.model
+            ));
+        }
+
+        warnings
+    }
+}
+
+/// Suggest the closest known model id for a possibly mistyped input.
+/// Returns `None` when no known model is reasonably close.
+pub fn suggest_model(input: &str, known: &[String]) -> Option<String> {
+    let input_lower = input.to_lowercase();
+    // Allow roughly one edit per three characters, and at least two
+    let max_distance = (input.chars().count() / 3).max(2);
+
+    known
+        .iter()
+        .map(|candidate| (candidate, edit_distance(&input_lower, &candidate.to_lowercase())))
+        .filter(|(_, distance)| *distance <= max_distance)
+        .min_by_key(|(_, distance)| *distance)
+        .map(|(candidate, _)| candidate.clone())
+}
+
+/// Helper: Levenshtein edit distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b_chars.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b_chars.len() + 1];
+        for (j, b_char) in b_chars.iter().enumerate() {
+            let substitution = previous[j] + if a_char == *b_char { 0 } else { 1 };
+            current[j + 1] = substitution
+                .min(previous[j + 1] + 1)
+                .min(current[j] + 1);
+        }
+        previous = current;
+    }
+
+    previous[b_chars.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn known_models() -> Vec<String> {
+        vec!["gpt-4o".to_string(), "gpt-4o-mini".to_string(), "claude-3-5-sonnet".to_string()]
+    }
+
+    fn create_test_agent(model: &str) -> Agent {
+        Agent {
+            id: "agent-1".to_string(),
+            name: "Test Agent".to_string(),
+            avatar: "avatar.png".to_string(),
+            system_prompt: String::new(),
+            model: model.to_string(),
+            temperature: 0.7,
+            context_token_limit: 4000,
+            max_output_tokens: 1000,
+            created_at: "2025-01-01T00:00:00Z".to_string(),
+            updated_at: "2025-01-01T00:00:00Z".to_string(),
+            tags: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_suggest_model_close_match() {
+        assert_eq!(suggest_model("gpt-4", &known_models()), Some("gpt-4o".to_string()));
+        assert_eq!(suggest_model("claude-3-5-sonet", &known_models()), Some("claude-3-5-sonnet".to_string()));
+        assert_eq!(suggest_model("GPT-4O-MINI", &known_models()), Some("gpt-4o-mini".to_string()));
+    }
+
+    #[test]
+    fn test_suggest_model_far_match() {
+        assert_eq!(suggest_model("llama-70b", &known_models()), None);
+        assert_eq!(suggest_model("gpt-4o", &[]), None);
+    }
+
+    #[test]
+    fn test_validate_with_models() {
+        assert!(create_test_agent("gpt-4o").validate_with_models(&known_models()).is_ok());
+
+        let error = create_test_agent("gpt4o").validate_with_models(&known_models()).unwrap_err();
+        assert!(error.contains("did you mean 'gpt-4o'"));
+
+        // No allow-list configured: only the non-empty check applies
+        assert!(create_test_agent("anything").validate().is_ok());
+        assert!(create_test_agent("").validate().is_err());
+    }
+
+    #[test]
+    fn test_default_agent_is_valid() {
+        let agent = Agent::default();
+        assert!(agent.validate().is_ok());
+        assert!(agent.warnings().is_empty());
+        assert_ne!(agent.id, Agent::default().id);
+    }
+
+    #[test]
+    fn test_max_output_tokens_within_context_limit() {
+        let mut agent = create_test_agent("gpt-4o");
+        agent.max_output_tokens = agent.context_token_limit;
+        assert!(agent.validate().is_ok());
+
+        agent.max_output_tokens = agent.context_token_limit + 1;
+        assert_eq!(
+            agent.validate().unwrap_err(),
+            "Agent max_output_tokens (4001) must not exceed context_token_limit (4000)"
+        );
+    }
+
+    #[test]
+    fn test_temperature_ceiling_warning() {
+        let mut agent = create_test_agent("claude-3-5-sonnet");
+        agent.temperature = 1.2;
+        assert!(agent.validate().is_ok());
+        assert_eq!(agent.warnings().len(), 1);
+
+        agent.model = "gpt-4o".to_string();
+        assert!(agent.warnings().is_empty());
+    }
+
+    #[test]
+    fn test_tag_validation() {
+        let mut agent = create_test_agent("gpt-4o");
+        agent.tags = vec!["coding".to_string(), "writing".to_string()];
+        assert!(agent.validate().is_ok());
+
+        agent.tags = vec![" ".to_string()];
+        assert!(agent.validate().is_err());
+        agent.tags = vec!["x".repeat(MAX_TAG_LENGTH + 1)];
+        assert!(agent.validate().is_err());
+    }
+
+    #[test]
+    fn test_agent_without_tags_deserializes() {
+        let json = r#"{"id":"agent-1","name":"Old","avatar":"a.png","system_prompt":"","model":"gpt-4",
+            "temperature":0.7,"context_token_limit":4096,"max_output_tokens":2048,"created_at":"2025-01-01T00:00:00Z"}"#;
+        let agent: Agent = serde_json::from_str(json).unwrap();
+        assert!(agent.tags.is_empty());
+        assert!(agent.updated_at.is_empty());
+    }
 }
@@ -1,8 +1,11 @@
 // Attachment data model (Rust)
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::Path;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+const MB: u64 = 1024 * 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum FileType {
     Image,
@@ -21,6 +24,49 @@ pub struct Attachment {
     pub file_type: FileType,
     pub file_size: u64,
     pub created_at: String,
+    /// Preview image generated for image attachments (relative to AppData)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub thumbnail: Option<String>,
+}
+
+/// Which attachments may be saved, and how large they may be
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AttachmentPolicy {
+    pub allowed_types: Vec<FileType>,
+    /// Largest file of any type not listed in `max_size_by_type`, in bytes
+    pub max_size_bytes: u64,
+    /// Per-type size caps, in bytes
+    pub max_size_by_type: HashMap<FileType, u64>,
+}
+
+impl Default for AttachmentPolicy {
+    /// Every type allowed, with caps generous enough for normal use
+    fn default() -> Self {
+        Self {
+            allowed_types: vec![
+                FileType::Image,
+                FileType::Document,
+                FileType::Pdf,
+                FileType::Audio,
+                FileType::Video,
+                FileType::Other,
+            ],
+            max_size_bytes: 100 * MB,
+            max_size_by_type: HashMap::from([
+                (FileType::Image, 25 * MB),
+                (FileType::Audio, 200 * MB),
+                (FileType::Video, 500 * MB),
+            ]),
+        }
+    }
+}
+
+impl AttachmentPolicy {
+    /// Size cap for a file type, in bytes
+    pub fn max_size_for(&self, file_type: FileType) -> u64 {
+        self.max_size_by_type.get(&file_type).copied().unwrap_or(self.max_size_bytes)
+    }
 }
 
 impl Attachment {
@@ -45,6 +91,33 @@ impl Attachment {
         Ok(())
     }
 
+    /// Check the attachment and its `data_len` bytes of content against `policy`.
+    /// The type is taken from the filename, not the caller-supplied `file_type`, so a
+    /// mislabelled file can't slip past the allowed types or a smaller size cap.
+    pub fn check_policy(&self, policy: &AttachmentPolicy, data_len: u64) -> Result<(), String> {
+        if self.file_size != data_len {
+            return Err(format!(
+                "Attachment file_size ({} bytes) does not match the data received ({} bytes)",
+                self.file_size, data_len
+            ));
+        }
+        let file_type = Self::detect_file_type(&self.filename);
+        if !policy.allowed_types.contains(&file_type) {
+            return Err(format!(
+                "Attachments of type {:?} are not allowed: {}",
+                file_type, self.filename
+            ));
+        }
+        let max_size = policy.max_size_for(file_type);
+        if data_len > max_size {
+            return Err(format!(
+                "Attachment {} is too large ({} bytes, limit for {:?} files is {} bytes)",
+                self.filename, data_len, file_type, max_size
+            ));
+        }
+        Ok(())
+    }
+
     /// Detect file type from filename extension
     pub fn detect_file_type(filename: &str) -> FileType {
         let ext = Path::new(filename)
@@ -2,11 +2,15 @@
 use serde::{Deserialize, Serialize};
 use super::attachment::Attachment;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum MessageSender {
     User,
     Agent,
+    /// System prompt or instruction injected into the conversation
+    System,
+    /// Tool response fed back to the model
+    Tool,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -16,12 +20,74 @@ pub struct ToolCall {
     pub result: Option<String>,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl ToolCall {
+    /// Validate ToolCall data against the given size limits
+    pub fn validate(&self, limits: &MessageLimits) -> Result<(), String> {
+        if self.tool_name.is_empty() {
+            return Err("ToolCall tool_name is required".to_string());
+        }
+        if self.arguments.len() > limits.max_tool_arguments_len {
+            return Err(format!(
+                "ToolCall '{}' arguments exceed {} bytes ({} bytes)",
+                self.tool_name, limits.max_tool_arguments_len, self.arguments.len()
+            ));
+        }
+        if let Some(result) = &self.result {
+            if result.len() > limits.max_tool_result_len {
+                return Err(format!(
+                    "ToolCall '{}' result exceeds {} bytes ({} bytes)",
+                    self.tool_name, limits.max_tool_result_len, result.len()
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Size limits enforced by Message validation to keep conversation files bounded
+#[derive(Debug, Clone, Copy)]
+pub struct MessageLimits {
+    pub max_content_len: usize,
+    pub max_tool_arguments_len: usize,
+    pub max_tool_result_len: usize,
+}
+
+impl Default for MessageLimits {
+    fn default() -> Self {
+        Self {
+            max_content_len: 1_000_000,      // 1 MB
+            max_tool_arguments_len: 100_000, // 100 KB
+            max_tool_result_len: 500_000,    // 500 KB
+        }
+    }
+}
+
+/// Approximate number of characters per token used by the token estimator
+const CHARS_PER_TOKEN: usize = 4;
+
+/// Roughly estimate the token count of a piece of text (chars / 4, rounded up)
+pub fn estimate_text_tokens(text: &str) -> u32 {
+    let chars = text.chars().count();
+    chars.div_ceil(CHARS_PER_TOKEN) as u32
+}
+
+/// Earlier content of an edited message
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MessageEdit {
+    pub content: String,
+    /// When this content was replaced
+    pub edited_at: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct MessageMetadata {
     pub tokens: Option<u32>,
     pub model_used: Option<String>,
     pub latency_ms: Option<u32>,
     pub tool_calls: Option<Vec<ToolCall>>,
+    /// Previous versions of the content, oldest first (kept only when requested on edit)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub edit_history: Option<Vec<MessageEdit>>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,14 +104,25 @@ pub struct Message {
 }
 
 impl Message {
-    /// Validate Message data
+    /// Validate Message data with the default size limits
     pub fn validate(&self) -> Result<(), String> {
+        self.validate_with_limits(&MessageLimits::default())
+    }
+
+    /// Validate Message data with custom size limits
+    pub fn validate_with_limits(&self, limits: &MessageLimits) -> Result<(), String> {
         if self.id.is_empty() {
             return Err("Message ID is required".to_string());
         }
-        if self.content.is_empty() {
+        if self.content.is_empty() && !self.allows_empty_content() {
             return Err("Message content is required".to_string());
         }
+        if self.content.len() > limits.max_content_len {
+            return Err(format!(
+                "Message content exceeds {} bytes ({} bytes)",
+                limits.max_content_len, self.content.len()
+            ));
+        }
         // Validate timestamp
         if chrono::DateTime::parse_from_rfc3339(&self.timestamp).is_err() {
             return Err("Message timestamp must be a valid ISO 8601 timestamp".to_string());
@@ -54,6 +131,283 @@ impl Message {
         for attachment in &self.attachments {
             attachment.validate()?;
         }
+        // Validate tool calls
+        if let Some(tool_calls) = self.metadata.as_ref().and_then(|m| m.tool_calls.as_ref()) {
+            for tool_call in tool_calls {
+                tool_call.validate(limits)?;
+            }
+        }
         Ok(())
     }
+
+    /// Estimate the tokens this message contributes to the model context,
+    /// including any tool call arguments and results
+    pub fn estimate_tokens(&self) -> u32 {
+        let mut tokens = estimate_text_tokens(&self.content);
+        if let Some(tool_calls) = self.metadata.as_ref().and_then(|m| m.tool_calls.as_ref()) {
+            for tool_call in tool_calls {
+                tokens += estimate_text_tokens(&tool_call.arguments);
+                if let Some(result) = &tool_call.result {
+                    tokens += estimate_text_tokens(result);
+                }
+            }
+        }
+        tokens
+    }
+
+    /// Tool responses, agent messages that only carry tool calls and messages still
+    /// streaming in may have no text
+    fn allows_empty_content(&self) -> bool {
+        if self.is_streaming {
+            return true;
+        }
+        match self.sender {
+            MessageSender::Tool => true,
+            MessageSender::Agent => self.metadata
+                .as_ref()
+                .and_then(|m| m.tool_calls.as_ref())
+                .is_some_and(|calls| !calls.is_empty()),
+            MessageSender::User | MessageSender::System => false,
+        }
+    }
+}
+
+/// Result of trimming a conversation to fit a token limit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrimmedMessages {
+    pub messages: Vec<Message>,
+    /// Number of earlier messages omitted to fit the limit
+    pub dropped: usize,
+}
+
+/// Drop the oldest messages until the estimated total fits under `token_limit`.
+///
+/// Messages are never split. `System` messages (when `keep_system` is set) and the
+/// most recent user message are always preserved, so the result may still exceed
+/// the limit if those alone are too large.
+pub fn trim_topic_to_limit(messages: &[Message], token_limit: u32, keep_system: bool) -> TrimmedMessages {
+    let last_user_index = messages
+        .iter()
+        .rposition(|m| matches!(m.sender, MessageSender::User));
+
+    let is_protected = |index: usize, message: &Message| {
+        Some(index) == last_user_index
+            || (keep_system && matches!(message.sender, MessageSender::System))
+    };
+
+    let mut total: u32 = messages.iter().map(|m| m.estimate_tokens()).sum();
+    let mut keep = vec![true; messages.len()];
+
+    for (index, message) in messages.iter().enumerate() {
+        if total <= token_limit {
+            break;
+        }
+        if is_protected(index, message) {
+            continue;
+        }
+        keep[index] = false;
+        total -= message.estimate_tokens();
+    }
+
+    let trimmed: Vec<Message> = messages
+        .iter()
+        .zip(&keep)
+        .filter(|(_, &kept)| kept)
+        .map(|(message, _)| message.clone())
+        .collect();
+
+    TrimmedMessages {
+        dropped: messages.len() - trimmed.len(),
+        messages: trimmed,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_test_message(content: &str) -> Message {
+        Message {
+            id: "msg-1".to_string(),
+            sender: MessageSender::User,
+            sender_id: None,
+            sender_name: None,
+            content: content.to_string(),
+            attachments: Vec::new(),
+            timestamp: "2025-01-01T00:00:00Z".to_string(),
+            is_streaming: false,
+            metadata: None,
+        }
+    }
+
+    fn with_tool_call(mut message: Message, tool_call: ToolCall) -> Message {
+        message.metadata = Some(MessageMetadata {
+            tokens: None,
+            model_used: None,
+            latency_ms: None,
+            tool_calls: Some(vec![tool_call]),
+            edit_history: None,
+        });
+        message
+    }
+
+    #[test]
+    fn test_valid_message() {
+        assert!(create_test_message("Hello").validate().is_ok());
+    }
+
+    #[test]
+    fn test_over_long_content_rejected() {
+        let limits = MessageLimits { max_content_len: 10, ..MessageLimits::default() };
+        let message = create_test_message("This content is far too long");
+
+        let error = message.validate_with_limits(&limits).unwrap_err();
+        assert!(error.contains("content exceeds 10 bytes"));
+    }
+
+    #[test]
+    fn test_empty_tool_name_rejected() {
+        let message = with_tool_call(create_test_message("Calling tool"), ToolCall {
+            tool_name: String::new(),
+            arguments: "{}".to_string(),
+            result: None,
+        });
+
+        assert!(message.validate().unwrap_err().contains("tool_name"));
+    }
+
+    #[test]
+    fn test_over_long_tool_result_rejected() {
+        let limits = MessageLimits { max_tool_result_len: 4, ..MessageLimits::default() };
+        let message = with_tool_call(create_test_message("Calling tool"), ToolCall {
+            tool_name: "search".to_string(),
+            arguments: "{}".to_string(),
+            result: Some("too much output".to_string()),
+        });
+
+        assert!(message.validate_with_limits(&limits).unwrap_err().contains("result exceeds"));
+    }
+
+    #[test]
+    fn test_sender_variants_round_trip() {
+        for (sender, name) in [
+            (MessageSender::User, "user"),
+            (MessageSender::Agent, "agent"),
+            (MessageSender::System, "system"),
+            (MessageSender::Tool, "tool"),
+        ] {
+            let mut message = create_test_message("Hello");
+            message.sender = sender;
+
+            let json = serde_json::to_value(&message).unwrap();
+            assert_eq!(json["sender"], name);
+
+            let parsed: Message = serde_json::from_value(json).unwrap();
+            assert_eq!(serde_json::to_value(&parsed.sender).unwrap(), name);
+        }
+    }
+
+    #[test]
+    fn test_empty_content_allowed_only_for_tool_roles() {
+        let mut message = create_test_message("");
+        assert!(message.validate().is_err());
+
+        message.sender = MessageSender::System;
+        assert!(message.validate().is_err());
+
+        message.sender = MessageSender::Tool;
+        assert!(message.validate().is_ok());
+
+        let mut message = with_tool_call(create_test_message(""), ToolCall {
+            tool_name: "search".to_string(),
+            arguments: "{}".to_string(),
+            result: None,
+        });
+        message.sender = MessageSender::Agent;
+        assert!(message.validate().is_ok());
+    }
+
+    #[test]
+    fn test_estimate_text_tokens() {
+        assert_eq!(estimate_text_tokens(""), 0);
+        assert_eq!(estimate_text_tokens("abcd"), 1);
+        assert_eq!(estimate_text_tokens("abcde"), 2);
+        // Counts characters, not bytes
+        assert_eq!(estimate_text_tokens("你好世界"), 1);
+    }
+
+    fn create_conversation(entries: &[(MessageSender, &str)]) -> Vec<Message> {
+        entries
+            .iter()
+            .enumerate()
+            .map(|(i, (sender, content))| {
+                let mut message = create_test_message(content);
+                message.id = format!("msg-{}", i);
+                message.sender = sender.clone();
+                message
+            })
+            .collect()
+    }
+
+    fn ids(messages: &[Message]) -> Vec<&str> {
+        messages.iter().map(|m| m.id.as_str()).collect()
+    }
+
+    #[test]
+    fn test_trim_keeps_everything_under_limit() {
+        let messages = create_conversation(&[
+            (MessageSender::User, "abcd"),
+            (MessageSender::Agent, "abcd"),
+        ]);
+
+        let result = trim_topic_to_limit(&messages, 10, true);
+        assert_eq!(result.dropped, 0);
+        assert_eq!(ids(&result.messages), vec!["msg-0", "msg-1"]);
+    }
+
+    #[test]
+    fn test_trim_drops_oldest_first() {
+        // Each message is 2 tokens
+        let messages = create_conversation(&[
+            (MessageSender::User, "12345678"),
+            (MessageSender::Agent, "12345678"),
+            (MessageSender::User, "12345678"),
+            (MessageSender::Agent, "12345678"),
+        ]);
+
+        let result = trim_topic_to_limit(&messages, 4, true);
+        assert_eq!(result.dropped, 2);
+        assert_eq!(ids(&result.messages), vec!["msg-2", "msg-3"]);
+    }
+
+    #[test]
+    fn test_trim_preserves_system_messages() {
+        let messages = create_conversation(&[
+            (MessageSender::System, "12345678"),
+            (MessageSender::User, "12345678"),
+            (MessageSender::Agent, "12345678"),
+            (MessageSender::User, "12345678"),
+        ]);
+
+        let result = trim_topic_to_limit(&messages, 4, true);
+        assert_eq!(ids(&result.messages), vec!["msg-0", "msg-3"]);
+        assert_eq!(result.dropped, 2);
+
+        // Without keep_system the system prompt is dropped like any other message
+        let result = trim_topic_to_limit(&messages, 4, false);
+        assert_eq!(ids(&result.messages), vec!["msg-2", "msg-3"]);
+    }
+
+    #[test]
+    fn test_trim_never_drops_latest_user_message() {
+        let messages = create_conversation(&[
+            (MessageSender::Agent, "12345678"),
+            (MessageSender::User, "this message alone is larger than the limit"),
+            (MessageSender::Agent, "12345678"),
+        ]);
+
+        let result = trim_topic_to_limit(&messages, 1, true);
+        assert_eq!(ids(&result.messages), vec!["msg-1"]);
+        assert_eq!(result.dropped, 2);
+    }
 }
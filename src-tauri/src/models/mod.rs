@@ -6,11 +6,15 @@ pub mod message;
 pub mod attachment;
 pub mod settings;
 pub mod notification;
+pub mod activity;
+pub mod search;
 
-pub use agent::Agent;
+pub use agent::{Agent, suggest_model};
 pub use group::{Group, CollaborationMode};
-pub use topic::{Topic, OwnerType};
-pub use message::{Message, MessageSender, MessageMetadata, ToolCall};
-pub use attachment::{Attachment, FileType};
+pub use topic::{Topic, OwnerType, TokenEstimate, MessageTokenEstimate, ConversationImport, TopicStats, TopicFilter, TopicBulkDelete};
+pub use message::{Message, MessageSender, MessageMetadata, MessageEdit, MessageLimits, ToolCall, TrimmedMessages};
+pub use attachment::{Attachment, AttachmentPolicy, FileType};
 pub use settings::{GlobalSettings, WindowPreferences, SidebarWidths, KeyboardShortcut};
 pub use notification::{Notification, NotificationType};
+pub use activity::{ActivityItem, ActivityKind};
+pub use search::{SearchHit, SearchOptions};
@@ -0,0 +1,36 @@
+// Conversation search result model (Rust)
+use serde::{Deserialize, Serialize};
+use super::{MessageSender, OwnerType};
+
+/// How `search_conversations` matches and filters messages
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SearchOptions {
+    /// Treat the query as a regular expression instead of literal text
+    pub use_regex: bool,
+    pub case_sensitive: bool,
+    /// Only messages from this sender
+    pub sender: Option<MessageSender>,
+    /// Only messages at or after this RFC 3339 timestamp
+    pub since: Option<String>,
+    /// Only messages at or before this RFC 3339 timestamp
+    pub until: Option<String>,
+    /// Maximum number of hits (50 if unset)
+    pub limit: Option<usize>,
+}
+
+/// A message matching a search query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchHit {
+    pub topic_id: String,
+    pub topic_title: String,
+    pub owner_id: String,
+    pub owner_type: OwnerType,
+    pub message_id: String,
+    /// Excerpt of the message content around the first match
+    pub snippet: String,
+    /// Match position in the message content, in UTF-16 code units (JavaScript string indices)
+    pub match_start: usize,
+    pub match_end: usize,
+    pub timestamp: String,
+}
@@ -1,5 +1,6 @@
 // GlobalSettings data model (Rust)
 use serde::{Deserialize, Serialize};
+use super::attachment::AttachmentPolicy;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WindowPreferences {
@@ -36,6 +37,49 @@ pub struct GlobalSettings {
     pub sidebar_widths: SidebarWidths,
     pub window_preferences: WindowPreferences,
     pub keyboard_shortcuts: Vec<KeyboardShortcut>,
+    #[serde(default)]
+    pub known_models: Vec<String>,    // 已知模型 ID 白名单 (为空则不校验)
+    #[serde(default = "default_true")]
+    pub notifications_enabled: bool,  // 是否弹出系统通知
+    #[serde(default)]
+    pub do_not_disturb: bool,         // 免打扰: 仅记录通知, 不弹出
+    #[serde(default)]
+    pub enforce_plugin_signatures: bool, // 仅安装已签名的插件
+    #[serde(default)]
+    pub trusted_plugin_keys: Vec<String>, // 受信任的 Ed25519 公钥 (hex)
+    #[serde(default)]
+    pub enforce_plugin_integrity: bool, // 拒绝启用安装后被修改过的插件
+    #[serde(default)]
+    pub per_plugin_audit_logs: bool, // 每个插件额外写入独立的审计日志
+    #[serde(default)]
+    pub http_proxy: Option<String>,   // 插件 HTTP 请求代理 (可选)
+    #[serde(default)]
+    pub https_proxy: Option<String>,  // 插件 HTTPS 请求代理 (可选)
+    #[serde(default)]
+    pub no_proxy: Vec<String>,        // 直连域名 (支持 *.example.com)
+    #[serde(default)]
+    pub plugin_registry_url: Option<String>, // 插件更新检查使用的注册表索引地址 (可选)
+    #[serde(default)]
+    pub default_plugin_permissions: Vec<String>, // 新安装插件默认授予的权限 (如 storage.read:plugin-data/{id}/*)
+    #[serde(default)]
+    pub plugins_directory: Option<String>, // 插件安装目录 (为空则使用 AppData/plugins)
+    #[serde(default)]
+    pub attachment_policy: AttachmentPolicy, // 附件类型与大小限制
+    #[serde(default = "default_true")]
+    pub strip_image_metadata: bool,   // 保存图片附件时移除 EXIF/GPS 等元数据
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: u32,    // 回收站保留天数 (compact_data 清理更早的项目)
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Days trashed items are kept before `compact_data` deletes them
+pub const DEFAULT_TRASH_RETENTION_DAYS: u32 = 30;
+
+fn default_trash_retention_days() -> u32 {
+    DEFAULT_TRASH_RETENTION_DAYS
 }
 
 impl GlobalSettings {
@@ -76,49 +120,198 @@ impl GlobalSettings {
                     keys: "Ctrl+F".to_string(),
                 },
             ],
+            known_models: Vec::new(),
+            notifications_enabled: true,
+            do_not_disturb: false,
+            enforce_plugin_signatures: false,
+            trusted_plugin_keys: Vec::new(),
+            enforce_plugin_integrity: false,
+            per_plugin_audit_logs: false,
+            http_proxy: None,
+            https_proxy: None,
+            no_proxy: Vec::new(),
+            plugin_registry_url: None,
+            default_plugin_permissions: Vec::new(),
+            plugins_directory: None,
+            attachment_policy: AttachmentPolicy::default(),
+            strip_image_metadata: true,
+            trash_retention_days: DEFAULT_TRASH_RETENTION_DAYS,
         }
     }
 
-    /// Validate GlobalSettings data
+    /// Validate GlobalSettings data, returning the first failure
     pub fn validate(&self) -> Result<(), String> {
-        // Validate URL
-        if url::Url::parse(&self.backend_url).is_err() {
-            return Err("Settings backend_url must be a valid HTTP(S) URL".to_string());
+        match self.validation_errors().into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Run every GlobalSettings check and collect all failures
+    pub fn validation_errors(&self) -> Vec<String> {
+        let mut errors = Vec::new();
+
+        // Validate URLs
+        if !is_url_with_scheme(&self.backend_url, &["http", "https"]) {
+            errors.push("Settings backend_url must be a valid HTTP(S) URL with a host".to_string());
+        }
+        if let Some(websocket_url) = &self.websocket_url {
+            if !websocket_url.is_empty() && !is_url_with_scheme(websocket_url, &["ws", "wss"]) {
+                errors.push("Settings websocket_url must be a valid WS(S) URL with a host".to_string());
+            }
         }
 
         if self.user_name.is_empty() || self.user_name.len() > 50 {
-            return Err("Settings user_name must be 1-50 characters".to_string());
+            errors.push("Settings user_name must be 1-50 characters".to_string());
         }
 
         if self.user_avatar.is_empty() {
-            return Err("Settings user_avatar is required".to_string());
+            errors.push("Settings user_avatar is required".to_string());
         }
 
         if self.theme.is_empty() {
-            return Err("Settings theme is required".to_string());
+            errors.push("Settings theme is required".to_string());
         }
 
         // Validate transparency
         if self.window_preferences.transparency < 0.0 || self.window_preferences.transparency > 1.0 {
-            return Err("Settings window transparency must be between 0.0 and 1.0".to_string());
+            errors.push("Settings window transparency must be between 0.0 and 1.0".to_string());
         }
 
         // Validate window size
         if self.window_preferences.width < 800 {
-            return Err("Settings window width must be >= 800".to_string());
+            errors.push("Settings window width must be >= 800".to_string());
         }
         if self.window_preferences.height < 600 {
-            return Err("Settings window height must be >= 600".to_string());
+            errors.push("Settings window height must be >= 600".to_string());
         }
 
         // Validate sidebar widths
         if self.sidebar_widths.agents_list < 200 || self.sidebar_widths.agents_list > 600 {
-            return Err("Settings agents_list sidebar width must be between 200 and 600".to_string());
+            errors.push("Settings agents_list sidebar width must be between 200 and 600".to_string());
         }
         if self.sidebar_widths.notifications < 200 || self.sidebar_widths.notifications > 600 {
-            return Err("Settings notifications sidebar width must be between 200 and 600".to_string());
+            errors.push("Settings notifications sidebar width must be between 200 and 600".to_string());
+        }
+
+        // Validate trusted plugin keys (32-byte Ed25519 public keys, hex encoded)
+        for key in &self.trusted_plugin_keys {
+            if key.len() != 64 || !key.chars().all(|c| c.is_ascii_hexdigit()) {
+                errors.push(format!("Settings trusted_plugin_keys entry is not a hex Ed25519 public key: {}", key));
+            }
         }
 
-        Ok(())
+        // Validate plugin proxy URLs
+        for (field, proxy) in [("http_proxy", &self.http_proxy), ("https_proxy", &self.https_proxy)] {
+            if let Some(proxy) = proxy {
+                if !proxy.is_empty() && !is_url_with_scheme(proxy, &["http", "https"]) {
+                    errors.push(format!("Settings {} must be a valid HTTP(S) URL with a host", field));
+                }
+            }
+        }
+
+        if let Some(url) = &self.plugin_registry_url {
+            if !url.is_empty() && !is_url_with_scheme(url, &["http", "https"]) {
+                errors.push("Settings plugin_registry_url must be a valid HTTP(S) URL with a host".to_string());
+            }
+        }
+
+        // A relocated plugins directory must be an absolute path
+        if let Some(dir) = &self.plugins_directory {
+            if !dir.is_empty() && !std::path::Path::new(dir).is_absolute() {
+                errors.push("Settings plugins_directory must be an absolute path".to_string());
+            }
+        }
+
+        let policy = &self.attachment_policy;
+        if policy.max_size_bytes == 0 || policy.max_size_by_type.values().any(|&size| size == 0) {
+            errors.push("Settings attachment_policy size limits must be positive".to_string());
+        }
+
+        errors
+    }
+}
+
+/// Helper: Check that a URL parses, uses one of the allowed schemes and has a non-empty host
+fn is_url_with_scheme(value: &str, schemes: &[&str]) -> bool {
+    match url::Url::parse(value) {
+        Ok(parsed) => {
+            schemes.contains(&parsed.scheme())
+                && parsed.host_str().is_some_and(|host| !host.is_empty())
+        }
+        Err(_) => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_missing_notification_fields_use_defaults() {
+        let mut json = serde_json::to_value(GlobalSettings::default()).unwrap();
+        let object = json.as_object_mut().unwrap();
+        object.remove("notifications_enabled");
+        object.remove("do_not_disturb");
+
+        let settings: GlobalSettings = serde_json::from_value(json).unwrap();
+        assert!(settings.notifications_enabled);
+        assert!(!settings.do_not_disturb);
+    }
+
+    #[test]
+    fn test_default_settings_are_valid() {
+        assert!(GlobalSettings::default().validate().is_ok());
+        assert!(GlobalSettings::default().validation_errors().is_empty());
+    }
+
+    #[test]
+    fn test_validation_errors_collects_every_failure() {
+        let mut settings = GlobalSettings::default();
+        settings.user_name = String::new();
+        settings.theme = String::new();
+        settings.window_preferences.width = 100;
+
+        let errors = settings.validation_errors();
+        assert_eq!(errors.len(), 3);
+        assert_eq!(settings.validate().unwrap_err(), errors[0]);
+    }
+
+    #[test]
+    fn test_backend_url_rejects_file_scheme() {
+        let mut settings = GlobalSettings::default();
+        settings.backend_url = "file:///etc/passwd".to_string();
+        assert!(settings.validate().unwrap_err().contains("backend_url"));
+    }
+
+    #[test]
+    fn test_backend_url_rejects_schemeless_string() {
+        let mut settings = GlobalSettings::default();
+        settings.backend_url = "localhost:6005/v1/chat/completions".to_string();
+        assert!(settings.validate().is_err());
+
+        settings.backend_url = "not a url".to_string();
+        assert!(settings.validate().is_err());
+    }
+
+    #[test]
+    fn test_valid_http_and_ws_urls() {
+        let mut settings = GlobalSettings::default();
+        settings.backend_url = "http://localhost:6005/v1/chat/completions".to_string();
+        assert!(settings.validate().is_ok());
+
+        settings.backend_url = "https://api.example.com/v1/chat/completions".to_string();
+        settings.websocket_url = Some("ws://localhost:6005".to_string());
+        assert!(settings.validate().is_ok());
+
+        settings.websocket_url = Some("wss://api.example.com/ws".to_string());
+        assert!(settings.validate().is_ok());
+    }
+
+    #[test]
+    fn test_websocket_url_requires_ws_scheme() {
+        let mut settings = GlobalSettings::default();
+        settings.websocket_url = Some("http://localhost:6005".to_string());
+        assert!(settings.validate().unwrap_err().contains("websocket_url"));
     }
 }
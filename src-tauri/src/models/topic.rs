@@ -1,6 +1,6 @@
 // Topic data model (Rust)
 use serde::{Deserialize, Serialize};
-use super::message::Message;
+use super::message::{Message, MessageSender};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -9,6 +9,102 @@ pub enum OwnerType {
     Group,
 }
 
+/// Estimated token usage of a single message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageTokenEstimate {
+    pub message_id: String,
+    pub tokens: u32,
+}
+
+/// Estimated token usage of a whole topic relative to its owner's context limit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenEstimate {
+    pub total_tokens: u32,
+    pub messages: Vec<MessageTokenEstimate>,
+    pub token_limit: Option<u32>,
+    pub over_limit: bool,
+}
+
+/// Outcome of importing a shared conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConversationImport {
+    /// Freshly assigned id of the imported topic
+    pub topic_id: String,
+    /// Non-fatal problems, e.g. attachments whose files are missing locally
+    pub warnings: Vec<String>,
+}
+
+/// Which topics a bulk delete selects; every criterion that is set must match
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TopicFilter {
+    /// Topics last updated before this RFC 3339 timestamp
+    pub older_than: Option<String>,
+    /// Topics without any messages
+    pub empty: bool,
+    /// Topics whose title contains this text (case-insensitive)
+    pub title_contains: Option<String>,
+    /// Also select pinned topics, which are otherwise always kept
+    pub include_pinned: bool,
+}
+
+impl TopicFilter {
+    /// A filter without criteria would select every topic, which is never what a cleanup means
+    pub fn validate(&self) -> Result<(), String> {
+        if let Some(older_than) = &self.older_than {
+            chrono::DateTime::parse_from_rfc3339(older_than)
+                .map_err(|_| format!("Invalid older_than timestamp: {}", older_than))?;
+        }
+        if self.older_than.is_none() && !self.empty && self.title_contains.as_deref().unwrap_or("").is_empty() {
+            return Err("Topic filter must set at least one criterion".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn matches(&self, topic: &Topic) -> bool {
+        if topic.pinned && !self.include_pinned {
+            return false;
+        }
+        let older = self.older_than.as_deref().map_or(true, |older_than| {
+            match (
+                chrono::DateTime::parse_from_rfc3339(&topic.updated_at),
+                chrono::DateTime::parse_from_rfc3339(older_than),
+            ) {
+                (Ok(updated_at), Ok(older_than)) => updated_at < older_than,
+                _ => topic.updated_at.as_str() < older_than,
+            }
+        });
+        let empty = !self.empty || topic.messages.is_empty();
+        let titled = self.title_contains.as_deref().map_or(true, |text| {
+            topic.title.to_lowercase().contains(&text.to_lowercase())
+        });
+        older && empty && titled
+    }
+}
+
+/// Outcome of a bulk topic delete
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TopicBulkDelete {
+    /// Topics moved to the trash (or, for a dry run, that would be)
+    pub topic_ids: Vec<String>,
+    pub count: usize,
+    pub dry_run: bool,
+}
+
+/// Summary counts over a topic's messages
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TopicStats {
+    pub message_count: usize,
+    pub user_messages: usize,
+    pub agent_messages: usize,
+    /// Characters of message content (not bytes)
+    pub total_characters: usize,
+    pub messages_with_attachments: usize,
+    pub tool_calls: usize,
+    /// Seconds between the earliest and latest message; None without a datable message
+    pub time_span_secs: Option<i64>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Topic {
     pub id: String,
@@ -18,6 +114,12 @@ pub struct Topic {
     pub messages: Vec<Message>,
     pub created_at: String,
     pub updated_at: String,
+    /// Pinned topics are listed before the others
+    #[serde(default)]
+    pub pinned: bool,
+    /// Id of the topic this one was forked from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub forked_from: Option<String>,
 }
 
 impl Topic {
@@ -41,4 +143,164 @@ impl Topic {
         }
         Ok(())
     }
+
+    /// Estimate token usage of all messages against an optional context limit
+    pub fn estimate_tokens(&self, token_limit: Option<u32>) -> TokenEstimate {
+        let messages: Vec<MessageTokenEstimate> = self.messages
+            .iter()
+            .map(|message| MessageTokenEstimate {
+                message_id: message.id.clone(),
+                tokens: message.estimate_tokens(),
+            })
+            .collect();
+
+        let total_tokens = messages.iter().map(|m| m.tokens).sum();
+
+        TokenEstimate {
+            total_tokens,
+            messages,
+            token_limit,
+            over_limit: token_limit.is_some_and(|limit| total_tokens > limit),
+        }
+    }
+
+    /// Message statistics for the analytics panel
+    pub fn stats(&self) -> TopicStats {
+        let mut stats = TopicStats {
+            message_count: self.messages.len(),
+            ..TopicStats::default()
+        };
+
+        for message in &self.messages {
+            match message.sender {
+                MessageSender::User => stats.user_messages += 1,
+                MessageSender::Agent => stats.agent_messages += 1,
+                MessageSender::System | MessageSender::Tool => {}
+            }
+            stats.total_characters += message.content.chars().count();
+            if !message.attachments.is_empty() {
+                stats.messages_with_attachments += 1;
+            }
+            stats.tool_calls += message.metadata
+                .as_ref()
+                .and_then(|metadata| metadata.tool_calls.as_ref())
+                .map_or(0, |calls| calls.len());
+        }
+
+        // Messages aren't guaranteed to be in timestamp order
+        let timestamps: Vec<_> = self.messages
+            .iter()
+            .filter_map(|message| chrono::DateTime::parse_from_rfc3339(&message.timestamp).ok())
+            .collect();
+        if let (Some(first), Some(last)) = (timestamps.iter().min(), timestamps.iter().max()) {
+            stats.time_span_secs = Some((*last - *first).num_seconds());
+        }
+
+        stats
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::MessageSender;
+
+    fn create_test_topic(contents: &[&str]) -> Topic {
+        Topic {
+            id: "topic-1".to_string(),
+            owner_id: "agent-1".to_string(),
+            owner_type: OwnerType::Agent,
+            title: "Test Topic".to_string(),
+            messages: contents
+                .iter()
+                .enumerate()
+                .map(|(i, content)| Message {
+                    id: format!("msg-{}", i),
+                    sender: MessageSender::User,
+                    sender_id: None,
+                    sender_name: None,
+                    content: content.to_string(),
+                    attachments: Vec::new(),
+                    timestamp: "2025-01-01T00:00:00Z".to_string(),
+                    is_streaming: false,
+                    metadata: None,
+                })
+                .collect(),
+            created_at: "2025-01-01T00:00:00Z".to_string(),
+            updated_at: "2025-01-01T00:00:00Z".to_string(),
+            pinned: false,
+            forked_from: None,
+        }
+    }
+
+    #[test]
+    fn test_estimate_tokens_for_small_topic() {
+        let topic = create_test_topic(&["Hello there!", "Hi", "How are you today?"]);
+
+        let estimate = topic.estimate_tokens(Some(100));
+        assert_eq!(estimate.messages.len(), 3);
+        assert_eq!(estimate.messages[0].message_id, "msg-0");
+        assert_eq!(estimate.messages[0].tokens, 3);
+        assert_eq!(estimate.messages[1].tokens, 1);
+        assert_eq!(estimate.messages[2].tokens, 5);
+        assert_eq!(estimate.total_tokens, 9);
+        assert!(!estimate.over_limit);
+
+        assert!(topic.estimate_tokens(Some(8)).over_limit);
+        assert!(!topic.estimate_tokens(None).over_limit);
+    }
+
+    #[test]
+    fn test_topic_without_pinned_field_loads() {
+        let json = r#"{"id":"topic-1","owner_id":"agent-1","owner_type":"agent","title":"Old",
+            "messages":[],"created_at":"2025-01-01T00:00:00Z","updated_at":"2025-01-01T00:00:00Z"}"#;
+        let topic: Topic = serde_json::from_str(json).unwrap();
+        assert!(!topic.pinned);
+    }
+
+    #[test]
+    fn test_stats() {
+        let mut topic = create_test_topic(&["Hello", "Hi there", "Let me check", "Done"]);
+        topic.messages[1].sender = MessageSender::Agent;
+        topic.messages[1].timestamp = "2025-01-01T00:10:00Z".to_string();
+        topic.messages[2].sender = MessageSender::Tool;
+        topic.messages[3].sender = MessageSender::Agent;
+        topic.messages[3].timestamp = "2025-01-01T00:05:00Z".to_string();
+        topic.messages[3].metadata = Some(crate::models::MessageMetadata {
+            tokens: None,
+            model_used: None,
+            latency_ms: None,
+            tool_calls: Some(vec![
+                crate::models::ToolCall { tool_name: "search".to_string(), arguments: "{}".to_string(), result: None },
+                crate::models::ToolCall { tool_name: "fetch".to_string(), arguments: "{}".to_string(), result: None },
+            ]),
+            edit_history: None,
+        });
+        topic.messages[0].attachments.push(crate::models::Attachment {
+            id: "att-1".to_string(),
+            filename: "a.png".to_string(),
+            file_path: "attachments/a.png".to_string(),
+            file_type: crate::models::FileType::Image,
+            file_size: 3,
+            created_at: "2025-01-01T00:00:00Z".to_string(),
+            thumbnail: None,
+        });
+
+        assert_eq!(topic.stats(), TopicStats {
+            message_count: 4,
+            user_messages: 1,
+            agent_messages: 2,
+            total_characters: 29,
+            messages_with_attachments: 1,
+            tool_calls: 2,
+            time_span_secs: Some(600),
+        });
+    }
+
+    #[test]
+    fn test_stats_for_empty_topic() {
+        let stats = create_test_topic(&[]).stats();
+        assert_eq!(stats, TopicStats::default());
+        assert_eq!(stats.time_span_secs, None);
+    }
 }
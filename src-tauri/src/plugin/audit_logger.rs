@@ -5,9 +5,10 @@
 use super::{PluginError, PluginId, PluginResult};
 use super::permission_manager::PermissionType;
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 use std::fs::{self, OpenOptions};
-use std::io::Write;
-use std::path::PathBuf;
+use std::io::{Read, Seek, SeekFrom, Write};
+use std::path::{Path, PathBuf};
 use chrono::Utc;
 
 /// PLUGIN-065: AuditLogEntry struct with all required fields
@@ -21,24 +22,64 @@ pub struct AuditLogEntry {
     pub result: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub error_message: Option<String>,
+    /// `entry_hash` of the previous entry in the daily file (`GENESIS_HASH` for the first)
+    #[serde(default)]
+    pub prev_hash: String,
+    /// SHA-256 over the entry fields and `prev_hash`, chaining the daily file
+    #[serde(default)]
+    pub entry_hash: String,
 }
 
+/// `prev_hash` of the first entry in a daily file
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+impl AuditLogEntry {
+    /// Hash of the entry fields (in a fixed order) plus `prev_hash`
+    pub fn compute_hash(&self) -> String {
+        let fields = (
+            &self.timestamp,
+            &self.plugin_id,
+            &self.permission_type,
+            &self.resource,
+            &self.action,
+            self.result,
+            &self.error_message,
+            &self.prev_hash,
+        );
+        let serialized = serde_json::to_string(&fields).unwrap_or_default();
+        hex::encode(Sha256::digest(serialized.as_bytes()))
+    }
+}
+
+/// Outcome of re-computing the hash chain of a daily log file
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ChainVerification {
+    Intact { entries: usize },
+    /// First link that doesn't verify (1-based line number in the file)
+    Broken { line: usize, reason: String },
+}
+
+/// Days of audit logs kept before rotation deletes them
+pub const AUDIT_LOG_RETENTION_DAYS: i64 = 30;
+
 /// Audit Logger - Central logging for plugin permission usage
 pub struct AuditLogger {
     log_dir: PathBuf,
+    /// Also write each entry to `audit-logs/{plugin_id}/YYYY-MM-DD.jsonl`
+    per_plugin_logs: bool,
 }
 
 impl AuditLogger {
     /// PLUGIN-065: Initialize audit logger with log directory
+    /// The directory is created with the first entry, so an unused logger leaves AppData untouched
     pub fn new(app_data_dir: PathBuf) -> Self {
         let log_dir = app_data_dir.join("audit-logs");
+        Self { log_dir, per_plugin_logs: false }
+    }
 
-        // Ensure log directory exists
-        if let Err(e) = fs::create_dir_all(&log_dir) {
-            eprintln!("[AuditLogger] Failed to create log directory: {}", e);
-        }
-
-        Self { log_dir }
+    /// Enable or disable the additional per-plugin log files (the combined daily file is always written)
+    pub fn set_per_plugin_logs(&mut self, enabled: bool) {
+        self.per_plugin_logs = enabled;
     }
 
     /// PLUGIN-066: Log permission check to daily JSONL file
@@ -59,9 +100,40 @@ impl AuditLogger {
             action: action.to_string(),
             result,
             error_message: error.map(String::from),
+            prev_hash: String::new(),
+            entry_hash: String::new(),
+        };
+
+        self.record(&entry);
+    }
+
+    /// Log a lifecycle event (e.g. an automatic restart) that isn't tied to a permission
+    pub fn log_lifecycle_event(
+        &mut self,
+        plugin_id: &str,
+        action: &str,
+        detail: &str,
+        result: bool,
+        error: Option<&str>,
+    ) {
+        let entry = AuditLogEntry {
+            timestamp: Utc::now().to_rfc3339(),
+            plugin_id: plugin_id.to_string(),
+            permission_type: "lifecycle".to_string(),
+            resource: detail.to_string(),
+            action: action.to_string(),
+            result,
+            error_message: error.map(String::from),
+            prev_hash: String::new(),
+            entry_hash: String::new(),
         };
 
-        if let Err(e) = self.append_log_entry(&entry) {
+        self.record(&entry);
+    }
+
+    /// Append an entry and rotate old log files
+    fn record(&mut self, entry: &AuditLogEntry) {
+        if let Err(e) = self.append_log_entry(entry.clone()) {
             eprintln!("[AuditLogger] Failed to log entry: {}", e);
         }
 
@@ -72,36 +144,148 @@ impl AuditLogger {
     }
 
     /// PLUGIN-066 & PLUGIN-067: Append entry to today's JSONL file
-    fn append_log_entry(&self, entry: &AuditLogEntry) -> PluginResult<()> {
-        let log_file_path = self.get_log_file_path();
+    fn append_log_entry(&self, mut entry: AuditLogEntry) -> PluginResult<()> {
+        fs::create_dir_all(&self.log_dir)?;
+        let log_file_path = self.get_log_file_path(&self.log_dir);
+
+        // Chain onto the last entry of today's combined file
+        entry.prev_hash = Self::last_entry_hash(&log_file_path)?.unwrap_or_else(|| GENESIS_HASH.to_string());
+        entry.entry_hash = entry.compute_hash();
 
         // PLUGIN-067: Serialize entry to JSON
-        let json = serde_json::to_string(entry)
+        let json = serde_json::to_string(&entry)
             .map_err(|e| PluginError::ManifestError(format!("Failed to serialize log entry: {}", e)))?;
 
-        // Append to JSONL file
+        Self::append_line(&log_file_path, &json)?;
+
+        if self.per_plugin_logs {
+            let plugin_dir = self.plugin_log_dir(&entry.plugin_id);
+            fs::create_dir_all(&plugin_dir)?;
+            Self::append_line(&self.get_log_file_path(&plugin_dir), &json)?;
+        }
+
+        Ok(())
+    }
+
+    /// `entry_hash` of the last entry in a log file, reading only the file's tail
+    fn last_entry_hash(log_file_path: &Path) -> PluginResult<Option<String>> {
+        let mut file = match fs::File::open(log_file_path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let len = file.metadata()?.len();
+
+        // Grow the window until it holds the whole last line
+        let mut window: u64 = 4096;
+        loop {
+            let start = len.saturating_sub(window);
+            file.seek(SeekFrom::Start(start))?;
+            let mut tail = Vec::new();
+            file.read_to_end(&mut tail)?;
+
+            let text = String::from_utf8_lossy(&tail);
+            let trimmed = text.trim_end();
+            match trimmed.rfind('\n') {
+                Some(pos) => return Ok(Self::parse_hash(&trimmed[pos + 1..])),
+                None if start == 0 => return Ok(Self::parse_hash(trimmed)),
+                None => window *= 2,
+            }
+        }
+    }
+
+    fn parse_hash(line: &str) -> Option<String> {
+        serde_json::from_str::<AuditLogEntry>(line)
+            .ok()
+            .map(|entry| entry.entry_hash)
+            .filter(|hash| !hash.is_empty())
+    }
+
+    /// Recompute the hash chain of the combined log for `date` (YYYY-MM-DD).
+    /// Per-plugin files hold copies of the combined entries and are not chained themselves.
+    pub fn verify_log_integrity(&self, date: &str) -> PluginResult<ChainVerification> {
+        let content = fs::read_to_string(self.log_dir.join(format!("{}.jsonl", date)))?;
+
+        let mut prev_hash = GENESIS_HASH.to_string();
+        let mut entries = 0;
+        for (index, line) in content.lines().enumerate() {
+            let line_number = index + 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let broken = |reason: &str| Ok(ChainVerification::Broken { line: line_number, reason: reason.to_string() });
+            let Ok(entry) = serde_json::from_str::<AuditLogEntry>(line) else {
+                return broken("unparseable entry");
+            };
+            if entry.entry_hash.is_empty() {
+                return broken("entry has no hash");
+            }
+            if entry.prev_hash != prev_hash {
+                return broken("previous hash mismatch (entry removed or reordered)");
+            }
+            if entry.compute_hash() != entry.entry_hash {
+                return broken("entry hash mismatch (entry edited)");
+            }
+
+            prev_hash = entry.entry_hash;
+            entries += 1;
+        }
+
+        Ok(ChainVerification::Intact { entries })
+    }
+
+    /// Append one JSONL line to a log file
+    fn append_line(log_file_path: &Path, json: &str) -> PluginResult<()> {
         let mut file = OpenOptions::new()
             .create(true)
             .append(true)
-            .open(&log_file_path)?;
+            .open(log_file_path)?;
 
         writeln!(file, "{}", json)?;
 
         Ok(())
     }
 
-    /// Get log file path for today (YYYY-MM-DD.jsonl)
-    fn get_log_file_path(&self) -> PathBuf {
+    /// Get log file path for today (YYYY-MM-DD.jsonl) inside `dir`
+    fn get_log_file_path(&self, dir: &Path) -> PathBuf {
         let date = Utc::now().format("%Y-%m-%d").to_string();
-        self.log_dir.join(format!("{}.jsonl", date))
+        dir.join(format!("{}.jsonl", date))
+    }
+
+    /// Per-plugin log directory; characters unsafe in a directory name are replaced
+    fn plugin_log_dir(&self, plugin_id: &str) -> PathBuf {
+        let name: String = plugin_id
+            .chars()
+            .map(|c| if c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.' { c } else { '_' })
+            .collect();
+        // "", "." and ".." would resolve to the combined log directory or its parent
+        let name = if name.trim_matches('.').is_empty() { format!("_{}", name) } else { name };
+        self.log_dir.join(name)
     }
 
     /// PLUGIN-068: Rotate logs - keep last 30 days, delete older
     fn rotate_old_logs(&self) -> PluginResult<()> {
-        let entries = fs::read_dir(&self.log_dir)?;
-        let cutoff = Utc::now() - chrono::Duration::days(30);
+        let cutoff = Utc::now() - chrono::Duration::days(AUDIT_LOG_RETENTION_DAYS);
         let cutoff_date = cutoff.format("%Y-%m-%d").to_string();
 
+        Self::rotate_dir(&self.log_dir, &cutoff_date)?;
+
+        // Per-plugin log directories rotate on the same schedule
+        for entry in fs::read_dir(&self.log_dir)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                Self::rotate_dir(&path, &cutoff_date)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Delete log files in `dir` dated before `cutoff_date`
+    fn rotate_dir(dir: &Path, cutoff_date: &str) -> PluginResult<()> {
+        let entries = fs::read_dir(dir)?;
+
         for entry in entries {
             let entry = entry?;
             let path = entry.path();
@@ -109,7 +293,7 @@ impl AuditLogger {
             if path.is_file() {
                 if let Some(file_name) = path.file_stem().and_then(|s| s.to_str()) {
                     // Check if file is older than 30 days
-                    if file_name < cutoff_date.as_str() {
+                    if file_name < cutoff_date {
                         if let Err(e) = fs::remove_file(&path) {
                             eprintln!("[AuditLogger] Failed to delete old log {}: {}", path.display(), e);
                         } else {
@@ -125,9 +309,34 @@ impl AuditLogger {
 
     /// PLUGIN-069: Read audit logs for UI display
     pub fn read_audit_logs(&self, from_date: Option<&str>, to_date: Option<&str>) -> PluginResult<Vec<AuditLogEntry>> {
+        if !self.log_dir.is_dir() {
+            return Ok(Vec::new());
+        }
+        Self::read_log_dir(&self.log_dir, from_date, to_date)
+    }
+
+    /// Read a single plugin's entries from its own log files (requires per-plugin logs)
+    pub fn read_plugin_audit_logs(
+        &self,
+        plugin_id: &str,
+        from_date: Option<&str>,
+        to_date: Option<&str>,
+    ) -> PluginResult<Vec<AuditLogEntry>> {
+        let plugin_dir = self.plugin_log_dir(plugin_id);
+        if !plugin_dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut entries = Self::read_log_dir(&plugin_dir, from_date, to_date)?;
+        entries.retain(|entry| entry.plugin_id == plugin_id);
+        Ok(entries)
+    }
+
+    /// Entries of the daily JSONL files in `dir` within the date range, most recent first
+    fn read_log_dir(dir: &Path, from_date: Option<&str>, to_date: Option<&str>) -> PluginResult<Vec<AuditLogEntry>> {
         let mut entries = Vec::new();
 
-        let dir_entries = fs::read_dir(&self.log_dir)?;
+        let dir_entries = fs::read_dir(dir)?;
 
         for entry in dir_entries {
             let entry = entry?;
@@ -195,3 +404,92 @@ impl AuditLogger {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_per_plugin_logs() {
+        let app_data = std::env::temp_dir().join(format!("vcp_audit_test_{}", uuid::Uuid::new_v4()));
+        let mut logger = AuditLogger::new(app_data.clone());
+
+        // Disabled by default: only the combined file is written
+        logger.log_lifecycle_event("plugin-a", "activate", "startup", true, None);
+        assert!(logger.read_plugin_audit_logs("plugin-a", None, None).unwrap().is_empty());
+
+        logger.set_per_plugin_logs(true);
+        logger.log_lifecycle_event("plugin-a", "deactivate", "shutdown", true, None);
+        logger.log_permission_check("plugin-b", &PermissionType::NetworkRequest, "api.example.com", "request", false, Some("denied"));
+
+        let combined = logger.read_audit_logs(None, None).unwrap();
+        assert_eq!(combined.len(), 3);
+
+        let plugin_a = logger.read_plugin_audit_logs("plugin-a", None, None).unwrap();
+        assert_eq!(plugin_a.len(), 1);
+        assert_eq!(plugin_a[0].action, "deactivate");
+
+        let plugin_b = logger.read_plugin_audit_logs("plugin-b", None, None).unwrap();
+        assert_eq!(plugin_b.len(), 1);
+        assert_eq!(plugin_b[0].error_message.as_deref(), Some("denied"));
+
+        let today = Utc::now().format("%Y-%m-%d").to_string();
+        assert!(app_data.join("audit-logs").join("plugin-a").join(format!("{}.jsonl", today)).is_file());
+        assert!(logger.read_plugin_audit_logs("plugin-a", Some("2000-01-01"), Some("2000-01-02")).unwrap().is_empty());
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_rotation_covers_plugin_logs() {
+        let app_data = std::env::temp_dir().join(format!("vcp_audit_rotation_test_{}", uuid::Uuid::new_v4()));
+        let mut logger = AuditLogger::new(app_data.clone());
+        logger.set_per_plugin_logs(true);
+
+        let plugin_dir = app_data.join("audit-logs").join("plugin-a");
+        fs::create_dir_all(&plugin_dir).unwrap();
+        fs::write(app_data.join("audit-logs").join("2000-01-01.jsonl"), "").unwrap();
+        fs::write(plugin_dir.join("2000-01-01.jsonl"), "").unwrap();
+
+        logger.log_lifecycle_event("plugin-a", "activate", "startup", true, None);
+
+        assert!(!app_data.join("audit-logs").join("2000-01-01.jsonl").exists());
+        assert!(!plugin_dir.join("2000-01-01.jsonl").exists());
+        assert_eq!(logger.read_plugin_audit_logs("plugin-a", None, None).unwrap().len(), 1);
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_hash_chain_verification() {
+        let app_data = std::env::temp_dir().join(format!("vcp_audit_chain_test_{}", uuid::Uuid::new_v4()));
+        let mut logger = AuditLogger::new(app_data.clone());
+        for action in ["activate", "request", "deactivate"] {
+            logger.log_lifecycle_event("plugin-a", action, "detail", true, None);
+        }
+
+        let today = Utc::now().format("%Y-%m-%d").to_string();
+        assert_eq!(logger.verify_log_integrity(&today).unwrap(), ChainVerification::Intact { entries: 3 });
+
+        let log_path = app_data.join("audit-logs").join(format!("{}.jsonl", today));
+        let lines: Vec<String> = fs::read_to_string(&log_path).unwrap().lines().map(String::from).collect();
+        assert_eq!(serde_json::from_str::<AuditLogEntry>(&lines[0]).unwrap().prev_hash, GENESIS_HASH);
+
+        // Editing the second entry breaks the chain at line 2
+        let edited = lines[1].replace("\"result\":true", "\"result\":false");
+        fs::write(&log_path, format!("{}\n{}\n{}\n", lines[0], edited, lines[2])).unwrap();
+        assert!(matches!(
+            logger.verify_log_integrity(&today).unwrap(),
+            ChainVerification::Broken { line: 2, .. }
+        ));
+
+        // Deleting it is detected at the entry that followed
+        fs::write(&log_path, format!("{}\n{}\n", lines[0], lines[2])).unwrap();
+        assert!(matches!(
+            logger.verify_log_integrity(&today).unwrap(),
+            ChainVerification::Broken { line: 2, .. }
+        ));
+
+        let _ = fs::remove_dir_all(&app_data);
+    }
+}
@@ -0,0 +1,150 @@
+// Command routing for contributed plugin commands
+// Maps each command ID to the running plugin that registered it
+
+use super::{PluginError, PluginId, PluginResult};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Executes a plugin's contributed command
+pub trait CommandHandler: Send + Sync {
+    fn execute(&self, command_id: &str, args: serde_json::Value) -> PluginResult<serde_json::Value>;
+}
+
+/// Default handler for manifest-only plugins: delegates to the plugin's sidecar hook
+pub struct SidecarCommandHandler {
+    pub plugin_id: PluginId,
+    pub entry_point: PathBuf,
+}
+
+impl SidecarCommandHandler {
+    pub fn new(plugin_id: PluginId, entry_point: PathBuf) -> Self {
+        Self { plugin_id, entry_point }
+    }
+}
+
+impl CommandHandler for SidecarCommandHandler {
+    fn execute(&self, command_id: &str, _args: serde_json::Value) -> PluginResult<serde_json::Value> {
+        // TODO: In a real implementation, this would:
+        // 1. Send {"type":"command","id":command_id,"args":args} to the plugin's sidecar (entry_point)
+        // 2. Wait for the command's result
+        //
+        // For now, commands complete without a result
+        println!("[CommandRegistry] Executing {} via sidecar of {}", command_id, self.plugin_id);
+        Ok(serde_json::Value::Null)
+    }
+}
+
+/// Registered commands of running plugins
+#[derive(Default)]
+pub struct CommandRegistry {
+    /// Command ID -> owning plugin
+    owners: HashMap<String, PluginId>,
+    /// Native implementations provided for plugins, used instead of the sidecar hook
+    handlers: HashMap<PluginId, Arc<dyn CommandHandler>>,
+}
+
+impl CommandRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Fail if any of `command_ids` is already registered by another plugin
+    pub fn check_available(&self, plugin_id: &str, command_ids: &[String]) -> PluginResult<()> {
+        for command_id in command_ids {
+            if let Some(owner) = self.owners.get(command_id) {
+                if owner != plugin_id {
+                    return Err(PluginError::ActivationError(format!(
+                        "Command '{}' is already registered by plugin '{}'",
+                        command_id, owner
+                    )));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Register all of a plugin's commands, or none if one is taken by another plugin
+    pub fn register(&mut self, plugin_id: &str, command_ids: &[String]) -> PluginResult<()> {
+        self.check_available(plugin_id, command_ids)?;
+
+        for command_id in command_ids {
+            self.owners.insert(command_id.clone(), plugin_id.to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Remove every command of a plugin, returning the removed command IDs
+    pub fn unregister_plugin(&mut self, plugin_id: &str) -> Vec<String> {
+        let mut removed: Vec<String> = self.owners
+            .iter()
+            .filter(|(_, owner)| owner.as_str() == plugin_id)
+            .map(|(command_id, _)| command_id.clone())
+            .collect();
+        removed.sort();
+
+        for command_id in &removed {
+            self.owners.remove(command_id);
+        }
+
+        removed
+    }
+
+    /// Plugin that registered `command_id`
+    pub fn owner(&self, command_id: &str) -> Option<&PluginId> {
+        self.owners.get(command_id)
+    }
+
+    /// Commands registered by a plugin, sorted
+    pub fn commands_of(&self, plugin_id: &str) -> Vec<String> {
+        let mut commands: Vec<String> = self.owners
+            .iter()
+            .filter(|(_, owner)| owner.as_str() == plugin_id)
+            .map(|(command_id, _)| command_id.clone())
+            .collect();
+        commands.sort();
+        commands
+    }
+
+    /// Provide a native implementation for a plugin's commands
+    pub fn provide(&mut self, plugin_id: &str, handler: Arc<dyn CommandHandler>) {
+        self.handlers.insert(plugin_id.to_string(), handler);
+    }
+
+    /// Native implementation provided for a plugin, if any
+    pub fn handler(&self, plugin_id: &str) -> Option<Arc<dyn CommandHandler>> {
+        self.handlers.get(plugin_id).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ids(ids: &[&str]) -> Vec<String> {
+        ids.iter().map(|id| id.to_string()).collect()
+    }
+
+    #[test]
+    fn test_register_and_unregister() {
+        let mut registry = CommandRegistry::new();
+        registry.register("weather", &ids(&["weather.show", "weather.refresh"])).unwrap();
+
+        assert_eq!(registry.owner("weather.show").map(String::as_str), Some("weather"));
+        assert_eq!(registry.commands_of("weather"), ids(&["weather.refresh", "weather.show"]));
+
+        // Re-registering the same plugin's commands is fine; another plugin can't take them
+        registry.register("weather", &ids(&["weather.show"])).unwrap();
+        assert!(matches!(
+            registry.register("clock", &ids(&["clock.show", "weather.show"])),
+            Err(PluginError::ActivationError(msg)) if msg.contains("already registered by plugin 'weather'")
+        ));
+        assert!(registry.owner("clock.show").is_none());
+
+        assert_eq!(registry.unregister_plugin("weather"), ids(&["weather.refresh", "weather.show"]));
+        assert!(registry.owner("weather.show").is_none());
+        registry.register("clock", &ids(&["weather.show"])).unwrap();
+    }
+}
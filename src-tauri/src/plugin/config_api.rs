@@ -0,0 +1,158 @@
+// Typed plugin settings layered over StorageAPI
+// Values are checked against the plugin's declared `configuration` schema on set,
+// and unset (or no longer valid) settings resolve to their declared defaults
+
+use super::{PluginError, PluginId, PluginResult};
+use super::manifest_parser::{ConfigValueType, ConfigurationProperty};
+use super::storage_api::StorageAPI;
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// Settings are stored under this prefix so they don't collide with the plugin's own storage keys
+const CONFIG_KEY_PREFIX: &str = "config:";
+
+pub struct PluginConfigAPI {
+    storage: Arc<StorageAPI>,
+    /// Declared settings per plugin
+    schemas: RwLock<HashMap<PluginId, Vec<ConfigurationProperty>>>,
+}
+
+impl PluginConfigAPI {
+    pub fn new(storage: Arc<StorageAPI>) -> Self {
+        Self {
+            storage,
+            schemas: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Register the settings a plugin declares in its manifest
+    pub fn register_schema(&self, plugin_id: &str, properties: Vec<ConfigurationProperty>) {
+        self.schemas.write().unwrap().insert(plugin_id.to_string(), properties);
+    }
+
+    /// Settings declared by a plugin
+    pub fn schema(&self, plugin_id: &str) -> Vec<ConfigurationProperty> {
+        self.schemas.read().unwrap().get(plugin_id).cloned().unwrap_or_default()
+    }
+
+    fn property(&self, plugin_id: &str, key: &str) -> PluginResult<ConfigurationProperty> {
+        self.schemas
+            .read()
+            .unwrap()
+            .get(plugin_id)
+            .and_then(|properties| properties.iter().find(|p| p.key == key))
+            .cloned()
+            .ok_or_else(|| PluginError::InvalidConfig(
+                format!("Plugin '{}' declares no setting '{}'", plugin_id, key)
+            ))
+    }
+
+    /// Current value of a setting, falling back to its default (`None` if it has neither)
+    pub fn get(&self, plugin_id: &str, key: &str) -> PluginResult<Option<serde_json::Value>> {
+        let property = self.property(plugin_id, key)?;
+        let stored = self.storage
+            .get(plugin_id, &format!("{}{}", CONFIG_KEY_PREFIX, key))?
+            .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).ok())
+            .map(|value| restore_integer(property.value_type, value))
+            .filter(|value| property.check_value(value).is_ok());
+
+        Ok(stored.or(property.default))
+    }
+
+    /// Set a setting; the value must match its declared type (and allowed values)
+    pub fn set(&self, plugin_id: &str, key: &str, value: serde_json::Value) -> PluginResult<()> {
+        let property = self.property(plugin_id, key)?;
+        property.check_value(&value)?;
+        self.storage.set(plugin_id, &format!("{}{}", CONFIG_KEY_PREFIX, key), &value.to_string())
+    }
+
+    /// Clear a setting so it resolves to its default again
+    pub fn reset(&self, plugin_id: &str, key: &str) -> PluginResult<()> {
+        self.property(plugin_id, key)?;
+        self.storage.delete(plugin_id, &format!("{}{}", CONFIG_KEY_PREFIX, key))?;
+        Ok(())
+    }
+
+    /// Every declared setting that has a value or default
+    pub fn get_all(&self, plugin_id: &str) -> PluginResult<serde_json::Map<String, serde_json::Value>> {
+        let mut values = serde_json::Map::new();
+        for property in self.schema(plugin_id) {
+            if let Some(value) = self.get(plugin_id, &property.key)? {
+                values.insert(property.key, value);
+            }
+        }
+        Ok(values)
+    }
+}
+
+/// StorageAPI keeps numbers as floats; give integer settings back their integer form
+fn restore_integer(value_type: ConfigValueType, value: serde_json::Value) -> serde_json::Value {
+    match (value_type, value.as_f64()) {
+        (ConfigValueType::Integer, Some(n)) if n.fract() == 0.0 && !value.is_i64() => serde_json::json!(n as i64),
+        _ => value,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn create_test_config() -> (PluginConfigAPI, Arc<StorageAPI>) {
+        let temp_dir = std::env::temp_dir().join(format!("vcp_config_test_{}", uuid::Uuid::new_v4()));
+        let storage = Arc::new(StorageAPI::new(temp_dir));
+        let config = PluginConfigAPI::new(Arc::clone(&storage));
+
+        let properties: Vec<ConfigurationProperty> = serde_json::from_value(json!([
+            { "key": "units", "type": "string", "default": "metric", "enum": ["metric", "imperial"] },
+            { "key": "refreshMinutes", "type": "integer", "default": 15 },
+            { "key": "showAlerts", "type": "boolean" }
+        ]))
+        .unwrap();
+        config.register_schema("weather", properties);
+
+        (config, storage)
+    }
+
+    #[test]
+    fn test_defaults_for_unset_settings() {
+        let (config, _) = create_test_config();
+
+        assert_eq!(config.get("weather", "units").unwrap(), Some(json!("metric")));
+        assert_eq!(config.get("weather", "refreshMinutes").unwrap(), Some(json!(15)));
+        assert_eq!(config.get("weather", "showAlerts").unwrap(), None);
+
+        config.set("weather", "refreshMinutes", json!(30)).unwrap();
+        assert_eq!(config.get("weather", "refreshMinutes").unwrap(), Some(json!(30)));
+
+        config.reset("weather", "refreshMinutes").unwrap();
+        assert_eq!(config.get("weather", "refreshMinutes").unwrap(), Some(json!(15)));
+
+        let all = config.get_all("weather").unwrap();
+        assert_eq!(all.len(), 2);
+        assert_eq!(all["units"], json!("metric"));
+    }
+
+    #[test]
+    fn test_type_enforcement() {
+        let (config, storage) = create_test_config();
+
+        assert!(matches!(
+            config.set("weather", "refreshMinutes", json!("often")),
+            Err(PluginError::InvalidConfig(msg)) if msg.contains("refreshMinutes")
+        ));
+        assert!(config.set("weather", "refreshMinutes", json!(2.5)).is_err());
+        assert!(config.set("weather", "units", json!("kelvin")).is_err());
+        assert!(config.set("weather", "showAlerts", json!(1)).is_err());
+        assert!(config.set("weather", "undeclared", json!(true)).is_err());
+
+        config.set("weather", "units", json!("imperial")).unwrap();
+        config.set("weather", "showAlerts", json!(true)).unwrap();
+        assert_eq!(config.get("weather", "units").unwrap(), Some(json!("imperial")));
+        assert_eq!(config.get("weather", "showAlerts").unwrap(), Some(json!(true)));
+
+        // A value written around the schema through raw storage is ignored
+        storage.set("weather", "config:units", "\"kelvin\"").unwrap();
+        assert_eq!(config.get("weather", "units").unwrap(), Some(json!("metric")));
+    }
+}
@@ -0,0 +1,171 @@
+// Publish/subscribe bus for plugin-contributed events
+// Subscriptions are tracked as lifecycle resources and dropped when their plugin deactivates
+
+use super::{PluginError, PluginId, PluginResult};
+use super::lifecycle_manager::{LifecycleManager, ResourceType};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+
+/// An event delivered to subscribers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginEvent {
+    pub name: String,
+    /// Plugin that published the event
+    pub source: PluginId,
+    pub payload: serde_json::Value,
+}
+
+struct Subscription {
+    plugin_id: PluginId,
+    listener_id: String,
+    sender: Sender<PluginEvent>,
+}
+
+/// Routes published events to every subscribed plugin
+pub struct PluginEventBus {
+    /// Subscriptions by event name
+    subscriptions: Mutex<HashMap<String, Vec<Subscription>>>,
+    lifecycle_manager: Arc<LifecycleManager>,
+}
+
+impl PluginEventBus {
+    pub fn new(lifecycle_manager: Arc<LifecycleManager>) -> Self {
+        Self {
+            subscriptions: Mutex::new(HashMap::new()),
+            lifecycle_manager,
+        }
+    }
+
+    /// Subscribe a plugin to `event_name`; events arrive on the returned receiver
+    /// until the plugin unsubscribes or deactivates
+    pub fn subscribe(&self, plugin_id: &str, event_name: &str) -> Receiver<PluginEvent> {
+        let (sender, receiver) = mpsc::channel();
+        let listener_id = uuid::Uuid::new_v4().to_string();
+
+        self.subscriptions
+            .lock()
+            .unwrap()
+            .entry(event_name.to_string())
+            .or_default()
+            .push(Subscription {
+                plugin_id: plugin_id.to_string(),
+                listener_id: listener_id.clone(),
+                sender,
+            });
+        self.lifecycle_manager.track_resource(plugin_id, ResourceType::EventListener {
+            event_name: event_name.to_string(),
+            listener_id,
+        });
+
+        receiver
+    }
+
+    /// Publish an event as `plugin_id`, returning how many subscribers received it.
+    /// Events are namespaced "pluginId.eventName"; a plugin may only publish in its own namespace.
+    pub fn publish(&self, plugin_id: &str, event_name: &str, payload: serde_json::Value) -> PluginResult<usize> {
+        let namespace = event_name.split('.').next().unwrap_or_default();
+        if namespace != plugin_id {
+            return Err(PluginError::PermissionDenied(format!(
+                "Plugin '{}' cannot publish event '{}' outside its namespace",
+                plugin_id, event_name
+            )));
+        }
+
+        let event = PluginEvent {
+            name: event_name.to_string(),
+            source: plugin_id.to_string(),
+            payload,
+        };
+
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+        let Some(subscribers) = subscriptions.get_mut(event_name) else {
+            return Ok(0);
+        };
+
+        // Subscribers whose receiver was dropped are pruned
+        let mut delivered = 0;
+        subscribers.retain(|subscription| {
+            let alive = subscription.sender.send(event.clone()).is_ok();
+            if alive {
+                delivered += 1;
+            } else {
+                self.untrack(event_name, subscription);
+            }
+            alive
+        });
+
+        Ok(delivered)
+    }
+
+    /// Drop every subscription of a plugin, returning how many were removed
+    pub fn unsubscribe_plugin(&self, plugin_id: &str) -> usize {
+        let mut removed = 0;
+        let mut subscriptions = self.subscriptions.lock().unwrap();
+
+        for (event_name, subscribers) in subscriptions.iter_mut() {
+            subscribers.retain(|subscription| {
+                if subscription.plugin_id != plugin_id {
+                    return true;
+                }
+                self.untrack(event_name, subscription);
+                removed += 1;
+                false
+            });
+        }
+        subscriptions.retain(|_, subscribers| !subscribers.is_empty());
+
+        removed
+    }
+
+    /// Number of live subscriptions to `event_name`
+    pub fn subscriber_count(&self, event_name: &str) -> usize {
+        self.subscriptions
+            .lock()
+            .unwrap()
+            .get(event_name)
+            .map(|subscribers| subscribers.len())
+            .unwrap_or(0)
+    }
+
+    fn untrack(&self, event_name: &str, subscription: &Subscription) {
+        self.lifecycle_manager.untrack_resource(&subscription.plugin_id, &ResourceType::EventListener {
+            event_name: event_name.to_string(),
+            listener_id: subscription.listener_id.clone(),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_publish_subscribe() {
+        let lifecycle = Arc::new(LifecycleManager::new());
+        let bus = PluginEventBus::new(lifecycle.clone());
+
+        let receiver = bus.subscribe("dashboard", "weather.updated");
+        assert_eq!(lifecycle.get_resource_count("dashboard"), 1);
+
+        let delivered = bus.publish("weather", "weather.updated", serde_json::json!({ "temp": 21 })).unwrap();
+        assert_eq!(delivered, 1);
+
+        let event = receiver.try_recv().unwrap();
+        assert_eq!(event.source, "weather");
+        assert_eq!(event.payload["temp"], 21);
+
+        // Publishing into another plugin's namespace is refused
+        assert!(matches!(
+            bus.publish("dashboard", "weather.updated", serde_json::Value::Null),
+            Err(PluginError::PermissionDenied(_))
+        ));
+
+        // Dropped receivers are pruned on the next publish
+        drop(receiver);
+        assert_eq!(bus.publish("weather", "weather.updated", serde_json::Value::Null).unwrap(), 0);
+        assert_eq!(bus.subscriber_count("weather.updated"), 0);
+        assert_eq!(lifecycle.get_resource_count("dashboard"), 0);
+    }
+}
@@ -3,11 +3,12 @@
 // Provides secure file operations with path validation and audit logging
 
 use super::{PluginError, PluginResult, PluginId};
-use super::permission_manager::PermissionManager;
+use super::permission_manager::{canonicalize_app_data, resolve_path, PermissionManager};
 use super::audit_logger::AuditLogger;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 use std::fs;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use glob::Pattern;
 use notify::{Watcher, RecursiveMode, Event};
@@ -40,10 +41,62 @@ pub struct FileWatchEvent {
     pub path: String,
 }
 
+/// How symlinks inside AppData are treated by path validation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SymlinkPolicy {
+    /// Reject any path that goes through a symlink
+    #[default]
+    Deny,
+    /// Follow symlinks wherever they point; scopes are checked against the link's location in AppData
+    Follow,
+    /// Allow symlinks whose targets resolve inside AppData
+    AllowWithinAppData,
+}
+
+/// Extensions of files that are commonly executed directly
+pub const EXECUTABLE_EXTENSIONS: &[&str] = &[
+    "exe", "com", "bat", "cmd", "msi", "scr", "ps1", "vbs", "lnk", "reg", "dll",
+    "sh", "bash", "command", "app", "so", "dylib", "jar",
+];
+
+/// Which file extensions a plugin may create via `write_file` (lowercase, without the dot)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum WriteExtensionPolicy {
+    /// Only these extensions; files without an extension are rejected
+    Allow(std::collections::HashSet<String>),
+    /// Anything except these extensions
+    Deny(std::collections::HashSet<String>),
+}
+
+impl Default for WriteExtensionPolicy {
+    fn default() -> Self {
+        Self::Deny(EXECUTABLE_EXTENSIONS.iter().map(|ext| ext.to_string()).collect())
+    }
+}
+
+impl WriteExtensionPolicy {
+    /// Whether a file named `path` may be written
+    pub fn allows(&self, path: &Path) -> bool {
+        let extension = path.extension().map(|ext| ext.to_string_lossy().to_lowercase());
+        match (self, extension) {
+            (Self::Allow(allowed), Some(ext)) => allowed.contains(&ext),
+            (Self::Allow(_), None) => false,
+            (Self::Deny(denied), Some(ext)) => !denied.contains(&ext),
+            (Self::Deny(_), None) => true,
+        }
+    }
+}
+
 /// PLUGIN-039 to PLUGIN-045: FileSystemAPI
 /// Manages all file operations with permission validation
 pub struct FileSystemAPI {
-    app_data_dir: PathBuf,
+    /// AppData directory, resolved once at construction (created if missing)
+    canonical_app_data: PathBuf,
+    symlink_policy: SymlinkPolicy,
+    /// Block every write, whatever the plugin was granted (e.g., previewing an untrusted plugin)
+    read_only: AtomicBool,
+    /// Per-plugin extension rules for written files (`WriteExtensionPolicy::default()` otherwise)
+    extension_policies: Mutex<std::collections::HashMap<PluginId, WriteExtensionPolicy>>,
     pub(crate) permission_manager: Arc<Mutex<PermissionManager>>,
     audit_logger: Arc<Mutex<AuditLogger>>,
     // File watchers stored per plugin
@@ -57,7 +110,10 @@ impl FileSystemAPI {
         audit_logger: Arc<Mutex<AuditLogger>>,
     ) -> Self {
         Self {
-            app_data_dir,
+            canonical_app_data: canonicalize_app_data(&app_data_dir),
+            symlink_policy: SymlinkPolicy::default(),
+            read_only: AtomicBool::new(false),
+            extension_policies: Mutex::new(std::collections::HashMap::new()),
             permission_manager,
             audit_logger,
             watchers: Arc::new(Mutex::new(std::collections::HashMap::new())),
@@ -69,11 +125,58 @@ impl FileSystemAPI {
         Arc::clone(&self.permission_manager)
     }
 
+    /// Set how symlinks inside AppData are treated (default: `SymlinkPolicy::Deny`)
+    pub fn set_symlink_policy(&mut self, policy: SymlinkPolicy) {
+        self.symlink_policy = policy;
+    }
+
+    /// Disable (or re-enable) all filesystem writes regardless of granted permissions
+    pub fn set_read_only(&self, read_only: bool) {
+        self.read_only.store(read_only, Ordering::SeqCst);
+    }
+
+    pub fn is_read_only(&self) -> bool {
+        self.read_only.load(Ordering::SeqCst)
+    }
+
+    /// Fail a write operation up front while the API is read-only
+    fn ensure_writable(&self, plugin_id: &str, operation: &str, path: &str) -> PluginResult<()> {
+        if self.is_read_only() {
+            self.log_operation(plugin_id, operation, Path::new(path), false, Some("filesystem is read-only"));
+            return Err(PluginError::PermissionDenied("filesystem is read-only".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Restrict which file extensions a plugin may write
+    pub fn set_write_extension_policy(&self, plugin_id: &str, policy: WriteExtensionPolicy) {
+        self.extension_policies.lock().unwrap().insert(plugin_id.to_string(), policy);
+    }
+
+    /// Reject writing a file whose extension the plugin's policy doesn't allow
+    fn check_write_extension(&self, plugin_id: &str, path: &str) -> PluginResult<()> {
+        let allowed = match self.extension_policies.lock().unwrap().get(plugin_id) {
+            Some(policy) => policy.allows(Path::new(path)),
+            None => WriteExtensionPolicy::default().allows(Path::new(path)),
+        };
+
+        if !allowed {
+            let extension = Path::new(path)
+                .extension()
+                .map(|ext| format!(".{}", ext.to_string_lossy()))
+                .unwrap_or_else(|| "(none)".to_string());
+            let reason = format!("File extension {} is not allowed", extension);
+            self.log_operation(plugin_id, "write", Path::new(path), false, Some(&reason));
+            return Err(PluginError::PermissionDenied(reason));
+        }
+        Ok(())
+    }
+
     /// PLUGIN-043: Validate path against security constraints
     /// - Must be within AppData directory
     /// - No parent directory (..) components
     /// - No absolute paths outside AppData
-    /// - No symlinks (future: could be added with extra validation)
+    /// - Symlinks handled according to the `SymlinkPolicy`
     fn validate_path(&self, plugin_id: &str, path: &Path, write: bool) -> PluginResult<PathBuf> {
         // Reject paths with parent directory components
         if path.components().any(|c| c == std::path::Component::ParentDir) {
@@ -90,52 +193,44 @@ impl FileSystemAPI {
         }
 
         // Construct full path within AppData
-        let full_path = self.app_data_dir.join(path);
-
-        // Canonicalize AppData directory for comparison
-        let canonical_app_data = self.app_data_dir.canonicalize().map_err(|e| {
-            PluginError::FileSystemError(format!("Failed to canonicalize AppData dir: {}", e))
-        })?;
+        let canonical_app_data = &self.canonical_app_data;
+        let full_path = canonical_app_data.join(path);
+
+        if self.symlink_policy == SymlinkPolicy::Deny {
+            if let Some(link) = find_symlink(canonical_app_data, path) {
+                return Err(PluginError::PermissionDenied(
+                    format!("Symlinks are not allowed: {}", link.display())
+                ));
+            }
+        }
 
-        // Canonicalize to resolve any symlinks or relative components
-        // Note: This will fail if the path doesn't exist yet (for write operations)
-        let canonical_path = if full_path.exists() {
-            full_path.canonicalize().map_err(|e| {
-                PluginError::FileSystemError(format!("Failed to canonicalize path: {}", e))
-            })?
+        let checked_path = if self.symlink_policy == SymlinkPolicy::Follow {
+            full_path
         } else {
-            // For non-existent paths (write operations), validate parent directory
-            if let Some(parent) = full_path.parent() {
-                if parent.exists() {
-                    let canonical_parent = parent.canonicalize().map_err(|e| {
-                        PluginError::FileSystemError(format!("Failed to canonicalize parent: {}", e))
-                    })?;
-                    canonical_parent.join(full_path.file_name().unwrap())
-                } else {
-                    // Parent doesn't exist yet, just use the AppData-relative path
-                    canonical_app_data.join(path)
-                }
-            } else {
-                canonical_app_data.join(path)
+            // Resolve symlinks and ensure the real location is still within AppData
+            let resolved = resolve_path(&full_path)?;
+            if !resolved.starts_with(canonical_app_data) {
+                return Err(PluginError::PermissionDenied(
+                    "Path escapes AppData directory".to_string()
+                ));
             }
+            resolved
         };
 
-        // Ensure canonical path is still within AppData
-        if !canonical_path.starts_with(&canonical_app_data) {
-            return Err(PluginError::PermissionDenied(
-                "Path escapes AppData directory".to_string()
-            ));
-        }
+        let relative = checked_path
+            .strip_prefix(canonical_app_data)
+            .map(|relative| relative.to_string_lossy().replace('\\', "/"))
+            .map_err(|_| PluginError::PermissionDenied("Path escapes AppData directory".to_string()))?;
 
         // Check permission with PermissionManager
         let pm = self.permission_manager.lock().unwrap();
-        if !pm.validate_filesystem_permission(plugin_id, &canonical_path, write) {
+        if !pm.validate_filesystem_scope(plugin_id, &relative, write) {
             return Err(PluginError::PermissionDenied(
-                format!("No {} permission for path: {}", if write { "write" } else { "read" }, canonical_path.display())
+                format!("No {} permission for path: {}", if write { "write" } else { "read" }, checked_path.display())
             ));
         }
 
-        Ok(canonical_path)
+        Ok(checked_path)
     }
 
     /// PLUGIN-045: Log file operation to audit logger
@@ -176,6 +271,9 @@ impl FileSystemAPI {
 
     /// PLUGIN-040: Write file contents with atomic write
     pub fn write_file(&self, plugin_id: &str, path: &str, contents: &str) -> PluginResult<()> {
+        self.ensure_writable(plugin_id, "write", path)?;
+        self.check_write_extension(plugin_id, path)?;
+
         let path_buf = PathBuf::from(path);
 
         // Validate path and permissions
@@ -260,7 +358,7 @@ impl FileSystemAPI {
             })?;
 
             let file_info = FileInfo {
-                path: entry_path.strip_prefix(&self.app_data_dir)
+                path: entry_path.strip_prefix(&self.canonical_app_data)
                     .unwrap_or(&entry_path)
                     .to_string_lossy()
                     .to_string(),
@@ -338,6 +436,8 @@ impl FileSystemAPI {
 
     /// Delete file
     pub fn delete_file(&self, plugin_id: &str, path: &str) -> PluginResult<()> {
+        self.ensure_writable(plugin_id, "delete", path)?;
+
         let path_buf = PathBuf::from(path);
 
         // Validate path and permissions
@@ -357,6 +457,8 @@ impl FileSystemAPI {
 
     /// Create directory
     pub fn create_directory(&self, plugin_id: &str, path: &str) -> PluginResult<()> {
+        self.ensure_writable(plugin_id, "mkdir", path)?;
+
         let path_buf = PathBuf::from(path);
 
         // Validate path and permissions
@@ -390,6 +492,21 @@ impl FileSystemAPI {
     }
 }
 
+/// First symlink met while walking `relative` down from `root`, if any
+fn find_symlink(root: &Path, relative: &Path) -> Option<PathBuf> {
+    let mut current = root.to_path_buf();
+    for component in relative.components() {
+        current.push(component);
+        match fs::symlink_metadata(&current) {
+            Ok(metadata) if metadata.file_type().is_symlink() => return Some(current),
+            Ok(_) => {}
+            // Nothing below a missing component exists yet
+            Err(_) => return None,
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -439,4 +556,154 @@ mod tests {
         let contents = fs_api.read_file(plugin_id, "test.txt").unwrap();
         assert_eq!(contents, "Hello, World!");
     }
+
+    #[test]
+    fn test_app_data_created_lazily_and_cached() {
+        let root = std::env::temp_dir().join(format!("vcp_fs_lazy_test_{}", uuid::Uuid::new_v4()));
+        let app_data = root.join("AppData");
+        assert!(!root.exists());
+
+        let pm = Arc::new(Mutex::new(PermissionManager::new(app_data.clone())));
+        let logger = Arc::new(Mutex::new(AuditLogger::new(app_data.clone())));
+        let fs_api = FileSystemAPI::new(app_data.clone(), pm, logger);
+
+        let canonical = app_data.canonicalize().unwrap();
+        assert_eq!(fs_api.canonical_app_data, canonical);
+        assert_eq!(fs_api.permission_manager.lock().unwrap().canonical_app_data_dir(), canonical.as_path());
+
+        {
+            let mut pm = fs_api.permission_manager.lock().unwrap();
+            pm.grant_permission("test-plugin", super::super::permission_manager::PermissionType::FilesystemWrite, "AppData/notes/*".to_string()).unwrap();
+            pm.grant_permission("test-plugin", super::super::permission_manager::PermissionType::FilesystemRead, "AppData/notes/*".to_string()).unwrap();
+        }
+
+        // AppData vanishing after startup no longer breaks validation; the write recreates it
+        std::fs::remove_dir_all(&app_data).unwrap();
+        fs_api.write_file("test-plugin", "notes/today.txt", "cached").unwrap();
+        assert_eq!(fs_api.read_file("test-plugin", "notes/today.txt").unwrap(), "cached");
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[cfg(unix)]
+    fn api_with_outside_link(policy: SymlinkPolicy) -> (FileSystemAPI, PathBuf) {
+        let mut fs_api = create_test_filesystem_api();
+        fs_api.set_symlink_policy(policy);
+
+        let outside = std::env::temp_dir().join(format!("vcp_fs_outside_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&outside).unwrap();
+        std::fs::write(outside.join("secret.txt"), "secret").unwrap();
+        std::os::unix::fs::symlink(&outside, fs_api.canonical_app_data.join("escape")).unwrap();
+
+        std::fs::create_dir_all(fs_api.canonical_app_data.join("real")).unwrap();
+        std::fs::write(fs_api.canonical_app_data.join("real").join("notes.txt"), "notes").unwrap();
+        std::os::unix::fs::symlink(fs_api.canonical_app_data.join("real"), fs_api.canonical_app_data.join("alias")).unwrap();
+
+        {
+            let mut pm = fs_api.permission_manager.lock().unwrap();
+            pm.grant_permission("test-plugin", super::super::permission_manager::PermissionType::FilesystemRead, "*".to_string()).unwrap();
+            pm.grant_permission("test-plugin", super::super::permission_manager::PermissionType::FilesystemWrite, "*".to_string()).unwrap();
+        }
+
+        (fs_api, outside)
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_outside_app_data_denied_by_default() {
+        let (fs_api, outside) = api_with_outside_link(SymlinkPolicy::default());
+
+        assert!(matches!(fs_api.read_file("test-plugin", "escape/secret.txt"), Err(PluginError::PermissionDenied(_))));
+        assert!(matches!(fs_api.write_file("test-plugin", "escape/new.txt", "x"), Err(PluginError::PermissionDenied(_))));
+        assert!(!outside.join("new.txt").exists());
+        // Deny rejects links even when they stay inside AppData
+        assert!(fs_api.read_file("test-plugin", "alias/notes.txt").is_err());
+        assert_eq!(fs_api.read_file("test-plugin", "real/notes.txt").unwrap(), "notes");
+
+        let _ = std::fs::remove_dir_all(&outside);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_symlink_policies_within_app_data_and_follow() {
+        let (fs_api, outside) = api_with_outside_link(SymlinkPolicy::AllowWithinAppData);
+        assert!(fs_api.read_file("test-plugin", "escape/secret.txt").is_err());
+        assert!(fs_api.write_file("test-plugin", "escape/new.txt", "x").is_err());
+        assert_eq!(fs_api.read_file("test-plugin", "alias/notes.txt").unwrap(), "notes");
+        let _ = std::fs::remove_dir_all(&outside);
+
+        let (fs_api, outside) = api_with_outside_link(SymlinkPolicy::Follow);
+        assert_eq!(fs_api.read_file("test-plugin", "escape/secret.txt").unwrap(), "secret");
+        let _ = std::fs::remove_dir_all(&outside);
+    }
+
+    #[test]
+    fn test_read_only_blocks_writes() {
+        let fs_api = create_test_filesystem_api();
+        let plugin_id = "test-plugin";
+        {
+            let mut pm = fs_api.permission_manager.lock().unwrap();
+            pm.grant_permission(plugin_id, super::super::permission_manager::PermissionType::FilesystemWrite, "*".to_string()).unwrap();
+            pm.grant_permission(plugin_id, super::super::permission_manager::PermissionType::FilesystemRead, "*".to_string()).unwrap();
+        }
+        fs_api.write_file(plugin_id, "existing.txt", "before").unwrap();
+
+        fs_api.set_read_only(true);
+        let denied = |result: PluginResult<()>| {
+            matches!(result, Err(PluginError::PermissionDenied(msg)) if msg == "filesystem is read-only")
+        };
+        assert!(denied(fs_api.write_file(plugin_id, "new.txt", "after")));
+        assert!(denied(fs_api.write_file(plugin_id, "existing.txt", "after")));
+        assert!(denied(fs_api.delete_file(plugin_id, "existing.txt")));
+        assert!(denied(fs_api.create_directory(plugin_id, "folder")));
+        assert!(!fs_api.canonical_app_data.join("new.txt").exists());
+        assert!(!fs_api.canonical_app_data.join("folder").exists());
+
+        // Reads are unaffected
+        assert_eq!(fs_api.read_file(plugin_id, "existing.txt").unwrap(), "before");
+        assert!(fs_api.exists(plugin_id, "existing.txt").unwrap());
+
+        let blocked = fs_api.audit_logger.lock().unwrap()
+            .read_audit_logs(None, None)
+            .unwrap()
+            .into_iter()
+            .filter(|entry| !entry.result && entry.error_message.as_deref() == Some("filesystem is read-only"))
+            .count();
+        assert_eq!(blocked, 4);
+
+        fs_api.set_read_only(false);
+        fs_api.write_file(plugin_id, "new.txt", "after").unwrap();
+    }
+
+    #[test]
+    fn test_write_extension_policy() {
+        let fs_api = create_test_filesystem_api();
+        let plugin_id = "test-plugin";
+        fs_api.permission_manager.lock().unwrap()
+            .grant_permission(plugin_id, super::super::permission_manager::PermissionType::FilesystemWrite, "*".to_string())
+            .unwrap();
+
+        // Executables are denied by default
+        fs_api.write_file(plugin_id, "config.json", "{}").unwrap();
+        assert!(matches!(
+            fs_api.write_file(plugin_id, "payload.EXE", "MZ"),
+            Err(PluginError::PermissionDenied(msg)) if msg == "File extension .EXE is not allowed"
+        ));
+        assert!(!fs_api.canonical_app_data.join("payload.EXE").exists());
+
+        // An allow-list replaces the default deny-list
+        fs_api.set_write_extension_policy(plugin_id, WriteExtensionPolicy::Allow(["json".to_string()].into_iter().collect()));
+        fs_api.write_file(plugin_id, "data.json", "[]").unwrap();
+        assert!(fs_api.write_file(plugin_id, "notes.txt", "text").is_err());
+        assert!(fs_api.write_file(plugin_id, "Makefile", "all:").is_err());
+
+        let rejection = fs_api.audit_logger.lock().unwrap()
+            .read_audit_logs(None, None)
+            .unwrap()
+            .into_iter()
+            .find(|entry| entry.resource == "payload.EXE")
+            .unwrap();
+        assert!(!rejection.result);
+        assert!(rejection.error_message.unwrap().contains(".EXE"));
+    }
 }
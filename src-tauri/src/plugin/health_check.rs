@@ -0,0 +1,46 @@
+// Plugin health checks
+// Pings running plugins so the host notices when a plugin process died silently
+
+use super::{PluginId, PluginState};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::Duration;
+
+/// How long a plugin may take to answer a ping
+pub const DEFAULT_HEALTH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Result of a health check
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum HealthStatus {
+    /// The plugin answered the ping
+    Healthy,
+    /// The plugin failed the ping and was marked `Crashed`
+    Unhealthy(String),
+    /// The plugin isn't `Running`, so there is nothing to ping
+    NotRunning,
+}
+
+/// Called with (plugin_id, from, to) whenever a plugin changes state outside of an explicit request
+pub type StateChangeListener = Box<dyn Fn(&str, PluginState, PluginState) + Send + Sync>;
+
+/// Pings a running plugin (injectable for testing)
+pub trait HealthProbe: Send + Sync {
+    /// Return an error describing the failure if the plugin doesn't answer within `timeout`
+    fn ping(&self, plugin_id: &PluginId, entry_point: &Path, timeout: Duration) -> Result<(), String>;
+}
+
+/// Default probe: a plugin is healthy while its sidecar entry point is still on disk.
+/// Service processes are additionally checked through the `ServiceRunner` before the probe runs,
+/// so this catches plugins whose files were removed or replaced while they were running.
+pub struct SidecarProbe;
+
+impl HealthProbe for SidecarProbe {
+    fn ping(&self, _plugin_id: &PluginId, entry_point: &Path, _timeout: Duration) -> Result<(), String> {
+        if entry_point.is_file() {
+            Ok(())
+        } else {
+            Err(format!("Entry point is missing: {}", entry_point.display()))
+        }
+    }
+}
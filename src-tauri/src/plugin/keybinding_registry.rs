@@ -0,0 +1,190 @@
+// Contributed keybindings of running plugins, with cross-plugin conflict detection
+// The first plugin to bind a key (in a given `when` context) keeps it; later bindings are recorded as conflicts
+
+use super::PluginId;
+use super::manifest_parser::Keybinding;
+use serde::{Deserialize, Serialize};
+
+/// A keybinding registered by a running plugin
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RegisteredKeybinding {
+    pub plugin_id: PluginId,
+    pub command: String,
+    /// Key as declared in the manifest (e.g. "Ctrl+Shift+K")
+    pub key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub when: Option<String>,
+}
+
+/// A binding that lost to an existing one, kept for the UI to resolve
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct KeybindingConflict {
+    /// Plugin whose binding is in effect
+    pub bound_plugin_id: PluginId,
+    pub bound_command: String,
+    /// Binding that was not applied
+    pub shadowed: RegisteredKeybinding,
+}
+
+#[derive(Debug, Default)]
+pub struct KeybindingRegistry {
+    bindings: Vec<RegisteredKeybinding>,
+    conflicts: Vec<KeybindingConflict>,
+}
+
+/// Canonical form of a key: case-insensitive with modifiers in a fixed order ("shift+ctrl+k" == "Ctrl+Shift+K").
+/// Chords ("Ctrl+K Ctrl+S") are normalized part by part.
+fn normalize_key(key: &str) -> String {
+    const MODIFIERS: [&str; 4] = ["ctrl", "alt", "shift", "meta"];
+
+    key.split_whitespace()
+        .map(|chord| {
+            let mut parts: Vec<String> = chord
+                .split('+')
+                .map(|part| match part.trim().to_lowercase().as_str() {
+                    "control" => "ctrl".to_string(),
+                    "cmd" | "command" | "win" | "super" => "meta".to_string(),
+                    "option" => "alt".to_string(),
+                    other => other.to_string(),
+                })
+                .collect();
+            let rank = |part: &String| MODIFIERS.iter().position(|m| m == part).unwrap_or(MODIFIERS.len());
+            parts.sort_by_key(rank);
+            parts.join("+")
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl KeybindingRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a plugin's keybindings, returning the ones that conflicted with existing bindings
+    pub fn register(&mut self, plugin_id: &str, keybindings: &[Keybinding]) -> Vec<KeybindingConflict> {
+        let incoming: Vec<RegisteredKeybinding> = keybindings
+            .iter()
+            .map(|binding| RegisteredKeybinding {
+                plugin_id: plugin_id.to_string(),
+                command: binding.command.clone(),
+                key: binding.key.clone(),
+                when: binding.when.clone(),
+            })
+            .collect();
+
+        self.insert_all(incoming)
+    }
+
+    fn insert_all(&mut self, incoming: Vec<RegisteredKeybinding>) -> Vec<KeybindingConflict> {
+        let mut conflicts = Vec::new();
+
+        for binding in incoming {
+            let normalized = normalize_key(&binding.key);
+            let existing = self.bindings
+                .iter()
+                .find(|b| normalize_key(&b.key) == normalized && b.when == binding.when);
+
+            match existing {
+                Some(existing) => conflicts.push(KeybindingConflict {
+                    bound_plugin_id: existing.plugin_id.clone(),
+                    bound_command: existing.command.clone(),
+                    shadowed: binding,
+                }),
+                None => self.bindings.push(binding),
+            }
+        }
+
+        self.conflicts.extend(conflicts.iter().cloned());
+        conflicts
+    }
+
+    /// Remove a plugin's bindings; bindings it was shadowing are applied in its place
+    pub fn unregister_plugin(&mut self, plugin_id: &str) {
+        self.bindings.retain(|binding| binding.plugin_id != plugin_id);
+        self.conflicts.retain(|conflict| conflict.shadowed.plugin_id != plugin_id);
+
+        let (released, kept): (Vec<_>, Vec<_>) = std::mem::take(&mut self.conflicts)
+            .into_iter()
+            .partition(|conflict| conflict.bound_plugin_id == plugin_id);
+        self.conflicts = kept;
+
+        self.insert_all(released.into_iter().map(|conflict| conflict.shadowed).collect());
+    }
+
+    /// Bindings in effect, in registration order
+    pub fn list(&self) -> Vec<RegisteredKeybinding> {
+        self.bindings.clone()
+    }
+
+    /// Bindings not applied because another plugin already bound the key
+    pub fn conflicts(&self) -> Vec<KeybindingConflict> {
+        self.conflicts.clone()
+    }
+
+    /// Command bound to `key` in `context`; bindings without a `when` clause apply in any context
+    pub fn resolve(&self, key: &str, context: Option<&str>) -> Option<String> {
+        let normalized = normalize_key(key);
+        let matching = |when: Option<&str>| {
+            self.bindings
+                .iter()
+                .find(|b| normalize_key(&b.key) == normalized && b.when.as_deref() == when)
+                .map(|b| b.command.clone())
+        };
+
+        context.and_then(|context| matching(Some(context))).or_else(|| matching(None))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn binding(command: &str, key: &str, when: Option<&str>) -> Keybinding {
+        Keybinding {
+            command: command.to_string(),
+            key: key.to_string(),
+            when: when.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_conflicting_keybindings() {
+        let mut registry = KeybindingRegistry::new();
+        assert!(registry.register("weather", &[binding("weather.show", "Ctrl+Shift+W", None)]).is_empty());
+
+        // Same key, spelled differently
+        let conflicts = registry.register("clock", &[binding("clock.show", "shift+ctrl+w", None)]);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].bound_plugin_id, "weather");
+        assert_eq!(conflicts[0].shadowed.command, "clock.show");
+        assert_eq!(registry.resolve("Ctrl+Shift+W", None), Some("weather.show".to_string()));
+
+        // The shadowed binding takes over once the winner goes away
+        registry.unregister_plugin("weather");
+        assert!(registry.conflicts().is_empty());
+        assert_eq!(registry.resolve("Ctrl+Shift+W", None), Some("clock.show".to_string()));
+
+        registry.unregister_plugin("clock");
+        assert!(registry.list().is_empty());
+    }
+
+    #[test]
+    fn test_context_scoped_keybindings_do_not_conflict() {
+        let mut registry = KeybindingRegistry::new();
+        registry.register("notes", &[binding("notes.save", "Ctrl+S", Some("notesFocus"))]);
+        let conflicts = registry.register("canvas", &[
+            binding("canvas.save", "Ctrl+S", Some("canvasFocus")),
+            binding("canvas.export", "Ctrl+S", None),
+        ]);
+
+        assert!(conflicts.is_empty());
+        assert_eq!(registry.list().len(), 3);
+        assert_eq!(registry.resolve("Ctrl+S", Some("notesFocus")), Some("notes.save".to_string()));
+        assert_eq!(registry.resolve("Ctrl+S", Some("canvasFocus")), Some("canvas.save".to_string()));
+        // Falls back to the context-free binding
+        assert_eq!(registry.resolve("Ctrl+S", Some("chatFocus")), Some("canvas.export".to_string()));
+        assert_eq!(registry.resolve("Ctrl+S", None), Some("canvas.export".to_string()));
+        assert!(registry.resolve("Ctrl+Q", None).is_none());
+    }
+}
@@ -5,7 +5,7 @@
 use super::{PluginError, PluginId, PluginResult, manifest_parser::PluginManifest};
 use std::collections::{HashMap, HashSet};
 use std::path::Path;
-use std::sync::{Arc, RwLock};
+use std::sync::{Arc, Mutex, RwLock};
 
 /// PLUGIN-028: Plugin lifecycle trait
 /// Defines the contract for plugin lifecycle hooks
@@ -53,6 +53,8 @@ pub enum ResourceType {
     Command(String),
     /// View registration
     View(String),
+    /// Background process of a service plugin
+    ServiceProcess(String),
 }
 
 /// PLUGIN-031: Resource tracker for cleanup
@@ -117,16 +119,22 @@ impl Default for ResourceTracker {
     }
 }
 
+/// Cancels an in-flight HTTP request
+type AbortFn = Box<dyn FnOnce() + Send>;
+
 /// Lifecycle Manager
 /// Coordinates plugin activation/deactivation and resource management
 pub struct LifecycleManager {
     resource_tracker: ResourceTracker,
+    /// Abort callbacks of in-flight HTTP requests, by plugin and request ID
+    request_aborts: Mutex<HashMap<PluginId, HashMap<String, AbortFn>>>,
 }
 
 impl LifecycleManager {
     pub fn new() -> Self {
         Self {
             resource_tracker: ResourceTracker::new(),
+            request_aborts: Mutex::new(HashMap::new()),
         }
     }
 
@@ -203,8 +211,8 @@ impl LifecycleManager {
                     // TODO: Close actual file handles
                 }
                 ResourceType::EventListener { event_name, listener_id } => {
+                    // Dropped from the PluginEventBus by the PluginManager
                     println!("[LifecycleManager] Unregistering event listener: {} ({})", event_name, listener_id);
-                    // TODO: Remove from event bus
                 }
                 ResourceType::Timer(timer_id) => {
                     println!("[LifecycleManager] Clearing timer: {}", timer_id);
@@ -212,15 +220,19 @@ impl LifecycleManager {
                 }
                 ResourceType::HttpRequest(request_id) => {
                     println!("[LifecycleManager] Aborting HTTP request: {}", request_id);
-                    // TODO: Abort ongoing request
+                    self.abort_request(plugin_id, request_id);
                 }
                 ResourceType::Command(command_id) => {
+                    // Removed from the CommandRegistry by the PluginManager
                     println!("[LifecycleManager] Unregistering command: {}", command_id);
-                    // TODO: Remove from command registry
                 }
                 ResourceType::View(view_id) => {
+                    // Removed from the ViewRegistry by the PluginManager
                     println!("[LifecycleManager] Unregistering view: {}", view_id);
-                    // TODO: Remove from view registry
+                }
+                ResourceType::ServiceProcess(service_id) => {
+                    // The process itself is stopped by the PluginManager's service runner
+                    println!("[LifecycleManager] Releasing service process: {}", service_id);
                 }
             }
         }
@@ -247,6 +259,44 @@ impl LifecycleManager {
         self.resource_tracker.untrack(plugin_id, resource)
     }
 
+    /// Track an in-flight HTTP request; `abort` is called if the plugin deactivates first
+    pub fn register_request(&self, plugin_id: &str, request_id: &str, abort: impl FnOnce() + Send + 'static) {
+        self.request_aborts
+            .lock()
+            .unwrap()
+            .entry(plugin_id.to_string())
+            .or_default()
+            .insert(request_id.to_string(), Box::new(abort));
+        self.resource_tracker.track(plugin_id, ResourceType::HttpRequest(request_id.to_string()));
+    }
+
+    /// Stop tracking a request that finished on its own
+    pub fn complete_request(&self, plugin_id: &str, request_id: &str) {
+        self.take_request_abort(plugin_id, request_id);
+        self.resource_tracker.untrack(plugin_id, &ResourceType::HttpRequest(request_id.to_string()));
+    }
+
+    /// Cancel an in-flight request; false if it already completed
+    pub fn abort_request(&self, plugin_id: &str, request_id: &str) -> bool {
+        match self.take_request_abort(plugin_id, request_id) {
+            Some(abort) => {
+                abort();
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn take_request_abort(&self, plugin_id: &str, request_id: &str) -> Option<AbortFn> {
+        let mut aborts = self.request_aborts.lock().unwrap();
+        let plugin_aborts = aborts.get_mut(plugin_id)?;
+        let abort = plugin_aborts.remove(request_id);
+        if plugin_aborts.is_empty() {
+            aborts.remove(plugin_id);
+        }
+        abort
+    }
+
     /// Get resource count for debugging
     pub fn get_resource_count(&self, plugin_id: &str) -> usize {
         self.resource_tracker.resource_count(plugin_id)
@@ -5,7 +5,17 @@
 use super::{PluginError, PluginResult};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use std::path::Path;
+use std::path::{Component, Path, PathBuf};
+
+/// Major manifest schema versions this host understands (minor/patch bumps within one are compatible)
+pub const SUPPORTED_MANIFEST_VERSIONS: &[u32] = &[1];
+
+/// Image formats accepted for a plugin icon
+const ICON_EXTENSIONS: &[&str] = &["png", "jpg", "jpeg", "svg", "ico", "webp"];
+
+/// Plugin types that still load but are scheduled for removal, with the type to use instead.
+/// Until removed they run as that type.
+const DEPRECATED_PLUGIN_TYPES: &[(&str, &str)] = &[("hybridservice", "service")];
 
 /// PLUGIN-022: Activation event types
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -66,6 +76,42 @@ impl ActivationEvent {
     }
 }
 
+/// Something that happened in the host and may activate plugins lazily
+#[derive(Debug, Clone, PartialEq)]
+pub enum RuntimeEvent {
+    /// A command was invoked
+    OnCommand(String),
+    /// A view was opened
+    OnView(String),
+    /// The app finished starting up
+    OnStartupFinished,
+    /// A document of this language was opened
+    OnLanguage(String),
+    /// A file at this path was opened
+    OnFileOpen(String),
+}
+
+impl ActivationEvent {
+    /// Whether this activation event fires for `event` (`onFileOpen` patterns are globs,
+    /// matched against the full path or the file name)
+    pub fn matches(&self, event: &RuntimeEvent) -> bool {
+        match (self, event) {
+            (Self::OnCommand(expected), RuntimeEvent::OnCommand(actual))
+            | (Self::OnView(expected), RuntimeEvent::OnView(actual))
+            | (Self::OnLanguage(expected), RuntimeEvent::OnLanguage(actual)) => expected == actual,
+            (Self::OnStartupFinished, RuntimeEvent::OnStartupFinished) => true,
+            (Self::OnFileOpen(pattern), RuntimeEvent::OnFileOpen(path)) => {
+                let Ok(pattern) = glob::Pattern::new(pattern) else {
+                    return false;
+                };
+                let file_name = Path::new(path).file_name().and_then(|n| n.to_str()).unwrap_or(path);
+                pattern.matches(path) || pattern.matches(file_name)
+            }
+            _ => false,
+        }
+    }
+}
+
 /// PLUGIN-023: Contribution point for commands
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Command {
@@ -118,7 +164,7 @@ pub struct View {
     pub location: ViewLocation,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "lowercase")]
 pub enum ViewLocation {
     Sidebar,
@@ -217,6 +263,103 @@ impl Keybinding {
     }
 }
 
+/// Value type of a plugin setting
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigValueType {
+    String,
+    Number,
+    Integer,
+    Boolean,
+    Array,
+    Object,
+}
+
+impl ConfigValueType {
+    /// Whether `value` is of this type (whole numbers like `3.0` count as integers)
+    pub fn accepts(&self, value: &serde_json::Value) -> bool {
+        match self {
+            Self::String => value.is_string(),
+            Self::Number => value.is_number(),
+            Self::Integer => value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0),
+            Self::Boolean => value.is_boolean(),
+            Self::Array => value.is_array(),
+            Self::Object => value.is_object(),
+        }
+    }
+}
+
+/// Contribution point for a user-configurable setting; the host renders a settings form from these
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigurationProperty {
+    pub key: String,
+    #[serde(rename = "type")]
+    pub value_type: ConfigValueType,
+    /// Value used while the user hasn't set one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default: Option<serde_json::Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Allowed values (rendered as a dropdown)
+    #[serde(default, rename = "enum", skip_serializing_if = "Option::is_none")]
+    pub allowed_values: Option<Vec<serde_json::Value>>,
+}
+
+impl ConfigurationProperty {
+    /// Check that `value` can be stored for this setting
+    pub fn check_value(&self, value: &serde_json::Value) -> PluginResult<()> {
+        if !self.value_type.accepts(value) {
+            return Err(PluginError::InvalidConfig(format!(
+                "Setting '{}' must be of type {:?}, got {}",
+                self.key, self.value_type, value
+            )));
+        }
+
+        if let Some(allowed) = &self.allowed_values {
+            if !allowed.contains(value) {
+                return Err(PluginError::InvalidConfig(format!(
+                    "Setting '{}' must be one of {:?}, got {}",
+                    self.key, allowed, value
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Validate the setting declaration
+    pub fn validate(&self) -> PluginResult<()> {
+        if self.key.is_empty() {
+            return Err(PluginError::ManifestError(
+                "Configuration key cannot be empty".to_string()
+            ));
+        }
+
+        if !self.key.chars().all(|c| c.is_alphanumeric() || c == '.' || c == '-' || c == '_') {
+            return Err(PluginError::ManifestError(
+                format!("Invalid characters in configuration key: {}", self.key)
+            ));
+        }
+
+        for value in self.allowed_values.iter().flatten() {
+            if !self.value_type.accepts(value) {
+                return Err(PluginError::ManifestError(
+                    format!("Allowed value {} of setting '{}' is not of type {:?}", value, self.key, self.value_type)
+                ));
+            }
+        }
+
+        if let Some(default) = &self.default {
+            self.check_value(default).map_err(|e| PluginError::ManifestError(
+                format!("Invalid default for setting '{}': {}", self.key, e)
+            ))?;
+        }
+
+        Ok(())
+    }
+}
+
 /// PLUGIN-023: Contribution points struct
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -229,6 +372,8 @@ pub struct ContributionPoints {
     pub events: Vec<Event>,
     #[serde(default)]
     pub keybindings: Vec<Keybinding>,
+    #[serde(default)]
+    pub configuration: Vec<ConfigurationProperty>,
 }
 
 impl ContributionPoints {
@@ -250,10 +395,81 @@ impl ContributionPoints {
             keybinding.validate()?;
         }
 
+        let mut keys = std::collections::HashSet::new();
+        for property in &self.configuration {
+            property.validate()?;
+            if !keys.insert(property.key.as_str()) {
+                return Err(PluginError::ManifestError(
+                    format!("Duplicate configuration key: {}", property.key)
+                ));
+            }
+        }
+
         Ok(())
     }
 }
 
+/// Manifest permission entry.
+/// Either a string (`"network.request:*"`, or `"network.request:*?"` for an optional permission)
+/// or a structured `{ "permission": "...", "optional": true }` object.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PermissionEntry {
+    Simple(String),
+    Detailed {
+        permission: String,
+        #[serde(default)]
+        optional: bool,
+    },
+}
+
+impl PermissionEntry {
+    /// Permission string without the optional marker (e.g., "filesystem.read:AppData/data/*")
+    pub fn permission(&self) -> &str {
+        match self {
+            Self::Simple(s) => s.strip_suffix('?').unwrap_or(s),
+            Self::Detailed { permission, .. } => permission,
+        }
+    }
+
+    /// Whether the plugin can run without this permission
+    pub fn is_optional(&self) -> bool {
+        match self {
+            Self::Simple(s) => s.ends_with('?'),
+            Self::Detailed { optional, .. } => *optional,
+        }
+    }
+}
+
+impl From<&str> for PermissionEntry {
+    fn from(s: &str) -> Self {
+        Self::Simple(s.to_string())
+    }
+}
+
+/// Non-fatal manifest issue, shown to the user during install
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ManifestWarning {
+    /// Machine-readable warning code (e.g., "broad_scope")
+    pub code: String,
+    pub message: String,
+}
+
+impl ManifestWarning {
+    pub fn new(code: &str, message: String) -> Self {
+        Self { code: code.to_string(), message }
+    }
+}
+
+/// Scopes granting access to everything a permission type can reach
+fn is_broad_scope(permission_type: &str, scope: &str) -> bool {
+    match permission_type {
+        "filesystem.read" | "filesystem.write" => matches!(scope, "*" | "AppData" | "AppData/" | "AppData/*"),
+        "network.request" => scope == "*",
+        _ => false,
+    }
+}
+
 /// PLUGIN-021: Plugin Manifest structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
@@ -275,7 +491,7 @@ pub struct PluginManifest {
     pub activation_events: Vec<String>,
 
     #[serde(default)]
-    pub permissions: Vec<String>,
+    pub permissions: Vec<PermissionEntry>,
 
     #[serde(default)]
     pub contributes: ContributionPoints,
@@ -285,6 +501,20 @@ pub struct PluginManifest {
 
     #[serde(default)]
     pub dependencies: HashMap<String, String>,
+
+    /// Icon image, as a path within the package
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub icon: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub homepage: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repository: Option<String>,
+
+    /// SPDX license identifier (e.g., "MIT")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub license: Option<String>,
 }
 
 fn default_plugin_type() -> String {
@@ -311,11 +541,108 @@ impl Default for PluginManifest {
             contributes: ContributionPoints::default(),
             engines: HashMap::new(),
             dependencies: HashMap::new(),
+            icon: None,
+            homepage: None,
+            repository: None,
+            license: None,
         }
     }
 }
 
 impl PluginManifest {
+    /// Permissions the plugin cannot be activated without
+    pub fn required_permissions(&self) -> Vec<String> {
+        self.permissions
+            .iter()
+            .filter(|p| !p.is_optional())
+            .map(|p| p.permission().to_string())
+            .collect()
+    }
+
+    /// Permissions the plugin can run without (with reduced functionality)
+    pub fn optional_permissions(&self) -> Vec<String> {
+        self.permissions
+            .iter()
+            .filter(|p| p.is_optional())
+            .map(|p| p.permission().to_string())
+            .collect()
+    }
+
+    /// Type the plugin runs as: its `pluginType`, or the replacement of a deprecated one
+    pub fn effective_plugin_type(&self) -> &str {
+        DEPRECATED_PLUGIN_TYPES
+            .iter()
+            .find(|(deprecated, _)| *deprecated == self.plugin_type)
+            .map_or(self.plugin_type.as_str(), |(_, replacement)| replacement)
+    }
+
+    /// Whether any of the manifest's `activationEvents` fires for `event`
+    pub fn matches_activation_event(&self, event: &RuntimeEvent) -> bool {
+        self.activation_events
+            .iter()
+            .filter_map(|event_str| ActivationEvent::from_str(event_str).ok())
+            .any(|activation_event| activation_event.matches(event))
+    }
+
+    /// Warnings for permissions requested with the broadest possible scope
+    /// (e.g., "filesystem.write:*" or "network.request:*"), encouraging narrower grants
+    pub fn scope_warnings(&self) -> Vec<ManifestWarning> {
+        self.permissions
+            .iter()
+            .filter_map(|entry| {
+                let permission = entry.permission();
+                let (permission_type, scope) = permission.split_once(':').unwrap_or((permission, "*"));
+
+                is_broad_scope(permission_type, scope).then(|| ManifestWarning::new(
+                    "broad_scope",
+                    format!(
+                        "Permission '{}' grants unrestricted {} access; consider requesting a narrower scope",
+                        permission, permission_type
+                    ),
+                ))
+            })
+            .collect()
+    }
+
+    /// Every non-fatal issue with the manifest: broad permission scopes, missing optional fields,
+    /// deprecated plugin types and activation events referring to nothing the plugin contributes
+    pub fn warnings(&self) -> Vec<ManifestWarning> {
+        let mut warnings = self.scope_warnings();
+
+        if self.display_name.is_empty() {
+            warnings.push(ManifestWarning::new(
+                "missing_field",
+                "Missing optional field: displayName; the plugin name will be shown instead".to_string(),
+            ));
+        }
+        if self.author.is_empty() {
+            warnings.push(ManifestWarning::new("missing_field", "Missing optional field: author".to_string()));
+        }
+
+        if let Some((_, replacement)) = DEPRECATED_PLUGIN_TYPES.iter().find(|(t, _)| *t == self.plugin_type) {
+            warnings.push(ManifestWarning::new(
+                "deprecated_plugin_type",
+                format!("Plugin type '{}' is deprecated; use '{}' instead", self.plugin_type, replacement),
+            ));
+        }
+
+        for event_str in &self.activation_events {
+            let dangling = match ActivationEvent::from_str(event_str) {
+                Ok(ActivationEvent::OnCommand(id)) => !self.contributes.commands.iter().any(|c| c.identifier == id),
+                Ok(ActivationEvent::OnView(id)) => !self.contributes.views.iter().any(|v| v.identifier == id),
+                _ => false,
+            };
+            if dangling {
+                warnings.push(ManifestWarning::new(
+                    "dangling_activation_event",
+                    format!("Activation event '{}' refers to nothing this plugin contributes", event_str),
+                ));
+            }
+        }
+
+        warnings
+    }
+
     /// PLUGIN-025: Validate manifest schema
     pub fn validate(&self) -> PluginResult<()> {
         // Required fields
@@ -343,6 +670,7 @@ impl PluginManifest {
                 format!("Invalid manifest version format: {}", self.manifest_version)
             ));
         }
+        check_manifest_schema_version(&self.manifest_version)?;
 
         // Validate plugin version format (x.y.z)
         if !is_valid_version(&self.version) {
@@ -360,7 +688,7 @@ impl PluginManifest {
 
         // Validate plugin type
         let valid_types = ["synchronous", "asynchronous", "static", "service", "messagePreprocessor"];
-        if !valid_types.contains(&self.plugin_type.as_str()) {
+        if !valid_types.contains(&self.effective_plugin_type()) {
             return Err(PluginError::ManifestValidation(
                 format!("Invalid plugin type: {}. Must be one of: {:?}", self.plugin_type, valid_types)
             ));
@@ -374,6 +702,10 @@ impl PluginManifest {
         // Validate contribution points
         self.contributes.validate()?;
 
+        if let Some(icon) = &self.icon {
+            validate_icon_path(icon)?;
+        }
+
         // Validate dependencies versions
         for (dep_name, dep_version) in &self.dependencies {
             if !is_valid_version_range(dep_version) {
@@ -385,6 +717,44 @@ impl PluginManifest {
 
         Ok(())
     }
+
+    /// Location of the manifest's icon inside an extracted package; fails if the file is missing
+    pub fn resolve_icon(&self, package_dir: &Path) -> PluginResult<Option<PathBuf>> {
+        let Some(icon) = &self.icon else {
+            return Ok(None);
+        };
+
+        let icon_path = package_dir.join(icon);
+        if !icon_path.is_file() {
+            return Err(PluginError::ManifestValidation(
+                format!("Icon not found in package: {}", icon)
+            ));
+        }
+
+        Ok(Some(icon_path))
+    }
+}
+
+/// Helper: Validate that an icon path stays inside the package and names a supported image
+fn validate_icon_path(icon: &str) -> PluginResult<()> {
+    let path = Path::new(icon);
+    if icon.is_empty() || !path.components().all(|c| matches!(c, Component::Normal(_))) {
+        return Err(PluginError::ManifestValidation(
+            format!("Icon must be a relative path within the package: {}", icon)
+        ));
+    }
+
+    let extension = path.extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_lowercase())
+        .unwrap_or_default();
+    if !ICON_EXTENSIONS.contains(&extension.as_str()) {
+        return Err(PluginError::ManifestValidation(
+            format!("Unsupported icon format: {} (expected one of {:?})", icon, ICON_EXTENSIONS)
+        ));
+    }
+
+    Ok(())
 }
 
 /// Helper: Validate version format (x.y.z)
@@ -397,6 +767,34 @@ fn is_valid_version(version: &str) -> bool {
     parts.iter().all(|part| part.parse::<u32>().is_ok())
 }
 
+/// Reject manifests written against a schema major version this host doesn't support
+fn check_manifest_schema_version(manifest_version: &str) -> PluginResult<()> {
+    let major: u32 = manifest_version
+        .split('.')
+        .next()
+        .and_then(|major| major.parse().ok())
+        .ok_or_else(|| PluginError::ManifestValidation(
+            format!("Invalid manifest version format: {}", manifest_version)
+        ))?;
+
+    if SUPPORTED_MANIFEST_VERSIONS.contains(&major) {
+        return Ok(());
+    }
+
+    let newest = SUPPORTED_MANIFEST_VERSIONS.iter().max().copied().unwrap_or(0);
+    if major > newest {
+        Err(PluginError::ManifestValidation(format!(
+            "Manifest version {} is newer than this app supports (up to {}.x); please update the app",
+            manifest_version, newest
+        )))
+    } else {
+        Err(PluginError::ManifestValidation(format!(
+            "Manifest version {} is no longer supported (supported: {:?})",
+            manifest_version, SUPPORTED_MANIFEST_VERSIONS
+        )))
+    }
+}
+
 /// Helper: Validate version range format
 fn is_valid_version_range(version_range: &str) -> bool {
     // Support simple version (1.0.0) or range (^1.0.0, ~1.0.0, >=1.0.0)
@@ -429,4 +827,234 @@ impl ManifestParser {
         manifest.validate()?;
         Ok(manifest)
     }
+
+    /// Parse and validate manifest, also returning its non-fatal warnings
+    pub fn parse_and_validate_verbose(&self, manifest_path: &Path) -> PluginResult<(PluginManifest, Vec<ManifestWarning>)> {
+        let manifest = self.parse_and_validate(manifest_path)?;
+        let warnings = manifest.warnings();
+        Ok((manifest, warnings))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_optional_permissions() {
+        let manifest: PluginManifest = serde_json::from_value(serde_json::json!({
+            "manifestVersion": "1.0.0",
+            "name": "weather",
+            "displayName": "Weather",
+            "version": "1.0.0",
+            "description": "Weather plugin",
+            "author": "Test Author",
+            "permissions": [
+                "storage.read",
+                "network.request:*?",
+                { "permission": "system.notify", "optional": true },
+                { "permission": "storage.write" }
+            ]
+        }))
+        .unwrap();
+
+        assert_eq!(manifest.required_permissions(), vec!["storage.read", "storage.write"]);
+        assert_eq!(manifest.optional_permissions(), vec!["network.request:*", "system.notify"]);
+    }
+
+    #[test]
+    fn test_matches_activation_event() {
+        let manifest = PluginManifest {
+            activation_events: vec!["onCommand:foo.bar".to_string(), "onFileOpen:*.md".to_string()],
+            ..PluginManifest::default()
+        };
+
+        assert!(manifest.matches_activation_event(&RuntimeEvent::OnCommand("foo.bar".to_string())));
+        assert!(!manifest.matches_activation_event(&RuntimeEvent::OnCommand("foo.baz".to_string())));
+        assert!(manifest.matches_activation_event(&RuntimeEvent::OnFileOpen("/notes/todo.md".to_string())));
+        assert!(!manifest.matches_activation_event(&RuntimeEvent::OnFileOpen("/notes/todo.txt".to_string())));
+        assert!(!manifest.matches_activation_event(&RuntimeEvent::OnStartupFinished));
+    }
+
+    #[test]
+    fn test_manifest_version_compatibility() {
+        let manifest = |manifest_version: &str| PluginManifest {
+            manifest_version: manifest_version.to_string(),
+            name: "weather".to_string(),
+            description: "Weather plugin".to_string(),
+            ..PluginManifest::default()
+        };
+
+        assert!(manifest("1.0.0").validate().is_ok());
+        // Minor bumps within a supported major load
+        assert!(manifest("1.4.2").validate().is_ok());
+
+        assert!(matches!(
+            manifest("2.0.0").validate(),
+            Err(PluginError::ManifestValidation(msg)) if msg.contains("please update the app")
+        ));
+        assert!(matches!(
+            manifest("1.x").validate(),
+            Err(PluginError::ManifestValidation(msg)) if msg.starts_with("Invalid manifest version format")
+        ));
+    }
+
+    #[test]
+    fn test_scope_warnings_for_broad_permissions() {
+        let manifest = PluginManifest {
+            permissions: vec![
+                PermissionEntry::from("filesystem.write:*"),
+                PermissionEntry::from("filesystem.read:AppData/*"),
+                PermissionEntry::from("network.request"),
+                PermissionEntry::from("network.request:*?"),
+            ],
+            ..PluginManifest::default()
+        };
+
+        let warnings = manifest.scope_warnings();
+        assert_eq!(warnings.len(), 4);
+        assert!(warnings.iter().all(|w| w.code == "broad_scope"));
+        assert!(warnings[0].message.contains("filesystem.write:*"));
+    }
+
+    #[test]
+    fn test_no_scope_warnings_for_narrow_permissions() {
+        let manifest = PluginManifest {
+            permissions: vec![
+                PermissionEntry::from("filesystem.write:AppData/plugin-data/weather/*"),
+                PermissionEntry::from("network.request:api.weather.com"),
+                PermissionEntry::from("network.request:*.example.com"),
+                PermissionEntry::from("storage.write"),
+                PermissionEntry::from("system.notify"),
+            ],
+            ..PluginManifest::default()
+        };
+
+        assert!(manifest.scope_warnings().is_empty());
+    }
+
+    #[test]
+    fn test_parse_and_validate_verbose_collects_warnings() {
+        let dir = std::env::temp_dir().join(format!("vcp_manifest_warnings_test_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let manifest_path = dir.join("manifest.json");
+        std::fs::write(&manifest_path, serde_json::json!({
+            "manifestVersion": "1.0.0",
+            "name": "weather",
+            "displayName": "",
+            "version": "1.0.0",
+            "description": "Weather plugin",
+            "author": "Test Author",
+            "activationEvents": ["onCommand:weather.show", "onCommand:weather.missing", "onStartupFinished"],
+            "permissions": ["network.request:*", "storage.read"],
+            "contributes": {
+                "commands": [{ "identifier": "weather.show", "title": "Show Weather" }]
+            }
+        }).to_string()).unwrap();
+
+        let parser = ManifestParser::new();
+        let (manifest, warnings) = parser.parse_and_validate_verbose(&manifest_path).unwrap();
+        assert_eq!(manifest.name, "weather");
+
+        let codes: Vec<&str> = warnings.iter().map(|w| w.code.as_str()).collect();
+        assert_eq!(codes, vec!["broad_scope", "missing_field", "dangling_activation_event"]);
+        assert!(warnings[1].message.contains("displayName"));
+        assert!(warnings[2].message.contains("weather.missing"));
+
+        // The pass/fail method accepts the same manifest
+        assert!(parser.parse_and_validate(&manifest_path).is_ok());
+
+        // A deprecated type still loads, with a warning, and runs as its replacement
+        let mut legacy: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&manifest_path).unwrap()).unwrap();
+        legacy["pluginType"] = serde_json::json!("hybridservice");
+        std::fs::write(&manifest_path, legacy.to_string()).unwrap();
+        let (manifest, warnings) = parser.parse_and_validate_verbose(&manifest_path).unwrap();
+        assert_eq!(manifest.effective_plugin_type(), "service");
+        let deprecated = warnings.iter().find(|w| w.code == "deprecated_plugin_type").unwrap();
+        assert!(deprecated.message.contains("'hybridservice' is deprecated; use 'service'"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_configuration_validation() {
+        let manifest = |configuration: serde_json::Value| -> PluginManifest {
+            serde_json::from_value(serde_json::json!({
+                "manifestVersion": "1.0.0",
+                "name": "weather",
+                "displayName": "Weather",
+                "version": "1.0.0",
+                "description": "Weather plugin",
+                "author": "Test Author",
+                "contributes": { "configuration": configuration }
+            }))
+            .unwrap()
+        };
+
+        let valid = manifest(serde_json::json!([
+            { "key": "units", "type": "string", "default": "metric", "enum": ["metric", "imperial"], "description": "Units" },
+            { "key": "refreshMinutes", "type": "integer", "default": 15 }
+        ]));
+        assert!(valid.validate().is_ok());
+        assert_eq!(valid.contributes.configuration[1].value_type, ConfigValueType::Integer);
+
+        // Default of the wrong type, default outside the allowed values, duplicate keys
+        assert!(manifest(serde_json::json!([{ "key": "refreshMinutes", "type": "integer", "default": "soon" }])).validate().is_err());
+        assert!(manifest(serde_json::json!([{ "key": "units", "type": "string", "default": "kelvin", "enum": ["metric"] }])).validate().is_err());
+        assert!(manifest(serde_json::json!([
+            { "key": "units", "type": "string" },
+            { "key": "units", "type": "boolean" }
+        ])).validate().is_err());
+    }
+
+    #[test]
+    fn test_icon_and_metadata_fields() {
+        let manifest: PluginManifest = serde_json::from_value(serde_json::json!({
+            "manifestVersion": "1.0.0",
+            "name": "weather",
+            "displayName": "Weather",
+            "version": "1.0.0",
+            "description": "Weather plugin",
+            "author": "Test Author",
+            "icon": "assets/icon.png",
+            "homepage": "https://example.com/weather",
+            "repository": "https://github.com/example/weather",
+            "license": "MIT"
+        }))
+        .unwrap();
+        assert!(manifest.validate().is_ok());
+        assert_eq!(manifest.icon.as_deref(), Some("assets/icon.png"));
+        assert_eq!(manifest.homepage.as_deref(), Some("https://example.com/weather"));
+        assert_eq!(manifest.repository.as_deref(), Some("https://github.com/example/weather"));
+        assert_eq!(manifest.license.as_deref(), Some("MIT"));
+
+        // The icon must exist in the package
+        let dir = std::env::temp_dir().join(format!("vcp_manifest_icon_test_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("assets")).unwrap();
+        assert!(matches!(
+            manifest.resolve_icon(&dir),
+            Err(PluginError::ManifestValidation(msg)) if msg.contains("Icon not found")
+        ));
+        std::fs::write(dir.join("assets").join("icon.png"), [0x89, b'P', b'N', b'G']).unwrap();
+        assert_eq!(manifest.resolve_icon(&dir).unwrap(), Some(dir.join("assets").join("icon.png")));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        // Paths escaping the package and non-image files are rejected
+        let with_icon = |icon: &str| PluginManifest { icon: Some(icon.to_string()), ..manifest.clone() };
+        assert!(with_icon("../icon.png").validate().is_err());
+        assert!(with_icon("/etc/icon.png").validate().is_err());
+        assert!(with_icon("index.js").validate().is_err());
+        assert!(PluginManifest { icon: None, ..manifest.clone() }.resolve_icon(Path::new("/nonexistent")).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_permission_entry_marker() {
+        let optional = PermissionEntry::from("filesystem.read:AppData/data/*?");
+        assert!(optional.is_optional());
+        assert_eq!(optional.permission(), "filesystem.read:AppData/data/*");
+
+        let required = PermissionEntry::from("filesystem.read:AppData/data/*");
+        assert!(!required.is_optional());
+        assert_eq!(required.permission(), "filesystem.read:AppData/data/*");
+    }
 }
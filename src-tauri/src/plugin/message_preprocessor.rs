@@ -0,0 +1,100 @@
+// Message preprocessing chain for `messagePreprocessor` plugins
+// Active preprocessors transform outgoing messages in dependency order
+
+use super::{PluginId, PluginResult};
+use crate::models::Message;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Transform applied to a message before it is sent
+pub trait MessagePreprocessor: Send + Sync {
+    fn preprocess(&self, message: Message) -> PluginResult<Message>;
+}
+
+/// Default preprocessor for manifest-only plugins: delegates to the plugin's sidecar hook
+pub struct SidecarPreprocessor {
+    pub plugin_id: PluginId,
+    pub entry_point: PathBuf,
+}
+
+impl SidecarPreprocessor {
+    pub fn new(plugin_id: PluginId, entry_point: PathBuf) -> Self {
+        Self { plugin_id, entry_point }
+    }
+}
+
+impl MessagePreprocessor for SidecarPreprocessor {
+    fn preprocess(&self, message: Message) -> PluginResult<Message> {
+        // TODO: In a real implementation, this would:
+        // 1. Send the message to the plugin's sidecar process (entry_point)
+        // 2. Wait for the transformed message
+        //
+        // For now, messages pass through unchanged
+        Ok(message)
+    }
+}
+
+/// Ordered chain of active message preprocessors
+#[derive(Default)]
+pub struct PreprocessorChain {
+    /// Native implementations provided for plugins, used instead of the sidecar hook
+    implementations: HashMap<PluginId, Arc<dyn MessagePreprocessor>>,
+    /// Active preprocessors in execution order
+    active: Vec<(PluginId, Arc<dyn MessagePreprocessor>)>,
+}
+
+impl PreprocessorChain {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Provide a native implementation for a plugin (used when it activates)
+    pub fn provide(&mut self, plugin_id: &str, preprocessor: Arc<dyn MessagePreprocessor>) {
+        self.implementations.insert(plugin_id.to_string(), preprocessor);
+    }
+
+    /// Add a plugin to the chain, falling back to `fallback` if no implementation was provided
+    pub fn activate(&mut self, plugin_id: &str, fallback: Arc<dyn MessagePreprocessor>) {
+        let preprocessor = self.implementations
+            .get(plugin_id)
+            .cloned()
+            .unwrap_or(fallback);
+
+        self.active.retain(|(id, _)| id != plugin_id);
+        self.active.push((plugin_id.to_string(), preprocessor));
+    }
+
+    /// Remove a plugin from the chain
+    pub fn deactivate(&mut self, plugin_id: &str) {
+        self.active.retain(|(id, _)| id != plugin_id);
+    }
+
+    /// Plugin IDs of active preprocessors, in execution order
+    pub fn active_ids(&self) -> Vec<PluginId> {
+        self.active.iter().map(|(id, _)| id.clone()).collect()
+    }
+
+    /// Reorder the chain to follow `order` (plugins missing from `order` keep their place at the end)
+    pub fn reorder(&mut self, order: &[PluginId]) {
+        let position = |id: &PluginId| order.iter().position(|o| o == id).unwrap_or(usize::MAX);
+        self.active.sort_by_key(|(id, _)| position(id));
+    }
+
+    /// Run every active preprocessor in order.
+    /// A failing preprocessor is skipped and the message continues unchanged.
+    pub fn run(&self, message: Message) -> Message {
+        let mut current = message;
+
+        for (plugin_id, preprocessor) in &self.active {
+            match preprocessor.preprocess(current.clone()) {
+                Ok(processed) => current = processed,
+                Err(e) => {
+                    println!("[PreprocessorChain] Preprocessor {} failed: {}", plugin_id, e);
+                }
+            }
+        }
+
+        current
+    }
+}
@@ -12,8 +12,20 @@ pub mod manifest_parser;
 pub mod lifecycle_manager;
 pub mod filesystem_api;
 pub mod network_proxy;
+pub mod websocket_proxy;
 pub mod storage_api;
 pub mod audit_logger;
+pub mod message_preprocessor;
+pub mod service_runner;
+pub mod health_check;
+pub mod package_verifier;
+pub mod command_registry;
+pub mod view_registry;
+pub mod event_bus;
+pub mod keybinding_registry;
+pub mod resource_limiter;
+pub mod config_api;
+pub mod update_check;
 
 /// Plugin lifecycle state machine
 /// Represents the current state of a plugin in its lifecycle
@@ -31,6 +43,8 @@ pub enum PluginState {
     Running,
     /// Plugin deactivate() hook called, cleaning up
     Deactivated,
+    /// Plugin stopped responding to health checks while running
+    Crashed,
 }
 
 impl PluginState {
@@ -52,6 +66,11 @@ impl PluginState {
             | (Deactivated, Uninstalled)
             // Re-activation
             | (Deactivated, Activated)
+            // Crash detection and recovery
+            | (Running, Crashed)
+            | (Crashed, Installed)
+            | (Crashed, Activated)
+            | (Crashed, Uninstalled)
         )
     }
 }
@@ -59,6 +78,19 @@ impl PluginState {
 /// Plugin identifier (unique plugin ID)
 pub type PluginId = String;
 
+/// Whether a crashed plugin is reactivated automatically
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum PluginRestartPolicy {
+    /// Stay `Crashed` until the user reactivates the plugin
+    #[default]
+    Never,
+    /// Restart with backoff, giving up after the maximum number of attempts
+    OnCrash,
+    /// Restart with backoff, without an attempt limit
+    Always,
+}
+
 /// Plugin metadata from installed package
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginMetadata {
@@ -73,6 +105,14 @@ pub struct PluginMetadata {
     pub state: PluginState,
     pub created_at: String,
     pub updated_at: String,
+    /// SHA-256 of the installed package files, for tamper detection
+    #[serde(default)]
+    pub content_hash: Option<String>,
+    #[serde(default)]
+    pub restart_policy: PluginRestartPolicy,
+    /// Installed location of the manifest's `icon`
+    #[serde(default)]
+    pub icon_path: Option<std::path::PathBuf>,
 }
 
 /// Result type for plugin operations
@@ -99,9 +139,6 @@ pub enum PluginError {
     #[error("Permission denied: {0}")]
     PermissionDenied(String),
 
-    #[error("Dependency resolution failed: {0}")]
-    DependencyError(String),
-
     #[error("Dependency resolution failed: {0}")]
     DependencyResolution(String),
 
@@ -119,6 +156,46 @@ pub enum PluginError {
 
     #[error("File system error: {0}")]
     FileSystemError(String),
+
+    #[error("Invalid plugin setting: {0}")]
+    InvalidConfig(String),
+}
+
+/// Serializable form of `PluginError` returned by plugin commands.
+/// `code` is stable and machine-readable so the frontend doesn't parse messages.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PluginErrorDto {
+    pub code: String,
+    pub message: String,
+}
+
+impl PluginError {
+    /// Stable error code for this variant
+    pub fn code(&self) -> &'static str {
+        match self {
+            PluginError::NotFound(_) => "NOT_FOUND",
+            PluginError::InvalidStateTransition { .. } => "INVALID_STATE",
+            PluginError::ManifestError(_) => "MANIFEST_ERROR",
+            PluginError::ManifestValidation(_) => "MANIFEST_INVALID",
+            PluginError::PermissionDenied(_) => "PERMISSION_DENIED",
+            PluginError::DependencyResolution(_) => "DEPENDENCY_ERROR",
+            PluginError::ActivationError(_) => "ACTIVATION_FAILED",
+            PluginError::IoError(_) => "IO_ERROR",
+            PluginError::ZipError(_) => "ZIP_ERROR",
+            PluginError::HookError(_) => "HOOK_ERROR",
+            PluginError::FileSystemError(_) => "FILESYSTEM_ERROR",
+            PluginError::InvalidConfig(_) => "INVALID_CONFIG",
+        }
+    }
+}
+
+impl From<PluginError> for PluginErrorDto {
+    fn from(error: PluginError) -> Self {
+        Self {
+            code: error.code().to_string(),
+            message: error.to_string(),
+        }
+    }
 }
 
 #[cfg(test)]
@@ -143,4 +220,40 @@ mod tests {
         assert!(!Running.can_transition_to(&Installed));
         assert!(!Loaded.can_transition_to(&Deactivated));
     }
+
+    #[test]
+    fn test_crashed_transitions() {
+        use PluginState::*;
+
+        assert!(Running.can_transition_to(&Crashed));
+        assert!(Crashed.can_transition_to(&Installed));
+        assert!(Crashed.can_transition_to(&Activated));
+        assert!(Crashed.can_transition_to(&Uninstalled));
+
+        assert!(!Installed.can_transition_to(&Crashed));
+        assert!(!Deactivated.can_transition_to(&Crashed));
+        assert!(!Crashed.can_transition_to(&Running));
+    }
+
+    #[test]
+    fn test_error_dto_codes() {
+        let dto = PluginErrorDto::from(PluginError::PermissionDenied("network.http:*".to_string()));
+        assert_eq!(dto.code, "PERMISSION_DENIED");
+        assert_eq!(dto.message, "Permission denied: network.http:*");
+
+        let dto = PluginErrorDto::from(PluginError::NotFound("weather".to_string()));
+        assert_eq!(dto.code, "NOT_FOUND");
+        assert_eq!(dto.message, "Plugin not found: weather");
+
+        let dto = PluginErrorDto::from(PluginError::InvalidStateTransition {
+            from: PluginState::Running,
+            to: PluginState::Installed,
+        });
+        assert_eq!(dto.code, "INVALID_STATE");
+
+        let io = std::io::Error::new(std::io::ErrorKind::NotFound, "missing");
+        assert_eq!(PluginErrorDto::from(PluginError::from(io)).code, "IO_ERROR");
+
+        assert_eq!(PluginError::DependencyResolution(String::new()).code(), "DEPENDENCY_ERROR");
+    }
 }
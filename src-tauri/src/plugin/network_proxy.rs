@@ -1,15 +1,22 @@
 // PLUGIN-047 to PLUGIN-052: NetworkAPI implementation
 // HTTP requests with domain whitelist, rate limiting, caching, and audit logging
 
-use super::{PluginError, PluginResult, PluginId};
-use super::permission_manager::{PermissionManager, PermissionType};
+use super::{PluginError, PluginResult, PluginId, PluginState};
+use super::permission_manager::{domain_matches_pattern, PermissionManager, PermissionType};
 use super::audit_logger::AuditLogger;
+use super::health_check::StateChangeListener;
+use super::lifecycle_manager::LifecycleManager;
+use crate::models::GlobalSettings;
 use serde::{Deserialize, Serialize};
-use std::sync::{Arc, Mutex};
-use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::collections::{HashMap, HashSet};
 use std::time::{Duration, Instant};
 use lru::LruCache;
 use std::num::NonZeroUsize;
+use hmac::{Hmac, Mac};
+use sha2::Sha256;
+use base64::prelude::{Engine as _, BASE64_STANDARD};
 
 /// HTTP method types
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -45,6 +52,24 @@ pub struct HttpRequest {
     pub headers: HashMap<String, String>,
     pub body: Option<String>,
     pub timeout_secs: Option<u64>,
+    /// Decompress gzip/deflate/brotli responses (set to false to receive the raw body)
+    #[serde(default = "default_decode")]
+    pub decode: bool,
+}
+
+fn default_decode() -> bool {
+    true
+}
+
+/// How `HttpResponse::body` is encoded
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BodyEncoding {
+    /// The body is UTF-8 text
+    #[default]
+    Text,
+    /// The body is not valid UTF-8 and is base64-encoded
+    Base64,
 }
 
 /// HTTP response structure
@@ -53,17 +78,54 @@ pub struct HttpResponse {
     pub status: u16,
     pub headers: HashMap<String, String>,
     pub body: String,
+    #[serde(default)]
+    pub body_encoding: BodyEncoding,
 }
 
 /// Cache entry with TTL
 #[derive(Debug, Clone)]
 struct CacheEntry {
+    plugin_id: PluginId,
+    url: String,
     response: HttpResponse,
     expires_at: Instant,
 }
 
+type ResponseCache = Arc<Mutex<LruCache<String, CacheEntry>>>;
+
+/// Observes a request right before it is sent
+pub type RequestInterceptor = Box<dyn Fn(&HttpRequest) + Send + Sync>;
+
+/// Observes a completed request with its response and how long it took
+pub type ResponseInterceptor = Box<dyn Fn(&HttpRequest, &HttpResponse, Duration) + Send + Sync>;
+
+/// Run an interceptor; a panicking interceptor is reported but never fails the request
+fn run_interceptor(kind: &str, interceptor: impl FnOnce()) {
+    if std::panic::catch_unwind(std::panic::AssertUnwindSafe(interceptor)).is_err() {
+        println!("[NetworkProxy] {} interceptor panicked; ignoring", kind);
+    }
+}
+
+/// Drop every cache entry matching `predicate`, returning how many were removed
+fn remove_cache_entries(cache: &ResponseCache, predicate: impl Fn(&CacheEntry) -> bool) -> usize {
+    let mut cache = cache.lock().unwrap();
+    let keys: Vec<String> = cache
+        .iter()
+        .filter(|(_, entry)| predicate(entry))
+        .map(|(key, _)| key.clone())
+        .collect();
+
+    for key in &keys {
+        cache.pop(key);
+    }
+    keys.len()
+}
+
+/// Requests per minute a plugin may make unless `NetworkProxy::set_rate_limit` says otherwise
+pub const DEFAULT_REQUESTS_PER_MINUTE: u32 = 100;
+
 /// Token bucket for rate limiting
-struct TokenBucket {
+pub(super) struct TokenBucket {
     tokens: f64,
     capacity: f64,
     refill_rate: f64, // tokens per second
@@ -71,7 +133,7 @@ struct TokenBucket {
 }
 
 impl TokenBucket {
-    fn new(capacity: f64, refill_rate: f64) -> Self {
+    pub(super) fn new(capacity: f64, refill_rate: f64) -> Self {
         Self {
             tokens: capacity,
             capacity,
@@ -80,7 +142,12 @@ impl TokenBucket {
         }
     }
 
-    fn try_consume(&mut self, tokens: f64) -> bool {
+    /// Full bucket allowing `per_minute` requests per minute
+    pub(super) fn per_minute(per_minute: u32) -> Self {
+        Self::new(per_minute as f64, per_minute as f64 / 60.0)
+    }
+
+    pub(super) fn try_consume(&mut self, tokens: f64) -> bool {
         self.refill();
         if self.tokens >= tokens {
             self.tokens -= tokens;
@@ -90,6 +157,23 @@ impl TokenBucket {
         }
     }
 
+    /// Current fill level (including what has refilled since the last request), changing nothing
+    pub(super) fn status(&self) -> RateLimitStatus {
+        let elapsed = self.last_refill.elapsed().as_secs_f64();
+        let tokens = (self.tokens + elapsed * self.refill_rate).min(self.capacity);
+        let seconds_until_next_token = if tokens >= 1.0 || self.refill_rate <= 0.0 {
+            0.0
+        } else {
+            (1.0 - tokens) / self.refill_rate
+        };
+
+        RateLimitStatus {
+            available_tokens: tokens,
+            capacity: self.capacity,
+            seconds_until_next_token,
+        }
+    }
+
     fn refill(&mut self) {
         let now = Instant::now();
         let elapsed = now.duration_since(self.last_refill).as_secs_f64();
@@ -98,27 +182,305 @@ impl TokenBucket {
     }
 }
 
+/// How much of a plugin's request budget is left
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RateLimitStatus {
+    /// Requests that can be made right now (fractional while refilling)
+    pub available_tokens: f64,
+    pub capacity: f64,
+    /// 0 when a request can be made right now
+    pub seconds_until_next_token: f64,
+}
+
+/// Sensitive request parts replaced with `***` before requests are written to the audit log
+#[derive(Debug, Clone)]
+pub struct RedactionRules {
+    /// Query parameter names (case-insensitive)
+    pub query_params: HashSet<String>,
+    /// Header names whose values are redacted (case-insensitive)
+    pub headers: HashSet<String>,
+}
+
+impl Default for RedactionRules {
+    fn default() -> Self {
+        Self {
+            query_params: ["token", "api_key", "password", "access_token"]
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+            headers: ["authorization"].iter().map(|s| s.to_string()).collect(),
+        }
+    }
+}
+
+impl RedactionRules {
+    const MASK: &'static str = "***";
+
+    /// URL with sensitive query parameter values masked
+    pub fn redact_url(&self, url: &str) -> String {
+        let Ok(mut parsed) = url::Url::parse(url) else {
+            return url.to_string();
+        };
+
+        let pairs: Vec<(String, String)> = parsed.query_pairs().into_owned().collect();
+        if !pairs.iter().any(|(name, _)| self.is_sensitive_param(name)) {
+            return url.to_string();
+        }
+
+        parsed.query_pairs_mut().clear().extend_pairs(pairs.iter().map(|(name, value)| {
+            if self.is_sensitive_param(name) {
+                (name.as_str(), Self::MASK)
+            } else {
+                (name.as_str(), value.as_str())
+            }
+        }));
+
+        parsed.to_string()
+    }
+
+    /// Mask the request URL and sensitive header values wherever they appear in `text`
+    /// (e.g. HTTP client errors that echo the URL)
+    pub fn redact_text(&self, text: &str, req: &HttpRequest) -> String {
+        let mut redacted = text.replace(&req.url, &self.redact_url(&req.url));
+
+        for (name, value) in &req.headers {
+            if !value.is_empty() && self.headers.contains(&name.to_lowercase()) {
+                redacted = redacted.replace(value.as_str(), Self::MASK);
+            }
+        }
+
+        redacted
+    }
+
+    fn is_sensitive_param(&self, name: &str) -> bool {
+        self.query_params.contains(&name.to_lowercase())
+    }
+}
+
+/// Value of a header the host injects into plugin requests
+#[derive(Debug, Clone)]
+pub enum InjectedHeader {
+    /// Fixed value (e.g., a shared auth token)
+    Static(String),
+    /// ID of the plugin making the request
+    PluginId,
+    /// Hex-encoded HMAC-SHA256 of the request body
+    BodySignature { secret: Vec<u8> },
+}
+
+impl InjectedHeader {
+    fn value(&self, plugin_id: &str, body: Option<&str>) -> String {
+        match self {
+            Self::Static(value) => value.clone(),
+            Self::PluginId => plugin_id.to_string(),
+            Self::BodySignature { secret } => {
+                let mut mac = Hmac::<Sha256>::new_from_slice(secret)
+                    .expect("HMAC accepts keys of any length");
+                mac.update(body.unwrap_or("").as_bytes());
+                hex::encode(mac.finalize().into_bytes())
+            }
+        }
+    }
+}
+
+/// Headers injected into requests whose host matches `domain_pattern`
+#[derive(Debug, Clone)]
+struct HeaderRule {
+    domain_pattern: String,
+    headers: HashMap<String, InjectedHeader>,
+}
+
+/// Outbound proxy for plugin HTTP requests
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ProxyConfig {
+    /// Proxy for http:// URLs
+    pub http: Option<String>,
+    /// Proxy for https:// URLs
+    pub https: Option<String>,
+    /// Domains reached directly (same patterns as network grants, e.g. *.corp.example.com)
+    #[serde(default)]
+    pub no_proxy: Vec<String>,
+}
+
+impl ProxyConfig {
+    /// Proxy configured in the global settings, if any
+    pub fn from_settings(settings: &GlobalSettings) -> Option<Self> {
+        Self {
+            http: settings.http_proxy.clone().filter(|url| !url.is_empty()),
+            https: settings.https_proxy.clone().filter(|url| !url.is_empty()),
+            no_proxy: settings.no_proxy.clone(),
+        }
+        .non_empty()
+    }
+
+    /// Proxy from the conventional HTTP_PROXY / HTTPS_PROXY / NO_PROXY variables, if any
+    pub fn from_env() -> Option<Self> {
+        let var = |name: &str| {
+            std::env::var(name)
+                .or_else(|_| std::env::var(name.to_lowercase()))
+                .ok()
+                .filter(|value| !value.is_empty())
+        };
+
+        Self {
+            http: var("HTTP_PROXY"),
+            https: var("HTTPS_PROXY"),
+            no_proxy: var("NO_PROXY")
+                .map(|list| list.split(',').map(|d| d.trim().to_string()).filter(|d| !d.is_empty()).collect())
+                .unwrap_or_default(),
+        }
+        .non_empty()
+    }
+
+    fn non_empty(self) -> Option<Self> {
+        (self.http.is_some() || self.https.is_some()).then_some(self)
+    }
+
+    /// Proxy URLs must be HTTP(S) URLs with a host
+    pub fn validate(&self) -> PluginResult<()> {
+        for proxy in self.http.iter().chain(self.https.iter()) {
+            let valid = url::Url::parse(proxy)
+                .map(|parsed| {
+                    matches!(parsed.scheme(), "http" | "https")
+                        && parsed.host_str().is_some_and(|host| !host.is_empty())
+                })
+                .unwrap_or(false);
+            if !valid {
+                return Err(PluginError::PermissionDenied(format!("Invalid proxy URL: {}", proxy)));
+            }
+        }
+        Ok(())
+    }
+
+    /// Proxy to use for `url`, or None to connect directly
+    pub fn proxy_for(&self, url: &url::Url) -> Option<&str> {
+        let host = url.host_str()?;
+        if self.no_proxy.iter().any(|pattern| domain_matches_pattern(host, pattern)) {
+            return None;
+        }
+
+        match url.scheme() {
+            "https" => self.https.as_deref(),
+            "http" => self.http.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// Proxy selection for a client configured with `config`
+fn proxy_rule(config: &ProxyConfig) -> reqwest::Proxy {
+    let config = config.clone();
+    reqwest::Proxy::custom(move |url| config.proxy_for(url).map(str::to_string))
+}
+
+/// Blocking HTTP client with the proxy configuration applied
+pub(super) fn build_client(proxy: Option<&ProxyConfig>, decode: bool) -> reqwest::Result<reqwest::blocking::Client> {
+    let mut builder = reqwest::blocking::Client::builder()
+        .gzip(decode)
+        .deflate(decode)
+        .brotli(decode);
+
+    if let Some(config) = proxy {
+        builder = builder.no_proxy().proxy(proxy_rule(config));
+    }
+
+    builder.build()
+}
+
+/// Async HTTP client with the proxy configuration applied (plugin requests, which must be cancellable)
+fn build_async_client(proxy: Option<&ProxyConfig>, decode: bool) -> reqwest::Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder()
+        .gzip(decode)
+        .deflate(decode)
+        .brotli(decode);
+
+    if let Some(config) = proxy {
+        builder = builder.no_proxy().proxy(proxy_rule(config));
+    }
+
+    builder.build()
+}
+
+/// Error returned for a request cancelled because its plugin was deactivated
+const REQUEST_ABORTED: &str = "HTTP request aborted: plugin deactivated";
+
+/// Send a prepared request and read the full response
+async fn send_request(http_req: reqwest::RequestBuilder, decode: bool) -> PluginResult<HttpResponse> {
+    let http_res = http_req.send().await.map_err(|e| {
+        PluginError::PermissionDenied(format!("HTTP request failed: {}", e))
+    })?;
+
+    // Build response
+    let status = http_res.status().as_u16();
+    let mut headers: HashMap<String, String> = http_res
+        .headers()
+        .iter()
+        .map(|(k, v)| (k.as_str().to_string(), v.to_str().unwrap_or("").to_string()))
+        .collect();
+
+    // The body is no longer encoded, so don't tell the plugin it is
+    if decode {
+        headers.remove("content-encoding");
+    }
+
+    let bytes = http_res.bytes().await.map_err(|e| {
+        PluginError::PermissionDenied(format!("Failed to read response body: {}", e))
+    })?;
+
+    // Binary bodies (images, raw compressed data) would be mangled by a lossy
+    // text conversion, so hand them over base64-encoded instead
+    let (body, body_encoding) = match String::from_utf8(bytes.to_vec()) {
+        Ok(text) => (text, BodyEncoding::Text),
+        Err(_) => (BASE64_STANDARD.encode(&bytes), BodyEncoding::Base64),
+    };
+
+    Ok(HttpResponse {
+        status,
+        headers,
+        body,
+        body_encoding,
+    })
+}
+
 /// PLUGIN-047 to PLUGIN-052: NetworkProxy
 /// Manages HTTP requests with domain whitelist, rate limiting, and caching
 pub struct NetworkProxy {
-    permission_manager: Arc<Mutex<PermissionManager>>,
-    audit_logger: Arc<Mutex<AuditLogger>>,
-    // Rate limiters per plugin (100 req/min default)
+    permission_manager: Arc<RwLock<PermissionManager>>,
+    audit_logger: Arc<RwLock<AuditLogger>>,
+    // Rate limiters per plugin (DEFAULT_REQUESTS_PER_MINUTE unless configured); the only
+    // rate limit on plugin HTTP requests
     rate_limiters: Arc<Mutex<HashMap<PluginId, TokenBucket>>>,
     // Response cache with LRU eviction
-    cache: Arc<Mutex<LruCache<String, CacheEntry>>>,
+    cache: ResponseCache,
     // Default cache TTL in seconds
     default_cache_ttl: u64,
     // Default timeout in seconds
     default_timeout: u64,
     // Maximum timeout in seconds
     max_timeout: u64,
+    // What to mask before requests reach the audit log
+    redaction: RedactionRules,
+    // Host-controlled headers for trusted backends
+    header_rules: Vec<HeaderRule>,
+    // Global kill-switch; overrides every plugin's network grants
+    network_enabled: Arc<AtomicBool>,
+    // Explicit outbound proxy (reqwest's system proxy detection applies otherwise)
+    proxy: Option<ProxyConfig>,
+    // Shared clients so connections are pooled and kept alive across requests
+    client: reqwest::Client,
+    // Same, without transparent decompression (for requests with `decode: false`)
+    raw_client: reqwest::Client,
+    // Registers in-flight requests so deactivating a plugin aborts them
+    lifecycle: Option<Arc<LifecycleManager>>,
+    // Read-only observers for metrics and debugging
+    request_interceptors: Vec<RequestInterceptor>,
+    response_interceptors: Vec<ResponseInterceptor>,
 }
 
 impl NetworkProxy {
     pub fn new(
-        permission_manager: Arc<Mutex<PermissionManager>>,
-        audit_logger: Arc<Mutex<AuditLogger>>,
+        permission_manager: Arc<RwLock<PermissionManager>>,
+        audit_logger: Arc<RwLock<AuditLogger>>,
     ) -> Self {
         Self {
             permission_manager,
@@ -129,6 +491,15 @@ impl NetworkProxy {
             default_cache_ttl: 300, // 5 minutes
             default_timeout: 30,    // 30 seconds
             max_timeout: 300,       // 5 minutes max
+            redaction: RedactionRules::default(),
+            header_rules: Vec::new(),
+            network_enabled: Arc::new(AtomicBool::new(true)),
+            proxy: None,
+            client: build_async_client(None, true).expect("Failed to build HTTP client"),
+            raw_client: build_async_client(None, false).expect("Failed to build HTTP client"),
+            lifecycle: None,
+            request_interceptors: Vec::new(),
+            response_interceptors: Vec::new(),
         }
     }
 
@@ -137,18 +508,148 @@ impl NetworkProxy {
         let mut limiters = self.rate_limiters.lock().unwrap();
         let limiter = limiters
             .entry(plugin_id.to_string())
-            .or_insert_with(|| TokenBucket::new(100.0, 100.0 / 60.0)); // 100 req/min
+            .or_insert_with(|| TokenBucket::per_minute(DEFAULT_REQUESTS_PER_MINUTE));
 
         limiter.try_consume(1.0)
     }
 
+    /// Remaining request budget of a plugin, so it can pace itself (consumes nothing)
+    pub fn rate_limit_status(&self, plugin_id: &str) -> RateLimitStatus {
+        // A plugin that hasn't made a request yet has a full default bucket waiting
+        match self.rate_limiters.lock().unwrap().get(plugin_id) {
+            Some(limiter) => limiter.status(),
+            None => TokenBucket::per_minute(DEFAULT_REQUESTS_PER_MINUTE).status(),
+        }
+    }
+
+    /// Allow a plugin `requests_per_minute` requests per minute (starting with a full bucket)
+    pub fn set_rate_limit(&self, plugin_id: &str, requests_per_minute: u32) {
+        self.rate_limiters
+            .lock()
+            .unwrap()
+            .insert(plugin_id.to_string(), TokenBucket::per_minute(requests_per_minute));
+    }
+
+    /// Drop a plugin's limiter, and with it any custom limit, e.g. once it is uninstalled
+    pub fn remove_rate_limit(&self, plugin_id: &str) {
+        self.rate_limiters.lock().unwrap().remove(plugin_id);
+    }
+
+    /// Turn all plugin network access off (or back on), regardless of granted permissions
+    pub fn set_network_enabled(&self, enabled: bool) {
+        self.network_enabled.store(enabled, Ordering::SeqCst);
+    }
+
+    pub fn is_network_enabled(&self) -> bool {
+        self.network_enabled.load(Ordering::SeqCst)
+    }
+
+    /// The kill switch itself, so other transports (e.g. `WebSocketProxy`) follow `set_network_enabled`
+    pub fn network_switch(&self) -> Arc<AtomicBool> {
+        Arc::clone(&self.network_enabled)
+    }
+
+    /// Route plugin requests through a proxy (None restores system proxy detection)
+    pub fn set_proxy(&mut self, config: Option<ProxyConfig>) -> PluginResult<()> {
+        if let Some(config) = &config {
+            config.validate()?;
+        }
+
+        let client_error = |e: reqwest::Error| PluginError::PermissionDenied(format!("HTTP client error: {}", e));
+        self.client = build_async_client(config.as_ref(), true).map_err(client_error)?;
+        self.raw_client = build_async_client(config.as_ref(), false).map_err(client_error)?;
+        self.proxy = config;
+        Ok(())
+    }
+
+    /// Observe every request sent over the network (cache hits are not sent)
+    pub fn add_request_interceptor(&mut self, interceptor: RequestInterceptor) {
+        self.request_interceptors.push(interceptor);
+    }
+
+    /// Observe every response received over the network, with the request latency
+    pub fn add_response_interceptor(&mut self, interceptor: ResponseInterceptor) {
+        self.response_interceptors.push(interceptor);
+    }
+
+    /// Make requests abortable through the plugin lifecycle
+    pub fn set_lifecycle_manager(&mut self, lifecycle: Arc<LifecycleManager>) {
+        self.lifecycle = Some(lifecycle);
+    }
+
+    /// Send a request as a task on the async runtime and wait for it.
+    /// With a lifecycle manager the task is registered so deactivating the plugin aborts it,
+    /// which drops the in-flight request and closes its connection.
+    fn dispatch(&self, plugin_id: &str, http_req: reqwest::RequestBuilder, decode: bool) -> PluginResult<HttpResponse> {
+        let (tx, rx) = std::sync::mpsc::channel();
+        let task = tauri::async_runtime::spawn(async move {
+            let _ = tx.send(send_request(http_req, decode).await);
+        });
+
+        let Some(lifecycle) = &self.lifecycle else {
+            return rx.recv().unwrap_or_else(|_| {
+                Err(PluginError::PermissionDenied("HTTP request task exited".to_string()))
+            });
+        };
+
+        let request_id = uuid::Uuid::new_v4().to_string();
+        lifecycle.register_request(plugin_id, &request_id, move || task.abort());
+
+        // An aborted task drops its sender without sending
+        let outcome = rx.recv().unwrap_or_else(|_| {
+            Err(PluginError::PermissionDenied(REQUEST_ABORTED.to_string()))
+        });
+        lifecycle.complete_request(plugin_id, &request_id);
+        outcome
+    }
+
+    /// Replace the audit log redaction rules
+    pub fn set_redaction_rules(&mut self, rules: RedactionRules) {
+        self.redaction = rules;
+    }
+
+    /// Inject `headers` into every request to hosts matching `domain_pattern`
+    /// (e.g., "*.internal.example.com"). Injected headers are host-reserved:
+    /// a plugin header with the same name is replaced.
+    pub fn add_header_rule(&mut self, domain_pattern: &str, headers: HashMap<String, InjectedHeader>) {
+        self.header_rules.push(HeaderRule {
+            domain_pattern: domain_pattern.to_string(),
+            headers,
+        });
+    }
+
+    /// Plugin headers with the matching header rules applied on top
+    fn apply_header_rules(&self, plugin_id: &str, req: &HttpRequest) -> HashMap<String, String> {
+        let mut headers = req.headers.clone();
+
+        let host = url::Url::parse(&req.url)
+            .ok()
+            .and_then(|url| url.host_str().map(String::from));
+        let Some(host) = host else {
+            return headers;
+        };
+
+        for rule in &self.header_rules {
+            if !domain_matches_pattern(&host, &rule.domain_pattern) {
+                continue;
+            }
+
+            for (name, injected) in &rule.headers {
+                headers.retain(|existing, _| !existing.eq_ignore_ascii_case(name));
+                headers.insert(name.clone(), injected.value(plugin_id, req.body.as_deref()));
+            }
+        }
+
+        headers
+    }
+
     /// Get reference to permission manager (for testing)
-    pub fn permission_manager(&self) -> &Arc<Mutex<PermissionManager>> {
+    pub fn permission_manager(&self) -> &Arc<RwLock<PermissionManager>> {
         &self.permission_manager
     }
 
     /// Get reference to audit logger (for testing)
-    pub fn audit_logger(&self) -> &Arc<Mutex<AuditLogger>> {
+    pub fn audit_logger(&self) -> &Arc<RwLock<AuditLogger>> {
         &self.audit_logger
     }
 
@@ -162,7 +663,7 @@ impl NetworkProxy {
             PluginError::PermissionDenied("URL has no host".to_string())
         })?;
 
-        let pm = self.permission_manager.lock().unwrap();
+        let pm = self.permission_manager.read().unwrap();
         if !pm.validate_network_permission(plugin_id, domain) {
             return Err(PluginError::PermissionDenied(
                 format!("No network permission for domain: {}", domain)
@@ -172,22 +673,27 @@ impl NetworkProxy {
         Ok(())
     }
 
-    /// PLUGIN-050: Generate cache key from URL and headers
-    fn cache_key(req: &HttpRequest) -> String {
-        // Include method, URL, and relevant headers in cache key
-        let mut key = format!("{}:{}", req.method.as_str(), req.url);
+    /// PLUGIN-050: Generate cache key from plugin, URL and headers
+    fn cache_key(plugin_id: &str, req: &HttpRequest) -> String {
+        // Include plugin, method, URL, and relevant headers in cache key
+        let mut key = format!("{}:{}:{}", plugin_id, req.method.as_str(), req.url);
 
         // Add Authorization header if present (different auth = different cache)
         if let Some(auth) = req.headers.get("Authorization") {
             key.push_str(&format!(":auth:{}", auth));
         }
 
+        // Raw and decoded bodies of the same resource differ
+        if !req.decode {
+            key.push_str(":raw");
+        }
+
         key
     }
 
     /// PLUGIN-050: Get cached response if valid
-    fn get_cached(&self, req: &HttpRequest) -> Option<HttpResponse> {
-        let key = Self::cache_key(req);
+    fn get_cached(&self, plugin_id: &str, req: &HttpRequest) -> Option<HttpResponse> {
+        let key = Self::cache_key(plugin_id, req);
         let mut cache = self.cache.lock().unwrap();
 
         if let Some(entry) = cache.get(&key) {
@@ -203,9 +709,11 @@ impl NetworkProxy {
     }
 
     /// PLUGIN-050: Store response in cache with TTL
-    fn cache_response(&self, req: &HttpRequest, response: &HttpResponse, ttl_secs: u64) {
-        let key = Self::cache_key(req);
+    fn cache_response(&self, plugin_id: &str, req: &HttpRequest, response: &HttpResponse, ttl_secs: u64) {
+        let key = Self::cache_key(plugin_id, req);
         let entry = CacheEntry {
+            plugin_id: plugin_id.to_string(),
+            url: req.url.clone(),
             response: response.clone(),
             expires_at: Instant::now() + Duration::from_secs(ttl_secs),
         };
@@ -214,35 +722,75 @@ impl NetworkProxy {
         cache.put(key, entry);
     }
 
+    /// Drop cached responses whose URL matches the glob `url_pattern` (e.g. `https://api.example.com/*`)
+    pub fn invalidate_cache(&self, url_pattern: &str) -> PluginResult<usize> {
+        let pattern = glob::Pattern::new(url_pattern).map_err(|e| {
+            PluginError::PermissionDenied(format!("Invalid cache pattern: {}", e))
+        })?;
+
+        Ok(remove_cache_entries(&self.cache, |entry| pattern.matches(&entry.url)))
+    }
+
+    /// Drop every cached response
+    pub fn clear_cache(&self) {
+        self.cache.lock().unwrap().clear();
+    }
+
+    /// Drop the responses cached for one plugin
+    pub fn clear_plugin_cache(&self, plugin_id: &str) -> usize {
+        remove_cache_entries(&self.cache, |entry| entry.plugin_id == plugin_id)
+    }
+
+    /// Listener for `PluginManager::on_state_change` that clears an uninstalled plugin's cache
+    pub fn cache_cleanup_listener(&self) -> StateChangeListener {
+        let cache = self.cache.clone();
+        Box::new(move |plugin_id, _from, to| {
+            if to == PluginState::Uninstalled {
+                remove_cache_entries(&cache, |entry| entry.plugin_id == plugin_id);
+            }
+        })
+    }
+
     /// PLUGIN-052: Log request/response to audit logger
+    /// Secrets in the URL and headers are redacted; the cache key keeps the real values
     fn log_request(&self, plugin_id: &str, req: &HttpRequest, success: bool, error: Option<&str>) {
-        let mut logger = self.audit_logger.lock().unwrap();
+        let resource = self.redaction.redact_url(&req.url);
+        let error = error.map(|e| self.redaction.redact_text(e, req));
+
+        let mut logger = self.audit_logger.write().unwrap();
         logger.log_permission_check(
             plugin_id,
             &PermissionType::NetworkRequest,
-            &req.url,
+            &resource,
             &format!("{} request", req.method.as_str()),
             success,
-            error,
+            error.as_deref(),
         );
     }
 
     /// PLUGIN-047: Execute HTTP request with all validations
     pub fn request(&self, plugin_id: &str, req: HttpRequest) -> PluginResult<HttpResponse> {
+        // Kill-switch is checked before anything else
+        if !self.is_network_enabled() {
+            self.log_request(plugin_id, &req, false, Some("plugin network disabled"));
+            return Err(PluginError::PermissionDenied("plugin network disabled".to_string()));
+        }
+
         // Step 1: Validate domain permission (PLUGIN-048)
         self.validate_domain(plugin_id, &req.url)?;
 
         // Step 2: Check rate limit (PLUGIN-049)
         if !self.check_rate_limit(plugin_id) {
             self.log_request(plugin_id, &req, false, Some("Rate limit exceeded"));
-            return Err(PluginError::PermissionDenied(
-                "Rate limit exceeded (100 req/min)".to_string()
-            ));
+            return Err(PluginError::PermissionDenied(format!(
+                "Rate limit exceeded ({} req/min)",
+                self.rate_limit_status(plugin_id).capacity
+            )));
         }
 
         // Step 3: Check cache (PLUGIN-050)
         if req.method.as_str() == "GET" {
-            if let Some(cached) = self.get_cached(&req) {
+            if let Some(cached) = self.get_cached(plugin_id, &req) {
                 self.log_request(plugin_id, &req, true, None);
                 return Ok(cached);
             }
@@ -253,10 +801,8 @@ impl NetworkProxy {
             .unwrap_or(self.default_timeout)
             .min(self.max_timeout);
 
-        let client = reqwest::blocking::Client::builder()
-            .timeout(Duration::from_secs(timeout))
-            .build()
-            .map_err(|e| PluginError::PermissionDenied(format!("HTTP client error: {}", e)))?;
+        // Compressed responses are decoded transparently unless the plugin asked for the raw body
+        let client = if req.decode { &self.client } else { &self.raw_client };
 
         let mut http_req = match req.method {
             HttpMethod::Get => client.get(&req.url),
@@ -270,8 +816,10 @@ impl NetworkProxy {
             }
         };
 
-        // Add headers
-        for (key, value) in &req.headers {
+        http_req = http_req.timeout(Duration::from_secs(timeout));
+
+        // Add headers (plugin headers first, then host-injected ones)
+        for (key, value) in &self.apply_header_rules(plugin_id, &req) {
             http_req = http_req.header(key, value);
         }
 
@@ -281,32 +829,25 @@ impl NetworkProxy {
         }
 
         // Execute request
-        let http_res = http_req.send().map_err(|e| {
-            self.log_request(plugin_id, &req, false, Some(&e.to_string()));
-            PluginError::PermissionDenied(format!("HTTP request failed: {}", e))
-        })?;
-
-        // Build response
-        let status = http_res.status().as_u16();
-        let headers: HashMap<String, String> = http_res
-            .headers()
-            .iter()
-            .map(|(k, v)| (k.as_str().to_string(), v.to_str().unwrap_or("").to_string()))
-            .collect();
+        for interceptor in &self.request_interceptors {
+            run_interceptor("Request", || interceptor(&req));
+        }
 
-        let body = http_res.text().map_err(|e| {
-            PluginError::PermissionDenied(format!("Failed to read response body: {}", e))
+        let started = Instant::now();
+        let response = self.dispatch(plugin_id, http_req, req.decode).map_err(|e| {
+            self.log_request(plugin_id, &req, false, Some(&e.to_string()));
+            e
         })?;
+        let elapsed = started.elapsed();
+        let status = response.status;
 
-        let response = HttpResponse {
-            status,
-            headers,
-            body,
-        };
+        for interceptor in &self.response_interceptors {
+            run_interceptor("Response", || interceptor(&req, &response, elapsed));
+        }
 
         // Step 5: Cache GET responses (PLUGIN-050)
         if req.method.as_str() == "GET" && status == 200 {
-            self.cache_response(&req, &response, self.default_cache_ttl);
+            self.cache_response(plugin_id, &req, &response, self.default_cache_ttl);
         }
 
         // Step 6: Log success (PLUGIN-052)
@@ -323,6 +864,7 @@ impl NetworkProxy {
             headers: HashMap::new(),
             body: None,
             timeout_secs: None,
+            decode: true,
         })
     }
 
@@ -334,6 +876,7 @@ impl NetworkProxy {
             headers,
             body: Some(body),
             timeout_secs: None,
+            decode: true,
         })
     }
 
@@ -345,6 +888,7 @@ impl NetworkProxy {
             headers,
             body: Some(body),
             timeout_secs: None,
+            decode: true,
         })
     }
 
@@ -356,6 +900,7 @@ impl NetworkProxy {
             headers: HashMap::new(),
             body: None,
             timeout_secs: None,
+            decode: true,
         })
     }
 }
@@ -369,8 +914,8 @@ mod tests {
         let temp_dir = std::env::temp_dir().join(format!("vcp_net_test_{}", uuid::Uuid::new_v4()));
         std::fs::create_dir_all(&temp_dir).unwrap();
 
-        let pm = Arc::new(Mutex::new(PermissionManager::new(temp_dir.clone())));
-        let logger = Arc::new(Mutex::new(AuditLogger::new(temp_dir)));
+        let pm = Arc::new(RwLock::new(PermissionManager::new(temp_dir.clone())));
+        let logger = Arc::new(RwLock::new(AuditLogger::new(temp_dir)));
 
         NetworkProxy::new(pm, logger)
     }
@@ -391,6 +936,69 @@ mod tests {
         assert!(allowed >= 95 && allowed <= 105, "Expected ~100 allowed requests, got {}", allowed);
     }
 
+    #[test]
+    fn test_rate_limit_status() {
+        let proxy = create_test_network_proxy();
+
+        let fresh = proxy.rate_limit_status("test-plugin");
+        assert_eq!(fresh.capacity, 100.0);
+        assert_eq!(fresh.available_tokens, 100.0);
+        assert_eq!(fresh.seconds_until_next_token, 0.0);
+        // Asking doesn't create a limiter
+        assert!(proxy.rate_limiters.lock().unwrap().is_empty());
+
+        for _ in 0..40 {
+            assert!(proxy.check_rate_limit("test-plugin"));
+        }
+        let status = proxy.rate_limit_status("test-plugin");
+        assert!((60.0..61.0).contains(&status.available_tokens), "{}", status.available_tokens);
+        // Reading the status didn't consume anything
+        assert!(proxy.rate_limit_status("test-plugin").available_tokens >= status.available_tokens);
+
+        while proxy.check_rate_limit("test-plugin") {}
+        let empty = proxy.rate_limit_status("test-plugin");
+        assert!(empty.available_tokens < 1.0);
+        // 100 tokens per minute: at most 0.6s to the next one
+        assert!(empty.seconds_until_next_token > 0.0 && empty.seconds_until_next_token <= 0.6);
+    }
+
+    #[test]
+    fn test_request_consumes_one_token() {
+        let mut server = mockito::Server::new();
+        let mock = server.mock("GET", "/quota").with_status(200).with_body("ok").expect(1).create();
+
+        let proxy = create_test_network_proxy();
+        proxy.permission_manager().write().unwrap()
+            .grant_permission("test-plugin", PermissionType::NetworkRequest, "*".to_string())
+            .unwrap();
+
+        proxy.get("test-plugin", &format!("{}/quota", server.url())).unwrap();
+
+        // One request, one token, from the proxy's bucket only
+        let status = proxy.rate_limit_status("test-plugin");
+        assert!((99.0..99.5).contains(&status.available_tokens), "{}", status.available_tokens);
+        mock.assert();
+    }
+
+    #[test]
+    fn test_per_plugin_rate_limit() {
+        let proxy = create_test_network_proxy();
+        proxy.permission_manager().write().unwrap()
+            .grant_permission("slow-plugin", PermissionType::NetworkRequest, "*".to_string())
+            .unwrap();
+
+        proxy.set_rate_limit("slow-plugin", 2);
+        assert_eq!(proxy.rate_limit_status("slow-plugin").capacity, 2.0);
+        assert!(proxy.check_rate_limit("slow-plugin"));
+        assert!(proxy.check_rate_limit("slow-plugin"));
+
+        let result = proxy.get("slow-plugin", "https://api.example.com/data");
+        assert!(matches!(result, Err(PluginError::PermissionDenied(msg)) if msg == "Rate limit exceeded (2 req/min)"));
+
+        // Other plugins keep the default
+        assert_eq!(proxy.rate_limit_status("test-plugin").capacity, DEFAULT_REQUESTS_PER_MINUTE as f64);
+    }
+
     #[test]
     fn test_cache_key_generation() {
         let req1 = HttpRequest {
@@ -399,10 +1007,11 @@ mod tests {
             headers: HashMap::new(),
             body: None,
             timeout_secs: None,
+            decode: true,
         };
 
-        let key1 = NetworkProxy::cache_key(&req1);
-        assert_eq!(key1, "GET:https://api.example.com/data");
+        let key1 = NetworkProxy::cache_key("test-plugin", &req1);
+        assert_eq!(key1, "test-plugin:GET:https://api.example.com/data");
 
         let mut headers = HashMap::new();
         headers.insert("Authorization".to_string(), "Bearer token123".to_string());
@@ -412,10 +1021,418 @@ mod tests {
             headers,
             body: None,
             timeout_secs: None,
+            decode: true,
         };
 
-        let key2 = NetworkProxy::cache_key(&req2);
+        let key2 = NetworkProxy::cache_key("test-plugin", &req2);
         assert!(key2.contains("auth:Bearer token123"));
         assert_ne!(key1, key2);
     }
+
+    #[test]
+    fn test_audit_log_redacts_secrets() {
+        let proxy = create_test_network_proxy();
+
+        let mut headers = HashMap::new();
+        headers.insert("Authorization".to_string(), "Bearer secret-token".to_string());
+        let req = HttpRequest {
+            url: "https://api.example.com/data?token=secret&q=weather".to_string(),
+            method: HttpMethod::Get,
+            headers,
+            body: None,
+            timeout_secs: None,
+            decode: true,
+        };
+
+        let error = format!("request to {} with Bearer secret-token failed", req.url);
+        proxy.log_request("test-plugin", &req, false, Some(&error));
+
+        let entries = proxy.audit_logger().read().unwrap().read_audit_logs(None, None).unwrap();
+        let entry = entries.iter().find(|e| e.plugin_id == "test-plugin").unwrap();
+        assert_eq!(entry.resource, "https://api.example.com/data?token=***&q=weather");
+
+        let logged_error = entry.error_message.as_deref().unwrap();
+        assert!(!logged_error.contains("secret"));
+        assert!(logged_error.contains("?token=***"));
+
+        // The cache key still distinguishes by the real credentials
+        assert!(NetworkProxy::cache_key("test-plugin", &req).contains("Bearer secret-token"));
+    }
+
+    #[test]
+    fn test_gzip_response_is_decoded() {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(b"{\"forecast\":\"sunny\"}").unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("GET", "/forecast")
+            .with_status(200)
+            .with_header("content-encoding", "gzip")
+            .with_body(compressed.clone())
+            .expect(2)
+            .create();
+
+        let proxy = create_test_network_proxy();
+        proxy.permission_manager().write().unwrap()
+            .grant_permission("test-plugin", PermissionType::NetworkRequest, "*".to_string())
+            .unwrap();
+
+        let url = format!("{}/forecast", server.url());
+        let response = proxy.get("test-plugin", &url).unwrap();
+        assert_eq!(response.body, "{\"forecast\":\"sunny\"}");
+        assert!(!response.headers.contains_key("content-encoding"));
+
+        // Opting out keeps the encoded body and header
+        let raw = proxy.request("test-plugin", HttpRequest {
+            url,
+            method: HttpMethod::Get,
+            headers: HashMap::new(),
+            body: None,
+            timeout_secs: None,
+            decode: false,
+        }).unwrap();
+        assert_eq!(raw.headers.get("content-encoding").map(String::as_str), Some("gzip"));
+        assert_eq!(raw.body_encoding, BodyEncoding::Base64);
+        assert_eq!(BASE64_STANDARD.decode(&raw.body).unwrap(), compressed);
+        assert_eq!(response.body_encoding, BodyEncoding::Text);
+
+        mock.assert();
+    }
+
+    #[test]
+    fn test_header_rules_inject_reserved_headers() {
+        let mut server = mockito::Server::new();
+        let body = "{\"event\":\"ping\"}";
+        let signature = InjectedHeader::BodySignature { secret: b"gateway-secret".to_vec() }
+            .value("test-plugin", Some(body));
+        let mock = server
+            .mock("POST", "/events")
+            .match_header("x-plugin-id", "test-plugin")
+            .match_header("x-signature", signature.as_str())
+            .match_header("x-trace", "plugin-value")
+            .with_status(200)
+            .create();
+
+        let mut proxy = create_test_network_proxy();
+        proxy.permission_manager().write().unwrap()
+            .grant_permission("test-plugin", PermissionType::NetworkRequest, "*".to_string())
+            .unwrap();
+
+        let mut injected = HashMap::new();
+        injected.insert("X-Plugin-Id".to_string(), InjectedHeader::PluginId);
+        injected.insert("X-Signature".to_string(), InjectedHeader::BodySignature { secret: b"gateway-secret".to_vec() });
+        proxy.add_header_rule("127.0.0.1", injected);
+
+        // The plugin cannot spoof a host-reserved header
+        let mut headers = HashMap::new();
+        headers.insert("x-plugin-id".to_string(), "spoofed".to_string());
+        headers.insert("X-Trace".to_string(), "plugin-value".to_string());
+
+        let response = proxy.post("test-plugin", &format!("{}/events", server.url()), body.to_string(), headers).unwrap();
+        assert_eq!(response.status, 200);
+        mock.assert();
+    }
+
+    #[test]
+    fn test_header_rules_only_apply_to_matching_domains() {
+        let mut proxy = create_test_network_proxy();
+        let mut injected = HashMap::new();
+        injected.insert("X-Gateway".to_string(), InjectedHeader::Static("1".to_string()));
+        proxy.add_header_rule("*.internal.example.com", injected);
+
+        let mut req = HttpRequest {
+            url: "https://api.internal.example.com/v1".to_string(),
+            method: HttpMethod::Get,
+            headers: HashMap::new(),
+            body: None,
+            timeout_secs: None,
+            decode: true,
+        };
+        assert_eq!(proxy.apply_header_rules("test-plugin", &req).get("X-Gateway").map(String::as_str), Some("1"));
+
+        req.url = "https://api.example.com/v1".to_string();
+        assert!(proxy.apply_header_rules("test-plugin", &req).is_empty());
+    }
+
+    #[test]
+    fn test_network_kill_switch() {
+        let mut server = mockito::Server::new();
+        let mock = server.mock("GET", "/status").with_status(200).with_body("ok").expect(1).create();
+
+        let proxy = create_test_network_proxy();
+        proxy.permission_manager().write().unwrap()
+            .grant_permission("test-plugin", PermissionType::NetworkRequest, "*".to_string())
+            .unwrap();
+        let url = format!("{}/status", server.url());
+
+        proxy.set_network_enabled(false);
+        assert!(!proxy.is_network_enabled());
+        assert!(matches!(
+            proxy.get("test-plugin", &url),
+            Err(PluginError::PermissionDenied(msg)) if msg == "plugin network disabled"
+        ));
+
+        proxy.set_network_enabled(true);
+        assert_eq!(proxy.get("test-plugin", &url).unwrap().body, "ok");
+
+        // Only the request made while enabled reached the server
+        mock.assert();
+        let blocked = proxy.audit_logger().read().unwrap()
+            .read_audit_logs(None, None)
+            .unwrap()
+            .into_iter()
+            .filter(|entry| entry.error_message.as_deref() == Some("plugin network disabled"))
+            .count();
+        assert_eq!(blocked, 1);
+    }
+
+    #[test]
+    fn test_requests_go_through_configured_proxy() {
+        // The mock server stands in for the corporate proxy
+        let mut proxy_server = mockito::Server::new();
+        let mock = proxy_server
+            .mock("GET", "/data")
+            .match_header("host", "plugin-api.example.test")
+            .with_status(200)
+            .with_body("via proxy")
+            .create();
+
+        let mut proxy = create_test_network_proxy();
+        proxy.permission_manager().write().unwrap()
+            .grant_permission("test-plugin", PermissionType::NetworkRequest, "*".to_string())
+            .unwrap();
+        proxy.set_proxy(Some(ProxyConfig {
+            http: Some(proxy_server.url()),
+            https: None,
+            no_proxy: Vec::new(),
+        })).unwrap();
+
+        let response = proxy.get("test-plugin", "http://plugin-api.example.test/data").unwrap();
+        assert_eq!(response.body, "via proxy");
+        mock.assert();
+    }
+
+    #[test]
+    fn test_proxy_bypass_and_validation() {
+        let config = ProxyConfig {
+            http: Some("http://proxy.corp.example.com:3128".to_string()),
+            https: Some("http://proxy.corp.example.com:3128".to_string()),
+            no_proxy: vec!["*.internal.example.com".to_string()],
+        };
+        let url = |u: &str| url::Url::parse(u).unwrap();
+        assert_eq!(config.proxy_for(&url("https://api.example.com/v1")), Some("http://proxy.corp.example.com:3128"));
+        assert_eq!(config.proxy_for(&url("https://wiki.internal.example.com/v1")), None);
+
+        let mut proxy = create_test_network_proxy();
+        let invalid = ProxyConfig { http: Some("proxy.corp:3128".to_string()), ..ProxyConfig::default() };
+        assert!(proxy.set_proxy(Some(invalid)).is_err());
+        assert!(proxy.set_proxy(Some(config)).is_ok());
+
+        let mut settings = GlobalSettings::default();
+        assert_eq!(ProxyConfig::from_settings(&settings), None);
+        settings.https_proxy = Some("http://proxy.corp.example.com:3128".to_string());
+        assert!(ProxyConfig::from_settings(&settings).is_some());
+    }
+
+    #[test]
+    fn test_sequential_requests_reuse_connection() {
+        use std::io::{BufRead, BufReader, Write};
+        use std::net::TcpListener;
+        use std::sync::atomic::AtomicUsize;
+
+        // Minimal keep-alive server that counts accepted connections
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let connections = Arc::new(AtomicUsize::new(0));
+        let accepted = connections.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(stream) = stream else { break };
+                accepted.fetch_add(1, Ordering::SeqCst);
+                std::thread::spawn(move || {
+                    let mut reader = BufReader::new(stream.try_clone().unwrap());
+                    let mut writer = stream;
+                    loop {
+                        // Read one request head (GETs carry no body)
+                        let mut line = String::new();
+                        loop {
+                            line.clear();
+                            if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                                return;
+                            }
+                            if line == "\r\n" {
+                                break;
+                            }
+                        }
+                        if writer.write_all(b"HTTP/1.1 200 OK\r\nContent-Length: 2\r\n\r\nok").is_err() {
+                            return;
+                        }
+                    }
+                });
+            }
+        });
+
+        let proxy = create_test_network_proxy();
+        proxy.permission_manager().write().unwrap()
+            .grant_permission("test-plugin", PermissionType::NetworkRequest, "*".to_string())
+            .unwrap();
+
+        // Distinct URLs so the response cache doesn't short-circuit
+        for i in 0..3 {
+            let response = proxy.get("test-plugin", &format!("http://127.0.0.1:{}/poll/{}", port, i)).unwrap();
+            assert_eq!(response.body, "ok");
+        }
+
+        assert_eq!(connections.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_deactivation_aborts_in_flight_request() {
+        use std::net::TcpListener;
+
+        // Accepts the connection but never answers; reports when the client hangs up
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let (closed_tx, closed_rx) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            use std::io::Read;
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 1024];
+            while matches!(stream.read(&mut buf), Ok(n) if n > 0) {}
+            let _ = closed_tx.send(());
+        });
+
+        let lifecycle = Arc::new(LifecycleManager::new());
+        let mut proxy = create_test_network_proxy();
+        proxy.set_lifecycle_manager(lifecycle.clone());
+        proxy.permission_manager().write().unwrap()
+            .grant_permission("test-plugin", PermissionType::NetworkRequest, "*".to_string())
+            .unwrap();
+
+        let proxy = Arc::new(proxy);
+        let worker = {
+            let proxy = proxy.clone();
+            std::thread::spawn(move || proxy.get("test-plugin", &format!("http://127.0.0.1:{}/slow", port)))
+        };
+
+        let started = Instant::now();
+        while lifecycle.get_resource_count("test-plugin") == 0 {
+            assert!(started.elapsed() < Duration::from_secs(5), "request was never registered");
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        let manifest: super::super::manifest_parser::PluginManifest = serde_json::from_str(
+            r#"{"manifestVersion":"1.0","name":"test-plugin","displayName":"Test","version":"1.0.0","description":"","author":""}"#
+        ).unwrap();
+        lifecycle.execute_deactivate_hook("test-plugin", std::path::Path::new("."), &manifest).unwrap();
+
+        let result = worker.join().unwrap();
+        assert!(matches!(
+            result,
+            Err(PluginError::PermissionDenied(msg)) if msg == REQUEST_ABORTED
+        ));
+        assert!(started.elapsed() < Duration::from_secs(10));
+        assert_eq!(lifecycle.get_resource_count("test-plugin"), 0);
+
+        // The request itself was cancelled, not just abandoned
+        closed_rx.recv_timeout(Duration::from_secs(5)).expect("connection was not closed");
+    }
+
+    fn cached_get(proxy: &NetworkProxy, plugin_id: &str, url: &str) {
+        let req = HttpRequest {
+            url: url.to_string(),
+            method: HttpMethod::Get,
+            headers: HashMap::new(),
+            body: None,
+            timeout_secs: None,
+            decode: true,
+        };
+        let response = HttpResponse { status: 200, headers: HashMap::new(), body: url.to_string(), body_encoding: BodyEncoding::Text };
+        proxy.cache_response(plugin_id, &req, &response, 300);
+    }
+
+    fn is_cached(proxy: &NetworkProxy, plugin_id: &str, url: &str) -> bool {
+        proxy.get_cached(plugin_id, &HttpRequest {
+            url: url.to_string(),
+            method: HttpMethod::Get,
+            headers: HashMap::new(),
+            body: None,
+            timeout_secs: None,
+            decode: true,
+        }).is_some()
+    }
+
+    #[test]
+    fn test_cache_invalidation_by_pattern() {
+        let proxy = create_test_network_proxy();
+        cached_get(&proxy, "weather", "https://api.example.com/forecast");
+        cached_get(&proxy, "weather", "https://api.example.com/alerts");
+        cached_get(&proxy, "news", "https://news.example.com/top");
+
+        assert_eq!(proxy.invalidate_cache("https://api.example.com/*").unwrap(), 2);
+        assert!(!is_cached(&proxy, "weather", "https://api.example.com/forecast"));
+        assert!(is_cached(&proxy, "news", "https://news.example.com/top"));
+        assert!(proxy.invalidate_cache("https://[").is_err());
+
+        // Uninstalling a plugin drops only its entries
+        cached_get(&proxy, "weather", "https://api.example.com/forecast");
+        let listener = proxy.cache_cleanup_listener();
+        listener("weather", PluginState::Installed, PluginState::Uninstalled);
+        assert!(!is_cached(&proxy, "weather", "https://api.example.com/forecast"));
+        assert!(is_cached(&proxy, "news", "https://news.example.com/top"));
+    }
+
+    #[test]
+    fn test_clear_cache() {
+        let proxy = create_test_network_proxy();
+        cached_get(&proxy, "weather", "https://api.example.com/forecast");
+        cached_get(&proxy, "news", "https://news.example.com/top");
+
+        assert_eq!(proxy.clear_plugin_cache("news"), 1);
+        assert!(is_cached(&proxy, "weather", "https://api.example.com/forecast"));
+
+        proxy.clear_cache();
+        assert!(!is_cached(&proxy, "weather", "https://api.example.com/forecast"));
+    }
+
+    #[test]
+    fn test_interceptors_observe_completed_request() {
+        let mut server = mockito::Server::new();
+        let mock = server.mock("GET", "/metrics").with_status(200).with_body("ok").create();
+
+        let mut proxy = create_test_network_proxy();
+        proxy.permission_manager().write().unwrap()
+            .grant_permission("test-plugin", PermissionType::NetworkRequest, "*".to_string())
+            .unwrap();
+
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let observed = Arc::new(Mutex::new(Vec::new()));
+        {
+            let sent = sent.clone();
+            proxy.add_request_interceptor(Box::new(move |req| sent.lock().unwrap().push(req.url.clone())));
+        }
+        {
+            let observed = observed.clone();
+            proxy.add_response_interceptor(Box::new(move |_req, res, elapsed| {
+                observed.lock().unwrap().push((res.status, elapsed));
+            }));
+        }
+        // A failing interceptor doesn't affect the request
+        proxy.add_response_interceptor(Box::new(|_, _, _| panic!("metrics backend unavailable")));
+
+        let url = format!("{}/metrics", server.url());
+        assert_eq!(proxy.get("test-plugin", &url).unwrap().body, "ok");
+        mock.assert();
+
+        assert_eq!(*sent.lock().unwrap(), vec![url]);
+        let observed = observed.lock().unwrap();
+        assert_eq!(observed.len(), 1);
+        assert_eq!(observed[0].0, 200);
+        assert!(observed[0].1 > Duration::ZERO);
+    }
 }
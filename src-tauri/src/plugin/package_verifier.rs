@@ -0,0 +1,249 @@
+// Plugin package authenticity checks
+// Ed25519 signatures over a digest of every file in the package
+
+use super::{PluginError, PluginResult};
+use crate::models::GlobalSettings;
+use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Detached signature shipped at the root of the plugin ZIP
+pub const SIGNATURE_FILE: &str = "signature.sig";
+
+/// AppData file with each plugin's content hash as recorded at install. It lives outside the
+/// plugins directory, so editing a plugin's files doesn't also rewrite the hash they are checked against.
+pub const INSTALL_HASHES_FILE: &str = "plugin-install-hashes.json";
+
+/// Which plugin signatures are trusted, and whether unsigned plugins may install
+#[derive(Debug, Clone, Default)]
+pub struct SignaturePolicy {
+    /// Abort installation unless the package is signed by a trusted key
+    pub enforce: bool,
+    pub trusted_keys: Vec<VerifyingKey>,
+}
+
+impl SignaturePolicy {
+    /// Build a policy from hex-encoded Ed25519 public keys
+    pub fn from_hex_keys(enforce: bool, keys: &[String]) -> PluginResult<Self> {
+        let trusted_keys = keys
+            .iter()
+            .map(|key| parse_public_key(key))
+            .collect::<PluginResult<Vec<_>>>()?;
+
+        Ok(Self { enforce, trusted_keys })
+    }
+
+    /// Policy configured in the global settings
+    pub fn from_settings(settings: &GlobalSettings) -> PluginResult<Self> {
+        Self::from_hex_keys(settings.enforce_plugin_signatures, &settings.trusted_plugin_keys)
+    }
+}
+
+/// Outcome of checking an extracted package
+#[derive(Debug, Clone, PartialEq)]
+pub enum SignatureStatus {
+    /// Signed by one of the trusted keys
+    Verified,
+    /// No signature file in the package
+    Unsigned,
+    /// Signature present but malformed or not from a trusted key (package may be tampered)
+    Invalid(String),
+}
+
+fn parse_public_key(key: &str) -> PluginResult<VerifyingKey> {
+    let bytes: [u8; 32] = hex::decode(key.trim())
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| PluginError::ManifestValidation(format!("Invalid trusted public key: {}", key)))?;
+
+    VerifyingKey::from_bytes(&bytes)
+        .map_err(|e| PluginError::ManifestValidation(format!("Invalid trusted public key {}: {}", key, e)))
+}
+
+/// Listing of every package file with its SHA-256 ("<hash>  <relative/path>" lines, sorted by path).
+/// The signature file itself is excluded; this listing is what publishers sign.
+pub fn package_digest(plugin_dir: &Path) -> PluginResult<Vec<u8>> {
+    let mut files = Vec::new();
+    collect_files(plugin_dir, plugin_dir, &mut files)?;
+    files.retain(|relative| relative != SIGNATURE_FILE);
+    files.sort();
+
+    let mut listing = String::new();
+    for relative in files {
+        let content = std::fs::read(plugin_dir.join(&relative))?;
+        listing.push_str(&format!("{}  {}\n", hex::encode(Sha256::digest(&content)), relative));
+    }
+
+    Ok(listing.into_bytes())
+}
+
+/// SHA-256 (hex) of the package digest, identifying the exact package contents
+pub fn content_hash(plugin_dir: &Path) -> PluginResult<String> {
+    Ok(hex::encode(Sha256::digest(package_digest(plugin_dir)?)))
+}
+
+/// Install-time content hashes by plugin id (empty when nothing was recorded yet)
+pub fn load_install_hashes(path: &Path) -> PluginResult<HashMap<String, String>> {
+    if !path.exists() {
+        return Ok(HashMap::new());
+    }
+
+    let content = std::fs::read_to_string(path)?;
+    serde_json::from_str(&content)
+        .map_err(|e| PluginError::ManifestError(format!("Failed to parse install hashes: {}", e)))
+}
+
+pub fn save_install_hashes(path: &Path, hashes: &HashMap<String, String>) -> PluginResult<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let content = serde_json::to_string_pretty(hashes)
+        .map_err(|e| PluginError::ManifestError(format!("Failed to serialize install hashes: {}", e)))?;
+    std::fs::write(path, content)?;
+    Ok(())
+}
+
+/// Relative paths (with '/' separators) of all files under `dir`
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<String>) -> PluginResult<()> {
+    for entry in std::fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(root, &path, files)?;
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            let parts: Vec<String> = relative
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy().to_string())
+                .collect();
+            files.push(parts.join("/"));
+        }
+    }
+
+    Ok(())
+}
+
+/// Check the signature of an extracted package against the trusted keys
+pub fn check_signature(plugin_dir: &Path, policy: &SignaturePolicy) -> PluginResult<SignatureStatus> {
+    let signature_path = plugin_dir.join(SIGNATURE_FILE);
+    if !signature_path.exists() {
+        return Ok(SignatureStatus::Unsigned);
+    }
+
+    // Accept either the raw 64 signature bytes or their hex encoding
+    let raw = std::fs::read(&signature_path)?;
+    let bytes = if raw.len() == 64 {
+        Some(raw)
+    } else {
+        hex::decode(String::from_utf8_lossy(&raw).trim()).ok()
+    };
+    let Some(signature) = bytes.and_then(|b| Signature::from_slice(&b).ok()) else {
+        return Ok(SignatureStatus::Invalid("malformed signature file".to_string()));
+    };
+
+    let digest = package_digest(plugin_dir)?;
+    if policy.trusted_keys.iter().any(|key| key.verify(&digest, &signature).is_ok()) {
+        Ok(SignatureStatus::Verified)
+    } else {
+        Ok(SignatureStatus::Invalid("not signed by a trusted key".to_string()))
+    }
+}
+
+/// Enforce the policy on an extracted package.
+/// Only a verified package passes when enforcement is on; otherwise problems are reported but allowed.
+pub fn verify_package(plugin_dir: &Path, policy: &SignaturePolicy) -> PluginResult<SignatureStatus> {
+    let status = check_signature(plugin_dir, policy)?;
+
+    if policy.enforce && status != SignatureStatus::Verified {
+        return Err(PluginError::ManifestValidation("signature verification failed".to_string()));
+    }
+
+    Ok(status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn signing_key() -> SigningKey {
+        SigningKey::from_bytes(&[7u8; 32])
+    }
+
+    fn trusted_policy(enforce: bool) -> SignaturePolicy {
+        SignaturePolicy {
+            enforce,
+            trusted_keys: vec![signing_key().verifying_key()],
+        }
+    }
+
+    fn create_package(signed: bool) -> std::path::PathBuf {
+        let dir = std::env::temp_dir().join(format!("vcp_signature_test_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(dir.join("lib")).unwrap();
+        std::fs::write(dir.join("manifest.json"), r#"{"name":"weather"}"#).unwrap();
+        std::fs::write(dir.join("lib").join("index.js"), "module.exports = {};").unwrap();
+
+        if signed {
+            let digest = package_digest(&dir).unwrap();
+            let signature = signing_key().sign(&digest);
+            std::fs::write(dir.join(SIGNATURE_FILE), hex::encode(signature.to_bytes())).unwrap();
+        }
+
+        dir
+    }
+
+    #[test]
+    fn test_valid_signature() {
+        let dir = create_package(true);
+
+        assert_eq!(verify_package(&dir, &trusted_policy(true)).unwrap(), SignatureStatus::Verified);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_tampered_manifest_fails() {
+        let dir = create_package(true);
+        std::fs::write(dir.join("manifest.json"), r#"{"name":"weather","permissions":["filesystem.write:*"]}"#).unwrap();
+
+        assert!(matches!(check_signature(&dir, &trusted_policy(true)).unwrap(), SignatureStatus::Invalid(_)));
+        assert!(matches!(
+            verify_package(&dir, &trusted_policy(true)),
+            Err(PluginError::ManifestValidation(msg)) if msg == "signature verification failed"
+        ));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_untrusted_key_fails() {
+        let dir = create_package(true);
+        let policy = SignaturePolicy {
+            enforce: true,
+            trusted_keys: vec![SigningKey::from_bytes(&[9u8; 32]).verifying_key()],
+        };
+
+        assert!(verify_package(&dir, &policy).is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_enforcement_toggle_for_unsigned_packages() {
+        let dir = create_package(false);
+
+        assert_eq!(verify_package(&dir, &trusted_policy(false)).unwrap(), SignatureStatus::Unsigned);
+        assert!(verify_package(&dir, &trusted_policy(true)).is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_policy_from_hex_keys() {
+        let key = hex::encode(signing_key().verifying_key().to_bytes());
+
+        let policy = SignaturePolicy::from_hex_keys(true, &[key]).unwrap();
+        assert_eq!(policy.trusted_keys.len(), 1);
+        assert!(SignaturePolicy::from_hex_keys(true, &["not-a-key".to_string()]).is_err());
+    }
+}
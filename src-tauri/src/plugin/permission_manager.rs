@@ -5,10 +5,9 @@
 use super::{PluginError, PluginId, PluginResult};
 use super::audit_logger::AuditLogger;
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
-use std::time::{Duration, Instant};
 use chrono::Utc;
 
 /// PLUGIN-011: PermissionType enum with all supported permissions
@@ -70,6 +69,18 @@ impl std::fmt::Display for PermissionType {
     }
 }
 
+/// Outcome of `PermissionManager::import_permissions`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionImport {
+    /// Number of grants added
+    pub imported: usize,
+    /// Existing grants dropped by a replacing import
+    pub removed: Vec<PluginPermission>,
+    /// Plugins in the export that aren't installed; their grants were not imported
+    pub skipped: Vec<PluginId>,
+}
+
 /// PLUGIN-012: PluginPermission struct with resource_scope
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PluginPermission {
@@ -129,6 +140,170 @@ fn is_valid_domain_pattern(pattern: &str) -> bool {
     }
 }
 
+/// Resolve the AppData directory once, creating it if it doesn't exist yet.
+/// Falls back to the path as given if it can't be resolved.
+pub(crate) fn canonicalize_app_data(app_data_dir: &Path) -> PathBuf {
+    if let Err(e) = std::fs::create_dir_all(app_data_dir) {
+        eprintln!("[PermissionManager] Failed to create AppData directory: {}", e);
+    }
+
+    app_data_dir.canonicalize().unwrap_or_else(|e| {
+        eprintln!("[PermissionManager] Failed to canonicalize AppData directory: {}", e);
+        app_data_dir.to_path_buf()
+    })
+}
+
+/// Canonicalize the longest existing prefix of `path` and append the not-yet-existing rest.
+/// A dangling symlink fails to resolve instead of being treated as a new file.
+pub(crate) fn resolve_path(path: &Path) -> PluginResult<PathBuf> {
+    let mut existing = path;
+    let mut missing = Vec::new();
+    while std::fs::symlink_metadata(existing).is_err() {
+        match (existing.parent(), existing.file_name()) {
+            (Some(parent), Some(name)) => {
+                missing.push(name);
+                existing = parent;
+            }
+            _ => break,
+        }
+    }
+
+    let mut resolved = existing.canonicalize().map_err(|e| {
+        PluginError::FileSystemError(format!("Failed to canonicalize path: {}", e))
+    })?;
+    for name in missing.into_iter().rev() {
+        resolved.push(name);
+    }
+
+    Ok(resolved)
+}
+
+/// Match a path (relative to AppData) against a filesystem scope.
+///
+/// - `dir/**` covers `dir` itself and everything beneath it
+/// - `dir/*` covers everything beneath `dir` (nested files included, as grants
+///   written before `**` existed expect), plus `dir` itself when `include_dir`
+///   is set (reading a directory means listing it)
+/// - any other scope matches that exact path
+///
+/// A bare `*` or `**` is relative to the AppData root.
+pub(crate) fn scope_matches(path: &str, scope: &str, include_dir: bool) -> bool {
+    // Normalize path separators to forward slashes for cross-platform matching
+    let normalized = path.replace('\\', "/");
+    let path = normalized.trim_end_matches('/');
+
+    let (dir, matches_dir) = if let Some(dir) = scope.strip_suffix("**") {
+        (dir, true)
+    } else if let Some(dir) = scope.strip_suffix('*') {
+        (dir, include_dir)
+    } else {
+        return path == scope;
+    };
+
+    let dir = dir.trim_end_matches('/');
+    if path == dir {
+        return matches_dir;
+    }
+    dir.is_empty() || path.starts_with(&format!("{}/", dir))
+}
+
+/// Whether a granted scope covers everything a requested scope can match
+fn scope_covers(granted: &str, requested: &str) -> bool {
+    if granted == requested {
+        return true;
+    }
+
+    match requested.strip_suffix("/**").or_else(|| requested.strip_suffix("/*")) {
+        // A wildcard request is covered by a wildcard grant of the same or a parent directory,
+        // except that only `**` reaches the requested directory itself
+        Some(dir) => {
+            let whole_dir = requested.ends_with("**");
+            granted.ends_with('*') && scope_matches(dir, granted, !whole_dir || granted.ends_with("**"))
+        }
+        None => scope_matches(requested, granted, false),
+    }
+}
+
+/// Match a domain against a whitelist pattern ("example.com" or "*.example.com")
+pub(crate) fn domain_matches_pattern(domain: &str, pattern: &str) -> bool {
+    if let Some(suffix) = pattern.strip_prefix("*.") {
+        // Wildcard subdomain (e.g., *.example.com)
+        // Exact match of base domain, or subdomain with dot separator
+        if domain == suffix {
+            return true;
+        }
+        if domain.ends_with(suffix) {
+            // Ensure there's a dot separator (not "notexample.com" matching "example.com")
+            let prefix_len = domain.len() - suffix.len();
+            return domain.chars().nth(prefix_len - 1) == Some('.');
+        }
+        false
+    } else {
+        // Exact domain match
+        domain == pattern
+    }
+}
+
+/// Aggregated validation activity for one permission type and resource
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PermissionUsage {
+    pub permission_type: String,
+    pub resource: String,
+    pub total: u64,
+    pub allowed: u64,
+    pub denied: u64,
+}
+
+/// Resource scope with `{id}` standing for the plugin id (e.g. "plugin-data/{id}/*")
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ScopeTemplate(String);
+
+impl ScopeTemplate {
+    pub fn new(template: impl Into<String>) -> Self {
+        Self(template.into())
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The scope for `plugin_id`
+    pub fn render(&self, plugin_id: &str) -> String {
+        self.0.replace("{id}", plugin_id)
+    }
+
+    /// Whether every rendering stays inside the plugin's own `AppData/plugin-data/{id}/` directory
+    fn within_own_data(&self) -> bool {
+        self.0
+            .strip_prefix("AppData/plugin-data/{id}/")
+            .is_some_and(|rest| !rest.contains("{id}") && !rest.split('/').any(|segment| segment == ".."))
+    }
+}
+
+/// Parse default grants written as "type:scope template" (e.g. "storage.read:plugin-data/{id}/*");
+/// the scope defaults to "*" when left out
+pub fn parse_default_grants(entries: &[String]) -> PluginResult<Vec<(PermissionType, ScopeTemplate)>> {
+    entries
+        .iter()
+        .map(|entry| {
+            let (type_str, scope) = entry.split_once(':').unwrap_or((entry.as_str(), "*"));
+            let permission_type = PermissionType::from_str(type_str.trim())
+                .ok_or_else(|| PluginError::InvalidConfig(format!("Unknown permission type: {}", type_str)))?;
+            Ok((permission_type, ScopeTemplate::new(scope.trim())))
+        })
+        .collect()
+}
+
+/// Network access and filesystem access outside the plugin's own data directory always need a prompt
+fn is_sensitive_default(permission_type: &PermissionType, template: &ScopeTemplate) -> bool {
+    match permission_type {
+        PermissionType::NetworkRequest => true,
+        PermissionType::FilesystemRead | PermissionType::FilesystemWrite => !template.within_own_data(),
+        _ => false,
+    }
+}
+
 /// PLUGIN-013: PermissionStorage with JSON persistence
 #[derive(Debug, Clone, Serialize, Deserialize)]
 struct PermissionStorage {
@@ -171,72 +346,24 @@ impl PermissionStorage {
     }
 }
 
-/// PLUGIN-016: Rate limiter using token bucket algorithm
-#[derive(Debug)]
-struct RateLimiter {
-    /// Maximum requests per time window
-    capacity: u32,
-    /// Current available tokens
-    tokens: Arc<RwLock<u32>>,
-    /// Token refill rate (requests per minute)
-    refill_rate: u32,
-    /// Last refill timestamp
-    last_refill: Arc<RwLock<Instant>>,
-}
-
-impl RateLimiter {
-    fn new(requests_per_minute: u32) -> Self {
-        Self {
-            capacity: requests_per_minute,
-            tokens: Arc::new(RwLock::new(requests_per_minute)),
-            refill_rate: requests_per_minute,
-            last_refill: Arc::new(RwLock::new(Instant::now())),
-        }
-    }
-
-    /// Try to consume a token. Returns true if allowed, false if rate limited.
-    fn try_acquire(&self) -> bool {
-        self.refill_tokens();
-
-        let mut tokens = self.tokens.write().unwrap();
-        if *tokens > 0 {
-            *tokens -= 1;
-            true
-        } else {
-            false
-        }
-    }
-
-    /// Refill tokens based on elapsed time
-    fn refill_tokens(&self) {
-        let now = Instant::now();
-        let mut last_refill = self.last_refill.write().unwrap();
-        let elapsed = now.duration_since(*last_refill);
-
-        // Refill tokens based on elapsed time (linear refill)
-        let tokens_to_add = (elapsed.as_secs_f64() / 60.0 * self.refill_rate as f64) as u32;
-
-        if tokens_to_add > 0 {
-            let mut tokens = self.tokens.write().unwrap();
-            *tokens = (*tokens + tokens_to_add).min(self.capacity);
-            *last_refill = now;
-        }
-    }
-}
+/// Decides which of a batch of requested permissions the user approves (one flag per permission)
+pub type AuthorizationHandler = Box<dyn Fn(&str, &[PluginPermission]) -> Vec<bool> + Send + Sync>;
 
 /// Permission Manager - Central controller for permission validation
 pub struct PermissionManager {
     permissions: HashMap<PluginId, Vec<PluginPermission>>,
     storage_path: PathBuf,
     app_data_dir: PathBuf,
-    /// Rate limiters per plugin (for network requests)
-    rate_limiters: HashMap<PluginId, RateLimiter>,
-    /// Default rate limit: 100 req/min
-    default_rate_limit: u32,
+    /// `app_data_dir` resolved once at construction, for path checks
+    app_data_canonical: PathBuf,
     audit_logger: Arc<RwLock<AuditLogger>>,
     /// Auto-approve permissions (for development/testing)
     /// When false, request_user_authorization will return false (deny all)
     auto_approve: bool,
+    /// Batch authorization decision; when unset, requests are denied unless `auto_approve` is on
+    authorization_handler: Option<AuthorizationHandler>,
+    /// Granted without prompting when a plugin is installed
+    default_grants: Vec<(PermissionType, ScopeTemplate)>,
 }
 
 impl PermissionManager {
@@ -247,8 +374,14 @@ impl PermissionManager {
     /// Create PermissionManager with configurable auto-approve setting
     /// Used by tests to disable auto-approval
     pub fn with_auto_approve(app_data_dir: PathBuf, auto_approve: bool) -> Self {
-        let storage_path = app_data_dir.join("plugin-permissions.json");
         let audit_logger = Arc::new(RwLock::new(AuditLogger::new(app_data_dir.clone())));
+        Self::with_audit_logger(app_data_dir, auto_approve, audit_logger)
+    }
+
+    /// Create PermissionManager that writes to an existing audit logger.
+    /// Every writer of the same daily log must share one logger, or their hash chains fork.
+    pub fn with_audit_logger(app_data_dir: PathBuf, auto_approve: bool, audit_logger: Arc<RwLock<AuditLogger>>) -> Self {
+        let storage_path = app_data_dir.join("plugin-permissions.json");
 
         // Load existing permissions
         let permissions = match PermissionStorage::load(&storage_path) {
@@ -259,11 +392,12 @@ impl PermissionManager {
         Self {
             permissions,
             storage_path,
+            app_data_canonical: canonicalize_app_data(&app_data_dir),
             app_data_dir,
-            rate_limiters: HashMap::new(),
-            default_rate_limit: 100,
             audit_logger,
             auto_approve,
+            authorization_handler: None,
+            default_grants: Vec::new(),
         }
     }
 
@@ -347,6 +481,91 @@ impl PermissionManager {
         Ok(())
     }
 
+    /// Set the permissions granted without prompting when a plugin is installed.
+    /// Sensitive permissions (network access, filesystem access outside `AppData/plugin-data/{id}/`)
+    /// are refused, as are templates that don't render to a valid scope.
+    pub fn set_default_grants(&mut self, grants: Vec<(PermissionType, ScopeTemplate)>) -> PluginResult<()> {
+        for (permission_type, template) in &grants {
+            if is_sensitive_default(permission_type, template) {
+                return Err(PluginError::PermissionDenied(format!(
+                    "{}:{} can't be granted by default", permission_type, template.as_str()
+                )));
+            }
+
+            let sample = PluginPermission {
+                plugin_id: "plugin".to_string(),
+                permission_type: permission_type.clone(),
+                resource_scope: template.render("plugin"),
+                granted: false,
+                granted_at: None,
+                granted_by: None,
+                expires_at: None,
+            };
+            sample.validate_scope()?;
+        }
+
+        self.default_grants = grants;
+        Ok(())
+    }
+
+    /// Grant the default permissions to a newly installed plugin, skipping ones it already has.
+    /// Returns the number of permissions granted.
+    pub fn apply_default_grants(&mut self, plugin_id: &str) -> PluginResult<usize> {
+        let now = Utc::now().to_rfc3339();
+        let mut granted = Vec::new();
+        for (permission_type, template) in &self.default_grants {
+            let resource_scope = template.render(plugin_id);
+            if self.has_permission(plugin_id, &format!("{}:{}", permission_type, resource_scope)) {
+                continue;
+            }
+
+            let permission = PluginPermission {
+                plugin_id: plugin_id.to_string(),
+                permission_type: permission_type.clone(),
+                resource_scope,
+                granted: true,
+                granted_at: Some(now.clone()),
+                granted_by: Some("default".to_string()),
+                expires_at: None,
+            };
+            permission.validate_scope()?;
+            granted.push(permission);
+        }
+
+        if granted.is_empty() {
+            return Ok(0);
+        }
+
+        let previous = self.permissions.get(plugin_id).cloned();
+        self.permissions
+            .entry(plugin_id.to_string())
+            .or_default()
+            .extend(granted.iter().cloned());
+
+        if let Err(e) = self.save_permissions() {
+            match previous {
+                Some(previous) => self.permissions.insert(plugin_id.to_string(), previous),
+                None => self.permissions.remove(plugin_id),
+            };
+            return Err(e);
+        }
+
+        // PLUGIN-019: Log default grants
+        let mut logger = self.audit_logger.write().unwrap();
+        for permission in &granted {
+            logger.log_permission_check(
+                plugin_id,
+                &permission.permission_type,
+                &permission.resource_scope,
+                "grant_default",
+                true,
+                None,
+            );
+        }
+
+        Ok(granted.len())
+    }
+
     /// PLUGIN-018: Revoke specific permission
     pub fn revoke_permission(
         &mut self,
@@ -372,6 +591,20 @@ impl PermissionManager {
         Ok(())
     }
 
+    /// Permissions currently granted to a plugin, as "type:scope" strings (sorted)
+    pub fn granted_permissions(&self, plugin_id: &str) -> Vec<String> {
+        let mut granted: Vec<String> = self.permissions
+            .get(plugin_id)
+            .into_iter()
+            .flatten()
+            .filter(|p| p.granted)
+            .map(|p| format!("{}:{}", p.permission_type.as_str(), p.resource_scope))
+            .collect();
+        granted.sort();
+        granted.dedup();
+        granted
+    }
+
     /// Check if a permission has already been granted
     pub fn has_permission(&self, plugin_id: &str, permission_str: &str) -> bool {
         let parts: Vec<&str> = permission_str.splitn(2, ':').collect();
@@ -383,15 +616,16 @@ impl PermissionManager {
                 return permissions.iter().any(|p| {
                     p.permission_type == permission_type
                     && p.granted
-                    && (p.resource_scope == "*" || self.matches_scope(resource_scope, &p.resource_scope))
+                    && (p.resource_scope == "*" || scope_covers(&p.resource_scope, resource_scope))
                 });
             }
         }
         false
     }
 
-    /// Parse permission string from manifest (e.g., "filesystem.read:/path/pattern")
-    pub fn request_permission(&mut self, plugin_id: &str, permission_str: &str) -> PluginResult<()> {
+    /// Parse a manifest permission string (e.g., "filesystem.read:/path/pattern") into an
+    /// ungranted permission with a validated scope
+    fn parse_permission(plugin_id: &str, permission_str: &str) -> PluginResult<PluginPermission> {
         let parts: Vec<&str> = permission_str.splitn(2, ':').collect();
         let permission_type_str = parts[0];
         let resource_scope = parts.get(1).unwrap_or(&"*").to_string();
@@ -403,8 +637,8 @@ impl PermissionManager {
 
         let permission = PluginPermission {
             plugin_id: plugin_id.to_string(),
-            permission_type: permission_type.clone(),
-            resource_scope: resource_scope.clone(),
+            permission_type,
+            resource_scope,
             granted: false,
             granted_at: None,
             granted_by: None,
@@ -414,11 +648,18 @@ impl PermissionManager {
         // Validate scope
         permission.validate_scope()?;
 
+        Ok(permission)
+    }
+
+    /// Parse permission string from manifest (e.g., "filesystem.read:/path/pattern")
+    pub fn request_permission(&mut self, plugin_id: &str, permission_str: &str) -> PluginResult<()> {
+        let permission = Self::parse_permission(plugin_id, permission_str)?;
+
         // Request user authorization
         let approved = self.request_user_authorization(plugin_id, &permission)?;
 
         if approved {
-            self.grant_permission(plugin_id, permission_type, resource_scope)?;
+            self.grant_permission(plugin_id, permission.permission_type, permission.resource_scope)?;
             Ok(())
         } else {
             Err(PluginError::PermissionDenied(
@@ -427,7 +668,138 @@ impl PermissionManager {
         }
     }
 
+    /// Request several manifest permissions with a single authorization decision.
+    /// All scopes are validated before the user is asked; permissions that are already
+    /// granted are reported as approved without prompting again. The approved subset is
+    /// granted atomically (persisted once, or not at all).
+    pub fn request_permissions_batch(
+        &mut self,
+        plugin_id: &str,
+        permissions: &[String],
+    ) -> PluginResult<Vec<(String, bool)>> {
+        // Validate every scope up front so an invalid entry never reaches the user
+        let mut pending = Vec::new();
+        for (index, permission_str) in permissions.iter().enumerate() {
+            if !self.has_permission(plugin_id, permission_str) {
+                pending.push((index, Self::parse_permission(plugin_id, permission_str)?));
+            }
+        }
+
+        let mut results: Vec<(String, bool)> = permissions
+            .iter()
+            .map(|p| (p.clone(), true))
+            .collect();
+
+        if pending.is_empty() {
+            return Ok(results);
+        }
+
+        let requested: Vec<PluginPermission> = pending.iter().map(|(_, p)| p.clone()).collect();
+        let decisions = self.request_batch_authorization(plugin_id, &requested)?;
+
+        let now = Utc::now().to_rfc3339();
+        let mut approved = Vec::new();
+        for ((index, mut permission), decision) in pending.into_iter().zip(decisions) {
+            results[index].1 = decision;
+            if decision {
+                permission.granted = true;
+                permission.granted_at = Some(now.clone());
+                permission.granted_by = Some("user".to_string());
+                approved.push(permission);
+            }
+        }
+
+        if approved.is_empty() {
+            return Ok(results);
+        }
+
+        // Grant the approved subset together, rolling back if it cannot be persisted
+        let previous = self.permissions.get(plugin_id).cloned();
+        self.permissions
+            .entry(plugin_id.to_string())
+            .or_default()
+            .extend(approved.iter().cloned());
+
+        if let Err(e) = self.save_permissions() {
+            match previous {
+                Some(previous) => self.permissions.insert(plugin_id.to_string(), previous),
+                None => self.permissions.remove(plugin_id),
+            };
+            return Err(e);
+        }
+
+        // PLUGIN-019: Log permission grants
+        let mut logger = self.audit_logger.write().unwrap();
+        for permission in &approved {
+            logger.log_permission_check(
+                plugin_id,
+                &permission.permission_type,
+                &permission.resource_scope,
+                "grant",
+                true,
+                None,
+            );
+        }
+
+        Ok(results)
+    }
+
+    /// Present a batch of permissions for one authorization decision.
+    /// Returns one approval flag per requested permission.
+    fn request_batch_authorization(
+        &self,
+        plugin_id: &str,
+        permissions: &[PluginPermission],
+    ) -> PluginResult<Vec<bool>> {
+        let decisions = match &self.authorization_handler {
+            Some(handler) => {
+                let decisions = handler(plugin_id, permissions);
+                if decisions.len() != permissions.len() {
+                    return Err(PluginError::PermissionDenied(format!(
+                        "Authorization returned {} decisions for {} permissions",
+                        decisions.len(),
+                        permissions.len()
+                    )));
+                }
+                decisions
+            }
+            // Without a handler nothing can ask the user, so requests are denied
+            // unless auto-approval was explicitly turned on
+            None => {
+                println!(
+                    "[PermissionManager] {} {} permission(s) for {} (auto-approve {})",
+                    if self.auto_approve { "Auto-approving" } else { "Denying" },
+                    permissions.len(),
+                    plugin_id,
+                    if self.auto_approve { "enabled" } else { "disabled" }
+                );
+                vec![self.auto_approve; permissions.len()]
+            }
+        };
+
+        // PLUGIN-019: Log each permission request
+        let mut logger = self.audit_logger.write().unwrap();
+        for (permission, approved) in permissions.iter().zip(&decisions) {
+            logger.log_permission_check(
+                plugin_id,
+                &permission.permission_type,
+                &permission.resource_scope,
+                "request",
+                *approved,
+                None,
+            );
+        }
+
+        Ok(decisions)
+    }
+
+    /// Replace the batch authorization decision (e.g., with a dialog, or a fixed answer in tests)
+    pub fn set_authorization_handler(&mut self, handler: AuthorizationHandler) {
+        self.authorization_handler = Some(handler);
+    }
+
     /// PLUGIN-014: Validate file system permission
+    /// Scopes follow `scope_matches`: `dir/*` and `dir/**` both cover nested paths
     pub fn validate_filesystem_permission(
         &self,
         plugin_id: &str,
@@ -441,61 +813,57 @@ impl PermissionManager {
         };
 
         // Get plugin permissions
-        let Some(permissions) = self.permissions.get(plugin_id) else {
+        if !self.permissions.contains_key(plugin_id) {
             self.log_validation(plugin_id, &permission_type, path.to_string_lossy().as_ref(), false, Some("No permissions found"));
             return false;
-        };
+        }
 
-        // Canonicalize paths
-        let app_data_canonical = match self.app_data_dir.canonicalize() {
-            Ok(p) => p,
-            Err(_) => {
-                self.log_validation(plugin_id, &permission_type, path.to_string_lossy().as_ref(), false, Some("AppData path error"));
+        let relative_path_str = match self.app_data_relative(path) {
+            Ok(relative) => relative,
+            Err(reason) => {
+                self.log_validation(plugin_id, &permission_type, path.to_string_lossy().as_ref(), false, Some(reason));
                 return false;
             }
         };
 
-        // Try to get canonical path, or construct relative path manually
-        let (canonical_path, relative_path_str) = match path.canonicalize() {
-            Ok(canonical) => {
-                // Path exists - use canonical path
-                if !canonical.starts_with(&app_data_canonical) {
-                    self.log_validation(plugin_id, &permission_type, path.to_string_lossy().as_ref(), false, Some("Path outside AppData"));
-                    return false;
-                }
-                let relative = canonical.strip_prefix(&app_data_canonical)
-                    .unwrap()
-                    .to_string_lossy()
-                    .to_string();
-                (canonical, relative)
-            }
-            Err(_) => {
-                // Path doesn't exist yet - validate parent and construct relative path
-                // This is common for write operations
-
-                // Security check: reject paths with ".." to prevent traversal attacks
-                if path.components().any(|c| c == std::path::Component::ParentDir) {
-                    self.log_validation(plugin_id, &permission_type, path.to_string_lossy().as_ref(), false, Some("Path traversal attempt (..)"));
-                    return false;
-                }
+        self.validate_filesystem_scope(plugin_id, &relative_path_str, write)
+    }
 
-                // First, ensure path starts with app_data_dir (canonical)
-                if !path.starts_with(&app_data_canonical) {
-                    self.log_validation(plugin_id, &permission_type, path.to_string_lossy().as_ref(), false, Some("Path outside AppData (non-canonical)"));
-                    return false;
-                }
+    /// Path relative to AppData ('/'-separated) used for scope matching.
+    /// Relative paths are taken as relative to AppData. Existing or not, the path is resolved
+    /// through its longest existing ancestor, so every caller is matched against the same root.
+    fn app_data_relative(&self, path: &Path) -> Result<String, &'static str> {
+        // Security check: reject paths with ".." to prevent traversal attacks
+        if path.components().any(|c| c == std::path::Component::ParentDir) {
+            return Err("Path traversal attempt (..)");
+        }
 
-                // Calculate relative path from app_data_dir
-                let relative = match path.strip_prefix(&app_data_canonical) {
-                    Ok(rel) => rel.to_string_lossy().to_string(),
-                    Err(_) => {
-                        self.log_validation(plugin_id, &permission_type, path.to_string_lossy().as_ref(), false, Some("Invalid path"));
-                        return false;
-                    }
-                };
+        let full_path = if path.is_absolute() {
+            path.to_path_buf()
+        } else {
+            self.app_data_canonical.join(path)
+        };
 
-                (path.to_path_buf(), relative)
-            }
+        let resolved = resolve_path(&full_path).map_err(|_| "Invalid path")?;
+        let relative = resolved
+            .strip_prefix(&self.app_data_canonical)
+            .map_err(|_| "Path outside AppData")?;
+
+        Ok(relative.to_string_lossy().replace('\\', "/"))
+    }
+
+    /// Check a path relative to AppData ('/'-separated) against the plugin's granted filesystem scopes.
+    /// The caller is responsible for having confined the path to AppData.
+    pub fn validate_filesystem_scope(&self, plugin_id: &str, relative_path: &str, write: bool) -> bool {
+        let permission_type = if write {
+            PermissionType::FilesystemWrite
+        } else {
+            PermissionType::FilesystemRead
+        };
+
+        let Some(permissions) = self.permissions.get(plugin_id) else {
+            self.log_validation(plugin_id, &permission_type, relative_path, false, Some("No permissions found"));
+            return false;
         };
 
         // Check if permission is granted
@@ -503,7 +871,7 @@ impl PermissionManager {
             if perm.permission_type == permission_type && perm.granted {
                 // Check scope matching
                 if perm.resource_scope == "*" {
-                    self.log_validation(plugin_id, &permission_type, path.to_string_lossy().as_ref(), true, None);
+                    self.log_validation(plugin_id, &permission_type, relative_path, true, None);
                     return true;
                 }
 
@@ -515,14 +883,15 @@ impl PermissionManager {
                     &perm.resource_scope
                 };
 
-                if self.matches_scope(&relative_path_str, scope_to_match) {
-                    self.log_validation(plugin_id, &permission_type, path.to_string_lossy().as_ref(), true, None);
+                // Read access to a `dir/*` scope includes listing `dir` itself
+                if scope_matches(relative_path, scope_to_match, !write) {
+                    self.log_validation(plugin_id, &permission_type, relative_path, true, None);
                     return true;
                 }
             }
         }
 
-        self.log_validation(plugin_id, &permission_type, path.to_string_lossy().as_ref(), false, Some("No matching permission"));
+        self.log_validation(plugin_id, &permission_type, relative_path, false, Some("No matching permission"));
         false
     }
 
@@ -561,32 +930,64 @@ impl PermissionManager {
         false
     }
 
-    /// PLUGIN-016: Check rate limit for network requests
-    pub fn check_rate_limit(&mut self, plugin_id: &str) -> bool {
-        // Get or create rate limiter for plugin
-        let limiter = self.rate_limiters
-            .entry(plugin_id.to_string())
-            .or_insert_with(|| RateLimiter::new(self.default_rate_limit));
+    /// Summarize what a plugin actually did since `since`: validation counts per
+    /// permission type and resource, split into allowed and denied (most used first)
+    pub fn usage_report(
+        &self,
+        plugin_id: &str,
+        since: chrono::DateTime<Utc>,
+    ) -> PluginResult<Vec<PermissionUsage>> {
+        let from_date = since.format("%Y-%m-%d").to_string();
+        let entries = {
+            let logger = self.audit_logger.read().unwrap();
+            logger.read_audit_logs(Some(&from_date), None)?
+        };
 
-        let allowed = limiter.try_acquire();
+        let mut usage: HashMap<(String, String), PermissionUsage> = HashMap::new();
+        for entry in entries {
+            if entry.plugin_id != plugin_id || entry.action != "validate" {
+                continue;
+            }
 
-        if !allowed {
-            self.log_validation(
-                plugin_id,
-                &PermissionType::NetworkRequest,
-                "rate_limit",
-                false,
-                Some("Rate limit exceeded"),
-            );
+            // Daily files only narrow by date; drop earlier entries from the first day
+            let in_range = chrono::DateTime::parse_from_rfc3339(&entry.timestamp)
+                .is_ok_and(|timestamp| timestamp >= since);
+            if !in_range {
+                continue;
+            }
+
+            let counts = usage
+                .entry((entry.permission_type.clone(), entry.resource.clone()))
+                .or_insert_with(|| PermissionUsage {
+                    permission_type: entry.permission_type,
+                    resource: entry.resource,
+                    total: 0,
+                    allowed: 0,
+                    denied: 0,
+                });
+
+            counts.total += 1;
+            if entry.result {
+                counts.allowed += 1;
+            } else {
+                counts.denied += 1;
+            }
         }
 
-        allowed
+        let mut report: Vec<PermissionUsage> = usage.into_values().collect();
+        report.sort_by(|a, b| {
+            b.total
+                .cmp(&a.total)
+                .then_with(|| a.permission_type.cmp(&b.permission_type))
+                .then_with(|| a.resource.cmp(&b.resource))
+        });
+
+        Ok(report)
     }
 
     /// Revoke all permissions for plugin
     pub fn revoke_all_permissions(&mut self, plugin_id: &str) -> PluginResult<()> {
         self.permissions.remove(plugin_id);
-        self.rate_limiters.remove(plugin_id);
         self.save_permissions()?;
 
         // PLUGIN-019: Log permission revocation
@@ -603,50 +1004,124 @@ impl PermissionManager {
         Ok(())
     }
 
-    /// Save permissions to disk (PLUGIN-013)
-    fn save_permissions(&self) -> PluginResult<()> {
+    /// Export all grants in the same shape as the permissions file, for moving them to another install
+    pub fn export_permissions(&self) -> serde_json::Value {
         let storage = PermissionStorage {
             permissions: self.permissions.clone(),
             version: "1.0.0".to_string(),
             updated_at: Utc::now().to_rfc3339(),
         };
 
-        storage.save(&self.storage_path)
+        serde_json::to_value(storage).unwrap_or_default()
     }
 
-    /// Helper: Match path against scope pattern
-    fn matches_scope(&self, path: &str, scope: &str) -> bool {
-        // Normalize path separators to forward slashes for cross-platform matching
-        let normalized_path = path.replace('\\', "/");
+    /// Restore grants from `export_permissions` output.
+    /// With `merge` the imported grants are added to the existing ones (duplicates skipped),
+    /// otherwise they replace them. Grants of plugins that aren't `installed` are left out.
+    /// Every entry is validated first, so nothing is applied if one of them is invalid.
+    pub fn import_permissions(
+        &mut self,
+        value: serde_json::Value,
+        merge: bool,
+        installed: &HashSet<PluginId>,
+    ) -> PluginResult<PermissionImport> {
+        let storage: PermissionStorage = serde_json::from_value(value)
+            .map_err(|e| PluginError::ManifestError(format!("Failed to parse permissions export: {}", e)))?;
+
+        for (plugin_id, permissions) in &storage.permissions {
+            for permission in permissions {
+                if &permission.plugin_id != plugin_id {
+                    return Err(PluginError::PermissionDenied(format!(
+                        "Permission for {} listed under {}", permission.plugin_id, plugin_id
+                    )));
+                }
+                permission.validate_scope()?;
+            }
+        }
 
-        // Simple wildcard matching (e.g., "plugin-data/*")
-        if scope.ends_with("/*") {
-            let prefix = &scope[..scope.len() - 2];
-            normalized_path.starts_with(prefix)
-        } else {
-            normalized_path == scope
+        let previous = self.permissions.clone();
+        if !merge {
+            self.permissions.clear();
         }
-    }
 
-    /// Helper: Match domain against whitelist pattern
-    fn matches_domain(&self, domain: &str, pattern: &str) -> bool {
-        if pattern.starts_with("*.") {
-            // Wildcard subdomain (e.g., *.example.com)
-            let suffix = &pattern[2..];
-            // Exact match of base domain, or subdomain with dot separator
-            if domain == suffix {
-                return true;
+        let mut imported = Vec::new();
+        let mut skipped = Vec::new();
+        for (plugin_id, permissions) in storage.permissions {
+            if !installed.contains(&plugin_id) {
+                skipped.push(plugin_id);
+                continue;
             }
-            if domain.ends_with(suffix) {
-                // Ensure there's a dot separator (not "notexample.com" matching "example.com")
-                let prefix_len = domain.len() - suffix.len();
-                return domain.chars().nth(prefix_len - 1) == Some('.');
+            let existing = self.permissions.entry(plugin_id).or_default();
+            for permission in permissions {
+                let duplicate = existing.iter().any(|p| {
+                    p.permission_type == permission.permission_type && p.resource_scope == permission.resource_scope
+                });
+                if !duplicate {
+                    existing.push(permission.clone());
+                    imported.push(permission);
+                }
             }
-            false
-        } else {
-            // Exact domain match
-            domain == pattern
         }
+        self.permissions.retain(|_, permissions| !permissions.is_empty());
+        skipped.sort();
+
+        // Grants a replacing import dropped (re-imported ones don't count)
+        let removed: Vec<PluginPermission> = previous
+            .values()
+            .flatten()
+            .filter(|old| {
+                !self.permissions.get(&old.plugin_id).into_iter().flatten().any(|p| {
+                    p.permission_type == old.permission_type && p.resource_scope == old.resource_scope
+                })
+            })
+            .cloned()
+            .collect();
+
+        if let Err(e) = self.save_permissions() {
+            self.permissions = previous;
+            return Err(e);
+        }
+
+        // PLUGIN-019: Log imported and dropped grants
+        let mut logger = self.audit_logger.write().unwrap();
+        for permission in &removed {
+            logger.log_permission_check(
+                &permission.plugin_id,
+                &permission.permission_type,
+                &permission.resource_scope,
+                "revoke",
+                true,
+                None,
+            );
+        }
+        for permission in &imported {
+            logger.log_permission_check(
+                &permission.plugin_id,
+                &permission.permission_type,
+                &permission.resource_scope,
+                "import",
+                true,
+                None,
+            );
+        }
+
+        Ok(PermissionImport { imported: imported.len(), removed, skipped })
+    }
+
+    /// Save permissions to disk (PLUGIN-013)
+    fn save_permissions(&self) -> PluginResult<()> {
+        let storage = PermissionStorage {
+            permissions: self.permissions.clone(),
+            version: "1.0.0".to_string(),
+            updated_at: Utc::now().to_rfc3339(),
+        };
+
+        storage.save(&self.storage_path)
+    }
+
+    /// Helper: Match domain against whitelist pattern
+    fn matches_domain(&self, domain: &str, pattern: &str) -> bool {
+        domain_matches_pattern(domain, pattern)
     }
 
     /// PLUGIN-019: Log validation result to audit logger
@@ -666,4 +1141,341 @@ impl PermissionManager {
     pub fn get_app_data_dir(&self) -> &PathBuf {
         &self.app_data_dir
     }
+
+    /// Canonical AppData path used for filesystem permission checks
+    pub fn canonical_app_data_dir(&self) -> &Path {
+        &self.app_data_canonical
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_app_data() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("vcp_permission_test_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn requested() -> Vec<String> {
+        vec![
+            "filesystem.read:AppData/plugin-data/*".to_string(),
+            "network.request:*.example.com".to_string(),
+            "system.notify".to_string(),
+        ]
+    }
+
+    fn manager_with_decisions(dir: &Path, decisions: Vec<bool>) -> PermissionManager {
+        let mut manager = PermissionManager::with_auto_approve(dir.to_path_buf(), false);
+        manager.set_authorization_handler(Box::new(move |_, permissions| {
+            assert_eq!(permissions.len(), decisions.len(), "batch should be presented at once");
+            decisions.clone()
+        }));
+        manager
+    }
+
+    #[test]
+    fn test_batch_all_approved() {
+        let dir = temp_app_data();
+        let mut manager = manager_with_decisions(&dir, vec![true, true, true]);
+
+        let results = manager.request_permissions_batch("test-plugin", &requested()).unwrap();
+
+        assert!(results.iter().all(|(_, granted)| *granted));
+        assert!(requested().iter().all(|p| manager.has_permission("test-plugin", p)));
+
+        // Granted permissions were persisted
+        let reloaded = PermissionManager::with_auto_approve(dir.clone(), false);
+        assert!(reloaded.has_permission("test-plugin", "system.notify"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_batch_partial_approval() {
+        let dir = temp_app_data();
+        let mut manager = manager_with_decisions(&dir, vec![true, false, true]);
+
+        let results = manager.request_permissions_batch("test-plugin", &requested()).unwrap();
+
+        let granted: Vec<bool> = results.iter().map(|(_, g)| *g).collect();
+        assert_eq!(granted, vec![true, false, true]);
+        assert!(manager.has_permission("test-plugin", "filesystem.read:AppData/plugin-data/*"));
+        assert!(!manager.has_permission("test-plugin", "network.request:*.example.com"));
+        assert!(manager.has_permission("test-plugin", "system.notify"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_batch_all_denied() {
+        let dir = temp_app_data();
+        let mut manager = manager_with_decisions(&dir, vec![false, false, false]);
+
+        let results = manager.request_permissions_batch("test-plugin", &requested()).unwrap();
+
+        assert!(results.iter().all(|(_, granted)| !granted));
+        assert!(requested().iter().all(|p| !manager.has_permission("test-plugin", p)));
+
+        // With no handler to ask the user, nothing is granted
+        let mut unattended = PermissionManager::with_auto_approve(dir.clone(), false);
+        let results = unattended.request_permissions_batch("other-plugin", &requested()).unwrap();
+        assert!(results.iter().all(|(_, granted)| !granted));
+        assert!(requested().iter().all(|p| !unattended.has_permission("other-plugin", p)));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_usage_report_aggregates_validations() {
+        let dir = temp_app_data();
+        let since = Utc::now() - chrono::Duration::seconds(1);
+        let mut manager = PermissionManager::with_auto_approve(dir.clone(), true);
+        manager.grant_permission("weather", PermissionType::NetworkRequest, "api.example.com".to_string()).unwrap();
+
+        for _ in 0..3 {
+            manager.validate_network_permission("weather", "api.example.com");
+        }
+        manager.validate_network_permission("weather", "tracker.net");
+        manager.validate_network_permission("other-plugin", "api.example.com");
+
+        let report = manager.usage_report("weather", since).unwrap();
+
+        assert_eq!(report, vec![
+            PermissionUsage {
+                permission_type: "network.request".to_string(),
+                resource: "api.example.com".to_string(),
+                total: 3,
+                allowed: 3,
+                denied: 0,
+            },
+            PermissionUsage {
+                permission_type: "network.request".to_string(),
+                resource: "tracker.net".to_string(),
+                total: 1,
+                allowed: 0,
+                denied: 1,
+            },
+        ]);
+
+        // Nothing happened after now
+        let later = Utc::now() + chrono::Duration::seconds(1);
+        assert!(manager.usage_report("weather", later).unwrap().is_empty());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_batch_rejects_invalid_scope_before_prompting() {
+        let dir = temp_app_data();
+        let mut manager = PermissionManager::with_auto_approve(dir.clone(), false);
+        manager.set_authorization_handler(Box::new(|_, _| panic!("should not prompt")));
+
+        let mut permissions = requested();
+        permissions.push("filesystem.write:/etc/passwd".to_string());
+
+        assert!(manager.request_permissions_batch("test-plugin", &permissions).is_err());
+        assert!(!manager.has_permission("test-plugin", "system.notify"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_scope_matching_shallow_and_recursive() {
+        // The scoped directory itself can be read (listed) but not written
+        assert!(scope_matches("data", "data/*", true));
+        assert!(!scope_matches("data", "data/*", false));
+        assert!(scope_matches("data/", "data/*", true));
+
+        // Files directly inside and nested files both match
+        assert!(scope_matches("data/notes.txt", "data/*", false));
+        assert!(scope_matches("data/2025/notes.txt", "data/*", false));
+        assert!(scope_matches("data/2025/notes.txt", "data/**", false));
+        assert!(scope_matches("data", "data/**", false));
+        assert!(scope_matches("data\\2025\\notes.txt", "data/**", false));
+
+        // Sibling directories sharing a prefix don't match
+        assert!(!scope_matches("database/notes.txt", "data/*", true));
+        assert!(!scope_matches("database/notes.txt", "data/**", true));
+
+        // Bare wildcards are relative to the AppData root
+        assert!(scope_matches("notes.txt", "*", false));
+        assert!(scope_matches("data/notes.txt", "*", false));
+        assert!(scope_matches("data/notes.txt", "**", false));
+
+        assert!(scope_matches("data/notes.txt", "data/notes.txt", false));
+        assert!(!scope_matches("data/other.txt", "data/notes.txt", false));
+    }
+
+    #[test]
+    fn test_validate_filesystem_scope_semantics() {
+        let dir = temp_app_data();
+        let root = dir.canonicalize().unwrap();
+        std::fs::create_dir_all(root.join("data").join("2025")).unwrap();
+        std::fs::write(root.join("data").join("notes.txt"), "notes").unwrap();
+        std::fs::write(root.join("data").join("2025").join("notes.txt"), "nested").unwrap();
+
+        let mut manager = PermissionManager::new(dir.clone());
+        manager.grant_permission("reader", PermissionType::FilesystemRead, "AppData/data/*".to_string()).unwrap();
+        manager.grant_permission("reader", PermissionType::FilesystemWrite, "AppData/data/*".to_string()).unwrap();
+
+        // Listing the granted directory and reading a file directly in it
+        assert!(manager.validate_filesystem_permission("reader", &root.join("data"), false));
+        assert!(manager.validate_filesystem_permission("reader", &root.join("data").join("notes.txt"), false));
+        // Nested files are covered too, but writing the directory itself needs `**`
+        assert!(manager.validate_filesystem_permission("reader", &root.join("data").join("2025").join("notes.txt"), false));
+        assert!(manager.validate_filesystem_permission("reader", &root.join("data").join("2025").join("notes.txt"), true));
+        assert!(!manager.validate_filesystem_permission("reader", &root.join("data"), true));
+
+        manager.grant_permission("archiver", PermissionType::FilesystemRead, "AppData/data/**".to_string()).unwrap();
+        assert!(manager.validate_filesystem_permission("archiver", &root.join("data"), false));
+        assert!(manager.validate_filesystem_permission("archiver", &root.join("data").join("2025").join("notes.txt"), false));
+
+        // Already granted scopes cover narrower requests only
+        assert!(manager.has_permission("archiver", "filesystem.read:AppData/data/2025/*"));
+        assert!(manager.has_permission("archiver", "filesystem.read:AppData/data/notes.txt"));
+        assert!(manager.has_permission("reader", "filesystem.read:AppData/data/*"));
+        assert!(manager.has_permission("reader", "filesystem.read:AppData/data/2025/**"));
+        assert!(!manager.has_permission("reader", "filesystem.read:AppData/data/**"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let dir = temp_app_data();
+        let mut source = PermissionManager::new(dir.clone());
+        source.grant_permission("weather", PermissionType::NetworkRequest, "api.example.com".to_string()).unwrap();
+        source.grant_permission("weather", PermissionType::StorageRead, "*".to_string()).unwrap();
+        source.grant_permission("reader", PermissionType::FilesystemRead, "AppData/data/*".to_string()).unwrap();
+        let exported = source.export_permissions();
+
+        let target_dir = temp_app_data();
+        let mut target = PermissionManager::new(target_dir.clone());
+        target.grant_permission("notes", PermissionType::SystemNotify, "*".to_string()).unwrap();
+        target.grant_permission("weather", PermissionType::StorageRead, "*".to_string()).unwrap();
+
+        let installed: HashSet<PluginId> = ["weather", "reader", "notes"].iter().map(|id| id.to_string()).collect();
+
+        // Merge keeps existing grants and skips duplicates
+        let merged = target.import_permissions(exported.clone(), true, &installed).unwrap();
+        assert_eq!(merged.imported, 2);
+        assert!(merged.removed.is_empty());
+        assert!(target.has_permission("notes", "system.notify"));
+        assert!(target.has_permission("weather", "network.request:api.example.com"));
+        assert!(target.has_permission("reader", "filesystem.read:AppData/data/*"));
+        assert_eq!(target.granted_permissions("weather").len(), 2);
+
+        // Replace drops grants that aren't in the export and logs their revocation; the result is persisted
+        let replaced = target.import_permissions(exported.clone(), false, &installed).unwrap();
+        assert_eq!(replaced.imported, 3);
+        assert_eq!(replaced.removed.len(), 1);
+        assert_eq!(replaced.removed[0].plugin_id, "notes");
+        let revoked = target.audit_logger.read().unwrap()
+            .read_audit_logs(None, None)
+            .unwrap()
+            .into_iter()
+            .any(|entry| entry.action == "revoke" && entry.plugin_id == "notes");
+        assert!(revoked);
+        let reloaded = PermissionManager::new(target_dir.clone());
+        assert!(!reloaded.has_permission("notes", "system.notify"));
+        assert!(reloaded.has_permission("weather", "storage.read"));
+        assert_eq!(reloaded.export_permissions()["permissions"], source.export_permissions()["permissions"]);
+
+        // Grants of plugins that aren't installed are left out
+        let only_weather: HashSet<PluginId> = ["weather".to_string()].into_iter().collect();
+        let partial = target.import_permissions(exported, false, &only_weather).unwrap();
+        assert_eq!(partial.skipped, vec!["reader".to_string()]);
+        assert!(!target.has_permission("reader", "filesystem.read:AppData/data/*"));
+        assert!(target.has_permission("weather", "network.request:api.example.com"));
+
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::remove_dir_all(&target_dir);
+    }
+
+    #[test]
+    fn test_import_rejects_invalid_scope() {
+        let dir = temp_app_data();
+        let mut source = PermissionManager::new(dir.clone());
+        source.grant_permission("writer", PermissionType::FilesystemWrite, "AppData/exports/**".to_string()).unwrap();
+        source.grant_permission("weather", PermissionType::NetworkRequest, "api.example.com".to_string()).unwrap();
+
+        let mut tampered = source.export_permissions();
+        tampered["permissions"]["writer"][0]["resource_scope"] = serde_json::json!("/etc/*");
+
+        let target_dir = temp_app_data();
+        let mut target = PermissionManager::new(target_dir.clone());
+        target.grant_permission("notes", PermissionType::SystemNotify, "*".to_string()).unwrap();
+        let installed: HashSet<PluginId> = ["writer", "weather", "notes"].iter().map(|id| id.to_string()).collect();
+
+        assert!(matches!(target.import_permissions(tampered, false, &installed), Err(PluginError::PermissionDenied(_))));
+        // Nothing was applied, not even the valid entries
+        assert!(target.has_permission("notes", "system.notify"));
+        assert!(!target.has_permission("weather", "network.request:api.example.com"));
+        assert!(!target.has_permission("writer", "filesystem.write:/etc/passwd"));
+
+        // A grant filed under another plugin is rejected too
+        let mut misfiled = source.export_permissions();
+        misfiled["permissions"]["weather"][0]["plugin_id"] = serde_json::json!("writer");
+        assert!(target.import_permissions(misfiled, true, &installed).is_err());
+        assert!(target.import_permissions(serde_json::json!({ "permissions": [] }), true, &installed).is_err());
+
+        let _ = std::fs::remove_dir_all(&dir);
+        let _ = std::fs::remove_dir_all(&target_dir);
+    }
+
+    #[test]
+    fn test_default_grants_refuse_sensitive_permissions() {
+        let dir = temp_app_data();
+        let mut manager = PermissionManager::new(dir.clone());
+
+        for (permission_type, template) in [
+            (PermissionType::NetworkRequest, "*.example.com"),
+            (PermissionType::FilesystemWrite, "AppData/*"),
+            (PermissionType::FilesystemWrite, "AppData/plugin-data/*"),
+            (PermissionType::FilesystemRead, "AppData/plugin-data/{id}/../other/*"),
+            (PermissionType::StorageRead, ""),
+        ] {
+            let grants = vec![(permission_type.clone(), ScopeTemplate::new(template))];
+            assert!(manager.set_default_grants(grants).is_err(), "{}:{} was accepted", permission_type, template);
+        }
+
+        assert!(parse_default_grants(&["storage.read".to_string(), "clipboard.read".to_string()]).is_err());
+        assert_eq!(
+            parse_default_grants(&["storage.read".to_string(), "storage.write:plugin-data/{id}/*".to_string()]).unwrap(),
+            vec![
+                (PermissionType::StorageRead, ScopeTemplate::new("*")),
+                (PermissionType::StorageWrite, ScopeTemplate::new("plugin-data/{id}/*")),
+            ]
+        );
+
+        manager.set_default_grants(vec![
+            (PermissionType::FilesystemWrite, ScopeTemplate::new("AppData/plugin-data/{id}/**")),
+            (PermissionType::SystemNotify, ScopeTemplate::new("*")),
+        ]).unwrap();
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_write_to_missing_nested_path() {
+        let dir = temp_app_data();
+        let root = dir.canonicalize().unwrap();
+        let mut manager = PermissionManager::new(dir.clone());
+        manager.grant_permission("writer", PermissionType::FilesystemWrite, "AppData/exports/**".to_string()).unwrap();
+
+        // Nothing below AppData exists yet; absolute and relative forms agree
+        assert!(manager.validate_filesystem_permission("writer", &root.join("exports").join("2025").join("report.md"), true));
+        assert!(manager.validate_filesystem_permission("writer", Path::new("exports/2025/report.md"), true));
+
+        // Outside the granted scope, or outside AppData altogether
+        assert!(!manager.validate_filesystem_permission("writer", &root.join("config").join("2025").join("report.md"), true));
+        assert!(!manager.validate_filesystem_permission("writer", Path::new("config/report.md"), true));
+        let outside = std::env::temp_dir().join(format!("vcp_outside_{}", uuid::Uuid::new_v4())).join("exports").join("report.md");
+        assert!(!manager.validate_filesystem_permission("writer", &outside, true));
+        assert!(!manager.validate_filesystem_permission("writer", Path::new("exports/../config/report.md"), true));
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
 }
@@ -2,15 +2,33 @@
 // Handles plugin loading, activation, dependency resolution, and lifecycle management
 
 use super::{
-    PluginError, PluginId, PluginMetadata, PluginResult, PluginState,
-    manifest_parser::{PluginManifest, ManifestParser},
-    permission_manager::PermissionManager,
-    lifecycle_manager::LifecycleManager,
+    PluginError, PluginId, PluginMetadata, PluginRestartPolicy, PluginResult, PluginState,
+    manifest_parser::{ConfigurationProperty, PluginManifest, ManifestParser, RuntimeEvent, ViewLocation},
+    permission_manager::{self, PermissionImport, PermissionManager, PermissionType, PermissionUsage, ScopeTemplate},
+    lifecycle_manager::{LifecycleManager, ResourceType},
+    service_runner::{CommandSpawner, RestartPolicy, ServiceExit, ServiceRunner, ServiceStatus},
+    message_preprocessor::{MessagePreprocessor, PreprocessorChain, SidecarPreprocessor},
+    package_verifier::{self, SignaturePolicy, SignatureStatus},
+    health_check::{HealthProbe, HealthStatus, SidecarProbe, StateChangeListener, DEFAULT_HEALTH_TIMEOUT},
+    audit_logger::AuditLogger,
+    command_registry::{CommandHandler, CommandRegistry, SidecarCommandHandler},
+    view_registry::{ContributedView, ViewRegistry},
+    event_bus::PluginEventBus,
+    keybinding_registry::{KeybindingConflict, KeybindingRegistry, RegisteredKeybinding},
+    resource_limiter::{LimitViolation, ProcSampler, ResourceLimits, ResourceSampler, ResourceUsage},
+    network_proxy::{NetworkProxy, ProxyConfig, RateLimitStatus},
+    update_check::{self, UpdateInfo},
+    storage_api::{self, StorageAPI},
+    config_api::PluginConfigAPI,
 };
+use crate::models::{GlobalSettings, Message};
 use std::collections::{HashMap, HashSet};
 use std::path::{Path, PathBuf};
-use std::sync::{Arc, RwLock};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError, RwLock};
+use std::time::Instant;
 use chrono::Utc;
+use serde::{Deserialize, Serialize};
 
 /// PLUGIN-002: PluginRegistry with HashMap<plugin_id, PluginState>
 /// Central registry tracking all installed plugins and their states
@@ -92,68 +110,462 @@ pub struct PluginManager {
     lifecycle_manager: Arc<LifecycleManager>,
     manifest_parser: ManifestParser,
     plugins_dir: PathBuf,
+    /// Per-plugin `StorageAPI` data (AppData/plugin-data)
+    plugin_data_dir: PathBuf,
+    /// Typed plugin settings, stored alongside the plugin's `StorageAPI` data
+    config_api: PluginConfigAPI,
+    /// Active `messagePreprocessor` plugins in dependency order
+    preprocessors: Arc<RwLock<PreprocessorChain>>,
+    /// Contributed commands of running plugins, for routing invocations
+    command_registry: Arc<RwLock<CommandRegistry>>,
+    /// Contributed views of running plugins, by UI location
+    view_registry: Arc<RwLock<ViewRegistry>>,
+    /// Contributed keybindings of running plugins, with conflicts between them
+    keybinding_registry: Arc<RwLock<KeybindingRegistry>>,
+    /// Inter-plugin events; subscriptions end when their plugin deactivates
+    event_bus: Arc<PluginEventBus>,
+    /// Supervised background processes of `service` plugins
+    service_runner: Arc<ServiceRunner>,
+    /// Package signature requirements for installation
+    signature_policy: Arc<RwLock<SignaturePolicy>>,
+    /// Refuse to activate plugins whose files changed since install
+    enforce_integrity: AtomicBool,
+    /// Content hashes recorded at install (persisted to `INSTALL_HASHES_FILE`), what discovery checks against
+    install_hashes: RwLock<HashMap<PluginId, String>>,
+    install_hashes_path: PathBuf,
+    /// Started in safe mode: nothing activates on its own, only on explicit request
+    safe_mode: AtomicBool,
+    /// Pings running plugins for `health_check`
+    health_probe: Arc<RwLock<Arc<dyn HealthProbe>>>,
+    /// Measures plugin processes for `get_plugin_resource_usage` and limit enforcement
+    resource_sampler: Arc<RwLock<Arc<dyn ResourceSampler>>>,
+    /// Notified when the manager changes a plugin's state on its own (e.g. a crash)
+    state_listeners: Arc<RwLock<Vec<StateChangeListener>>>,
+    /// Backoff and attempt cap for restarting crashed plugins
+    crash_backoff: Arc<RwLock<RestartPolicy>>,
+    /// Pending automatic restarts of crashed plugins
+    crash_restarts: Arc<RwLock<HashMap<PluginId, CrashRestart>>>,
+    /// Per-plugin operation locks: activate/deactivate/uninstall of one plugin run one at a time,
+    /// while operations on different plugins proceed in parallel
+    plugin_locks: Mutex<HashMap<PluginId, Arc<Mutex<()>>>>,
+    /// Outbound proxy for the host's own requests (update checks); system proxy detection if unset
+    http_proxy: RwLock<Option<ProxyConfig>>,
+    /// Registry index checked for plugin updates (`plugin_registry_url` setting)
+    registry_url: RwLock<Option<String>>,
+    /// Permission-checked HTTP for plugins, sharing this manager's permissions, audit log and lifecycle
+    network_proxy: Arc<RwLock<NetworkProxy>>,
+    audit_logger: Arc<RwLock<AuditLogger>>,
+}
+
+/// What uninstalling a plugin would remove, for a confirmation dialog
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UninstallPreview {
+    pub plugin_id: PluginId,
+    pub display_name: String,
+    pub version: String,
+    /// Size of the installed plugin files
+    pub install_size_bytes: u64,
+    /// Keys the plugin has in its `StorageAPI` storage
+    pub storage_key_count: usize,
+    pub granted_permissions: Vec<String>,
+    pub commands: Vec<String>,
+    pub views: Vec<String>,
+}
+
+/// Installed plugins and the dependencies between them, for rendering as a diagram
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyGraph {
+    pub nodes: Vec<DependencyNode>,
+    pub edges: Vec<DependencyEdge>,
+    /// Dependencies that aren't installed
+    pub missing: Vec<PluginId>,
+    /// Dependency cycles (see `detect_cycles`)
+    pub cycles: Vec<Vec<PluginId>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyNode {
+    pub plugin_id: PluginId,
+    pub version: String,
+    pub state: PluginState,
+}
+
+/// `from` depends on `to`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DependencyEdge {
+    pub from: PluginId,
+    pub to: PluginId,
+    /// Version range `from` requires (e.g., "^1.0.0")
+    pub version_range: String,
+}
+
+/// Restart bookkeeping for a crashed plugin
+#[derive(Debug, Clone)]
+struct CrashRestart {
+    /// Restarts attempted since the user last activated the plugin
+    attempts: u32,
+    /// When the next restart is due (`None` once attempts are exhausted)
+    next_attempt_at: Option<Instant>,
+}
+
+/// Move a directory, copying when a rename isn't possible (e.g. across volumes)
+fn move_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    if std::fs::rename(from, to).is_ok() {
+        return Ok(());
+    }
+
+    copy_dir(from, to)?;
+    std::fs::remove_dir_all(from)
+}
+
+/// Extract a plugin package into `dest`, refusing entries that would land outside it
+fn extract_package(zip_path: &Path, dest: &Path) -> PluginResult<()> {
+    let file = std::fs::File::open(zip_path)?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| PluginError::ZipError(e.to_string()))?;
+
+    for i in 0..archive.len() {
+        let mut entry = archive.by_index(i)
+            .map_err(|e| PluginError::ZipError(e.to_string()))?;
+        let relative = entry.enclosed_name()
+            .map(Path::to_path_buf)
+            .ok_or_else(|| PluginError::ZipError(format!("Unsafe path in plugin package: {}", entry.name())))?;
+        let outpath = dest.join(relative);
+
+        if entry.is_dir() {
+            std::fs::create_dir_all(&outpath)?;
+        } else {
+            if let Some(p) = outpath.parent() {
+                std::fs::create_dir_all(p)?;
+            }
+            let mut outfile = std::fs::File::create(&outpath)?;
+            std::io::copy(&mut entry, &mut outfile)?;
+        }
+    }
+    Ok(())
+}
+
+/// Lock a plugin's operation lock; a panic in an earlier operation doesn't block the plugin for good
+fn hold(lock: &Mutex<()>) -> MutexGuard<'_, ()> {
+    lock.lock().unwrap_or_else(PoisonError::into_inner)
+}
+
+/// Total size of the files under `dir` (0 if it doesn't exist)
+fn dir_size(dir: &Path) -> std::io::Result<u64> {
+    if !dir.is_dir() {
+        return Ok(0);
+    }
+
+    let mut size = 0;
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            size += dir_size(&entry.path())?;
+        } else if file_type.is_file() {
+            size += entry.metadata()?.len();
+        }
+    }
+    Ok(size)
+}
+
+fn copy_dir(from: &Path, to: &Path) -> std::io::Result<()> {
+    std::fs::create_dir_all(to)?;
+    for entry in std::fs::read_dir(from)? {
+        let path = entry?.path();
+        let target = to.join(path.file_name().unwrap_or_default());
+        if path.is_dir() {
+            copy_dir(&path, &target)?;
+        } else {
+            std::fs::copy(&path, &target)?;
+        }
+    }
+    Ok(())
+}
+
+/// Command-line flag that starts the app with plugins disabled
+pub const SAFE_MODE_ARG: &str = "--safe-mode";
+/// Environment variable that starts the app with plugins disabled (any value but "0"/"false")
+pub const SAFE_MODE_ENV: &str = "VCP_SAFE_MODE";
+/// Marker file in AppData that starts the app with plugins disabled (e.g. left by a crash-recovery prompt)
+pub const SAFE_MODE_MARKER: &str = ".safe-mode";
+
+/// Whether startup should enter safe mode, from the process arguments, `VCP_SAFE_MODE` and the marker file
+pub fn safe_mode_requested(args: &[String], env_value: Option<&str>, app_data_dir: &Path) -> bool {
+    args.iter().any(|arg| arg == SAFE_MODE_ARG)
+        || env_value.is_some_and(|value| !matches!(value.trim(), "" | "0" | "false"))
+        || app_data_dir.join(SAFE_MODE_MARKER).exists()
 }
 
 impl PluginManager {
+    /// Manager that grants every requested permission without asking (development and tests only)
     pub fn new(app_data_dir: PathBuf) -> Self {
         Self::with_auto_approve(app_data_dir, true)
     }
 
-    /// Create PluginManager with configurable auto-approve setting
-    /// Used by tests to disable automatic permission approval
+    /// Create PluginManager with configurable auto-approve setting.
+    /// The app passes `false`, so requests are only granted through an authorization handler.
     pub fn with_auto_approve(app_data_dir: PathBuf, auto_approve: bool) -> Self {
         let plugins_dir = app_data_dir.join("plugins");
+        Self::build(app_data_dir, plugins_dir, auto_approve)
+    }
+
+    /// Create PluginManager that installs and loads plugins from `plugins_dir` instead of `AppData/plugins`
+    /// (e.g. a folder shared across profiles or on another volume)
+    pub fn with_plugins_dir(app_data_dir: PathBuf, plugins_dir: PathBuf, auto_approve: bool) -> Self {
+        Self::build(app_data_dir, plugins_dir, auto_approve)
+    }
+
+    fn build(app_data_dir: PathBuf, plugins_dir: PathBuf, auto_approve: bool) -> Self {
+        let lifecycle_manager = Arc::new(LifecycleManager::new());
+        let install_hashes_path = app_data_dir.join(package_verifier::INSTALL_HASHES_FILE);
+        let install_hashes = package_verifier::load_install_hashes(&install_hashes_path).unwrap_or_else(|e| {
+            println!("[PluginManager] Failed to load install hashes: {}", e);
+            HashMap::new()
+        });
+
+        // One logger for permission, lifecycle and network entries, so they extend a single hash chain
+        let audit_logger = Arc::new(RwLock::new(AuditLogger::new(app_data_dir.clone())));
+        let permission_manager = Arc::new(RwLock::new(
+            PermissionManager::with_audit_logger(app_data_dir.clone(), auto_approve, audit_logger.clone())
+        ));
+
+        let mut network_proxy = NetworkProxy::new(permission_manager.clone(), audit_logger.clone());
+        network_proxy.set_lifecycle_manager(lifecycle_manager.clone());
+        // Uninstalling a plugin drops its cached responses
+        let state_listeners = vec![network_proxy.cache_cleanup_listener()];
 
         Self {
             registry: Arc::new(RwLock::new(PluginRegistry::new())),
-            permission_manager: Arc::new(RwLock::new(
-                PermissionManager::with_auto_approve(app_data_dir.clone(), auto_approve)
-            )),
-            lifecycle_manager: Arc::new(LifecycleManager::new()),
+            permission_manager,
+            event_bus: Arc::new(PluginEventBus::new(lifecycle_manager.clone())),
+            lifecycle_manager,
             manifest_parser: ManifestParser::new(),
             plugins_dir,
+            plugin_data_dir: app_data_dir.join("plugin-data"),
+            config_api: PluginConfigAPI::new(Arc::new(StorageAPI::new(app_data_dir.join("plugin-data")))),
+            preprocessors: Arc::new(RwLock::new(PreprocessorChain::new())),
+            command_registry: Arc::new(RwLock::new(CommandRegistry::new())),
+            view_registry: Arc::new(RwLock::new(ViewRegistry::new())),
+            keybinding_registry: Arc::new(RwLock::new(KeybindingRegistry::new())),
+            service_runner: Arc::new(ServiceRunner::new(Arc::new(CommandSpawner), RestartPolicy::default())),
+            signature_policy: Arc::new(RwLock::new(SignaturePolicy::default())),
+            enforce_integrity: AtomicBool::new(false),
+            install_hashes: RwLock::new(install_hashes),
+            install_hashes_path,
+            safe_mode: AtomicBool::new(false),
+            health_probe: Arc::new(RwLock::new(Arc::new(SidecarProbe))),
+            resource_sampler: Arc::new(RwLock::new(Arc::new(ProcSampler))),
+            state_listeners: Arc::new(RwLock::new(state_listeners)),
+            crash_backoff: Arc::new(RwLock::new(RestartPolicy::default())),
+            crash_restarts: Arc::new(RwLock::new(HashMap::new())),
+            plugin_locks: Mutex::new(HashMap::new()),
+            http_proxy: RwLock::new(None),
+            registry_url: RwLock::new(None),
+            network_proxy: Arc::new(RwLock::new(network_proxy)),
+            audit_logger,
+        }
+    }
+
+    /// Directory plugins are installed into and loaded from
+    pub fn plugins_dir(&self) -> &Path {
+        &self.plugins_dir
+    }
+
+    /// Create the plugins directory if needed and check that it can be written.
+    /// Done before each install, and at startup only for a relocated directory, so the default
+    /// `AppData/plugins` isn't created until a plugin is installed.
+    pub fn ensure_plugins_dir_writable(&self) -> PluginResult<()> {
+        std::fs::create_dir_all(&self.plugins_dir)?;
+
+        let probe = self.plugins_dir.join(format!(".write-test-{}", uuid::Uuid::new_v4()));
+        std::fs::write(&probe, b"")
+            .and_then(|_| std::fs::remove_file(&probe))
+            .map_err(|e| PluginError::IoError(std::io::Error::new(
+                e.kind(),
+                format!("Plugins directory {} is not writable: {}", self.plugins_dir.display(), e),
+            )))
+    }
+
+    /// Register plugins already installed in the plugins directory (e.g. after it was relocated).
+    /// Folders without a valid manifest are skipped; returns the newly registered plugin IDs.
+    pub fn discover_installed_plugins(&self) -> PluginResult<Vec<PluginId>> {
+        if !self.plugins_dir.is_dir() {
+            return Ok(Vec::new());
+        }
+
+        let mut discovered = Vec::new();
+        for entry in std::fs::read_dir(&self.plugins_dir)? {
+            let install_path = entry?.path();
+            if !install_path.join("manifest.json").is_file() {
+                continue;
+            }
+
+            let manifest = match self.parse_and_validate_manifest(&install_path) {
+                Ok(manifest) => manifest,
+                Err(e) => {
+                    println!("[PluginManager] Skipping plugin in {}: {}", install_path.display(), e);
+                    continue;
+                }
+            };
+            let plugin_id = manifest.name.clone();
+            if self.registry.read().unwrap().get_metadata(&plugin_id).is_some() {
+                continue;
+            }
+            let icon_path = match manifest.resolve_icon(&install_path) {
+                Ok(icon_path) => icon_path,
+                Err(e) => {
+                    println!("[PluginManager] Ignoring icon of {}: {}", plugin_id, e);
+                    None
+                }
+            };
+
+            let Some(content_hash) = self.discovered_content_hash(&plugin_id, &install_path) else {
+                continue;
+            };
+
+            let now = Utc::now().to_rfc3339();
+            let metadata = PluginMetadata {
+                id: plugin_id.clone(),
+                name: manifest.name.clone(),
+                display_name: manifest.display_name.clone(),
+                version: manifest.version.clone(),
+                description: manifest.description.clone(),
+                author: manifest.author.clone(),
+                plugin_type: manifest.plugin_type.clone(),
+                install_path: install_path.clone(),
+                state: PluginState::Installed,
+                created_at: now.clone(),
+                updated_at: now,
+                content_hash: Some(content_hash),
+                restart_policy: PluginRestartPolicy::default(),
+                icon_path,
+            };
+
+            self.registry.write().unwrap().register(metadata, manifest)?;
+            discovered.push(plugin_id);
+        }
+
+        Ok(discovered)
+    }
+
+    /// Hash a discovered plugin is held to. A plugin installed through `load_plugin_from_zip` is checked
+    /// against the hash recorded then, so files changed since fail `verify_plugin_integrity`. One with no
+    /// record (e.g. copied into the folder by hand) must pass the signature policy and is recorded now.
+    /// `None` skips the plugin.
+    fn discovered_content_hash(&self, plugin_id: &str, install_path: &Path) -> Option<String> {
+        let current = match package_verifier::content_hash(install_path) {
+            Ok(hash) => hash,
+            Err(e) => {
+                println!("[PluginManager] Skipping plugin {}: {}", plugin_id, e);
+                return None;
+            }
+        };
+
+        let recorded = self.install_hashes.read().unwrap().get(plugin_id).cloned();
+        if let Some(recorded) = recorded {
+            if recorded != current {
+                println!("[PluginManager] Files of plugin {} changed since it was installed", plugin_id);
+            }
+            return Some(recorded);
+        }
+
+        let signature_status = {
+            let policy = self.signature_policy.read().unwrap();
+            package_verifier::verify_package(install_path, &policy)
+        };
+        if let Err(e) = signature_status {
+            println!("[PluginManager] Skipping plugin {} with no recorded install: {}", plugin_id, e);
+            return None;
+        }
+        self.record_install_hash(plugin_id, Some(current.clone()));
+        Some(current)
+    }
+
+    /// Record (or with `None`, forget) a plugin's install-time content hash
+    fn record_install_hash(&self, plugin_id: &str, hash: Option<String>) {
+        let mut hashes = self.install_hashes.write().unwrap();
+        match hash {
+            Some(hash) => hashes.insert(plugin_id.to_string(), hash),
+            None => hashes.remove(plugin_id),
+        };
+        if let Err(e) = package_verifier::save_install_hashes(&self.install_hashes_path, &hashes) {
+            println!("[PluginManager] Failed to save install hashes: {}", e);
+        }
+    }
+
+    /// Set the package signature policy (e.g., from `SignaturePolicy::from_settings`)
+    pub fn set_signature_policy(&self, policy: SignaturePolicy) {
+        *self.signature_policy.write().unwrap() = policy;
+    }
+
+    /// Apply the plugin-related global settings.
+    /// Called at startup before plugins are discovered and again whenever settings are saved.
+    pub fn apply_settings(&self, settings: &GlobalSettings) {
+        let policy = SignaturePolicy::from_settings(settings).unwrap_or_else(|e| {
+            // Keep enforcing with no trusted keys rather than silently accepting anything
+            println!("[PluginManager] Ignoring invalid trusted plugin keys: {}", e);
+            SignaturePolicy { enforce: settings.enforce_plugin_signatures, trusted_keys: Vec::new() }
+        });
+        self.set_signature_policy(policy);
+        self.set_integrity_enforcement(settings.enforce_plugin_integrity);
+        self.audit_logger.write().unwrap().set_per_plugin_logs(settings.per_plugin_audit_logs);
+        self.set_http_proxy(ProxyConfig::from_settings(settings));
+        self.set_registry_url(settings.plugin_registry_url.clone().filter(|url| !url.is_empty()));
+
+        let default_grants = permission_manager::parse_default_grants(&settings.default_plugin_permissions)
+            .and_then(|grants| self.set_default_permission_grants(grants));
+        if let Err(e) = default_grants {
+            // Grant nothing by default rather than keep an outdated list
+            println!("[PluginManager] Ignoring invalid default plugin permissions: {}", e);
+            let _ = self.set_default_permission_grants(Vec::new());
         }
     }
 
     /// PLUGIN-003: Load plugin from ZIP package
     /// Extracts ZIP to AppData/plugins/{plugin_id}/ and registers metadata
     pub fn load_plugin_from_zip(&self, zip_path: &Path) -> PluginResult<PluginId> {
+        self.ensure_plugins_dir_writable()?;
+
         // Extract ZIP to temporary location
         let temp_dir = std::env::temp_dir().join(format!("vcp_plugin_{}", uuid::Uuid::new_v4()));
         std::fs::create_dir_all(&temp_dir)?;
 
-        // Extract ZIP
-        let file = std::fs::File::open(zip_path)?;
-        let mut archive = zip::ZipArchive::new(file)
-            .map_err(|e| PluginError::ZipError(e.to_string()))?;
-
-        for i in 0..archive.len() {
-            let mut file = archive.by_index(i)
-                .map_err(|e| PluginError::ZipError(e.to_string()))?;
-            let outpath = temp_dir.join(file.name());
-
-            if file.name().ends_with('/') {
-                std::fs::create_dir_all(&outpath)?;
-            } else {
-                if let Some(p) = outpath.parent() {
-                    std::fs::create_dir_all(p)?;
-                }
-                let mut outfile = std::fs::File::create(&outpath)?;
-                std::io::copy(&mut file, &mut outfile)?;
+        // Nothing extracted is kept unless the package makes it to its install location
+        let manifest = match self.prepare_package(zip_path, &temp_dir) {
+            Ok(manifest) => manifest,
+            Err(e) => {
+                let _ = std::fs::remove_dir_all(&temp_dir);
+                return Err(e);
             }
-        }
-
-        // PLUGIN-004: Parse and validate manifest
-        let manifest = self.parse_and_validate_manifest(&temp_dir)?;
+        };
         let plugin_id = manifest.name.clone();
 
+        // Replacing the files and registering must not interleave with another operation on the plugin
+        let lock = self.plugin_lock(&plugin_id);
+        let _guard = hold(&lock);
+
         // Move to final location
         let install_path = self.plugins_dir.join(&plugin_id);
-        if install_path.exists() {
-            std::fs::remove_dir_all(&install_path)?;
+        let moved = (|| {
+            if install_path.exists() {
+                std::fs::remove_dir_all(&install_path)?;
+            }
+            std::fs::create_dir_all(self.plugins_dir.as_path())?;
+            move_dir(&temp_dir, &install_path)
+        })();
+        if let Err(e) = moved {
+            let _ = std::fs::remove_dir_all(&temp_dir);
+            return Err(e.into());
         }
-        std::fs::create_dir_all(self.plugins_dir.as_path())?;
-        std::fs::rename(&temp_dir, &install_path)?;
+
+        // Record the installed contents for later tamper checks, also across restarts
+        let content_hash = package_verifier::content_hash(&install_path)?;
+        self.record_install_hash(&plugin_id, Some(content_hash.clone()));
 
         // Create metadata
         let metadata = PluginMetadata {
@@ -168,24 +580,119 @@ impl PluginManager {
             state: PluginState::Installed,
             created_at: Utc::now().to_rfc3339(),
             updated_at: Utc::now().to_rfc3339(),
+            content_hash: Some(content_hash),
+            restart_policy: PluginRestartPolicy::default(),
+            icon_path: manifest.icon.as_ref().map(|icon| install_path.join(icon)),
         };
 
         // Register plugin
-        let mut registry = self.registry.write().unwrap();
-        registry.register(metadata, manifest)?;
+        self.registry.write().unwrap().register(metadata, manifest)?;
+
+        // Without the defaults the plugin is simply prompted for them on activation
+        if let Err(e) = self.permission_manager.write().unwrap().apply_default_grants(&plugin_id) {
+            println!("[PluginManager] Failed to grant default permissions to {}: {}", plugin_id, e);
+        }
 
         Ok(plugin_id)
     }
 
+    /// Extract a package into `temp_dir` and check its signature and manifest
+    fn prepare_package(&self, zip_path: &Path, temp_dir: &Path) -> PluginResult<PluginManifest> {
+        extract_package(zip_path, temp_dir)?;
+
+        // Check the package signature before trusting anything in it
+        let signature_status = {
+            let policy = self.signature_policy.read().unwrap();
+            package_verifier::verify_package(temp_dir, &policy)?
+        };
+        match signature_status {
+            SignatureStatus::Verified => {}
+            SignatureStatus::Unsigned => {
+                println!("[PluginManager] Installing unsigned plugin from {}", zip_path.display());
+            }
+            SignatureStatus::Invalid(reason) => {
+                println!("[PluginManager] Plugin signature invalid ({}), installing because enforcement is off", reason);
+            }
+        }
+
+        // PLUGIN-004: Parse and validate manifest
+        let manifest = self.parse_and_validate_manifest(temp_dir)?;
+        for warning in manifest.warnings() {
+            println!("[PluginManager] Manifest warning for {}: {}", manifest.name, warning.message);
+        }
+        manifest.resolve_icon(temp_dir)?;
+
+        Ok(manifest)
+    }
+
+    /// Refuse (or allow) activation of plugins that fail `verify_plugin_integrity`
+    pub fn set_integrity_enforcement(&self, enforce: bool) {
+        self.enforce_integrity.store(enforce, Ordering::Relaxed);
+    }
+
+    /// Enter (or leave) safe mode. In safe mode activation events and crash restarts
+    /// activate nothing; `activate_plugin` still works so the user can enable plugins one by one.
+    pub fn set_safe_mode(&self, safe_mode: bool) {
+        self.safe_mode.store(safe_mode, Ordering::Relaxed);
+    }
+
+    pub fn is_safe_mode(&self) -> bool {
+        self.safe_mode.load(Ordering::Relaxed)
+    }
+
+    /// Re-hash the plugin's installed files and compare with the hash recorded at install.
+    /// Plugins registered without a recorded hash have nothing to compare against and pass.
+    pub fn verify_plugin_integrity(&self, plugin_id: &str) -> PluginResult<bool> {
+        let (install_path, expected) = {
+            let registry = self.registry.read().unwrap();
+            let metadata = registry.get_metadata(plugin_id)
+                .ok_or_else(|| PluginError::NotFound(plugin_id.to_string()))?;
+            (metadata.install_path.clone(), metadata.content_hash.clone())
+        };
+
+        let Some(expected) = expected else {
+            return Ok(true);
+        };
+
+        if !install_path.exists() {
+            return Ok(false);
+        }
+
+        Ok(package_verifier::content_hash(&install_path)? == expected)
+    }
+
     /// PLUGIN-004: Parse and validate manifest
     fn parse_and_validate_manifest(&self, plugin_dir: &Path) -> PluginResult<PluginManifest> {
         let manifest_path = plugin_dir.join("manifest.json");
         self.manifest_parser.parse_and_validate(&manifest_path)
     }
 
+    /// Operation lock of a plugin
+    fn plugin_lock(&self, plugin_id: &str) -> Arc<Mutex<()>> {
+        Arc::clone(self.plugin_locks.lock().unwrap().entry(plugin_id.to_string()).or_default())
+    }
+
+    /// Drop the lock entry of an uninstalled plugin, unless another operation already waits on it
+    fn prune_plugin_lock(&self, plugin_id: &str) {
+        let mut locks = self.plugin_locks.lock().unwrap();
+        // One reference is the map's, one the uninstall's own
+        if locks.get(plugin_id).is_some_and(|lock| Arc::strong_count(lock) <= 2) {
+            locks.remove(plugin_id);
+        }
+    }
+
     /// PLUGIN-005: Activate plugin
     /// Checks permissions, runs activate() hook, updates state to Running
     pub fn activate_plugin(&self, plugin_id: &str) -> PluginResult<()> {
+        let lock = self.plugin_lock(plugin_id);
+        let _guard = hold(&lock);
+
+        // Activating by hand starts the crash-restart count over
+        self.crash_restarts.write().unwrap().remove(plugin_id);
+        self.activate(plugin_id)
+    }
+
+    fn activate(&self, plugin_id: &str) -> PluginResult<()> {
         // Get manifest
         let manifest = {
             let registry = self.registry.read().unwrap();
@@ -194,19 +701,42 @@ impl PluginManager {
                 .clone()
         };
 
+        if self.enforce_integrity.load(Ordering::Relaxed) && !self.verify_plugin_integrity(plugin_id)? {
+            return Err(PluginError::ActivationError(
+                format!("Plugin '{}' was modified after installation", plugin_id)
+            ));
+        }
+
         // Request permissions BEFORE state changes
         // This ensures we fail early if permissions are denied
+        // All permissions are presented together; already granted ones are not asked again.
+        // Only denied required permissions block activation.
         {
+            let requested: Vec<String> = manifest.permissions
+                .iter()
+                .map(|p| p.permission().to_string())
+                .collect();
+
             let mut perm_mgr = self.permission_manager.write().unwrap();
-            for permission in &manifest.permissions {
-                // Check if permission already granted (e.g., via explicit grant_permission() call)
-                if !perm_mgr.has_permission(plugin_id, permission) {
-                    // Not granted yet, request it (will check auto_approve)
-                    perm_mgr.request_permission(plugin_id, permission)?;
-                }
+            let decisions = perm_mgr.request_permissions_batch(plugin_id, &requested)?;
+            let denied = denied_required_permissions(&manifest, &decisions);
+
+            if !denied.is_empty() {
+                return Err(PluginError::PermissionDenied(format!(
+                    "Permission(s) {} denied for plugin '{}'",
+                    denied.join(", "),
+                    plugin_id
+                )));
             }
         }
 
+        // A command ID can only belong to one plugin
+        let command_ids: Vec<String> = manifest.contributes.commands
+            .iter()
+            .map(|command| command.identifier.clone())
+            .collect();
+        self.command_registry.read().unwrap().check_available(plugin_id, &command_ids)?;
+
         // Check current state to determine transition path
         let current_state = {
             let registry = self.registry.read().unwrap();
@@ -218,7 +748,8 @@ impl PluginManager {
         // State transition logic:
         // - Installed → Loaded → Activated → Running (normal activation)
         // - Deactivated → Activated → Running (reactivation)
-        if current_state != PluginState::Deactivated {
+        // - Crashed → Activated → Running (restart)
+        if current_state != PluginState::Deactivated && current_state != PluginState::Crashed {
             // Normal activation path: go through Loaded state
             let mut registry = self.registry.write().unwrap();
             registry.update_state(plugin_id, PluginState::Loaded)?;
@@ -240,6 +771,27 @@ impl PluginManager {
 
         self.lifecycle_manager.execute_activate_hook(plugin_id, &install_path, &manifest)?;
 
+        // messagePreprocessor plugins join the preprocessing chain
+        if manifest.effective_plugin_type() == "messagePreprocessor" {
+            self.add_to_preprocessor_chain(plugin_id, install_path.join(&manifest.main))?;
+        }
+
+        // service plugins get a supervised background process
+        if manifest.effective_plugin_type() == "service" {
+            self.service_runner.start(plugin_id, install_path.join(&manifest.main))?;
+            self.lifecycle_manager.track_resource(plugin_id, ResourceType::ServiceProcess(plugin_id.to_string()));
+        }
+
+        self.command_registry.write().unwrap().register(plugin_id, &command_ids)?;
+        self.view_registry.write().unwrap().register(plugin_id, &manifest.contributes.views);
+        let conflicts = self.keybinding_registry.write().unwrap().register(plugin_id, &manifest.contributes.keybindings);
+        for conflict in conflicts {
+            println!(
+                "[PluginManager] Keybinding {} of {} conflicts with {} ({}); keeping the existing binding",
+                conflict.shadowed.key, plugin_id, conflict.bound_command, conflict.bound_plugin_id
+            );
+        }
+
         // Update state to Running
         {
             let mut registry = self.registry.write().unwrap();
@@ -253,6 +805,12 @@ impl PluginManager {
     /// PLUGIN-006: Deactivate plugin
     /// Runs deactivate() hook, cleans up resources, updates state
     pub fn deactivate_plugin(&self, plugin_id: &str) -> PluginResult<()> {
+        let lock = self.plugin_lock(plugin_id);
+        let _guard = hold(&lock);
+        self.deactivate(plugin_id)
+    }
+
+    fn deactivate(&self, plugin_id: &str) -> PluginResult<()> {
         // Get manifest
         let manifest = {
             let registry = self.registry.read().unwrap();
@@ -267,6 +825,17 @@ impl PluginManager {
             registry.update_state(plugin_id, PluginState::Deactivated)?;
         }
 
+        self.preprocessors.write().unwrap().deactivate(plugin_id);
+        self.command_registry.write().unwrap().unregister_plugin(plugin_id);
+        self.view_registry.write().unwrap().unregister_plugin(plugin_id);
+        self.keybinding_registry.write().unwrap().unregister_plugin(plugin_id);
+        self.event_bus.unsubscribe_plugin(plugin_id);
+
+        if manifest.effective_plugin_type() == "service" {
+            // Not found just means the service was never started
+            let _ = self.service_runner.stop(plugin_id);
+        }
+
         // Execute deactivate hook
         let install_path = {
             let registry = self.registry.read().unwrap();
@@ -280,133 +849,775 @@ impl PluginManager {
         Ok(())
     }
 
-    /// PLUGIN-007: Dependency resolution with topological sort
-    pub fn resolve_dependencies(&self, plugin_id: &str) -> PluginResult<Vec<PluginId>> {
-        let registry = self.registry.read().unwrap();
-        let manifest = registry.get_manifest(plugin_id)
-            .ok_or_else(|| PluginError::NotFound(plugin_id.to_string()))?;
-
-        let mut order = Vec::new();
-        let mut visited = HashSet::new();
-        let mut temp_mark = HashSet::new();
+    /// Provide a native preprocessor implementation for a `messagePreprocessor` plugin.
+    /// Used instead of the sidecar hook when the plugin activates.
+    pub fn register_message_preprocessor(&self, plugin_id: &str, preprocessor: Arc<dyn MessagePreprocessor>) {
+        self.preprocessors.write().unwrap().provide(plugin_id, preprocessor);
+    }
 
-        self.visit_dependency(
-            plugin_id,
-            &registry,
-            &mut order,
-            &mut visited,
-            &mut temp_mark,
-        )?;
+    /// Provide a native implementation for a plugin's contributed commands.
+    /// Used instead of the sidecar hook when the commands are invoked.
+    pub fn register_command_handler(&self, plugin_id: &str, handler: Arc<dyn CommandHandler>) {
+        self.command_registry.write().unwrap().provide(plugin_id, handler);
+    }
 
-        Ok(order)
+    /// Plugin that registered `command_id`, if it is running
+    pub fn command_owner(&self, command_id: &str) -> Option<PluginId> {
+        self.command_registry.read().unwrap().owner(command_id).cloned()
     }
 
-    fn visit_dependency(
-        &self,
-        plugin_id: &str,
-        registry: &PluginRegistry,
-        order: &mut Vec<PluginId>,
-        visited: &mut HashSet<PluginId>,
-        temp_mark: &mut HashSet<PluginId>,
-    ) -> PluginResult<()> {
-        if visited.contains(plugin_id) {
-            return Ok(());
+    /// Invoke a contributed command, activating the plugin that contributes it first if needed
+    pub fn invoke_command(&self, command_id: &str, args: serde_json::Value) -> PluginResult<serde_json::Value> {
+        let plugin_id = match self.command_owner(command_id) {
+            Some(plugin_id) => plugin_id,
+            None => self.command_contributor(command_id)?,
+        };
+
+        if self.get_plugin_state(&plugin_id) != Some(PluginState::Running) {
+            self.activate_plugins(std::slice::from_ref(&plugin_id))?;
         }
 
-        if temp_mark.contains(plugin_id) {
-            return Err(PluginError::DependencyError(
-                format!("Circular dependency detected involving plugin: {}", plugin_id)
-            ));
+        let handler = self.command_registry.read().unwrap().handler(&plugin_id);
+        let handler = match handler {
+            Some(handler) => handler,
+            None => {
+                let registry = self.registry.read().unwrap();
+                let entry_point = match (registry.get_metadata(&plugin_id), registry.get_manifest(&plugin_id)) {
+                    (Some(metadata), Some(manifest)) => metadata.install_path.join(&manifest.main),
+                    _ => return Err(PluginError::NotFound(plugin_id)),
+                };
+                Arc::new(SidecarCommandHandler::new(plugin_id.clone(), entry_point)) as Arc<dyn CommandHandler>
+            }
+        };
+
+        handler.execute(command_id, args)
+    }
+
+    /// The installed plugin whose manifest contributes `command_id` (an error if none or several do)
+    fn command_contributor(&self, command_id: &str) -> PluginResult<PluginId> {
+        let registry = self.registry.read().unwrap();
+        let mut contributors: Vec<PluginId> = registry
+            .list_plugins()
+            .into_iter()
+            .filter(|metadata| {
+                registry.get_manifest(&metadata.id).is_some_and(|manifest| {
+                    manifest.contributes.commands.iter().any(|command| command.identifier == command_id)
+                })
+            })
+            .map(|metadata| metadata.id.clone())
+            .collect();
+        contributors.sort();
+
+        match contributors.len() {
+            0 => Err(PluginError::HookError(format!("No plugin contributes command '{}'", command_id))),
+            1 => Ok(contributors.remove(0)),
+            _ => Err(PluginError::HookError(format!(
+                "Command '{}' is contributed by several plugins ({}); activate one of them first",
+                command_id,
+                contributors.join(", ")
+            ))),
         }
+    }
 
-        temp_mark.insert(plugin_id.to_string());
+    /// Views contributed by running plugins at `location`
+    pub fn list_views(&self, location: ViewLocation) -> Vec<ContributedView> {
+        self.view_registry.read().unwrap().list(location)
+    }
 
-        let manifest = registry.get_manifest(plugin_id)
-            .ok_or_else(|| PluginError::NotFound(plugin_id.to_string()))?;
+    /// Settings a plugin declares in its manifest's `configuration` contribution
+    pub fn get_config_schema(&self, plugin_id: &str) -> PluginResult<Vec<ConfigurationProperty>> {
+        self.registry
+            .read()
+            .unwrap()
+            .get_manifest(plugin_id)
+            .map(|manifest| manifest.contributes.configuration.clone())
+            .ok_or_else(|| PluginError::NotFound(plugin_id.to_string()))
+    }
+
+    /// Config API with the plugin's current manifest schema registered
+    fn plugin_config(&self, plugin_id: &str) -> PluginResult<&PluginConfigAPI> {
+        self.config_api.register_schema(plugin_id, self.get_config_schema(plugin_id)?);
+        Ok(&self.config_api)
+    }
+
+    /// Every declared setting of a plugin that has a value or default
+    pub fn get_plugin_config(&self, plugin_id: &str) -> PluginResult<serde_json::Map<String, serde_json::Value>> {
+        self.plugin_config(plugin_id)?.get_all(plugin_id)
+    }
 
-        for (dep_id, _version) in &manifest.dependencies {
-            self.visit_dependency(dep_id, registry, order, visited, temp_mark)?;
+    /// Set one of a plugin's declared settings (`None` resets it to its default)
+    pub fn set_plugin_config(&self, plugin_id: &str, key: &str, value: Option<serde_json::Value>) -> PluginResult<()> {
+        let config = self.plugin_config(plugin_id)?;
+        match value {
+            Some(value) => config.set(plugin_id, key, value),
+            None => config.reset(plugin_id, key),
         }
+    }
 
-        temp_mark.remove(plugin_id);
-        visited.insert(plugin_id.to_string());
-        order.push(plugin_id.to_string());
+    /// Bytes of the icon a plugin declares in its manifest
+    pub fn get_plugin_icon(&self, plugin_id: &str) -> PluginResult<Vec<u8>> {
+        let icon_path = self.registry
+            .read()
+            .unwrap()
+            .get_metadata(plugin_id)
+            .ok_or_else(|| PluginError::NotFound(plugin_id.to_string()))?
+            .icon_path
+            .clone()
+            .ok_or_else(|| PluginError::FileSystemError(format!("Plugin '{}' has no icon", plugin_id)))?;
+
+        std::fs::read(&icon_path).map_err(|e| PluginError::FileSystemError(
+            format!("Failed to read icon of {}: {}", plugin_id, e)
+        ))
+    }
+
+    /// Keybindings in effect for running plugins
+    pub fn list_keybindings(&self) -> Vec<RegisteredKeybinding> {
+        self.keybinding_registry.read().unwrap().list()
+    }
+
+    /// Keybindings not applied because another plugin already bound the same key
+    pub fn keybinding_conflicts(&self) -> Vec<KeybindingConflict> {
+        self.keybinding_registry.read().unwrap().conflicts()
+    }
+
+    /// Command bound to `key` in the given `when` context
+    pub fn resolve_key(&self, key: &str, context: Option<&str>) -> Option<String> {
+        self.keybinding_registry.read().unwrap().resolve(key, context)
+    }
+
+    /// Add an activated plugin to the preprocessing chain, keeping the chain in dependency order
+    fn add_to_preprocessor_chain(&self, plugin_id: &str, entry_point: PathBuf) -> PluginResult<()> {
+        let active_ids = {
+            let mut chain = self.preprocessors.write().unwrap();
+            chain.activate(plugin_id, Arc::new(SidecarPreprocessor::new(plugin_id.to_string(), entry_point)));
+            chain.active_ids()
+        };
+
+        let order = self.resolve_plugin_dependencies(&active_ids)?;
+        self.preprocessors.write().unwrap().reorder(&order);
 
         Ok(())
     }
 
-    /// PLUGIN-008: Uninstall plugin
-    /// Deactivates, removes files, clears permissions
-    pub fn uninstall_plugin(&self, plugin_id: &str) -> PluginResult<()> {
-        // Deactivate if running
-        {
+    /// Run a message through all active `messagePreprocessor` plugins.
+    /// Dependencies run before the plugins that depend on them.
+    pub fn run_message_preprocessors(&self, message: Message) -> Message {
+        self.preprocessors.read().unwrap().run(message)
+    }
+
+    /// Status of a `service` plugin's background process
+    pub fn get_service_status(&self, plugin_id: &str) -> PluginResult<ServiceStatus> {
+        self.service_runner
+            .status(plugin_id)
+            .ok_or_else(|| PluginError::NotFound(plugin_id.to_string()))
+    }
+
+    /// Event bus plugins publish and subscribe to contributed events on
+    pub fn event_bus(&self) -> &Arc<PluginEventBus> {
+        &self.event_bus
+    }
+
+    /// Lifecycle manager shared with the plugin APIs (e.g. to track in-flight requests)
+    pub fn lifecycle_manager(&self) -> &Arc<LifecycleManager> {
+        &self.lifecycle_manager
+    }
+
+    /// Replace the probe used by `health_check`
+    pub fn set_health_probe(&self, probe: Arc<dyn HealthProbe>) {
+        *self.health_probe.write().unwrap() = probe;
+    }
+
+    /// Register a callback for state changes the manager makes on its own (e.g. crashes)
+    pub fn on_state_change(&self, listener: StateChangeListener) {
+        self.state_listeners.write().unwrap().push(listener);
+    }
+
+    fn notify_state_change(&self, plugin_id: &str, from: PluginState, to: PluginState) {
+        for listener in self.state_listeners.read().unwrap().iter() {
+            listener(plugin_id, from, to);
+        }
+    }
+
+    /// Ping a `Running` plugin. A plugin that fails the check is moved to `Crashed`.
+    pub fn health_check(&self, plugin_id: &str) -> PluginResult<HealthStatus> {
+        let (state, plugin_type, entry_point) = {
             let registry = self.registry.read().unwrap();
             let metadata = registry.get_metadata(plugin_id)
                 .ok_or_else(|| PluginError::NotFound(plugin_id.to_string()))?;
+            let (main, plugin_type) = registry.get_manifest(plugin_id)
+                .map(|m| (m.main.clone(), m.effective_plugin_type().to_string()))
+                .unwrap_or_default();
+            (metadata.state, plugin_type, metadata.install_path.join(main))
+        };
 
-            if metadata.state == PluginState::Running {
-                drop(registry);
-                self.deactivate_plugin(plugin_id)?;
-            }
+        if state != PluginState::Running {
+            return Ok(HealthStatus::NotRunning);
         }
 
-        // Remove from registry
-        let (metadata, _manifest) = {
-            let mut registry = self.registry.write().unwrap();
-            registry.remove(plugin_id)?
+        // A service between restarts is still supervised; only a given-up or stopped one is dead
+        let service_check = if plugin_type == "service" {
+            match self.service_runner.status(plugin_id) {
+                Some(status) if status.gave_up => Err("Service process failed too many times".to_string()),
+                Some(status) if !status.running && status.last_exit == Some(ServiceExit::Success) => {
+                    Err("Service process exited".to_string())
+                }
+                Some(_) => Ok(()),
+                None => Err("Service process is not running".to_string()),
+            }
+        } else {
+            Ok(())
         };
 
-        // Remove plugin files
-        if metadata.install_path.exists() {
-            std::fs::remove_dir_all(&metadata.install_path)?;
-        }
+        let result = service_check.and_then(|_| {
+            let probe = Arc::clone(&self.health_probe.read().unwrap());
+            probe.ping(&plugin_id.to_string(), &entry_point, DEFAULT_HEALTH_TIMEOUT)
+        });
 
-        // Clear permissions
-        {
-            let mut perm_mgr = self.permission_manager.write().unwrap();
-            perm_mgr.revoke_all_permissions(plugin_id)?;
+        match result {
+            Ok(()) => Ok(HealthStatus::Healthy),
+            Err(reason) => {
+                self.mark_crashed(plugin_id, &reason)?;
+                Ok(HealthStatus::Unhealthy(reason))
+            }
         }
-
-        Ok(())
     }
 
-    /// PLUGIN-009: Error handling with rollback
-    pub fn activate_plugin_with_rollback(&self, plugin_id: &str) -> PluginResult<()> {
-        match self.activate_plugin(plugin_id) {
-            Ok(_) => Ok(()),
-            Err(e) => {
-                // Rollback: attempt to deactivate
-                let _ = self.deactivate_plugin(plugin_id);
+    /// Health-check every `Running` plugin (run periodically by the background checker)
+    pub fn check_running_plugins(&self) -> Vec<(PluginId, HealthStatus)> {
+        let running: Vec<PluginId> = {
+            let registry = self.registry.read().unwrap();
+            registry.list_plugins()
+                .into_iter()
+                .filter(|metadata| metadata.state == PluginState::Running)
+                .map(|metadata| metadata.id.clone())
+                .collect()
+        };
 
-                // Reset state to Installed
-                let mut registry = self.registry.write().unwrap();
-                if let Some(metadata) = registry.plugins.get_mut(plugin_id) {
-                    metadata.state = PluginState::Installed;
-                }
+        running
+            .into_iter()
+            .filter_map(|plugin_id| {
+                let status = self.health_check(&plugin_id).ok()?;
+                Some((plugin_id, status))
+            })
+            .collect()
+    }
 
-                Err(e)
-            }
+    /// Set the resource caps for a plugin; the OS-enforced ones apply from its next launch.
+    /// Caps this platform can't enforce are rejected.
+    pub fn set_resource_limits(&self, plugin_id: &str, limits: ResourceLimits) -> PluginResult<()> {
+        if self.registry.read().unwrap().get_metadata(plugin_id).is_none() {
+            return Err(PluginError::NotFound(plugin_id.to_string()));
         }
+        limits.check_supported().map_err(PluginError::InvalidConfig)?;
+        self.service_runner.set_limits(plugin_id, limits);
+        Ok(())
     }
 
-    /// Get list of all plugins
-    pub fn list_plugins(&self) -> Vec<PluginMetadata> {
-        let registry = self.registry.read().unwrap();
-        registry.list_plugins().into_iter().cloned().collect()
+    /// Resource caps configured for a plugin
+    pub fn get_resource_limits(&self, plugin_id: &str) -> ResourceLimits {
+        self.service_runner.limits(plugin_id)
     }
 
-    /// PLUGIN-079: Get plugin state
-    pub fn get_plugin_state(&self, plugin_id: &str) -> Option<PluginState> {
-        let registry = self.registry.read().unwrap();
-        registry.get_metadata(plugin_id).map(|m| m.state)
+    /// Replace the sampler used to measure plugin processes
+    pub fn set_resource_sampler(&self, sampler: Arc<dyn ResourceSampler>) {
+        *self.resource_sampler.write().unwrap() = sampler;
     }
 
-    /// PLUGIN-079: Grant permission to plugin
-    pub fn grant_permission(&self, plugin_id: &str, permission: &str) -> PluginResult<()> {
-        let mut pm = self.permission_manager.write().unwrap();
+    /// Current memory/CPU usage of a plugin's process (unmeasured fields are `None`)
+    pub fn get_plugin_resource_usage(&self, plugin_id: &str) -> PluginResult<ResourceUsage> {
+        if self.registry.read().unwrap().get_metadata(plugin_id).is_none() {
+            return Err(PluginError::NotFound(plugin_id.to_string()));
+        }
 
-        // Parse permission string (e.g., "filesystem.read:AppData/test/*")
-        let parts: Vec<&str> = permission.split(':').collect();
+        let pid = self.service_runner.status(plugin_id).and_then(|status| status.pid);
+        let sampler = Arc::clone(&self.resource_sampler.read().unwrap());
+        Ok(sampler
+            .sample(&plugin_id.to_string(), pid)
+            .unwrap_or_else(|| ResourceUsage::unknown(plugin_id, pid)))
+    }
+
+    /// Check every `Running` plugin with resource caps against its current usage.
+    /// Plugins over a hard cap are moved to `Crashed` (run periodically by the background checker).
+    pub fn enforce_resource_limits(&self) -> Vec<(PluginId, LimitViolation)> {
+        let running: Vec<PluginId> = {
+            let registry = self.registry.read().unwrap();
+            registry.list_plugins()
+                .into_iter()
+                .filter(|metadata| metadata.state == PluginState::Running)
+                .map(|metadata| metadata.id.clone())
+                .collect()
+        };
+
+        let mut violations = Vec::new();
+        for plugin_id in running {
+            let limits = self.service_runner.limits(&plugin_id);
+            if limits.is_unlimited() {
+                continue;
+            }
+
+            let Some(violation) = self.get_plugin_resource_usage(&plugin_id)
+                .ok()
+                .and_then(|usage| limits.check(&usage))
+            else {
+                continue;
+            };
+
+            let reason = violation.to_string();
+            self.audit_logger
+                .write()
+                .unwrap()
+                .log_lifecycle_event(&plugin_id, "resource_limit", &reason, false, Some(&reason));
+            if self.mark_crashed(&plugin_id, &reason).is_ok() {
+                violations.push((plugin_id, violation));
+            }
+        }
+
+        violations
+    }
+
+    /// Move a running plugin to `Crashed` and release what it was holding
+    fn mark_crashed(&self, plugin_id: &str, reason: &str) -> PluginResult<()> {
+        let lock = self.plugin_lock(plugin_id);
+        let _guard = hold(&lock);
+
+        {
+            let mut registry = self.registry.write().unwrap();
+            // Deactivated or uninstalled since the check that found it failing
+            let state = registry.get_metadata(plugin_id)
+                .ok_or_else(|| PluginError::NotFound(plugin_id.to_string()))?
+                .state;
+            if state != PluginState::Running {
+                return Err(PluginError::InvalidStateTransition { from: state, to: PluginState::Crashed });
+            }
+            registry.update_state(plugin_id, PluginState::Crashed)?;
+        }
+        println!("[PluginManager] Plugin {} crashed: {}", plugin_id, reason);
+
+        self.release_runtime(plugin_id);
+        self.notify_state_change(plugin_id, PluginState::Running, PluginState::Crashed);
+        self.schedule_crash_restart(plugin_id);
+        Ok(())
+    }
+
+    /// Drop a plugin's preprocessor, contributions, event subscriptions, service process and
+    /// tracked resources without running hooks
+    fn release_runtime(&self, plugin_id: &str) {
+        self.preprocessors.write().unwrap().deactivate(plugin_id);
+        self.command_registry.write().unwrap().unregister_plugin(plugin_id);
+        self.view_registry.write().unwrap().unregister_plugin(plugin_id);
+        self.keybinding_registry.write().unwrap().unregister_plugin(plugin_id);
+        self.event_bus.unsubscribe_plugin(plugin_id);
+        // Not found just means the plugin has no service process
+        let _ = self.service_runner.stop(plugin_id);
+        self.lifecycle_manager.resource_tracker().clear_plugin_resources(plugin_id);
+    }
+
+    /// Set whether a crashed plugin is restarted automatically
+    pub fn set_restart_policy(&self, plugin_id: &str, policy: PluginRestartPolicy) -> PluginResult<()> {
+        let state = {
+            let mut registry = self.registry.write().unwrap();
+            let metadata = registry.plugins.get_mut(plugin_id)
+                .ok_or_else(|| PluginError::NotFound(plugin_id.to_string()))?;
+            metadata.restart_policy = policy;
+            metadata.state
+        };
+
+        self.crash_restarts.write().unwrap().remove(plugin_id);
+        if state == PluginState::Crashed {
+            self.schedule_crash_restart(plugin_id);
+        }
+
+        Ok(())
+    }
+
+    /// Set the backoff and attempt cap used when restarting crashed plugins
+    pub fn set_crash_restart_backoff(&self, backoff: RestartPolicy) {
+        *self.crash_backoff.write().unwrap() = backoff;
+    }
+
+    /// Queue the next automatic restart of a crashed plugin, if its policy allows one
+    fn schedule_crash_restart(&self, plugin_id: &str) {
+        let policy = self.registry.read().unwrap()
+            .get_metadata(plugin_id)
+            .map(|metadata| metadata.restart_policy)
+            .unwrap_or_default();
+        if policy == PluginRestartPolicy::Never || self.is_safe_mode() {
+            return;
+        }
+
+        let backoff = self.crash_backoff.read().unwrap().clone();
+        let mut restarts = self.crash_restarts.write().unwrap();
+        let restart = restarts.entry(plugin_id.to_string()).or_insert(CrashRestart {
+            attempts: 0,
+            next_attempt_at: None,
+        });
+
+        if policy == PluginRestartPolicy::OnCrash && restart.attempts >= backoff.max_restarts {
+            restart.next_attempt_at = None;
+            println!("[PluginManager] Plugin {} crashed {} times, not restarting", plugin_id, restart.attempts + 1);
+            return;
+        }
+
+        restart.next_attempt_at = Some(Instant::now() + backoff.backoff_for(restart.attempts + 1));
+    }
+
+    /// Restart crashed plugins whose backoff has elapsed (run periodically by the background checker).
+    /// Returns the plugins that are running again.
+    pub fn restart_crashed_plugins(&self) -> Vec<PluginId> {
+        let now = Instant::now();
+        let due: Vec<PluginId> = self.crash_restarts.read().unwrap()
+            .iter()
+            .filter(|(_, restart)| restart.next_attempt_at.is_some_and(|at| at <= now))
+            .map(|(plugin_id, _)| plugin_id.clone())
+            .collect();
+
+        let mut restarted = Vec::new();
+        for plugin_id in due {
+            let lock = self.plugin_lock(&plugin_id);
+            let _guard = hold(&lock);
+
+            // Reactivated or uninstalled by hand in the meantime
+            if self.get_plugin_state(&plugin_id) != Some(PluginState::Crashed) {
+                self.crash_restarts.write().unwrap().remove(&plugin_id);
+                continue;
+            }
+
+            let attempt = {
+                let mut restarts = self.crash_restarts.write().unwrap();
+                let Some(restart) = restarts.get_mut(&plugin_id) else { continue };
+                restart.attempts += 1;
+                restart.next_attempt_at = None;
+                restart.attempts
+            };
+            let detail = format!("attempt {}", attempt);
+
+            match self.activate(&plugin_id) {
+                Ok(()) => {
+                    self.audit_logger.write().unwrap().log_lifecycle_event(&plugin_id, "restart", &detail, true, None);
+                    self.notify_state_change(&plugin_id, PluginState::Crashed, PluginState::Running);
+                    restarted.push(plugin_id);
+                }
+                Err(e) => {
+                    self.audit_logger.write().unwrap()
+                        .log_lifecycle_event(&plugin_id, "restart", &detail, false, Some(&e.to_string()));
+
+                    // Undo the partial activation and wait for the next attempt
+                    self.release_runtime(&plugin_id);
+                    if let Some(metadata) = self.registry.write().unwrap().plugins.get_mut(&plugin_id) {
+                        metadata.state = PluginState::Crashed;
+                    }
+                    self.schedule_crash_restart(&plugin_id);
+                }
+            }
+        }
+
+        restarted
+    }
+
+    /// PLUGIN-007: Dependency resolution with topological sort
+    pub fn resolve_dependencies(&self, plugin_id: &str) -> PluginResult<Vec<PluginId>> {
+        let registry = self.registry.read().unwrap();
+        topo_sort(&[plugin_id.to_string()], &registry)
+    }
+
+    /// Report what `uninstall_plugin` would remove, without changing anything
+    pub fn preview_uninstall(&self, plugin_id: &str) -> PluginResult<UninstallPreview> {
+        let (metadata, manifest) = {
+            let registry = self.registry.read().unwrap();
+            let metadata = registry.get_metadata(plugin_id)
+                .ok_or_else(|| PluginError::NotFound(plugin_id.to_string()))?
+                .clone();
+            let manifest = registry.get_manifest(plugin_id).cloned().unwrap_or_default();
+            (metadata, manifest)
+        };
+
+        Ok(UninstallPreview {
+            plugin_id: plugin_id.to_string(),
+            display_name: metadata.display_name,
+            version: metadata.version,
+            install_size_bytes: dir_size(&metadata.install_path)?,
+            storage_key_count: storage_api::stored_key_count(&self.plugin_data_dir, plugin_id)?,
+            granted_permissions: self.permission_manager.read().unwrap().granted_permissions(plugin_id),
+            commands: manifest.contributes.commands.iter().map(|c| c.identifier.clone()).collect(),
+            views: manifest.contributes.views.iter().map(|v| v.identifier.clone()).collect(),
+        })
+    }
+
+    /// PLUGIN-008: Uninstall plugin
+    /// Deactivates, removes files, clears permissions. With `keep_data` its `plugin-data/{id}/`
+    /// directory is preserved, so reinstalling restores the user's settings; otherwise it is removed too.
+    pub fn uninstall_plugin(&self, plugin_id: &str, keep_data: bool) -> PluginResult<()> {
+        let lock = self.plugin_lock(plugin_id);
+        let _guard = hold(&lock);
+
+        // Deactivate if running
+        {
+            let registry = self.registry.read().unwrap();
+            let metadata = registry.get_metadata(plugin_id)
+                .ok_or_else(|| PluginError::NotFound(plugin_id.to_string()))?;
+
+            if metadata.state == PluginState::Running {
+                drop(registry);
+                self.deactivate(plugin_id)?;
+            }
+        }
+
+        self.crash_restarts.write().unwrap().remove(plugin_id);
+
+        // Remove from registry
+        let (metadata, _manifest) = {
+            let mut registry = self.registry.write().unwrap();
+            registry.remove(plugin_id)?
+        };
+
+        // Remove plugin files
+        if metadata.install_path.exists() {
+            std::fs::remove_dir_all(&metadata.install_path)?;
+        }
+        self.record_install_hash(plugin_id, None);
+
+        // Clear permissions
+        self.revoke_all_permissions(plugin_id)?;
+
+        let data_dir = self.plugin_data_dir.join(plugin_id);
+        if !keep_data && data_dir.exists() {
+            std::fs::remove_dir_all(&data_dir)?;
+        }
+
+        self.notify_state_change(plugin_id, metadata.state, PluginState::Uninstalled);
+        self.prune_plugin_lock(plugin_id);
+        Ok(())
+    }
+
+    /// PLUGIN-009: Error handling with rollback
+    pub fn activate_plugin_with_rollback(&self, plugin_id: &str) -> PluginResult<()> {
+        let lock = self.plugin_lock(plugin_id);
+        let _guard = hold(&lock);
+
+        self.crash_restarts.write().unwrap().remove(plugin_id);
+        match self.activate(plugin_id) {
+            Ok(_) => Ok(()),
+            Err(e) => {
+                // Rollback: attempt to deactivate
+                let _ = self.deactivate(plugin_id);
+
+                // Reset state to Installed
+                let mut registry = self.registry.write().unwrap();
+                if let Some(metadata) = registry.plugins.get_mut(plugin_id) {
+                    metadata.state = PluginState::Installed;
+                }
+
+                Err(e)
+            }
+        }
+    }
+
+    /// Activate several plugins, and the plugins they depend on, in dependency order.
+    /// If one fails, the plugins activated by this call are deactivated again.
+    /// Returns the plugins running afterwards, in activation order.
+    pub fn activate_plugins(&self, plugin_ids: &[String]) -> PluginResult<Vec<PluginId>> {
+        let order = self.resolve_plugin_dependencies(plugin_ids)?;
+
+        let mut activated: Vec<PluginId> = Vec::new();
+        for plugin_id in &order {
+            // Already running plugins are left alone (and never rolled back)
+            if self.get_plugin_state(plugin_id) == Some(PluginState::Running) {
+                continue;
+            }
+
+            if let Err(e) = self.activate_plugin_with_rollback(plugin_id) {
+                println!(
+                    "[PluginManager] Activating {} failed, rolling back {} plugin(s): {}",
+                    plugin_id,
+                    activated.len(),
+                    e
+                );
+                for done in activated.iter().rev() {
+                    if let Err(rollback_error) = self.deactivate_plugin(done) {
+                        println!("[PluginManager] Failed to roll back {}: {}", done, rollback_error);
+                    }
+                }
+                return Err(e);
+            }
+
+            activated.push(plugin_id.clone());
+        }
+
+        Ok(order)
+    }
+
+    /// Lazily activate every installed plugin whose `activationEvents` match `event`, dependencies first.
+    /// Running plugins are left alone and deactivated ones stay off; returns the plugins activated.
+    pub fn dispatch_event(&self, event: RuntimeEvent) -> PluginResult<Vec<PluginId>> {
+        if self.is_safe_mode() {
+            println!("[PluginManager] Safe mode: ignoring {:?}", event);
+            return Ok(Vec::new());
+        }
+
+        let matching: Vec<PluginId> = {
+            let registry = self.registry.read().unwrap();
+            registry
+                .list_plugins()
+                .into_iter()
+                .filter(|metadata| matches!(metadata.state, PluginState::Installed | PluginState::Loaded))
+                .filter(|metadata| {
+                    registry
+                        .get_manifest(&metadata.id)
+                        .is_some_and(|manifest| manifest.matches_activation_event(&event))
+                })
+                .map(|metadata| metadata.id.clone())
+                .collect()
+        };
+
+        if matching.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let already_running: HashSet<PluginId> = self
+            .list_plugins()
+            .into_iter()
+            .filter(|metadata| metadata.state == PluginState::Running)
+            .map(|metadata| metadata.id)
+            .collect();
+
+        println!("[PluginManager] {:?} activates {:?}", event, matching);
+        let order = self.activate_plugins(&matching)?;
+
+        Ok(order.into_iter().filter(|id| !already_running.contains(id)).collect())
+    }
+
+    /// Activate the plugins that asked to start with the app (`onStartupFinished`); skipped in safe mode
+    pub fn activate_startup_plugins(&self) -> PluginResult<Vec<PluginId>> {
+        self.dispatch_event(RuntimeEvent::OnStartupFinished)
+    }
+
+    /// Proxy for update checks and plugin requests (e.g. `ProxyConfig::from_settings`)
+    pub fn set_http_proxy(&self, proxy: Option<ProxyConfig>) {
+        if let Err(e) = self.network_proxy.write().unwrap().set_proxy(proxy.clone()) {
+            println!("[PluginManager] Ignoring invalid proxy for plugin requests: {}", e);
+        }
+        *self.http_proxy.write().unwrap() = proxy;
+    }
+
+    fn require_installed(&self, plugin_id: &str) -> PluginResult<()> {
+        match self.registry.read().unwrap().get_manifest(plugin_id) {
+            Some(_) => Ok(()),
+            None => Err(PluginError::NotFound(plugin_id.to_string())),
+        }
+    }
+
+    /// Remaining request budget of an installed plugin (consumes nothing)
+    pub fn rate_limit_status(&self, plugin_id: &str) -> PluginResult<RateLimitStatus> {
+        self.require_installed(plugin_id)?;
+        Ok(self.network_proxy.read().unwrap().rate_limit_status(plugin_id))
+    }
+
+    /// Allow an installed plugin `requests_per_minute` requests per minute, until the app restarts
+    pub fn set_rate_limit(&self, plugin_id: &str, requests_per_minute: u32) -> PluginResult<()> {
+        self.require_installed(plugin_id)?;
+        if requests_per_minute == 0 {
+            return Err(PluginError::InvalidConfig("Rate limit must allow at least one request per minute".to_string()));
+        }
+        self.network_proxy.read().unwrap().set_rate_limit(plugin_id, requests_per_minute);
+        Ok(())
+    }
+
+    /// Registry index used by `check_plugin_updates` (`None` disables update checks)
+    pub fn set_registry_url(&self, registry_url: Option<String>) {
+        *self.registry_url.write().unwrap() = registry_url;
+    }
+
+    /// Installed plugins with a newer version in the configured registry index.
+    /// Plugins that couldn't be checked are included with an `error`. Nothing is installed.
+    pub fn check_plugin_updates(&self) -> PluginResult<Vec<UpdateInfo>> {
+        let registry_url = self
+            .registry_url
+            .read()
+            .unwrap()
+            .clone()
+            .ok_or_else(|| PluginError::InvalidConfig("No plugin registry URL configured".to_string()))?;
+        let installed: Vec<(PluginId, String)> = self
+            .list_plugins()
+            .into_iter()
+            .map(|metadata| (metadata.id, metadata.version))
+            .collect();
+        let proxy = self.http_proxy.read().unwrap().clone();
+
+        update_check::check_updates(&registry_url, proxy.as_ref(), &installed)
+    }
+
+    /// Get list of all plugins
+    pub fn list_plugins(&self) -> Vec<PluginMetadata> {
+        let registry = self.registry.read().unwrap();
+        registry.list_plugins().into_iter().cloned().collect()
+    }
+
+    /// PLUGIN-079: Get plugin state
+    pub fn get_plugin_state(&self, plugin_id: &str) -> Option<PluginState> {
+        let registry = self.registry.read().unwrap();
+        registry.get_metadata(plugin_id).map(|m| m.state)
+    }
+
+    /// Permission usage of a plugin since `since`, for the plugin activity panel
+    pub fn get_permission_usage(&self, plugin_id: &str, since: chrono::DateTime<Utc>) -> PluginResult<Vec<PermissionUsage>> {
+        let pm = self.permission_manager.read().unwrap();
+        pm.usage_report(plugin_id, since)
+    }
+
+    /// Permissions granted to every newly installed plugin, see `PermissionManager::set_default_grants`
+    pub fn set_default_permission_grants(&self, grants: Vec<(PermissionType, ScopeTemplate)>) -> PluginResult<()> {
+        self.permission_manager.write().unwrap().set_default_grants(grants)
+    }
+
+    /// All granted permissions, see `PermissionManager::export_permissions`
+    pub fn export_permissions(&self) -> serde_json::Value {
+        self.permission_manager.read().unwrap().export_permissions()
+    }
+
+    /// Restore exported permissions for installed plugins, see `PermissionManager::import_permissions`.
+    /// Plugins left without network access by a replacing import lose their rate limiter.
+    pub fn import_permissions(&self, value: serde_json::Value, merge: bool) -> PluginResult<PermissionImport> {
+        let installed: HashSet<PluginId> = self.list_plugins().into_iter().map(|metadata| metadata.id).collect();
+        let mut pm = self.permission_manager.write().unwrap();
+        let result = pm.import_permissions(value, merge, &installed)?;
+
+        let network_prefix = format!("{}:", PermissionType::NetworkRequest.as_str());
+        let network = self.network_proxy.read().unwrap();
+        for permission in result.removed.iter().filter(|p| p.permission_type == PermissionType::NetworkRequest) {
+            let still_granted = pm
+                .granted_permissions(&permission.plugin_id)
+                .iter()
+                .any(|granted| granted.starts_with(&network_prefix));
+            if !still_granted {
+                network.remove_rate_limit(&permission.plugin_id);
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Revoke one type of permission from a plugin. Revoking network access also drops its rate limiter.
+    pub fn revoke_permission(&self, plugin_id: &str, permission_type: &PermissionType) -> PluginResult<()> {
+        self.permission_manager.write().unwrap().revoke_permission(plugin_id, permission_type)?;
+        if *permission_type == PermissionType::NetworkRequest {
+            self.network_proxy.read().unwrap().remove_rate_limit(plugin_id);
+        }
+        Ok(())
+    }
+
+    /// Revoke every permission of a plugin and drop its rate limiter
+    pub fn revoke_all_permissions(&self, plugin_id: &str) -> PluginResult<()> {
+        self.permission_manager.write().unwrap().revoke_all_permissions(plugin_id)?;
+        self.network_proxy.read().unwrap().remove_rate_limit(plugin_id);
+        Ok(())
+    }
+
+    /// PLUGIN-079: Grant permission to plugin
+    pub fn grant_permission(&self, plugin_id: &str, permission: &str) -> PluginResult<()> {
+        let mut pm = self.permission_manager.write().unwrap();
+
+        // Parse permission string (e.g., "filesystem.read:AppData/test/*")
+        let parts: Vec<&str> = permission.split(':').collect();
         if parts.is_empty() {
             return Err(PluginError::PermissionDenied(
                 format!("Invalid permission format: {}", permission)
@@ -438,59 +1649,165 @@ impl PluginManager {
         pm.grant_permission(plugin_id, permission_type, resource_scope)
     }
 
+    /// Installed plugins (sorted by ID) and their dependency edges; read-only
+    pub fn dependency_graph(&self) -> DependencyGraph {
+        let registry = self.registry.read().unwrap();
+
+        let mut nodes: Vec<DependencyNode> = registry.list_plugins()
+            .into_iter()
+            .map(|metadata| DependencyNode {
+                plugin_id: metadata.id.clone(),
+                version: metadata.version.clone(),
+                state: metadata.state,
+            })
+            .collect();
+        nodes.sort_by(|a, b| a.plugin_id.cmp(&b.plugin_id));
+
+        let mut edges: Vec<DependencyEdge> = registry.manifests
+            .iter()
+            .flat_map(|(plugin_id, manifest)| {
+                manifest.dependencies.iter().map(move |(dep_id, version_range)| DependencyEdge {
+                    from: plugin_id.clone(),
+                    to: dep_id.clone(),
+                    version_range: version_range.clone(),
+                })
+            })
+            .collect();
+        edges.sort_by(|a, b| a.from.cmp(&b.from).then_with(|| a.to.cmp(&b.to)));
+
+        let mut missing: Vec<PluginId> = edges
+            .iter()
+            .filter(|edge| registry.get_manifest(&edge.to).is_none())
+            .map(|edge| edge.to.clone())
+            .collect();
+        missing.sort();
+        missing.dedup();
+
+        DependencyGraph {
+            nodes,
+            edges,
+            missing,
+            cycles: find_cycles(&registry),
+        }
+    }
+
+    /// Dependency cycles among installed plugins, each as a path back to its start (e.g. ["a", "b", "a"])
+    pub fn detect_cycles(&self) -> Vec<Vec<PluginId>> {
+        find_cycles(&self.registry.read().unwrap())
+    }
+
     /// PLUGIN-079: Resolve plugin dependencies (topological sort)
     /// Returns plugins in activation order (dependencies first)
     pub fn resolve_plugin_dependencies(&self, plugin_ids: &[String]) -> PluginResult<Vec<PluginId>> {
         let registry = self.registry.read().unwrap();
+        topo_sort(plugin_ids, &registry)
+    }
+}
 
-        let mut sorted = Vec::new();
-        let mut visiting = HashSet::new();
-        let mut visited = HashSet::new();
-
-        fn visit(
-            plugin_id: &str,
-            registry: &PluginRegistry,
-            visiting: &mut HashSet<String>,
-            visited: &mut HashSet<String>,
-            sorted: &mut Vec<String>,
-        ) -> PluginResult<()> {
-            if visited.contains(plugin_id) {
-                return Ok(());
-            }
+/// Depth-first walk state shared by `topo_sort` and `find_cycles`
+#[derive(Default)]
+struct DependencyWalk {
+    /// Plugins on the current path from a root
+    path: Vec<PluginId>,
+    visited: HashSet<PluginId>,
+    /// Visited plugins, dependencies first
+    sorted: Vec<PluginId>,
+    /// Dependencies not in the registry are skipped instead of failing the walk
+    skip_missing: bool,
+}
 
-            if visiting.contains(plugin_id) {
-                return Err(PluginError::DependencyResolution(
-                    format!("Circular dependency detected: {}", plugin_id)
-                ));
-            }
+impl DependencyWalk {
+    /// Visit `plugin_id` and its dependencies (in name order). `on_cycle` receives each cycle's
+    /// full path, e.g. ["a", "b", "a"]; returning an error stops the walk.
+    fn visit(
+        &mut self,
+        plugin_id: &str,
+        registry: &PluginRegistry,
+        on_cycle: &mut dyn FnMut(Vec<PluginId>) -> PluginResult<()>,
+    ) -> PluginResult<()> {
+        if self.visited.contains(plugin_id) {
+            return Ok(());
+        }
 
-            visiting.insert(plugin_id.to_string());
+        if let Some(start) = self.path.iter().position(|id| id == plugin_id) {
+            let mut cycle = self.path[start..].to_vec();
+            cycle.push(plugin_id.to_string());
+            return on_cycle(cycle);
+        }
 
-            // Get manifest to check dependencies
-            if let Some(manifest) = registry.get_manifest(plugin_id) {
-                for (dep_id, _version) in &manifest.dependencies {
-                    visit(dep_id, registry, visiting, visited, sorted)?;
-                }
+        let Some(manifest) = registry.get_manifest(plugin_id) else {
+            if self.skip_missing {
+                return Ok(());
             }
+            return Err(PluginError::NotFound(plugin_id.to_string()));
+        };
 
-            visiting.remove(plugin_id);
-            visited.insert(plugin_id.to_string());
-            sorted.push(plugin_id.to_string());
+        let mut dependencies: Vec<&PluginId> = manifest.dependencies.keys().collect();
+        dependencies.sort();
 
-            Ok(())
+        self.path.push(plugin_id.to_string());
+        for dep_id in dependencies {
+            self.visit(dep_id, registry, on_cycle)?;
         }
+        self.path.pop();
 
-        for plugin_id in plugin_ids {
-            visit(plugin_id, &registry, &mut visiting, &mut visited, &mut sorted)?;
-        }
+        self.visited.insert(plugin_id.to_string());
+        self.sorted.push(plugin_id.to_string());
 
-        Ok(sorted)
+        Ok(())
     }
 }
 
+/// Order `roots` and their transitive dependencies so dependencies come first.
+/// A cycle is reported with its full path, e.g. "a -> b -> c -> a".
+fn topo_sort(roots: &[PluginId], registry: &PluginRegistry) -> PluginResult<Vec<PluginId>> {
+    let mut walk = DependencyWalk::default();
+    let mut fail_on_cycle = |cycle: Vec<PluginId>| Err(PluginError::DependencyResolution(
+        format!("Circular dependency detected: {}", cycle.join(" -> "))
+    ));
+
+    for plugin_id in roots {
+        walk.visit(plugin_id, registry, &mut fail_on_cycle)?;
+    }
+
+    Ok(walk.sorted)
+}
+
+/// Every dependency cycle among registered plugins: one per dependency that closes a loop
+/// during a walk over all plugins in name order. Missing dependencies are ignored.
+fn find_cycles(registry: &PluginRegistry) -> Vec<Vec<PluginId>> {
+    let mut walk = DependencyWalk { skip_missing: true, ..DependencyWalk::default() };
+    let mut cycles = Vec::new();
+    let mut record_cycle = |cycle: Vec<PluginId>| {
+        cycles.push(cycle);
+        Ok(())
+    };
+
+    let mut plugin_ids: Vec<&PluginId> = registry.manifests.keys().collect();
+    plugin_ids.sort();
+    for plugin_id in plugin_ids {
+        // Cycles are recorded rather than returned as errors, so the walk can't fail
+        let _ = walk.visit(plugin_id, registry, &mut record_cycle);
+    }
+
+    cycles
+}
+
+/// Required manifest permissions that were denied in a batch request
+fn denied_required_permissions(manifest: &PluginManifest, decisions: &[(String, bool)]) -> Vec<String> {
+    let required = manifest.required_permissions();
+    decisions
+        .iter()
+        .filter(|(permission, granted)| !granted && required.contains(permission))
+        .map(|(permission, _)| permission.clone())
+        .collect()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::plugin::manifest_parser::PermissionEntry;
+    use crate::plugin::permission_manager::PermissionType;
 
     #[test]
     fn test_plugin_registry() {
@@ -508,6 +1825,9 @@ mod tests {
             state: PluginState::Installed,
             created_at: Utc::now().to_rfc3339(),
             updated_at: Utc::now().to_rfc3339(),
+            content_hash: None,
+            restart_policy: PluginRestartPolicy::default(),
+            icon_path: None,
         };
 
         let manifest = PluginManifest::default();
@@ -532,6 +1852,9 @@ mod tests {
             state: PluginState::Installed,
             created_at: Utc::now().to_rfc3339(),
             updated_at: Utc::now().to_rfc3339(),
+            content_hash: None,
+            restart_policy: PluginRestartPolicy::default(),
+            icon_path: None,
         };
 
         let manifest = PluginManifest::default();
@@ -545,4 +1868,1187 @@ mod tests {
         // Invalid transition (Running → Installed)
         assert!(registry.update_state("test-plugin", PluginState::Installed).is_err());
     }
+
+    struct AppendPreprocessor(&'static str);
+
+    impl MessagePreprocessor for AppendPreprocessor {
+        fn preprocess(&self, mut message: Message) -> PluginResult<Message> {
+            message.content.push_str(self.0);
+            Ok(message)
+        }
+    }
+
+    fn register_preprocessor_plugin(manager: &PluginManager, name: &str, dependencies: &[&str]) {
+        let metadata = PluginMetadata {
+            id: name.to_string(),
+            name: name.to_string(),
+            display_name: name.to_string(),
+            version: "1.0.0".to_string(),
+            description: "A preprocessor plugin".to_string(),
+            author: "Test Author".to_string(),
+            plugin_type: "messagePreprocessor".to_string(),
+            install_path: manager.plugins_dir.join(name),
+            state: PluginState::Installed,
+            created_at: Utc::now().to_rfc3339(),
+            updated_at: Utc::now().to_rfc3339(),
+            content_hash: None,
+            restart_policy: PluginRestartPolicy::default(),
+            icon_path: None,
+        };
+
+        let manifest = PluginManifest {
+            name: name.to_string(),
+            plugin_type: "messagePreprocessor".to_string(),
+            dependencies: dependencies.iter().map(|d| (d.to_string(), "^1.0.0".to_string())).collect(),
+            ..PluginManifest::default()
+        };
+
+        manager.registry.write().unwrap().register(metadata, manifest).unwrap();
+    }
+
+    fn test_message(content: &str) -> Message {
+        Message {
+            id: "msg-1".to_string(),
+            sender: crate::models::MessageSender::User,
+            sender_id: None,
+            sender_name: None,
+            content: content.to_string(),
+            attachments: Vec::new(),
+            timestamp: Utc::now().to_rfc3339(),
+            is_streaming: false,
+            metadata: None,
+        }
+    }
+
+    fn register_plugin_with_permissions(manager: &PluginManager, name: &str, permissions: &[&str]) {
+        let metadata = PluginMetadata {
+            id: name.to_string(),
+            name: name.to_string(),
+            display_name: name.to_string(),
+            version: "1.0.0".to_string(),
+            description: "A test plugin".to_string(),
+            author: "Test Author".to_string(),
+            plugin_type: "synchronous".to_string(),
+            install_path: manager.plugins_dir.join(name),
+            state: PluginState::Installed,
+            created_at: Utc::now().to_rfc3339(),
+            updated_at: Utc::now().to_rfc3339(),
+            content_hash: None,
+            restart_policy: PluginRestartPolicy::default(),
+            icon_path: None,
+        };
+
+        let manifest = PluginManifest {
+            name: name.to_string(),
+            permissions: permissions.iter().map(|p| PermissionEntry::from(*p)).collect(),
+            ..PluginManifest::default()
+        };
+
+        manager.registry.write().unwrap().register(metadata, manifest).unwrap();
+    }
+
+    #[test]
+    fn test_denied_required_permissions() {
+        let manifest = PluginManifest {
+            permissions: vec![PermissionEntry::from("storage.read"), PermissionEntry::from("network.request:*?")],
+            ..PluginManifest::default()
+        };
+
+        let optional_denied = vec![("storage.read".to_string(), true), ("network.request:*".to_string(), false)];
+        assert!(denied_required_permissions(&manifest, &optional_denied).is_empty());
+
+        let required_denied = vec![("storage.read".to_string(), false), ("network.request:*".to_string(), true)];
+        assert_eq!(denied_required_permissions(&manifest, &required_denied), vec!["storage.read"]);
+    }
+
+    #[test]
+    fn test_activation_with_optional_permissions_denied() {
+        let app_data = std::env::temp_dir().join(format!("vcp_optional_permission_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::new(app_data.clone());
+
+        // Deny every network permission
+        manager.permission_manager.write().unwrap().set_authorization_handler(Box::new(|_, permissions| {
+            permissions.iter().map(|p| p.permission_type != PermissionType::NetworkRequest).collect()
+        }));
+
+        register_plugin_with_permissions(&manager, "weather", &["storage.read", "network.request:*?"]);
+        assert!(manager.activate_plugin("weather").is_ok());
+
+        register_plugin_with_permissions(&manager, "sync", &["storage.read", "network.request:*"]);
+        assert!(matches!(manager.activate_plugin("sync"), Err(PluginError::PermissionDenied(_))));
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    /// Write a plugin ZIP with a valid manifest for `name` plus `extra_files`
+    fn write_plugin_zip(dir: &Path, name: &str, extra_files: &[(&str, &[u8])]) -> PathBuf {
+        use std::io::Write;
+
+        let manifest = serde_json::json!({
+            "manifestVersion": "1.0.0",
+            "name": name,
+            "displayName": name,
+            "version": "1.0.0",
+            "description": "A test plugin",
+            "author": "Test Author"
+        });
+
+        std::fs::create_dir_all(dir).unwrap();
+        let zip_path = dir.join(format!("{}.zip", name));
+        let mut zip = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+        let options = zip::write::FileOptions::default();
+
+        zip.start_file("manifest.json", options).unwrap();
+        zip.write_all(manifest.to_string().as_bytes()).unwrap();
+        for (file_name, content) in extra_files {
+            zip.start_file(*file_name, options).unwrap();
+            zip.write_all(content).unwrap();
+        }
+        zip.finish().unwrap();
+
+        zip_path
+    }
+
+    #[test]
+    fn test_install_rejects_paths_outside_package() {
+        let app_data = std::env::temp_dir().join(format!("vcp_zip_slip_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::new(app_data.clone());
+        let escaped = format!("vcp_zip_slip_{}.txt", uuid::Uuid::new_v4());
+        let entry = format!("../{}", escaped);
+        let zip_path = write_plugin_zip(&app_data.join("packages"), "sneaky", &[(entry.as_str(), b"outside")]);
+
+        assert!(matches!(
+            manager.load_plugin_from_zip(&zip_path),
+            Err(PluginError::ZipError(msg)) if msg.contains("Unsafe path")
+        ));
+        assert!(!std::env::temp_dir().join(&escaped).exists());
+        assert!(manager.get_plugin_state("sneaky").is_none());
+        assert!(!manager.plugins_dir().join("sneaky").exists());
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_install_resolves_manifest_icon() {
+        use std::io::Write;
+
+        let app_data = std::env::temp_dir().join(format!("vcp_icon_install_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::new(app_data.clone());
+        let packages = app_data.join("packages");
+        std::fs::create_dir_all(&packages).unwrap();
+
+        let write_zip = |name: &str, files: &[(&str, &[u8])]| {
+            let zip_path = packages.join(format!("{}.zip", name));
+            let mut zip = zip::ZipWriter::new(std::fs::File::create(&zip_path).unwrap());
+            let options = zip::write::FileOptions::default();
+            zip.start_file("manifest.json", options).unwrap();
+            zip.write_all(serde_json::json!({
+                "manifestVersion": "1.0.0",
+                "name": name,
+                "displayName": name,
+                "version": "1.0.0",
+                "description": "A test plugin",
+                "author": "Test Author",
+                "icon": "icon.png"
+            }).to_string().as_bytes()).unwrap();
+            for (file_name, content) in files {
+                zip.start_file(*file_name, options).unwrap();
+                zip.write_all(content).unwrap();
+            }
+            zip.finish().unwrap();
+            zip_path
+        };
+
+        let with_icon = write_zip("weather", &[("icon.png", &[0x89, b'P', b'N', b'G'])]);
+        manager.load_plugin_from_zip(&with_icon).unwrap();
+        assert_eq!(manager.get_plugin_icon("weather").unwrap(), vec![0x89, b'P', b'N', b'G']);
+
+        let without_icon = write_zip("clock", &[("index.js", b"module.exports = {};")]);
+        assert!(matches!(
+            manager.load_plugin_from_zip(&without_icon),
+            Err(PluginError::ManifestValidation(msg)) if msg.contains("Icon not found")
+        ));
+        assert!(manager.get_plugin_state("clock").is_none());
+        assert!(!manager.plugins_dir().join("clock").exists());
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_install_applies_default_grants() {
+        let app_data = std::env::temp_dir().join(format!("vcp_default_grants_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::with_auto_approve(app_data.clone(), false);
+        let settings = GlobalSettings {
+            default_plugin_permissions: vec![
+                "storage.read:plugin-data/{id}/*".to_string(),
+                "storage.write:plugin-data/{id}/*".to_string(),
+                "filesystem.write:AppData/plugin-data/{id}/**".to_string(),
+            ],
+            ..GlobalSettings::default()
+        };
+        manager.apply_settings(&settings);
+
+        for name in ["weather", "clock"] {
+            let zip_path = write_plugin_zip(&app_data.join("packages"), name, &[("index.js", b"module.exports = {};")]);
+            manager.load_plugin_from_zip(&zip_path).unwrap();
+        }
+
+        let permissions = manager.permission_manager.read().unwrap();
+        assert_eq!(permissions.granted_permissions("weather"), vec![
+            "filesystem.write:AppData/plugin-data/weather/**".to_string(),
+            "storage.read:plugin-data/weather/*".to_string(),
+            "storage.write:plugin-data/weather/*".to_string(),
+        ]);
+        assert!(permissions.has_permission("clock", "storage.write:plugin-data/clock/*"));
+        assert!(!permissions.has_permission("clock", "storage.read:plugin-data/weather/*"));
+        assert!(!permissions.has_permission("clock", "filesystem.write:AppData/plugin-data/weather/**"));
+        // Anything else still needs a prompt
+        assert!(!permissions.has_permission("weather", "network.request:*"));
+        drop(permissions);
+
+        // Reinstalling doesn't grant the same permissions twice
+        let zip_path = app_data.join("packages").join("weather.zip");
+        manager.load_plugin_from_zip(&zip_path).unwrap();
+        assert_eq!(manager.export_permissions()["permissions"]["weather"].as_array().unwrap().len(), 3);
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_signature_enforcement_on_install() {
+        let app_data = std::env::temp_dir().join(format!("vcp_signature_install_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::new(app_data.clone());
+        let zip_path = write_plugin_zip(&app_data.join("packages"), "unsigned", &[("index.js", b"module.exports = {};")]);
+
+        manager.set_signature_policy(SignaturePolicy { enforce: true, trusted_keys: Vec::new() });
+        assert!(matches!(
+            manager.load_plugin_from_zip(&zip_path),
+            Err(PluginError::ManifestValidation(msg)) if msg == "signature verification failed"
+        ));
+        assert!(manager.get_plugin_state("unsigned").is_none());
+
+        manager.set_signature_policy(SignaturePolicy::default());
+        assert_eq!(manager.load_plugin_from_zip(&zip_path).unwrap(), "unsigned");
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_signature_enforcement_from_settings() {
+        let app_data = std::env::temp_dir().join(format!("vcp_signature_settings_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::new(app_data.clone());
+        let zip_path = write_plugin_zip(&app_data.join("packages"), "unsigned", &[("index.js", b"module.exports = {};")]);
+
+        let mut settings = GlobalSettings::default();
+        settings.enforce_plugin_signatures = true;
+        manager.apply_settings(&settings);
+        assert!(manager.load_plugin_from_zip(&zip_path).is_err());
+        assert!(manager.get_plugin_state("unsigned").is_none());
+
+        // A malformed trusted key doesn't switch enforcement off
+        settings.trusted_plugin_keys = vec!["not-a-key".to_string()];
+        manager.apply_settings(&settings);
+        assert!(manager.load_plugin_from_zip(&zip_path).is_err());
+
+        settings.enforce_plugin_signatures = false;
+        settings.trusted_plugin_keys.clear();
+        manager.apply_settings(&settings);
+        assert_eq!(manager.load_plugin_from_zip(&zip_path).unwrap(), "unsigned");
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_integrity_check_detects_modified_files() {
+        let app_data = std::env::temp_dir().join(format!("vcp_integrity_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::new(app_data.clone());
+        let zip_path = write_plugin_zip(&app_data.join("packages"), "weather", &[("index.js", b"module.exports = {};")]);
+
+        let plugin_id = manager.load_plugin_from_zip(&zip_path).unwrap();
+        assert!(manager.verify_plugin_integrity(&plugin_id).unwrap());
+
+        let install_path = manager.plugins_dir.join(&plugin_id);
+        std::fs::write(install_path.join("index.js"), "stealCookies();").unwrap();
+        assert!(!manager.verify_plugin_integrity(&plugin_id).unwrap());
+
+        let settings = GlobalSettings { enforce_plugin_integrity: true, ..GlobalSettings::default() };
+        manager.apply_settings(&settings);
+        assert!(matches!(manager.activate_plugin(&plugin_id), Err(PluginError::ActivationError(_))));
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_message_preprocessor_chain() {
+        let app_data = std::env::temp_dir().join(format!("vcp_preprocessor_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::new(app_data.clone());
+
+        // "signature" depends on "translate", so translate must run first
+        register_preprocessor_plugin(&manager, "signature", &["translate"]);
+        register_preprocessor_plugin(&manager, "translate", &[]);
+        manager.register_message_preprocessor("signature", Arc::new(AppendPreprocessor(" -- sent from VCPChat")));
+        manager.register_message_preprocessor("translate", Arc::new(AppendPreprocessor(" [translated]")));
+
+        manager.activate_plugin("signature").unwrap();
+        assert_eq!(manager.run_message_preprocessors(test_message("Hello")).content, "Hello -- sent from VCPChat");
+
+        manager.activate_plugin("translate").unwrap();
+        assert_eq!(
+            manager.run_message_preprocessors(test_message("Hello")).content,
+            "Hello [translated] -- sent from VCPChat"
+        );
+
+        manager.deactivate_plugin("signature").unwrap();
+        assert_eq!(manager.run_message_preprocessors(test_message("Hello")).content, "Hello [translated]");
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_dependency_cycle_reports_full_path() {
+        let app_data = std::env::temp_dir().join(format!("vcp_dependency_cycle_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::new(app_data.clone());
+
+        register_preprocessor_plugin(&manager, "alpha", &["beta"]);
+        register_preprocessor_plugin(&manager, "beta", &["gamma"]);
+        register_preprocessor_plugin(&manager, "gamma", &["alpha"]);
+
+        let single = manager.resolve_dependencies("alpha").unwrap_err();
+        let batch = manager.resolve_plugin_dependencies(&["alpha".to_string()]).unwrap_err();
+
+        for error in [single, batch] {
+            match error {
+                PluginError::DependencyResolution(message) => {
+                    assert!(message.contains("alpha -> beta -> gamma -> alpha"), "{}", message);
+                }
+                other => panic!("unexpected error: {}", other),
+            }
+        }
+
+        // Acyclic graphs resolve dependencies first
+        register_preprocessor_plugin(&manager, "app", &["lib"]);
+        register_preprocessor_plugin(&manager, "lib", &[]);
+        assert_eq!(manager.resolve_dependencies("app").unwrap(), vec!["lib", "app"]);
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    struct FailingProbe;
+
+    impl HealthProbe for FailingProbe {
+        fn ping(&self, _plugin_id: &PluginId, _entry_point: &Path, _timeout: std::time::Duration) -> Result<(), String> {
+            Err("no response to ping".to_string())
+        }
+    }
+
+    #[test]
+    fn test_unhealthy_plugin_is_marked_crashed() {
+        let app_data = std::env::temp_dir().join(format!("vcp_health_check_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::new(app_data.clone());
+        register_plugin_with_permissions(&manager, "weather", &[]);
+        let entry_point = manager.plugins_dir.join("weather").join(PluginManifest::default().main);
+        std::fs::create_dir_all(entry_point.parent().unwrap()).unwrap();
+        std::fs::write(&entry_point, "module.exports = {};").unwrap();
+
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&events);
+        manager.on_state_change(Box::new(move |plugin_id, from, to| {
+            recorded.lock().unwrap().push((plugin_id.to_string(), from, to));
+        }));
+
+        assert_eq!(manager.health_check("weather").unwrap(), HealthStatus::NotRunning);
+
+        manager.activate_plugin("weather").unwrap();
+        assert_eq!(manager.health_check("weather").unwrap(), HealthStatus::Healthy);
+        assert!(events.lock().unwrap().is_empty());
+
+        manager.set_health_probe(Arc::new(FailingProbe));
+        let results = manager.check_running_plugins();
+        assert_eq!(results, vec![("weather".to_string(), HealthStatus::Unhealthy("no response to ping".to_string()))]);
+        assert_eq!(manager.get_plugin_state("weather"), Some(PluginState::Crashed));
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![("weather".to_string(), PluginState::Running, PluginState::Crashed)]
+        );
+
+        // A crashed plugin can be activated again
+        manager.set_health_probe(Arc::new(SidecarProbe));
+        manager.activate_plugin("weather").unwrap();
+        assert_eq!(manager.get_plugin_state("weather"), Some(PluginState::Running));
+
+        // The default probe notices the plugin's files disappearing
+        std::fs::remove_file(&entry_point).unwrap();
+        assert!(matches!(manager.health_check("weather").unwrap(), HealthStatus::Unhealthy(_)));
+        assert_eq!(manager.get_plugin_state("weather"), Some(PluginState::Crashed));
+
+        assert!(manager.health_check("missing").is_err());
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    fn instant_backoff(max_restarts: u32) -> RestartPolicy {
+        RestartPolicy {
+            max_restarts,
+            initial_backoff: std::time::Duration::ZERO,
+            max_backoff: std::time::Duration::ZERO,
+            ..RestartPolicy::default()
+        }
+    }
+
+    fn crash(manager: &PluginManager, plugin_id: &str) {
+        manager.set_health_probe(Arc::new(FailingProbe));
+        assert!(matches!(manager.health_check(plugin_id).unwrap(), HealthStatus::Unhealthy(_)));
+        manager.set_health_probe(Arc::new(SidecarProbe));
+    }
+
+    /// Reports a fixed usage for every plugin
+    struct FixedSampler(u64);
+
+    impl ResourceSampler for FixedSampler {
+        fn sample(&self, plugin_id: &PluginId, pid: Option<u32>) -> Option<ResourceUsage> {
+            Some(ResourceUsage { memory_bytes: Some(self.0), ..ResourceUsage::unknown(plugin_id, pid) })
+        }
+    }
+
+    // Memory caps can only be set where they are enforced
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_plugin_over_resource_limit_is_marked_crashed() {
+        let app_data = std::env::temp_dir().join(format!("vcp_resource_limit_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::new(app_data.clone());
+        register_plugin_with_permissions(&manager, "weather", &[]);
+        register_plugin_with_permissions(&manager, "clock", &[]);
+
+        let events = Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = Arc::clone(&events);
+        manager.on_state_change(Box::new(move |plugin_id, from, to| {
+            recorded.lock().unwrap().push((plugin_id.to_string(), from, to));
+        }));
+
+        let limits = ResourceLimits { max_memory_bytes: Some(1000), max_cpu_time_ms: None };
+        manager.set_resource_limits("weather", limits.clone()).unwrap();
+        assert_eq!(manager.get_resource_limits("weather"), limits);
+        assert!(manager.set_resource_limits("missing", limits).is_err());
+
+        manager.activate_plugin("weather").unwrap();
+        manager.activate_plugin("clock").unwrap();
+        manager.set_resource_sampler(Arc::new(FixedSampler(500)));
+        assert!(manager.enforce_resource_limits().is_empty());
+        assert_eq!(manager.get_plugin_resource_usage("weather").unwrap().memory_bytes, Some(500));
+
+        // Only the plugin with a cap is stopped
+        manager.set_resource_sampler(Arc::new(FixedSampler(2000)));
+        let violations = manager.enforce_resource_limits();
+        assert_eq!(
+            violations,
+            vec![("weather".to_string(), LimitViolation::Memory { used_bytes: 2000, limit_bytes: 1000 })]
+        );
+        assert_eq!(manager.get_plugin_state("weather"), Some(PluginState::Crashed));
+        assert_eq!(manager.get_plugin_state("clock"), Some(PluginState::Running));
+        assert_eq!(
+            *events.lock().unwrap(),
+            vec![("weather".to_string(), PluginState::Running, PluginState::Crashed)]
+        );
+
+        let logged = manager.audit_logger.read().unwrap()
+            .read_audit_logs(None, None)
+            .unwrap()
+            .into_iter()
+            .any(|entry| entry.action == "resource_limit" && entry.plugin_id == "weather" && !entry.result);
+        assert!(logged);
+        // Per-plugin log files are off unless enabled in settings
+        assert!(manager.audit_logger.read().unwrap().read_plugin_audit_logs("weather", None, None).unwrap().is_empty());
+
+        manager.apply_settings(&GlobalSettings { per_plugin_audit_logs: true, ..GlobalSettings::default() });
+        manager.activate_plugin("weather").unwrap();
+        manager.enforce_resource_limits();
+        assert!(!manager.audit_logger.read().unwrap().read_plugin_audit_logs("weather", None, None).unwrap().is_empty());
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_permission_and_lifecycle_entries_share_one_chain() {
+        let app_data = std::env::temp_dir().join(format!("vcp_audit_chain_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::new(app_data.clone());
+
+        for round in 0..3 {
+            manager.permission_manager.write().unwrap()
+                .grant_permission("weather", PermissionType::NetworkRequest, format!("https://api{}.example.com/*", round))
+                .unwrap();
+            manager.audit_logger.write().unwrap().log_lifecycle_event("weather", "restart", "test", true, None);
+        }
+
+        let today = chrono::Utc::now().format("%Y-%m-%d").to_string();
+        let verification = manager.audit_logger.read().unwrap().verify_log_integrity(&today).unwrap();
+        assert!(matches!(verification, crate::plugin::audit_logger::ChainVerification::Intact { entries: 6 }));
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_crashed_plugin_restarts_until_attempt_cap() {
+        let app_data = std::env::temp_dir().join(format!("vcp_crash_restart_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::new(app_data.clone());
+        manager.set_crash_restart_backoff(instant_backoff(2));
+        register_plugin_with_permissions(&manager, "weather", &[]);
+        manager.set_restart_policy("weather", PluginRestartPolicy::OnCrash).unwrap();
+        manager.activate_plugin("weather").unwrap();
+
+        for _ in 0..2 {
+            crash(&manager, "weather");
+            assert_eq!(manager.restart_crashed_plugins(), vec!["weather".to_string()]);
+            assert_eq!(manager.get_plugin_state("weather"), Some(PluginState::Running));
+        }
+
+        // The third crash exceeds the cap of two restarts
+        crash(&manager, "weather");
+        assert!(manager.restart_crashed_plugins().is_empty());
+        assert_eq!(manager.get_plugin_state("weather"), Some(PluginState::Crashed));
+
+        let restarts: Vec<_> = manager.audit_logger.read().unwrap()
+            .read_audit_logs(None, None)
+            .unwrap()
+            .into_iter()
+            .filter(|entry| entry.action == "restart" && entry.plugin_id == "weather")
+            .collect();
+        assert_eq!(restarts.len(), 2);
+        assert!(restarts.iter().all(|entry| entry.result));
+
+        // Reactivating by hand resets the count
+        manager.activate_plugin("weather").unwrap();
+        crash(&manager, "weather");
+        assert_eq!(manager.restart_crashed_plugins(), vec!["weather".to_string()]);
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_restart_policy_never_and_always() {
+        let app_data = std::env::temp_dir().join(format!("vcp_restart_policy_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::new(app_data.clone());
+        manager.set_crash_restart_backoff(instant_backoff(1));
+        register_plugin_with_permissions(&manager, "never", &[]);
+        register_plugin_with_permissions(&manager, "always", &[]);
+        manager.set_restart_policy("always", PluginRestartPolicy::Always).unwrap();
+        manager.activate_plugin("never").unwrap();
+        manager.activate_plugin("always").unwrap();
+
+        for _ in 0..3 {
+            crash(&manager, "always");
+            assert_eq!(manager.restart_crashed_plugins(), vec!["always".to_string()]);
+        }
+
+        crash(&manager, "never");
+        assert!(manager.restart_crashed_plugins().is_empty());
+        assert_eq!(manager.get_plugin_state("never"), Some(PluginState::Crashed));
+
+        // A crashed plugin can still be uninstalled
+        manager.uninstall_plugin("never", true).unwrap();
+        assert!(manager.set_restart_policy("never", PluginRestartPolicy::Always).is_err());
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_preview_uninstall_reports_without_deleting() {
+        let app_data = std::env::temp_dir().join(format!("vcp_uninstall_preview_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::new(app_data.clone());
+        register_plugin_with_permissions(&manager, "weather", &["network.request:api.weather.com"]);
+        {
+            let mut registry = manager.registry.write().unwrap();
+            let manifest = registry.manifests.get_mut("weather").unwrap();
+            manifest.contributes.commands.push(serde_json::from_value(
+                serde_json::json!({ "identifier": "weather.show", "title": "Show Weather" })
+            ).unwrap());
+        }
+        manager.activate_plugin("weather").unwrap();
+
+        let install_path = manager.plugins_dir().join("weather");
+        std::fs::create_dir_all(install_path.join("lib")).unwrap();
+        std::fs::write(install_path.join("index.js"), [b'x'; 100]).unwrap();
+        std::fs::write(install_path.join("lib").join("util.js"), [b'x'; 20]).unwrap();
+
+        let storage = crate::plugin::storage_api::StorageAPI::new(app_data.join("plugin-data"));
+        storage.set("weather", "city", "Berlin").unwrap();
+        storage.set("weather", "units", "metric").unwrap();
+
+        let preview = manager.preview_uninstall("weather").unwrap();
+        assert_eq!(preview.install_size_bytes, 120);
+        assert_eq!(preview.storage_key_count, 2);
+        assert_eq!(preview.granted_permissions, vec!["network.request:api.weather.com".to_string()]);
+        assert_eq!(preview.commands, vec!["weather.show".to_string()]);
+        assert!(preview.views.is_empty());
+
+        // Nothing was removed or changed
+        assert!(install_path.join("index.js").exists());
+        assert_eq!(manager.get_plugin_state("weather"), Some(PluginState::Running));
+        assert!(manager.permission_manager.read().unwrap().has_permission("weather", "network.request:api.weather.com"));
+
+        assert!(matches!(manager.preview_uninstall("missing"), Err(PluginError::NotFound(_))));
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_uninstall_keeps_or_removes_plugin_data() {
+        let app_data = std::env::temp_dir().join(format!("vcp_uninstall_data_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::new(app_data.clone());
+        let storage_dir = app_data.join("plugin-data");
+
+        for plugin_id in ["kept", "wiped"] {
+            register_plugin_with_permissions(&manager, plugin_id, &[]);
+            std::fs::create_dir_all(manager.plugins_dir().join(plugin_id)).unwrap();
+            crate::plugin::storage_api::StorageAPI::new(storage_dir.clone()).set(plugin_id, "city", "Berlin").unwrap();
+        }
+
+        manager.uninstall_plugin("kept", true).unwrap();
+        manager.uninstall_plugin("wiped", false).unwrap();
+        assert!(manager.plugin_locks.lock().unwrap().is_empty());
+
+        assert!(!manager.plugins_dir().join("kept").exists());
+        assert!(!manager.plugins_dir().join("wiped").exists());
+
+        // Reinstalling finds the retained data again
+        let storage = crate::plugin::storage_api::StorageAPI::new(storage_dir.clone());
+        assert_eq!(storage.get("kept", "city").unwrap(), Some("\"Berlin\"".to_string()));
+        assert!(!storage_dir.join("wiped").exists());
+        assert_eq!(storage.get("wiped", "city").unwrap(), None);
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_uninstall_clears_cached_responses() {
+        let app_data = std::env::temp_dir().join(format!("vcp_uninstall_cache_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::new(app_data.clone());
+        let mut server = mockito::Server::new();
+        let _mock = server.mock("GET", "/forecast").with_status(200).with_body("sunny").create();
+        let url = format!("{}/forecast", server.url());
+
+        for plugin_id in ["weather", "clock"] {
+            register_plugin_with_permissions(&manager, plugin_id, &[]);
+            manager.permission_manager.write().unwrap()
+                .grant_permission(plugin_id, PermissionType::NetworkRequest, "*".to_string())
+                .unwrap();
+            manager.network_proxy.read().unwrap().get(plugin_id, &url).unwrap();
+        }
+
+        manager.uninstall_plugin("weather", false).unwrap();
+
+        let proxy = manager.network_proxy.read().unwrap();
+        assert_eq!(proxy.clear_plugin_cache("weather"), 0);
+        assert_eq!(proxy.clear_plugin_cache("clock"), 1);
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_concurrent_operations_on_one_plugin_stay_consistent() {
+        for round in 0..10 {
+            let app_data = std::env::temp_dir().join(format!("vcp_plugin_lock_test_{}", uuid::Uuid::new_v4()));
+            let manager = PluginManager::new(app_data.clone());
+            register_plugin_with_permissions(&manager, "weather", &["network.request:api.weather.com"]);
+            {
+                let mut registry = manager.registry.write().unwrap();
+                let manifest = registry.manifests.get_mut("weather").unwrap();
+                manifest.contributes.commands.push(serde_json::from_value(
+                    serde_json::json!({ "identifier": "weather.show", "title": "Show Weather" })
+                ).unwrap());
+            }
+            std::fs::create_dir_all(manager.plugins_dir().join("weather")).unwrap();
+
+            std::thread::scope(|scope| {
+                for worker in 0..4 {
+                    let manager = &manager;
+                    scope.spawn(move || {
+                        for i in 0..25 {
+                            if worker == 0 && i == round {
+                                let _ = manager.uninstall_plugin("weather", false);
+                            } else if (worker + i) % 2 == 0 {
+                                let _ = manager.activate_plugin("weather");
+                            } else {
+                                let _ = manager.deactivate_plugin("weather");
+                            }
+                        }
+                    });
+                }
+            });
+
+            // Uninstalled for good: nothing left behind by an activation that raced the uninstall
+            assert_eq!(manager.get_plugin_state("weather"), None);
+            assert_eq!(manager.command_owner("weather.show"), None);
+            assert!(!manager.plugins_dir().join("weather").exists());
+            assert!(manager.permission_manager.read().unwrap().granted_permissions("weather").is_empty());
+
+            let _ = std::fs::remove_dir_all(&app_data);
+        }
+    }
+
+    #[test]
+    fn test_dependency_graph_and_cycles() {
+        let app_data = std::env::temp_dir().join(format!("vcp_dependency_graph_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::new(app_data.clone());
+        register_preprocessor_plugin(&manager, "signature", &["translate"]);
+        register_preprocessor_plugin(&manager, "translate", &["dictionary", "spellcheck"]);
+        register_preprocessor_plugin(&manager, "dictionary", &[]);
+
+        let graph = manager.dependency_graph();
+        let node_ids: Vec<&str> = graph.nodes.iter().map(|n| n.plugin_id.as_str()).collect();
+        assert_eq!(node_ids, vec!["dictionary", "signature", "translate"]);
+        let edges: Vec<(&str, &str)> = graph.edges.iter().map(|e| (e.from.as_str(), e.to.as_str())).collect();
+        assert_eq!(edges, vec![("signature", "translate"), ("translate", "dictionary"), ("translate", "spellcheck")]);
+        assert_eq!(graph.edges[0].version_range, "^1.0.0");
+        assert_eq!(graph.missing, vec!["spellcheck".to_string()]);
+        assert!(graph.cycles.is_empty());
+        assert!(manager.detect_cycles().is_empty());
+
+        // dictionary -> signature closes a loop through translate
+        register_preprocessor_plugin(&manager, "spellcheck", &["spellcheck"]);
+        manager.registry.write().unwrap().manifests.get_mut("dictionary").unwrap()
+            .dependencies.insert("signature".to_string(), "^1.0.0".to_string());
+
+        let cycles = manager.detect_cycles();
+        assert_eq!(cycles, vec![
+            vec!["dictionary".to_string(), "signature".to_string(), "translate".to_string(), "dictionary".to_string()],
+            vec!["spellcheck".to_string(), "spellcheck".to_string()],
+        ]);
+        assert_eq!(manager.dependency_graph().cycles, cycles);
+        assert!(manager.dependency_graph().missing.is_empty());
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_activate_plugins_in_dependency_order() {
+        let app_data = std::env::temp_dir().join(format!("vcp_bulk_activation_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::new(app_data.clone());
+        register_preprocessor_plugin(&manager, "signature", &["translate"]);
+        register_preprocessor_plugin(&manager, "translate", &["dictionary"]);
+        register_preprocessor_plugin(&manager, "dictionary", &[]);
+        manager.activate_plugin("dictionary").unwrap();
+
+        let running = manager.activate_plugins(&["signature".to_string()]).unwrap();
+        assert_eq!(running, vec!["dictionary", "translate", "signature"]);
+        for plugin_id in &running {
+            assert_eq!(manager.get_plugin_state(plugin_id), Some(PluginState::Running));
+        }
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_activate_plugins_rolls_back_on_failure() {
+        let app_data = std::env::temp_dir().join(format!("vcp_bulk_rollback_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::with_auto_approve(app_data.clone(), false);
+        register_plugin_with_permissions(&manager, "base", &[]);
+        register_plugin_with_permissions(&manager, "running", &[]);
+        register_plugin_with_permissions(&manager, "needs-storage", &["storage.read"]);
+        manager.registry.write().unwrap().manifests.get_mut("needs-storage").unwrap()
+            .dependencies.insert("base".to_string(), "^1.0.0".to_string());
+        manager.activate_plugin("running").unwrap();
+
+        // "base" activates first, then "needs-storage" is denied its permission
+        let result = manager.activate_plugins(&["running".to_string(), "needs-storage".to_string()]);
+        assert!(matches!(result, Err(PluginError::PermissionDenied(_))));
+
+        assert_eq!(manager.get_plugin_state("base"), Some(PluginState::Deactivated));
+        assert_eq!(manager.get_plugin_state("needs-storage"), Some(PluginState::Installed));
+        // Plugins that were already running aren't part of the rollback
+        assert_eq!(manager.get_plugin_state("running"), Some(PluginState::Running));
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_custom_plugins_dir() {
+        let root = std::env::temp_dir().join(format!("vcp_plugins_dir_test_{}", uuid::Uuid::new_v4()));
+        let app_data = root.join("AppData");
+        let shared_plugins = root.join("shared-plugins");
+        let manager = PluginManager::with_plugins_dir(app_data.clone(), shared_plugins.clone(), false);
+        assert!(!shared_plugins.exists());
+
+        let zip_path = write_plugin_zip(&root.join("packages"), "relocated", &[("index.js", b"module.exports = {};")]);
+        let plugin_id = manager.load_plugin_from_zip(&zip_path).unwrap();
+
+        let installed = manager.list_plugins().into_iter().find(|p| p.id == plugin_id).unwrap();
+        assert_eq!(installed.install_path, shared_plugins.join("relocated"));
+        assert!(shared_plugins.join("relocated").join("index.js").is_file());
+        assert!(!app_data.join("plugins").exists());
+
+        // A manager started later against the same folder picks up the existing install
+        let restarted = PluginManager::with_plugins_dir(app_data.clone(), shared_plugins.clone(), false);
+        assert_eq!(restarted.discover_installed_plugins().unwrap(), vec!["relocated".to_string()]);
+        assert_eq!(restarted.get_plugin_state("relocated"), Some(PluginState::Installed));
+        assert!(restarted.discover_installed_plugins().unwrap().is_empty());
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_discovery_checks_install_time_hash() {
+        let root = std::env::temp_dir().join(format!("vcp_discovery_hash_test_{}", uuid::Uuid::new_v4()));
+        let app_data = root.join("AppData");
+        let manager = PluginManager::new(app_data.clone());
+        let zip_path = write_plugin_zip(&root.join("packages"), "weather", &[("index.js", b"module.exports = {};")]);
+        manager.load_plugin_from_zip(&zip_path).unwrap();
+
+        // Files edited while the app was closed no longer match the hash recorded at install
+        std::fs::write(manager.plugins_dir().join("weather").join("index.js"), b"tampered").unwrap();
+        let restarted = PluginManager::new(app_data.clone());
+        restarted.set_integrity_enforcement(true);
+        assert_eq!(restarted.discover_installed_plugins().unwrap(), vec!["weather".to_string()]);
+        assert!(!restarted.verify_plugin_integrity("weather").unwrap());
+        assert!(restarted.activate_plugin("weather").is_err());
+
+        // A plugin dropped into the folder by hand has to pass the signature policy
+        std::fs::create_dir_all(restarted.plugins_dir().join("sideloaded")).unwrap();
+        std::fs::write(
+            restarted.plugins_dir().join("sideloaded").join("manifest.json"),
+            serde_json::json!({
+                "manifestVersion": "1.0.0",
+                "name": "sideloaded",
+                "displayName": "sideloaded",
+                "version": "1.0.0",
+                "description": "Copied in by hand",
+                "author": "Test Author"
+            }).to_string(),
+        ).unwrap();
+        let enforcing = PluginManager::new(app_data.clone());
+        enforcing.set_signature_policy(SignaturePolicy { enforce: true, trusted_keys: Vec::new() });
+        let discovered = enforcing.discover_installed_plugins().unwrap();
+        assert!(!discovered.contains(&"sideloaded".to_string()));
+
+        // Uninstalling forgets the recorded hash
+        restarted.uninstall_plugin("weather", false).unwrap();
+        let hashes = package_verifier::load_install_hashes(&app_data.join(package_verifier::INSTALL_HASHES_FILE)).unwrap();
+        assert!(!hashes.contains_key("weather"));
+
+        let _ = std::fs::remove_dir_all(&root);
+    }
+
+    #[test]
+    fn test_dispatch_event_activates_matching_plugin() {
+        let app_data = std::env::temp_dir().join(format!("vcp_dispatch_event_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::new(app_data.clone());
+        register_plugin_with_permissions(&manager, "foo", &[]);
+        register_plugin_with_permissions(&manager, "other", &[]);
+        register_plugin_with_permissions(&manager, "helper", &[]);
+        {
+            let mut registry = manager.registry.write().unwrap();
+            let foo = registry.manifests.get_mut("foo").unwrap();
+            foo.activation_events = vec!["onCommand:foo.bar".to_string()];
+            foo.dependencies.insert("helper".to_string(), "^1.0.0".to_string());
+            registry.manifests.get_mut("other").unwrap().activation_events = vec!["onCommand:other.run".to_string()];
+        }
+
+        let activated = manager.dispatch_event(RuntimeEvent::OnCommand("foo.bar".to_string())).unwrap();
+        assert_eq!(activated, vec!["helper".to_string(), "foo".to_string()]);
+        assert_eq!(manager.get_plugin_state("foo"), Some(PluginState::Running));
+        assert_eq!(manager.get_plugin_state("other"), Some(PluginState::Installed));
+
+        // Already running: nothing more to do
+        assert!(manager.dispatch_event(RuntimeEvent::OnCommand("foo.bar".to_string())).unwrap().is_empty());
+        assert!(manager.dispatch_event(RuntimeEvent::OnView("unknown".to_string())).unwrap().is_empty());
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_safe_mode_skips_auto_activation() {
+        let app_data = std::env::temp_dir().join(format!("vcp_safe_mode_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::new(app_data.clone());
+        register_plugin_with_permissions(&manager, "foo", &[]);
+        manager.registry.write().unwrap().manifests.get_mut("foo").unwrap().activation_events =
+            vec!["onStartupFinished".to_string()];
+
+        assert!(!manager.is_safe_mode());
+        manager.set_safe_mode(true);
+        assert!(manager.is_safe_mode());
+
+        assert!(manager.activate_startup_plugins().unwrap().is_empty());
+        assert_eq!(manager.get_plugin_state("foo"), Some(PluginState::Installed));
+
+        // Manual activation is still allowed
+        manager.activate_plugin("foo").unwrap();
+        assert_eq!(manager.get_plugin_state("foo"), Some(PluginState::Running));
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_safe_mode_requested() {
+        let app_data = std::env::temp_dir().join(format!("vcp_safe_mode_marker_test_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&app_data).unwrap();
+        let args = vec!["vcpchat".to_string()];
+
+        assert!(!safe_mode_requested(&args, None, &app_data));
+        assert!(!safe_mode_requested(&args, Some("0"), &app_data));
+        assert!(safe_mode_requested(&args, Some("1"), &app_data));
+        assert!(safe_mode_requested(&["vcpchat".to_string(), "--safe-mode".to_string()], None, &app_data));
+
+        std::fs::write(app_data.join(SAFE_MODE_MARKER), b"").unwrap();
+        assert!(safe_mode_requested(&args, None, &app_data));
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    struct EchoCommand;
+
+    impl CommandHandler for EchoCommand {
+        fn execute(&self, command_id: &str, args: serde_json::Value) -> PluginResult<serde_json::Value> {
+            Ok(serde_json::json!({ "command": command_id, "args": args }))
+        }
+    }
+
+    fn contribute_commands(manager: &PluginManager, plugin_id: &str, command_ids: &[&str]) {
+        let mut registry = manager.registry.write().unwrap();
+        registry.manifests.get_mut(plugin_id).unwrap().contributes.commands = command_ids
+            .iter()
+            .map(|id| crate::plugin::manifest_parser::Command {
+                identifier: id.to_string(),
+                title: id.to_string(),
+                description: None,
+            })
+            .collect();
+    }
+
+    #[test]
+    fn test_command_registry_routing() {
+        let app_data = std::env::temp_dir().join(format!("vcp_command_registry_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::new(app_data.clone());
+        register_plugin_with_permissions(&manager, "weather", &[]);
+        register_plugin_with_permissions(&manager, "clock", &[]);
+        register_plugin_with_permissions(&manager, "copycat", &[]);
+        contribute_commands(&manager, "weather", &["weather.show"]);
+        contribute_commands(&manager, "clock", &["clock.show"]);
+        manager.register_command_handler("weather", Arc::new(EchoCommand));
+
+        // Registered on activation
+        manager.activate_plugin("clock").unwrap();
+        assert_eq!(manager.command_owner("clock.show"), Some("clock".to_string()));
+        assert!(manager.command_owner("weather.show").is_none());
+
+        // Invoking routes to the owner, activating it on demand
+        let result = manager.invoke_command("weather.show", serde_json::json!({ "city": "Paris" })).unwrap();
+        assert_eq!(result["command"], "weather.show");
+        assert_eq!(result["args"]["city"], "Paris");
+        assert_eq!(manager.get_plugin_state("weather"), Some(PluginState::Running));
+        assert_eq!(manager.get_plugin_state("copycat"), Some(PluginState::Installed));
+
+        // A second plugin can't claim the same command
+        contribute_commands(&manager, "copycat", &["weather.show"]);
+        assert!(matches!(manager.activate_plugin("copycat"), Err(PluginError::ActivationError(_))));
+        assert_eq!(manager.get_plugin_state("copycat"), Some(PluginState::Installed));
+
+        // Cleared on deactivation
+        manager.deactivate_plugin("clock").unwrap();
+        assert!(manager.command_owner("clock.show").is_none());
+        assert!(manager.invoke_command("missing.command", serde_json::Value::Null).is_err());
+
+        // With its owner stopped, a command contributed twice is ambiguous
+        manager.deactivate_plugin("weather").unwrap();
+        assert!(matches!(
+            manager.invoke_command("weather.show", serde_json::Value::Null),
+            Err(PluginError::HookError(msg)) if msg.contains("copycat, weather")
+        ));
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_plugin_config_follows_manifest_schema() {
+        let app_data = std::env::temp_dir().join(format!("vcp_plugin_config_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::new(app_data.clone());
+        register_plugin_with_permissions(&manager, "weather", &[]);
+        manager.registry.write().unwrap().manifests.get_mut("weather").unwrap().contributes.configuration = vec![
+            ConfigurationProperty {
+                key: "units".to_string(),
+                value_type: crate::plugin::manifest_parser::ConfigValueType::String,
+                default: Some(serde_json::json!("metric")),
+                description: None,
+                allowed_values: Some(vec![serde_json::json!("metric"), serde_json::json!("imperial")]),
+            },
+        ];
+
+        assert_eq!(manager.get_plugin_config("weather").unwrap()["units"], serde_json::json!("metric"));
+
+        manager.set_plugin_config("weather", "units", Some(serde_json::json!("imperial"))).unwrap();
+        assert_eq!(manager.get_plugin_config("weather").unwrap()["units"], serde_json::json!("imperial"));
+        assert!(manager.set_plugin_config("weather", "units", Some(serde_json::json!("kelvin"))).is_err());
+        assert!(manager.set_plugin_config("weather", "undeclared", Some(serde_json::json!(1))).is_err());
+
+        manager.set_plugin_config("weather", "units", None).unwrap();
+        assert_eq!(manager.get_plugin_config("weather").unwrap()["units"], serde_json::json!("metric"));
+        assert!(matches!(manager.get_plugin_config("missing"), Err(PluginError::NotFound(_))));
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_rate_limit_of_installed_plugins() {
+        let app_data = std::env::temp_dir().join(format!("vcp_rate_limit_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::new(app_data.clone());
+        register_plugin_with_permissions(&manager, "weather", &[]);
+
+        assert_eq!(manager.rate_limit_status("weather").unwrap().capacity, 100.0);
+        manager.set_rate_limit("weather", 5).unwrap();
+        assert_eq!(manager.rate_limit_status("weather").unwrap().capacity, 5.0);
+
+        assert!(matches!(manager.set_rate_limit("weather", 0), Err(PluginError::InvalidConfig(_))));
+        assert!(matches!(manager.rate_limit_status("missing"), Err(PluginError::NotFound(_))));
+        assert!(matches!(manager.set_rate_limit("missing", 5), Err(PluginError::NotFound(_))));
+
+        // Revoking network access, or uninstalling, drops the custom limit
+        let capacity = || manager.network_proxy.read().unwrap().rate_limit_status("weather").capacity;
+        manager.revoke_permission("weather", &PermissionType::NetworkRequest).unwrap();
+        assert_eq!(capacity(), 100.0);
+        let empty_export = manager.export_permissions();
+        manager.permission_manager.write().unwrap()
+            .grant_permission("weather", PermissionType::NetworkRequest, "api.example.com".to_string())
+            .unwrap();
+        manager.set_rate_limit("weather", 5).unwrap();
+        let replaced = manager.import_permissions(empty_export, false).unwrap();
+        assert_eq!(replaced.removed.len(), 1);
+        assert_eq!(capacity(), 100.0);
+        manager.set_rate_limit("weather", 5).unwrap();
+        manager.uninstall_plugin("weather", false).unwrap();
+        assert_eq!(capacity(), 100.0);
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_update_check_uses_configured_registry() {
+        let app_data = std::env::temp_dir().join(format!("vcp_update_check_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::new(app_data.clone());
+        register_plugin_with_permissions(&manager, "weather", &[]);
+        assert!(matches!(manager.check_plugin_updates(), Err(PluginError::InvalidConfig(_))));
+
+        let mut server = mockito::Server::new();
+        let _index = server
+            .mock("GET", "/index.json")
+            .with_status(200)
+            .with_body(r#"{ "plugins": { "weather": { "version": "99.0.0" } } }"#)
+            .create();
+        let settings = GlobalSettings {
+            plugin_registry_url: Some(format!("{}/index.json", server.url())),
+            ..GlobalSettings::default()
+        };
+        manager.apply_settings(&settings);
+        let updates = manager.check_plugin_updates().unwrap();
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].latest_version.as_deref(), Some("99.0.0"));
+
+        // Clearing the setting disables the check again
+        manager.apply_settings(&GlobalSettings { plugin_registry_url: Some(String::new()), ..GlobalSettings::default() });
+        assert!(manager.check_plugin_updates().is_err());
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_views_follow_plugin_activation() {
+        let app_data = std::env::temp_dir().join(format!("vcp_view_registry_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::new(app_data.clone());
+        register_plugin_with_permissions(&manager, "weather", &[]);
+        manager.registry.write().unwrap().manifests.get_mut("weather").unwrap().contributes.views = vec![
+            crate::plugin::manifest_parser::View {
+                identifier: "weather.forecast".to_string(),
+                title: "Forecast".to_string(),
+                description: None,
+                location: ViewLocation::Sidebar,
+            },
+        ];
+
+        assert!(manager.list_views(ViewLocation::Sidebar).is_empty());
+
+        manager.activate_plugin("weather").unwrap();
+        assert_eq!(manager.list_views(ViewLocation::Sidebar), vec![ContributedView {
+            plugin_id: "weather".to_string(),
+            view_identifier: "weather.forecast".to_string(),
+            title: "Forecast".to_string(),
+        }]);
+        assert!(manager.list_views(ViewLocation::Panel).is_empty());
+
+        manager.deactivate_plugin("weather").unwrap();
+        assert!(manager.list_views(ViewLocation::Sidebar).is_empty());
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_event_subscriptions_end_on_deactivate() {
+        let app_data = std::env::temp_dir().join(format!("vcp_event_bus_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::new(app_data.clone());
+        register_plugin_with_permissions(&manager, "dashboard", &[]);
+        manager.activate_plugin("dashboard").unwrap();
+
+        let receiver = manager.event_bus().subscribe("dashboard", "weather.updated");
+        assert_eq!(manager.event_bus().publish("weather", "weather.updated", serde_json::json!(1)).unwrap(), 1);
+        assert_eq!(receiver.try_recv().unwrap().payload, serde_json::json!(1));
+
+        manager.deactivate_plugin("dashboard").unwrap();
+        assert_eq!(manager.event_bus().subscriber_count("weather.updated"), 0);
+        assert_eq!(manager.event_bus().publish("weather", "weather.updated", serde_json::json!(2)).unwrap(), 0);
+        assert!(matches!(receiver.try_recv(), Err(std::sync::mpsc::TryRecvError::Disconnected)));
+        assert_eq!(manager.lifecycle_manager().get_resource_count("dashboard"), 0);
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_crash_releases_contributions_and_subscriptions() {
+        let app_data = std::env::temp_dir().join(format!("vcp_crash_release_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::new(app_data.clone());
+        register_plugin_with_permissions(&manager, "weather", &[]);
+        manager.registry.write().unwrap().manifests.get_mut("weather").unwrap().contributes.views = vec![
+            crate::plugin::manifest_parser::View {
+                identifier: "weather.forecast".to_string(),
+                title: "Forecast".to_string(),
+                description: None,
+                location: ViewLocation::Sidebar,
+            },
+        ];
+        manager.activate_plugin("weather").unwrap();
+        let receiver = manager.event_bus().subscribe("weather", "clock.tick");
+        assert_eq!(manager.list_views(ViewLocation::Sidebar).len(), 1);
+
+        manager.mark_crashed("weather", "test").unwrap();
+        assert!(manager.list_views(ViewLocation::Sidebar).is_empty());
+        // Only a running plugin can crash
+        assert!(matches!(manager.mark_crashed("weather", "again"), Err(PluginError::InvalidStateTransition { .. })));
+        assert_eq!(manager.event_bus().subscriber_count("clock.tick"), 0);
+        assert!(matches!(receiver.try_recv(), Err(std::sync::mpsc::TryRecvError::Disconnected)));
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
+
+    #[test]
+    fn test_keybindings_follow_plugin_activation() {
+        let app_data = std::env::temp_dir().join(format!("vcp_keybinding_test_{}", uuid::Uuid::new_v4()));
+        let manager = PluginManager::new(app_data.clone());
+        for (name, command) in [("weather", "weather.show"), ("clock", "clock.show")] {
+            register_plugin_with_permissions(&manager, name, &[]);
+            manager.registry.write().unwrap().manifests.get_mut(name).unwrap().contributes.keybindings = vec![
+                crate::plugin::manifest_parser::Keybinding {
+                    command: command.to_string(),
+                    key: "Ctrl+Alt+T".to_string(),
+                    when: None,
+                },
+            ];
+        }
+
+        manager.activate_plugin("weather").unwrap();
+        manager.activate_plugin("clock").unwrap();
+        assert_eq!(manager.resolve_key("Ctrl+Alt+T", None), Some("weather.show".to_string()));
+        assert_eq!(manager.keybinding_conflicts().len(), 1);
+
+        manager.deactivate_plugin("weather").unwrap();
+        assert_eq!(manager.resolve_key("Ctrl+Alt+T", None), Some("clock.show".to_string()));
+        manager.deactivate_plugin("clock").unwrap();
+        assert!(manager.list_keybindings().is_empty());
+
+        let _ = std::fs::remove_dir_all(&app_data);
+    }
 }
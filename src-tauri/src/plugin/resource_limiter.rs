@@ -0,0 +1,225 @@
+// Resource caps for plugin processes
+// Caps are applied by the OS at launch where supported (CPU time via RLIMIT_CPU on Unix) and
+// checked against sampled usage (/proc, Linux only). A cap neither mechanism covers on the current
+// platform is rejected when set rather than silently ignored
+
+use super::PluginId;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// Hard caps for a plugin's process (`None` = unlimited)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceLimits {
+    /// Resident memory
+    pub max_memory_bytes: Option<u64>,
+    /// Total CPU time (user + system)
+    pub max_cpu_time_ms: Option<u64>,
+}
+
+/// Current resource usage of a plugin's process (fields are `None` when it can't be measured)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceUsage {
+    pub plugin_id: PluginId,
+    pub pid: Option<u32>,
+    pub memory_bytes: Option<u64>,
+    pub cpu_time_ms: Option<u64>,
+}
+
+impl ResourceUsage {
+    /// Usage of a plugin that has no measurable process
+    pub fn unknown(plugin_id: &str, pid: Option<u32>) -> Self {
+        Self {
+            plugin_id: plugin_id.to_string(),
+            pid,
+            memory_bytes: None,
+            cpu_time_ms: None,
+        }
+    }
+}
+
+/// A cap the plugin went over
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LimitViolation {
+    Memory { used_bytes: u64, limit_bytes: u64 },
+    CpuTime { used_ms: u64, limit_ms: u64 },
+}
+
+impl std::fmt::Display for LimitViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Memory { used_bytes, limit_bytes } => {
+                write!(f, "memory limit exceeded ({} bytes used, limit {})", used_bytes, limit_bytes)
+            }
+            Self::CpuTime { used_ms, limit_ms } => {
+                write!(f, "CPU time limit exceeded ({} ms used, limit {})", used_ms, limit_ms)
+            }
+        }
+    }
+}
+
+/// Memory is only capped by sampling, which needs `/proc`
+const MEMORY_LIMIT_SUPPORTED: bool = cfg!(target_os = "linux");
+/// CPU time is capped by RLIMIT_CPU on any Unix
+const CPU_TIME_LIMIT_SUPPORTED: bool = cfg!(unix);
+
+impl ResourceLimits {
+    /// Fails naming the first cap this platform can't enforce
+    pub fn check_supported(&self) -> Result<(), String> {
+        if self.max_memory_bytes.is_some() && !MEMORY_LIMIT_SUPPORTED {
+            return Err("memory limits are not supported on this platform".to_string());
+        }
+        if self.max_cpu_time_ms.is_some() && !CPU_TIME_LIMIT_SUPPORTED {
+            return Err("CPU time limits are not supported on this platform".to_string());
+        }
+        Ok(())
+    }
+
+    pub fn is_unlimited(&self) -> bool {
+        self.max_memory_bytes.is_none() && self.max_cpu_time_ms.is_none()
+    }
+
+    /// First cap `usage` goes over, if any (unmeasured values never violate)
+    pub fn check(&self, usage: &ResourceUsage) -> Option<LimitViolation> {
+        if let (Some(used_bytes), Some(limit_bytes)) = (usage.memory_bytes, self.max_memory_bytes) {
+            if used_bytes > limit_bytes {
+                return Some(LimitViolation::Memory { used_bytes, limit_bytes });
+            }
+        }
+        if let (Some(used_ms), Some(limit_ms)) = (usage.cpu_time_ms, self.max_cpu_time_ms) {
+            if used_ms > limit_ms {
+                return Some(LimitViolation::CpuTime { used_ms, limit_ms });
+            }
+        }
+        None
+    }
+}
+
+/// Measures a plugin's process (injectable for testing)
+pub trait ResourceSampler: Send + Sync {
+    fn sample(&self, plugin_id: &PluginId, pid: Option<u32>) -> Option<ResourceUsage>;
+}
+
+/// Reads usage of the plugin's process from the OS (`/proc` on Linux; unmeasured elsewhere)
+pub struct ProcSampler;
+
+impl ResourceSampler for ProcSampler {
+    fn sample(&self, plugin_id: &PluginId, pid: Option<u32>) -> Option<ResourceUsage> {
+        let pid = pid?;
+        let proc_dir = Path::new("/proc").join(pid.to_string());
+        if !proc_dir.is_dir() {
+            return None;
+        }
+
+        Some(ResourceUsage {
+            plugin_id: plugin_id.clone(),
+            pid: Some(pid),
+            memory_bytes: read_rss_bytes(&proc_dir),
+            cpu_time_ms: read_cpu_time_ms(&proc_dir),
+        })
+    }
+}
+
+/// `VmRSS` from /proc/<pid>/status
+fn read_rss_bytes(proc_dir: &Path) -> Option<u64> {
+    let status = std::fs::read_to_string(proc_dir.join("status")).ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmRSS:"))?;
+    let kib: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kib * 1024)
+}
+
+/// utime + stime from /proc/<pid>/stat, in milliseconds
+#[cfg(unix)]
+fn read_cpu_time_ms(proc_dir: &Path) -> Option<u64> {
+    let stat = std::fs::read_to_string(proc_dir.join("stat")).ok()?;
+    // Fields after the parenthesized command name; utime and stime are fields 14 and 15
+    let fields: Vec<&str> = stat.rsplit_once(')')?.1.split_whitespace().collect();
+    let ticks: u64 = fields.get(11)?.parse::<u64>().ok()? + fields.get(12)?.parse::<u64>().ok()?;
+
+    let ticks_per_second = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+    if ticks_per_second <= 0 {
+        return None;
+    }
+    Some(ticks * 1000 / ticks_per_second as u64)
+}
+
+#[cfg(not(unix))]
+fn read_cpu_time_ms(_proc_dir: &Path) -> Option<u64> {
+    None
+}
+
+/// Apply the caps the OS can enforce to a command before it is spawned.
+/// On Unix the CPU time cap becomes RLIMIT_CPU (the kernel kills the process when it is used up);
+/// memory is enforced by sampling, since address-space limits break runtimes like node.
+#[cfg(unix)]
+pub fn apply_os_limits(command: &mut std::process::Command, limits: &ResourceLimits) {
+    use std::os::unix::process::CommandExt;
+
+    if let Some(limit_ms) = limits.max_cpu_time_ms {
+        let seconds = limit_ms.div_ceil(1000).max(1) as libc::rlim_t;
+        // Safety: only calls the async-signal-safe setrlimit between fork and exec
+        unsafe {
+            command.pre_exec(move || {
+                let limit = libc::rlimit { rlim_cur: seconds, rlim_max: seconds };
+                if libc::setrlimit(libc::RLIMIT_CPU, &limit) != 0 {
+                    return Err(std::io::Error::last_os_error());
+                }
+                Ok(())
+            });
+        }
+    }
+}
+
+/// No OS-level caps on this platform; limits are enforced by sampling only
+#[cfg(not(unix))]
+pub fn apply_os_limits(_command: &mut std::process::Command, _limits: &ResourceLimits) {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn usage(memory_bytes: Option<u64>, cpu_time_ms: Option<u64>) -> ResourceUsage {
+        ResourceUsage { memory_bytes, cpu_time_ms, ..ResourceUsage::unknown("plugin", Some(1)) }
+    }
+
+    #[test]
+    fn test_limit_checks() {
+        let limits = ResourceLimits { max_memory_bytes: Some(1024), max_cpu_time_ms: Some(500) };
+
+        assert_eq!(limits.check(&usage(Some(1024), Some(500))), None);
+        assert_eq!(
+            limits.check(&usage(Some(2048), Some(100))),
+            Some(LimitViolation::Memory { used_bytes: 2048, limit_bytes: 1024 })
+        );
+        assert_eq!(
+            limits.check(&usage(None, Some(501))),
+            Some(LimitViolation::CpuTime { used_ms: 501, limit_ms: 500 })
+        );
+        assert_eq!(limits.check(&usage(None, None)), None);
+
+        assert!(ResourceLimits::default().is_unlimited());
+        assert_eq!(ResourceLimits::default().check(&usage(Some(u64::MAX), Some(u64::MAX))), None);
+    }
+
+    #[test]
+    fn test_unenforceable_limits_are_rejected() {
+        assert!(ResourceLimits::default().check_supported().is_ok());
+
+        let memory = ResourceLimits { max_memory_bytes: Some(1024), max_cpu_time_ms: None };
+        assert_eq!(memory.check_supported().is_ok(), cfg!(target_os = "linux"));
+
+        let cpu_time = ResourceLimits { max_memory_bytes: None, max_cpu_time_ms: Some(500) };
+        assert_eq!(cpu_time.check_supported().is_ok(), cfg!(unix));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_proc_sampler_reads_own_process() {
+        let usage = ProcSampler.sample(&"self".to_string(), Some(std::process::id())).unwrap();
+        assert!(usage.memory_bytes.unwrap() > 0);
+        assert!(usage.cpu_time_ms.is_some());
+        assert!(ProcSampler.sample(&"none".to_string(), None).is_none());
+    }
+}
@@ -0,0 +1,496 @@
+// Background runner for `service` plugins
+// Launches a service plugin's entry point as a long-lived child process and
+// restarts it with exponential backoff (up to a retry cap) when it exits unexpectedly
+
+use super::{PluginError, PluginId, PluginResult};
+use super::resource_limiter::{apply_os_limits, ResourceLimits};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::Duration;
+
+/// How a service process exited
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ServiceExit {
+    /// Exited with status code 0; the service is not restarted
+    Success,
+    /// Exited with a non-zero code (or was killed by a signal when `None`)
+    Failed(Option<i32>),
+    /// The process could not be spawned or polled
+    Error(String),
+}
+
+/// Current state of a service plugin's background process
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceStatus {
+    pub plugin_id: PluginId,
+    pub running: bool,
+    /// OS process ID of the current process, when known
+    pub pid: Option<u32>,
+    pub restart_count: u32,
+    pub last_exit: Option<ServiceExit>,
+    /// Restarts were exhausted and the service is no longer supervised
+    pub gave_up: bool,
+}
+
+/// Restart policy for crashed services
+#[derive(Debug, Clone)]
+pub struct RestartPolicy {
+    pub max_restarts: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// How often a running process is checked for exit
+    pub poll_interval: Duration,
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        Self {
+            max_restarts: 5,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(30),
+            poll_interval: Duration::from_millis(200),
+        }
+    }
+}
+
+impl RestartPolicy {
+    /// Delay before restart number `attempt` (1-based): doubles each time, capped at `max_backoff`
+    pub fn backoff_for(&self, attempt: u32) -> Duration {
+        let factor = 2u32.saturating_pow(attempt.saturating_sub(1));
+        self.initial_backoff
+            .checked_mul(factor)
+            .unwrap_or(self.max_backoff)
+            .min(self.max_backoff)
+    }
+}
+
+/// A running service process
+pub trait ServiceProcess: Send {
+    /// Check whether the process has exited without blocking
+    fn try_wait(&mut self) -> std::io::Result<Option<ServiceExit>>;
+    /// Terminate the process
+    fn kill(&mut self) -> std::io::Result<()>;
+    /// OS process ID, if the process has one
+    fn id(&self) -> Option<u32> {
+        None
+    }
+}
+
+/// Launches service processes (injectable for testing)
+pub trait ServiceSpawner: Send + Sync {
+    fn spawn(&self, entry_point: &Path, limits: &ResourceLimits) -> std::io::Result<Box<dyn ServiceProcess>>;
+}
+
+impl ServiceProcess for std::process::Child {
+    fn try_wait(&mut self) -> std::io::Result<Option<ServiceExit>> {
+        Ok(std::process::Child::try_wait(self)?.map(|status| {
+            if status.success() {
+                ServiceExit::Success
+            } else {
+                ServiceExit::Failed(status.code())
+            }
+        }))
+    }
+
+    fn kill(&mut self) -> std::io::Result<()> {
+        std::process::Child::kill(self)?;
+        let _ = self.wait();
+        Ok(())
+    }
+
+    fn id(&self) -> Option<u32> {
+        Some(std::process::Child::id(self))
+    }
+}
+
+/// Spawns the entry point as a child process (JavaScript entry points run under `node`),
+/// with whatever resource caps the OS can enforce
+pub struct CommandSpawner;
+
+impl ServiceSpawner for CommandSpawner {
+    fn spawn(&self, entry_point: &Path, limits: &ResourceLimits) -> std::io::Result<Box<dyn ServiceProcess>> {
+        let mut command = match entry_point.extension().and_then(|e| e.to_str()) {
+            Some("js") | Some("mjs") | Some("cjs") => {
+                let mut command = std::process::Command::new("node");
+                command.arg(entry_point);
+                command
+            }
+            _ => std::process::Command::new(entry_point),
+        };
+
+        if let Some(dir) = entry_point.parent() {
+            command.current_dir(dir);
+        }
+        apply_os_limits(&mut command, limits);
+
+        Ok(Box::new(command.spawn()?))
+    }
+}
+
+struct ServiceHandle {
+    status: Arc<Mutex<ServiceStatus>>,
+    stop: Arc<AtomicBool>,
+    thread: Option<JoinHandle<()>>,
+}
+
+/// Supervises the background processes of all running service plugins
+pub struct ServiceRunner {
+    spawner: Arc<dyn ServiceSpawner>,
+    policy: RestartPolicy,
+    services: Mutex<HashMap<PluginId, ServiceHandle>>,
+    /// Resource caps applied when a plugin's service is (re)launched
+    limits: Mutex<HashMap<PluginId, ResourceLimits>>,
+}
+
+impl ServiceRunner {
+    pub fn new(spawner: Arc<dyn ServiceSpawner>, policy: RestartPolicy) -> Self {
+        Self {
+            spawner,
+            policy,
+            services: Mutex::new(HashMap::new()),
+            limits: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Set the resource caps for a plugin's service; takes effect on its next launch
+    pub fn set_limits(&self, plugin_id: &str, limits: ResourceLimits) {
+        let mut all = self.limits.lock().unwrap();
+        if limits.is_unlimited() {
+            all.remove(plugin_id);
+        } else {
+            all.insert(plugin_id.to_string(), limits);
+        }
+    }
+
+    /// Resource caps configured for a plugin (unlimited by default)
+    pub fn limits(&self, plugin_id: &str) -> ResourceLimits {
+        self.limits.lock().unwrap().get(plugin_id).cloned().unwrap_or_default()
+    }
+
+    /// Launch a service and supervise it in a background thread.
+    /// Fails if the service is already running or the first launch fails.
+    pub fn start(&self, plugin_id: &str, entry_point: PathBuf) -> PluginResult<()> {
+        let mut services = self.services.lock().unwrap();
+        if services.get(plugin_id).is_some_and(|h| h.status.lock().unwrap().running) {
+            return Err(PluginError::ActivationError(
+                format!("Service already running: {}", plugin_id)
+            ));
+        }
+
+        let limits = self.limits(plugin_id);
+        let process = self.spawner.spawn(&entry_point, &limits).map_err(|e| PluginError::ActivationError(
+            format!("Failed to start service {}: {}", plugin_id, e)
+        ))?;
+
+        let status = Arc::new(Mutex::new(ServiceStatus {
+            plugin_id: plugin_id.to_string(),
+            running: true,
+            pid: process.id(),
+            restart_count: 0,
+            last_exit: None,
+            gave_up: false,
+        }));
+        let stop = Arc::new(AtomicBool::new(false));
+
+        let thread = {
+            let spawner = Arc::clone(&self.spawner);
+            let policy = self.policy.clone();
+            let status = Arc::clone(&status);
+            let stop = Arc::clone(&stop);
+            std::thread::spawn(move || supervise(process, spawner, entry_point, limits, policy, status, stop))
+        };
+
+        services.insert(plugin_id.to_string(), ServiceHandle {
+            status,
+            stop,
+            thread: Some(thread),
+        });
+
+        Ok(())
+    }
+
+    /// Stop supervising a service and kill its process
+    pub fn stop(&self, plugin_id: &str) -> PluginResult<()> {
+        let handle = self.services.lock().unwrap().remove(plugin_id);
+        let mut handle = handle.ok_or_else(|| PluginError::NotFound(plugin_id.to_string()))?;
+
+        handle.stop.store(true, Ordering::SeqCst);
+        if let Some(thread) = handle.thread.take() {
+            let _ = thread.join();
+        }
+
+        Ok(())
+    }
+
+    /// Current status of a service
+    pub fn status(&self, plugin_id: &str) -> Option<ServiceStatus> {
+        self.services
+            .lock()
+            .unwrap()
+            .get(plugin_id)
+            .map(|handle| handle.status.lock().unwrap().clone())
+    }
+}
+
+impl Drop for ServiceRunner {
+    fn drop(&mut self) {
+        let ids: Vec<PluginId> = self.services.lock().unwrap().keys().cloned().collect();
+        for plugin_id in ids {
+            let _ = self.stop(&plugin_id);
+        }
+    }
+}
+
+/// Sleep for `duration` in `poll_interval` steps; returns true if asked to stop meanwhile
+fn sleep_unless_stopped(duration: Duration, poll_interval: Duration, stop: &AtomicBool) -> bool {
+    let mut remaining = duration;
+    while !remaining.is_zero() {
+        if stop.load(Ordering::SeqCst) {
+            return true;
+        }
+        let step = remaining.min(poll_interval);
+        std::thread::sleep(step);
+        remaining -= step;
+    }
+    stop.load(Ordering::SeqCst)
+}
+
+/// Supervisor loop: wait for the process to exit, then restart it with backoff
+fn supervise(
+    process: Box<dyn ServiceProcess>,
+    spawner: Arc<dyn ServiceSpawner>,
+    entry_point: PathBuf,
+    limits: ResourceLimits,
+    policy: RestartPolicy,
+    status: Arc<Mutex<ServiceStatus>>,
+    stop: Arc<AtomicBool>,
+) {
+    let mut process = Some(process);
+
+    loop {
+        // Wait for the current process (if any) to exit
+        let exit = match process.as_mut() {
+            Some(child) => loop {
+                if stop.load(Ordering::SeqCst) {
+                    let _ = child.kill();
+                    let mut status = status.lock().unwrap();
+                    status.running = false;
+                    status.pid = None;
+                    return;
+                }
+
+                match child.try_wait() {
+                    Ok(Some(exit)) => break exit,
+                    Ok(None) => std::thread::sleep(policy.poll_interval),
+                    Err(e) => break ServiceExit::Error(e.to_string()),
+                }
+            },
+            None => status.lock().unwrap().last_exit.clone().unwrap_or(ServiceExit::Error("Not running".to_string())),
+        };
+
+        let restart_count = {
+            let mut status = status.lock().unwrap();
+            status.running = false;
+            status.pid = None;
+            status.last_exit = Some(exit.clone());
+
+            if exit == ServiceExit::Success {
+                return;
+            }
+            if status.restart_count >= policy.max_restarts {
+                status.gave_up = true;
+                println!("[ServiceRunner] Service {} failed too many times, giving up", status.plugin_id);
+                return;
+            }
+
+            status.restart_count += 1;
+            status.restart_count
+        };
+
+        if sleep_unless_stopped(policy.backoff_for(restart_count), policy.poll_interval, &stop) {
+            return;
+        }
+
+        process = match spawner.spawn(&entry_point, &limits) {
+            Ok(child) => {
+                let mut status = status.lock().unwrap();
+                status.running = true;
+                status.pid = child.id();
+                Some(child)
+            }
+            Err(e) => {
+                status.lock().unwrap().last_exit = Some(ServiceExit::Error(e.to_string()));
+                None
+            }
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU32;
+    use std::time::Instant;
+
+    /// Process that exits with `exit` after `polls_until_exit` polls (never when `None`)
+    struct FakeProcess {
+        polls_until_exit: Option<u32>,
+        exit: ServiceExit,
+        killed: Arc<AtomicBool>,
+    }
+
+    impl ServiceProcess for FakeProcess {
+        fn try_wait(&mut self) -> std::io::Result<Option<ServiceExit>> {
+            match self.polls_until_exit.as_mut() {
+                Some(0) => Ok(Some(self.exit.clone())),
+                Some(remaining) => {
+                    *remaining -= 1;
+                    Ok(None)
+                }
+                None => Ok(None),
+            }
+        }
+
+        fn kill(&mut self) -> std::io::Result<()> {
+            self.killed.store(true, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    struct FakeSpawner {
+        spawns: AtomicU32,
+        last_limits: Mutex<Option<ResourceLimits>>,
+        polls_until_exit: Option<u32>,
+        exit: ServiceExit,
+        killed: Arc<AtomicBool>,
+    }
+
+    impl FakeSpawner {
+        fn new(polls_until_exit: Option<u32>, exit: ServiceExit) -> Arc<Self> {
+            Arc::new(Self {
+                spawns: AtomicU32::new(0),
+                last_limits: Mutex::new(None),
+                polls_until_exit,
+                exit,
+                killed: Arc::new(AtomicBool::new(false)),
+            })
+        }
+    }
+
+    impl ServiceSpawner for FakeSpawner {
+        fn spawn(&self, _entry_point: &Path, limits: &ResourceLimits) -> std::io::Result<Box<dyn ServiceProcess>> {
+            self.spawns.fetch_add(1, Ordering::SeqCst);
+            *self.last_limits.lock().unwrap() = Some(limits.clone());
+            Ok(Box::new(FakeProcess {
+                polls_until_exit: self.polls_until_exit,
+                exit: self.exit.clone(),
+                killed: Arc::clone(&self.killed),
+            }))
+        }
+    }
+
+    fn fast_policy(max_restarts: u32) -> RestartPolicy {
+        RestartPolicy {
+            max_restarts,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(4),
+            poll_interval: Duration::from_millis(1),
+        }
+    }
+
+    fn wait_until(runner: &ServiceRunner, plugin_id: &str, done: impl Fn(&ServiceStatus) -> bool) -> ServiceStatus {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        loop {
+            let status = runner.status(plugin_id).unwrap();
+            if done(&status) || Instant::now() > deadline {
+                return status;
+            }
+            std::thread::sleep(Duration::from_millis(2));
+        }
+    }
+
+    #[test]
+    fn test_backoff_doubles_and_caps() {
+        let policy = RestartPolicy {
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(10),
+            ..RestartPolicy::default()
+        };
+
+        assert_eq!(policy.backoff_for(1), Duration::from_secs(1));
+        assert_eq!(policy.backoff_for(2), Duration::from_secs(2));
+        assert_eq!(policy.backoff_for(4), Duration::from_secs(8));
+        assert_eq!(policy.backoff_for(5), Duration::from_secs(10));
+        assert_eq!(policy.backoff_for(100), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn test_crashing_service_restarts_until_cap() {
+        let spawner = FakeSpawner::new(Some(1), ServiceExit::Failed(Some(1)));
+        let runner = ServiceRunner::new(spawner.clone(), fast_policy(3));
+
+        runner.start("svc", PathBuf::from("index.js")).unwrap();
+        let status = wait_until(&runner, "svc", |s| s.gave_up);
+
+        assert!(status.gave_up);
+        assert!(!status.running);
+        assert_eq!(status.restart_count, 3);
+        assert_eq!(status.last_exit, Some(ServiceExit::Failed(Some(1))));
+        // Initial launch plus three restarts
+        assert_eq!(spawner.spawns.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn test_clean_exit_is_not_restarted() {
+        let spawner = FakeSpawner::new(Some(0), ServiceExit::Success);
+        let runner = ServiceRunner::new(spawner.clone(), fast_policy(3));
+
+        runner.start("svc", PathBuf::from("index.js")).unwrap();
+        let status = wait_until(&runner, "svc", |s| s.last_exit.is_some());
+
+        assert_eq!(status.last_exit, Some(ServiceExit::Success));
+        assert_eq!(status.restart_count, 0);
+        assert_eq!(spawner.spawns.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_stop_kills_running_service() {
+        let spawner = FakeSpawner::new(None, ServiceExit::Success);
+        let runner = ServiceRunner::new(spawner.clone(), fast_policy(3));
+
+        runner.start("svc", PathBuf::from("index.js")).unwrap();
+        assert!(runner.status("svc").unwrap().running);
+        assert!(runner.start("svc", PathBuf::from("index.js")).is_err());
+
+        runner.stop("svc").unwrap();
+        assert!(spawner.killed.load(Ordering::SeqCst));
+        assert!(runner.status("svc").is_none());
+        assert!(runner.stop("svc").is_err());
+    }
+
+    #[test]
+    fn test_limits_are_passed_to_spawner() {
+        let spawner = FakeSpawner::new(None, ServiceExit::Success);
+        let runner = ServiceRunner::new(spawner.clone(), fast_policy(3));
+        let limits = ResourceLimits { max_memory_bytes: Some(64 * 1024 * 1024), max_cpu_time_ms: Some(10_000) };
+
+        runner.set_limits("svc", limits.clone());
+        assert_eq!(runner.limits("svc"), limits);
+        assert!(runner.limits("other").is_unlimited());
+
+        runner.start("svc", PathBuf::from("index.js")).unwrap();
+        assert_eq!(spawner.last_limits.lock().unwrap().clone(), Some(limits));
+        runner.stop("svc").unwrap();
+
+        // Clearing the caps launches unlimited again
+        runner.set_limits("svc", ResourceLimits::default());
+        runner.start("svc", PathBuf::from("index.js")).unwrap();
+        assert_eq!(spawner.last_limits.lock().unwrap().clone(), Some(ResourceLimits::default()));
+    }
+}
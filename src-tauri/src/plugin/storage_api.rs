@@ -5,7 +5,7 @@ use super::{PluginError, PluginResult, PluginId};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, Mutex};
 
 /// Storage value type - stores JSON-serializable data
@@ -24,6 +24,31 @@ struct PluginStorageData {
     data: HashMap<String, StorageValue>,
 }
 
+/// Storage file of a plugin: {storage_dir}/{plugin_id}/storage.json
+fn storage_file(storage_dir: &Path, plugin_id: &str) -> PathBuf {
+    storage_dir.join(plugin_id).join("storage.json")
+}
+
+/// Read a plugin's storage file (empty if it doesn't exist yet)
+fn read_storage_file(path: &Path) -> PluginResult<PluginStorageData> {
+    if path.exists() {
+        let content = fs::read_to_string(path).map_err(|e| {
+            PluginError::PermissionDenied(format!("Failed to read storage: {}", e))
+        })?;
+
+        serde_json::from_str(&content).map_err(|e| {
+            PluginError::PermissionDenied(format!("Failed to parse storage: {}", e))
+        })
+    } else {
+        Ok(PluginStorageData::default())
+    }
+}
+
+/// Number of keys a plugin has persisted under `storage_dir`, read without loading a `StorageAPI`
+pub fn stored_key_count(storage_dir: &Path, plugin_id: &str) -> PluginResult<usize> {
+    Ok(read_storage_file(&storage_file(storage_dir, plugin_id))?.data.len())
+}
+
 /// PLUGIN-055: PluginStorage struct with HashMap per plugin_id
 /// Manages isolated key-value storage for each plugin
 pub struct StorageAPI {
@@ -34,13 +59,8 @@ pub struct StorageAPI {
 }
 
 impl StorageAPI {
-    /// Create new StorageAPI instance
+    /// Create new StorageAPI instance (a plugin's directory is created when it first saves)
     pub fn new(storage_dir: PathBuf) -> Self {
-        // Ensure storage directory exists
-        if !storage_dir.exists() {
-            let _ = fs::create_dir_all(&storage_dir);
-        }
-
         Self {
             storage: Arc::new(Mutex::new(HashMap::new())),
             storage_dir,
@@ -49,26 +69,12 @@ impl StorageAPI {
 
     /// Get storage file path for a plugin
     fn get_storage_path(&self, plugin_id: &str) -> PathBuf {
-        self.storage_dir
-            .join(plugin_id)
-            .join("storage.json")
+        storage_file(&self.storage_dir, plugin_id)
     }
 
     /// Load storage from disk for a plugin
     fn load_storage(&self, plugin_id: &str) -> PluginResult<PluginStorageData> {
-        let path = self.get_storage_path(plugin_id);
-
-        if path.exists() {
-            let content = fs::read_to_string(&path).map_err(|e| {
-                PluginError::PermissionDenied(format!("Failed to read storage: {}", e))
-            })?;
-
-            serde_json::from_str(&content).map_err(|e| {
-                PluginError::PermissionDenied(format!("Failed to parse storage: {}", e))
-            })
-        } else {
-            Ok(PluginStorageData::default())
-        }
+        read_storage_file(&self.get_storage_path(plugin_id))
     }
 
     /// PLUGIN-059: Persist storage to AppData/plugin-data/{plugin_id}/storage.json
@@ -0,0 +1,225 @@
+// Plugin update availability check
+// Compares installed plugin versions against the latest versions advertised by a registry index
+
+use super::{PluginError, PluginId, PluginResult};
+use super::network_proxy::{build_client, ProxyConfig};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Read;
+use std::time::Duration;
+
+/// How long to wait for the registry before reporting it unreachable
+const REGISTRY_TIMEOUT: Duration = Duration::from_secs(15);
+
+/// Largest registry index accepted; bigger responses are reported as an error
+const MAX_INDEX_BYTES: u64 = 4 * 1024 * 1024;
+
+/// Registry index: `{ "plugins": { "<plugin id>": { "version": "1.2.0", ... } } }`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryIndex {
+    #[serde(default)]
+    pub plugins: HashMap<PluginId, RegistryEntry>,
+}
+
+/// Latest release of a plugin as advertised by the registry
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RegistryEntry {
+    pub version: String,
+    #[serde(default)]
+    pub download_url: Option<String>,
+}
+
+/// An installed plugin with a newer version available, or one that couldn't be checked
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UpdateInfo {
+    pub plugin_id: PluginId,
+    pub current_version: String,
+    /// Newer version advertised by the registry (`None` if the check failed)
+    pub latest_version: Option<String>,
+    pub download_url: Option<String>,
+    /// Why this plugin couldn't be checked
+    pub error: Option<String>,
+}
+
+impl UpdateInfo {
+    fn failed(plugin_id: &str, current_version: &str, error: String) -> Self {
+        Self {
+            plugin_id: plugin_id.to_string(),
+            current_version: current_version.to_string(),
+            latest_version: None,
+            download_url: None,
+            error: Some(error),
+        }
+    }
+}
+
+/// Parse a version, tolerating a leading `v` ("v1.2.0")
+fn parse_version(version: &str) -> Result<semver::Version, String> {
+    semver::Version::parse(version.trim().trim_start_matches('v'))
+        .map_err(|e| format!("Invalid version {}: {}", version, e))
+}
+
+/// Download and parse the registry index
+fn fetch_index(registry_url: &str, proxy: Option<&ProxyConfig>) -> Result<RegistryIndex, String> {
+    let client = build_client(proxy, true)
+        .map_err(|e| format!("Failed to create HTTP client: {}", e))?;
+
+    let response = client
+        .get(registry_url)
+        .timeout(REGISTRY_TIMEOUT)
+        .send()
+        .map_err(|e| format!("Registry unreachable: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!("Registry returned HTTP {}", response.status().as_u16()));
+    }
+
+    if response.content_length().is_some_and(|len| len > MAX_INDEX_BYTES) {
+        return Err(format!("Registry index is larger than {} bytes", MAX_INDEX_BYTES));
+    }
+    // The length header may be missing or wrong, so also stop reading past the cap
+    let mut body = Vec::new();
+    response
+        .take(MAX_INDEX_BYTES + 1)
+        .read_to_end(&mut body)
+        .map_err(|e| format!("Failed to read registry index: {}", e))?;
+    if body.len() as u64 > MAX_INDEX_BYTES {
+        return Err(format!("Registry index is larger than {} bytes", MAX_INDEX_BYTES));
+    }
+
+    serde_json::from_slice::<RegistryIndex>(&body)
+        .map_err(|e| format!("Invalid registry index: {}", e))
+}
+
+/// Compare `installed` (plugin id, version) against `index`; plugins the registry
+/// doesn't list, or lists at the same or an older version, are left out
+pub fn find_updates(installed: &[(PluginId, String)], index: &RegistryIndex) -> Vec<UpdateInfo> {
+    let mut updates = Vec::new();
+
+    for (plugin_id, current_version) in installed {
+        let Some(entry) = index.plugins.get(plugin_id) else {
+            continue;
+        };
+
+        let versions = parse_version(current_version).and_then(|current| Ok((current, parse_version(&entry.version)?)));
+        match versions {
+            Ok((current, latest)) if latest > current => updates.push(UpdateInfo {
+                plugin_id: plugin_id.clone(),
+                current_version: current_version.clone(),
+                latest_version: Some(entry.version.clone()),
+                download_url: entry.download_url.clone(),
+                error: None,
+            }),
+            Ok(_) => {}
+            Err(e) => updates.push(UpdateInfo::failed(plugin_id, current_version, e)),
+        }
+    }
+
+    updates.sort_by(|a, b| a.plugin_id.cmp(&b.plugin_id));
+    updates
+}
+
+/// Check `installed` plugins against the index at `registry_url`. Read-only: nothing is downloaded
+/// besides the index. If the registry can't be reached, every plugin is reported with the error.
+pub fn check_updates(
+    registry_url: &str,
+    proxy: Option<&ProxyConfig>,
+    installed: &[(PluginId, String)],
+) -> PluginResult<Vec<UpdateInfo>> {
+    let valid_url = url::Url::parse(registry_url)
+        .map(|url| matches!(url.scheme(), "http" | "https"))
+        .unwrap_or(false);
+    if !valid_url {
+        return Err(PluginError::InvalidConfig(format!("Invalid registry URL: {}", registry_url)));
+    }
+
+    match fetch_index(registry_url, proxy) {
+        Ok(index) => Ok(find_updates(installed, &index)),
+        Err(e) => {
+            let mut failed: Vec<UpdateInfo> = installed
+                .iter()
+                .map(|(plugin_id, version)| UpdateInfo::failed(plugin_id, version, e.clone()))
+                .collect();
+            failed.sort_by(|a, b| a.plugin_id.cmp(&b.plugin_id));
+            Ok(failed)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn installed() -> Vec<(PluginId, String)> {
+        [("weather", "1.2.0"), ("notes", "2.0.0"), ("clock", "1.0.0"), ("legacy", "0.9.0"), ("local-only", "1.0.0")]
+            .iter()
+            .map(|(id, version)| (id.to_string(), version.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_version_comparisons() {
+        let index: RegistryIndex = serde_json::from_value(serde_json::json!({
+            "plugins": {
+                "weather": { "version": "1.10.0", "downloadUrl": "https://plugins.example.com/weather-1.10.0.zip" },
+                "notes": { "version": "2.0.0" },
+                "clock": { "version": "v1.0.1" },
+                "legacy": { "version": "0.9.0-beta.1" }
+            }
+        }))
+        .unwrap();
+
+        let updates = find_updates(&installed(), &index);
+        let ids: Vec<&str> = updates.iter().map(|u| u.plugin_id.as_str()).collect();
+        // 1.10.0 > 1.2.0 numerically; same version and pre-releases of the installed one are not updates
+        assert_eq!(ids, vec!["clock", "weather"]);
+        assert_eq!(updates[1].latest_version.as_deref(), Some("1.10.0"));
+        assert_eq!(updates[1].download_url.as_deref(), Some("https://plugins.example.com/weather-1.10.0.zip"));
+        assert!(updates.iter().all(|u| u.error.is_none()));
+
+        let broken: RegistryIndex = serde_json::from_value(serde_json::json!({
+            "plugins": { "notes": { "version": "latest" }, "weather": { "version": "2.0.0" } }
+        }))
+        .unwrap();
+        let updates = find_updates(&installed(), &broken);
+        assert!(updates[0].plugin_id == "notes" && updates[0].error.as_deref().unwrap().contains("Invalid version latest"));
+        assert_eq!(updates[1].latest_version.as_deref(), Some("2.0.0"));
+    }
+
+    #[test]
+    fn test_check_against_mocked_registry() {
+        let mut server = mockito::Server::new();
+        let mock = server
+            .mock("GET", "/index.json")
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{ "plugins": { "notes": { "version": "2.1.0" }, "clock": { "version": "1.0.0" } } }"#)
+            .create();
+
+        let updates = check_updates(&format!("{}/index.json", server.url()), None, &installed()).unwrap();
+        assert_eq!(updates.len(), 1);
+        assert_eq!(updates[0].plugin_id, "notes");
+        assert_eq!(updates[0].current_version, "2.0.0");
+        assert_eq!(updates[0].latest_version.as_deref(), Some("2.1.0"));
+        mock.assert();
+
+        // A failing registry is reported for every plugin instead of failing the check
+        let _down = server.mock("GET", "/down.json").with_status(503).create();
+        let failed = check_updates(&format!("{}/down.json", server.url()), None, &installed()).unwrap();
+        assert_eq!(failed.len(), installed().len());
+        assert!(failed.iter().all(|u| u.error.as_deref() == Some("Registry returned HTTP 503")));
+
+        // An oversized index is rejected without being parsed
+        let _huge = server
+            .mock("GET", "/huge.json")
+            .with_status(200)
+            .with_body(vec![b' '; MAX_INDEX_BYTES as usize + 1])
+            .create();
+        let failed = check_updates(&format!("{}/huge.json", server.url()), None, &installed()).unwrap();
+        assert!(failed.iter().all(|u| u.error.as_deref().unwrap().contains("larger than")));
+
+        assert!(check_updates("ftp://plugins.example.com/index.json", None, &installed()).is_err());
+    }
+}
@@ -0,0 +1,81 @@
+// Contributed views of running plugins, grouped by where the host UI renders them
+
+use super::PluginId;
+use super::manifest_parser::{View, ViewLocation};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A view contributed by a running plugin
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ContributedView {
+    pub plugin_id: PluginId,
+    pub view_identifier: String,
+    pub title: String,
+}
+
+/// Registered views by location
+#[derive(Debug, Default)]
+pub struct ViewRegistry {
+    views: HashMap<ViewLocation, Vec<ContributedView>>,
+}
+
+impl ViewRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register a plugin's views, replacing any it registered before
+    pub fn register(&mut self, plugin_id: &str, views: &[View]) {
+        self.unregister_plugin(plugin_id);
+
+        for view in views {
+            self.views.entry(view.location).or_default().push(ContributedView {
+                plugin_id: plugin_id.to_string(),
+                view_identifier: view.identifier.clone(),
+                title: view.title.clone(),
+            });
+        }
+    }
+
+    /// Remove every view of a plugin
+    pub fn unregister_plugin(&mut self, plugin_id: &str) {
+        for views in self.views.values_mut() {
+            views.retain(|view| view.plugin_id != plugin_id);
+        }
+    }
+
+    /// Views at `location`, in registration order
+    pub fn list(&self, location: ViewLocation) -> Vec<ContributedView> {
+        self.views.get(&location).cloned().unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn view(identifier: &str, location: ViewLocation) -> View {
+        View {
+            identifier: identifier.to_string(),
+            title: identifier.to_string(),
+            description: None,
+            location,
+        }
+    }
+
+    #[test]
+    fn test_views_by_location() {
+        let mut registry = ViewRegistry::new();
+        registry.register("weather", &[view("weather.sidebar", ViewLocation::Sidebar), view("weather.panel", ViewLocation::Panel)]);
+        registry.register("clock", &[view("clock.sidebar", ViewLocation::Sidebar)]);
+
+        let sidebar: Vec<String> = registry.list(ViewLocation::Sidebar).into_iter().map(|v| v.view_identifier).collect();
+        assert_eq!(sidebar, vec!["weather.sidebar", "clock.sidebar"]);
+        assert_eq!(registry.list(ViewLocation::Panel)[0].plugin_id, "weather");
+        assert!(registry.list(ViewLocation::Editor).is_empty());
+
+        registry.unregister_plugin("weather");
+        assert_eq!(registry.list(ViewLocation::Sidebar).len(), 1);
+        assert!(registry.list(ViewLocation::Panel).is_empty());
+    }
+}
@@ -0,0 +1,551 @@
+// WebSocket proxy for plugins
+// Streaming connections with the same domain whitelist, rate limiting, and audit logging as NetworkProxy
+
+use super::{PluginError, PluginResult, PluginId};
+use super::permission_manager::{PermissionManager, PermissionType};
+use super::audit_logger::AuditLogger;
+use super::network_proxy::{RedactionRules, TokenBucket};
+use std::collections::HashMap;
+use std::io::ErrorKind;
+use std::net::{TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender, TryRecvError};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tungstenite::stream::MaybeTlsStream;
+use tungstenite::{Message as WsMessage, WebSocket};
+
+/// Error returned while the plugin network kill switch is off
+const NETWORK_DISABLED: &str = "plugin network disabled";
+
+/// Message or state change delivered to the plugin
+#[derive(Debug, Clone, PartialEq)]
+pub enum WebSocketEvent {
+    Text(String),
+    Binary(Vec<u8>),
+    /// Connection closed (with the error, if it was not a clean close)
+    Closed(Option<String>),
+}
+
+/// Message queued by the plugin for the connection worker
+enum Outbound {
+    Text(String),
+    Binary(Vec<u8>),
+    Close,
+}
+
+/// Plugin-side handle of an open WebSocket connection.
+/// Dropping the handle closes the connection.
+pub struct WebSocketConnection {
+    pub id: String,
+    pub plugin_id: PluginId,
+    outbound: Sender<Outbound>,
+    /// Messages received from the server
+    pub incoming: Receiver<WebSocketEvent>,
+    rate_limiters: Arc<Mutex<HashMap<PluginId, TokenBucket>>>,
+    messages_per_minute: u32,
+    network_enabled: Arc<AtomicBool>,
+}
+
+impl WebSocketConnection {
+    /// Send a text message (subject to the plugin's outbound rate limit)
+    pub fn send_text(&self, text: &str) -> PluginResult<()> {
+        self.send(Outbound::Text(text.to_string()))
+    }
+
+    /// Send a binary message (subject to the plugin's outbound rate limit)
+    pub fn send_binary(&self, data: Vec<u8>) -> PluginResult<()> {
+        self.send(Outbound::Binary(data))
+    }
+
+    /// Close the connection
+    pub fn close(&self) {
+        let _ = self.outbound.send(Outbound::Close);
+    }
+
+    fn send(&self, message: Outbound) -> PluginResult<()> {
+        if !self.network_enabled.load(Ordering::SeqCst) {
+            return Err(PluginError::PermissionDenied(NETWORK_DISABLED.to_string()));
+        }
+
+        let allowed = {
+            let mut limiters = self.rate_limiters.lock().unwrap();
+            let per_minute = self.messages_per_minute as f64;
+            limiters
+                .entry(self.plugin_id.clone())
+                .or_insert_with(|| TokenBucket::new(per_minute, per_minute / 60.0))
+                .try_consume(1.0)
+        };
+
+        if !allowed {
+            return Err(PluginError::PermissionDenied(format!(
+                "WebSocket rate limit exceeded ({} msg/min)",
+                self.messages_per_minute
+            )));
+        }
+
+        self.outbound.send(message).map_err(|_| {
+            PluginError::PermissionDenied(format!("WebSocket connection {} is closed", self.id))
+        })
+    }
+}
+
+impl Drop for WebSocketConnection {
+    fn drop(&mut self) {
+        self.close();
+    }
+}
+
+/// WebSocketProxy
+/// Opens plugin WebSocket connections after the same permission checks as HTTP requests
+pub struct WebSocketProxy {
+    permission_manager: Arc<Mutex<PermissionManager>>,
+    audit_logger: Arc<Mutex<AuditLogger>>,
+    // Open connection count per plugin
+    open_connections: Arc<Mutex<HashMap<PluginId, usize>>>,
+    // Outbound message rate limiters per plugin
+    rate_limiters: Arc<Mutex<HashMap<PluginId, TokenBucket>>>,
+    // Maximum simultaneous connections per plugin
+    max_connections: usize,
+    // Outbound messages allowed per minute per plugin
+    messages_per_minute: u32,
+    // How long the worker waits for incoming data before checking the outbound queue
+    poll_interval: Duration,
+    // Upper bound on the TCP connect and the WebSocket handshake
+    handshake_timeout: Duration,
+    // Sensitive query parameters masked before URLs are written to the audit log
+    redaction: RedactionRules,
+    // Global kill switch (shared with `NetworkProxy`); when off, nothing connects or sends
+    network_enabled: Arc<AtomicBool>,
+}
+
+impl WebSocketProxy {
+    pub fn new(
+        permission_manager: Arc<Mutex<PermissionManager>>,
+        audit_logger: Arc<Mutex<AuditLogger>>,
+    ) -> Self {
+        Self::with_limits(permission_manager, audit_logger, 5, 100)
+    }
+
+    /// Create a proxy with custom connection and message rate limits
+    pub fn with_limits(
+        permission_manager: Arc<Mutex<PermissionManager>>,
+        audit_logger: Arc<Mutex<AuditLogger>>,
+        max_connections: usize,
+        messages_per_minute: u32,
+    ) -> Self {
+        Self {
+            permission_manager,
+            audit_logger,
+            open_connections: Arc::new(Mutex::new(HashMap::new())),
+            rate_limiters: Arc::new(Mutex::new(HashMap::new())),
+            max_connections,
+            messages_per_minute,
+            poll_interval: Duration::from_millis(50),
+            handshake_timeout: Duration::from_secs(10),
+            redaction: RedactionRules::default(),
+            network_enabled: Arc::new(AtomicBool::new(true)),
+        }
+    }
+
+    /// Follow another proxy's kill switch (see `NetworkProxy::network_switch`)
+    pub fn set_network_switch(&mut self, network_enabled: Arc<AtomicBool>) {
+        self.network_enabled = network_enabled;
+    }
+
+    /// Set how long connecting and the WebSocket handshake may take
+    pub fn set_handshake_timeout(&mut self, timeout: Duration) {
+        self.handshake_timeout = timeout;
+    }
+
+    /// Replace the audit log redaction rules
+    pub fn set_redaction_rules(&mut self, rules: RedactionRules) {
+        self.redaction = rules;
+    }
+
+    /// Number of open connections for a plugin
+    pub fn connection_count(&self, plugin_id: &str) -> usize {
+        let open = self.open_connections.lock().unwrap();
+        open.get(plugin_id).copied().unwrap_or(0)
+    }
+
+    /// Open a WebSocket connection (ws:// or wss://) for a plugin
+    pub fn connect(&self, plugin_id: &str, url: &str) -> PluginResult<WebSocketConnection> {
+        // Only the redacted URL is ever written to the audit log
+        let logged_url = self.redaction.redact_url(url);
+        let redact = |error: &str| error.replace(url, &logged_url);
+
+        if !self.network_enabled.load(Ordering::SeqCst) {
+            log_event(&self.audit_logger, plugin_id, &logged_url, "websocket connect", false, Some(NETWORK_DISABLED));
+            return Err(PluginError::PermissionDenied(NETWORK_DISABLED.to_string()));
+        }
+
+        // Step 1: Validate handshake host against the network whitelist
+        if let Err(e) = self.validate_domain(plugin_id, url) {
+            log_event(&self.audit_logger, plugin_id, &logged_url, "websocket connect", false, Some(&redact(&e.to_string())));
+            return Err(e);
+        }
+
+        // Step 2: Reserve a connection slot
+        {
+            let mut open = self.open_connections.lock().unwrap();
+            let count = open.entry(plugin_id.to_string()).or_insert(0);
+            if *count >= self.max_connections {
+                drop(open);
+                let error = format!("WebSocket connection limit reached ({})", self.max_connections);
+                log_event(&self.audit_logger, plugin_id, &logged_url, "websocket connect", false, Some(&error));
+                return Err(PluginError::PermissionDenied(error));
+            }
+            *count += 1;
+        }
+
+        // Step 3: Handshake
+        let socket = match handshake(url, self.handshake_timeout) {
+            Ok(socket) => socket,
+            Err(e) => {
+                release_slot(&self.open_connections, plugin_id);
+                log_event(&self.audit_logger, plugin_id, &logged_url, "websocket connect", false, Some(&redact(&e)));
+                return Err(PluginError::PermissionDenied(format!("WebSocket connection failed: {}", e)));
+            }
+        };
+
+        if let Err(e) = set_read_timeout(&socket, self.poll_interval) {
+            release_slot(&self.open_connections, plugin_id);
+            return Err(PluginError::IoError(e));
+        }
+
+        log_event(&self.audit_logger, plugin_id, &logged_url, "websocket connect", true, None);
+
+        // Step 4: Hand the socket to a worker thread
+        let (outbound_tx, outbound_rx) = channel();
+        let (incoming_tx, incoming_rx) = channel();
+        let worker = ConnectionWorker {
+            plugin_id: plugin_id.to_string(),
+            url: logged_url,
+            socket,
+            outbound: outbound_rx,
+            incoming: incoming_tx,
+            open_connections: self.open_connections.clone(),
+            audit_logger: self.audit_logger.clone(),
+            network_enabled: self.network_enabled.clone(),
+        };
+        thread::spawn(move || worker.run());
+
+        Ok(WebSocketConnection {
+            id: uuid::Uuid::new_v4().to_string(),
+            plugin_id: plugin_id.to_string(),
+            outbound: outbound_tx,
+            incoming: incoming_rx,
+            rate_limiters: self.rate_limiters.clone(),
+            messages_per_minute: self.messages_per_minute,
+            network_enabled: self.network_enabled.clone(),
+        })
+    }
+
+    /// Validate the handshake host (reuses the HTTP network permission)
+    fn validate_domain(&self, plugin_id: &str, url: &str) -> PluginResult<()> {
+        let parsed_url = url::Url::parse(url).map_err(|e| {
+            PluginError::PermissionDenied(format!("Invalid URL: {}", e))
+        })?;
+
+        if parsed_url.scheme() != "ws" && parsed_url.scheme() != "wss" {
+            return Err(PluginError::PermissionDenied(
+                format!("Unsupported WebSocket scheme: {}", parsed_url.scheme())
+            ));
+        }
+
+        let domain = parsed_url.host_str().ok_or_else(|| {
+            PluginError::PermissionDenied("URL has no host".to_string())
+        })?;
+
+        let pm = self.permission_manager.lock().unwrap();
+        if !pm.validate_network_permission(plugin_id, domain) {
+            return Err(PluginError::PermissionDenied(
+                format!("No network permission for domain: {}", domain)
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+/// Connect and perform the WebSocket handshake, giving up after `timeout`
+fn handshake(url: &str, timeout: Duration) -> Result<WebSocket<MaybeTlsStream<TcpStream>>, String> {
+    let parsed_url = url::Url::parse(url).map_err(|e| e.to_string())?;
+    let host = parsed_url.host_str().ok_or("URL has no host")?;
+    let port = parsed_url.port_or_known_default().ok_or("URL has no port")?;
+
+    let addrs = (host, port).to_socket_addrs().map_err(|e| e.to_string())?;
+    let mut last_error = format!("Could not resolve {}", host);
+    let mut stream = None;
+    for addr in addrs {
+        match TcpStream::connect_timeout(&addr, timeout) {
+            Ok(connected) => {
+                stream = Some(connected);
+                break;
+            }
+            Err(e) => last_error = e.to_string(),
+        }
+    }
+    let stream = stream.ok_or(last_error)?;
+
+    // A server that accepts the connection but never answers must not hang the plugin
+    stream.set_read_timeout(Some(timeout)).map_err(|e| e.to_string())?;
+    stream.set_write_timeout(Some(timeout)).map_err(|e| e.to_string())?;
+
+    let (socket, _response) = tungstenite::client_tls(url, stream).map_err(|e| match e {
+        tungstenite::HandshakeError::Interrupted(_) => "WebSocket handshake timed out".to_string(),
+        tungstenite::HandshakeError::Failure(e) => e.to_string(),
+    })?;
+
+    Ok(socket)
+}
+
+/// Owns the socket: forwards queued outbound messages and incoming frames until closed
+struct ConnectionWorker {
+    plugin_id: PluginId,
+    url: String,
+    socket: WebSocket<MaybeTlsStream<TcpStream>>,
+    outbound: Receiver<Outbound>,
+    incoming: Sender<WebSocketEvent>,
+    open_connections: Arc<Mutex<HashMap<PluginId, usize>>>,
+    audit_logger: Arc<Mutex<AuditLogger>>,
+    network_enabled: Arc<AtomicBool>,
+}
+
+impl ConnectionWorker {
+    fn run(mut self) {
+        let error = self.pump();
+        let _ = self.socket.close(None);
+        let _ = self.socket.flush();
+
+        release_slot(&self.open_connections, &self.plugin_id);
+        log_event(&self.audit_logger, &self.plugin_id, &self.url, "websocket disconnect", error.is_none(), error.as_deref());
+        let _ = self.incoming.send(WebSocketEvent::Closed(error));
+    }
+
+    /// Returns the error that ended the connection, or None for a clean close
+    fn pump(&mut self) -> Option<String> {
+        loop {
+            // Turning the kill switch off also drops open connections
+            if !self.network_enabled.load(Ordering::SeqCst) {
+                return Some(NETWORK_DISABLED.to_string());
+            }
+
+            // Drain messages queued by the plugin
+            loop {
+                let message = match self.outbound.try_recv() {
+                    Ok(Outbound::Text(text)) => WsMessage::Text(text),
+                    Ok(Outbound::Binary(data)) => WsMessage::Binary(data),
+                    // Explicit close, or the plugin dropped its handle
+                    Ok(Outbound::Close) | Err(TryRecvError::Disconnected) => return None,
+                    Err(TryRecvError::Empty) => break,
+                };
+
+                if let Err(e) = self.socket.send(message) {
+                    return Some(e.to_string());
+                }
+            }
+
+            // Wait briefly for incoming data
+            match self.socket.read() {
+                Ok(WsMessage::Text(text)) => {
+                    let _ = self.incoming.send(WebSocketEvent::Text(text));
+                }
+                Ok(WsMessage::Binary(data)) => {
+                    let _ = self.incoming.send(WebSocketEvent::Binary(data));
+                }
+                Ok(WsMessage::Close(_)) => return None,
+                // Ping/pong are answered by tungstenite
+                Ok(_) => {}
+                Err(tungstenite::Error::Io(e))
+                    if e.kind() == ErrorKind::WouldBlock || e.kind() == ErrorKind::TimedOut => {}
+                Err(tungstenite::Error::ConnectionClosed) => return None,
+                Err(e) => return Some(e.to_string()),
+            }
+        }
+    }
+}
+
+/// Make reads return periodically so the worker can service outbound messages
+fn set_read_timeout(socket: &WebSocket<MaybeTlsStream<TcpStream>>, timeout: Duration) -> std::io::Result<()> {
+    let stream = match socket.get_ref() {
+        MaybeTlsStream::Plain(stream) => stream,
+        MaybeTlsStream::NativeTls(stream) => stream.get_ref(),
+        _ => return Ok(()),
+    };
+    stream.set_read_timeout(Some(timeout))?;
+    // Writes keep the handshake timeout; lift it so slow servers only stall the worker thread
+    stream.set_write_timeout(None)
+}
+
+fn release_slot(open_connections: &Mutex<HashMap<PluginId, usize>>, plugin_id: &str) {
+    let mut open = open_connections.lock().unwrap();
+    if let Some(count) = open.get_mut(plugin_id) {
+        *count = count.saturating_sub(1);
+        if *count == 0 {
+            open.remove(plugin_id);
+        }
+    }
+}
+
+/// Log connect/disconnect events to the audit logger
+fn log_event(audit_logger: &Mutex<AuditLogger>, plugin_id: &str, url: &str, action: &str, success: bool, error: Option<&str>) {
+    let mut logger = audit_logger.lock().unwrap();
+    logger.log_permission_check(
+        plugin_id,
+        &PermissionType::NetworkRequest,
+        url,
+        action,
+        success,
+        error,
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::net::TcpListener;
+
+    fn create_test_proxy(max_connections: usize, messages_per_minute: u32) -> WebSocketProxy {
+        let temp_dir = std::env::temp_dir().join(format!("vcp_ws_test_{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&temp_dir).unwrap();
+
+        let mut pm = PermissionManager::new(temp_dir.clone());
+        pm.grant_permission("test-plugin", PermissionType::NetworkRequest, "*".to_string()).unwrap();
+        let logger = Arc::new(Mutex::new(AuditLogger::new(temp_dir)));
+
+        WebSocketProxy::with_limits(Arc::new(Mutex::new(pm)), logger, max_connections, messages_per_minute)
+    }
+
+    /// Echo server accepting `connections` clients; returns its ws:// URL
+    fn spawn_echo_server(connections: usize) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        thread::spawn(move || {
+            for stream in listener.incoming().take(connections) {
+                let mut socket = tungstenite::accept(stream.unwrap()).unwrap();
+                thread::spawn(move || {
+                    while let Ok(message) = socket.read() {
+                        if message.is_close() {
+                            break;
+                        }
+                        if socket.send(message).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+        });
+
+        url
+    }
+
+    fn recv(connection: &WebSocketConnection) -> WebSocketEvent {
+        connection.incoming.recv_timeout(Duration::from_secs(5)).unwrap()
+    }
+
+    #[test]
+    fn test_send_and_receive() {
+        let proxy = create_test_proxy(5, 100);
+        let url = spawn_echo_server(1);
+
+        let connection = proxy.connect("test-plugin", &url).unwrap();
+        assert_eq!(proxy.connection_count("test-plugin"), 1);
+
+        connection.send_text("hello").unwrap();
+        assert_eq!(recv(&connection), WebSocketEvent::Text("hello".to_string()));
+
+        connection.close();
+        assert_eq!(recv(&connection), WebSocketEvent::Closed(None));
+        assert_eq!(proxy.connection_count("test-plugin"), 0);
+    }
+
+    #[test]
+    fn test_domain_must_be_whitelisted() {
+        let proxy = create_test_proxy(5, 100);
+        let url = spawn_echo_server(1);
+
+        assert!(matches!(proxy.connect("other-plugin", &url), Err(PluginError::PermissionDenied(_))));
+        assert!(proxy.connect("test-plugin", "https://example.com/socket").is_err());
+    }
+
+    #[test]
+    fn test_connection_limit() {
+        let proxy = create_test_proxy(1, 100);
+        let url = spawn_echo_server(2);
+
+        let first = proxy.connect("test-plugin", &url).unwrap();
+        assert!(proxy.connect("test-plugin", &url).is_err());
+
+        // Closing frees the slot
+        first.close();
+        assert_eq!(recv(&first), WebSocketEvent::Closed(None));
+        assert!(proxy.connect("test-plugin", &url).is_ok());
+    }
+
+    #[test]
+    fn test_handshake_times_out() {
+        let mut proxy = create_test_proxy(5, 100);
+        proxy.set_handshake_timeout(Duration::from_millis(200));
+
+        // Accepts TCP connections but never answers the handshake
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("ws://{}", listener.local_addr().unwrap());
+
+        let started = std::time::Instant::now();
+        assert!(proxy.connect("test-plugin", &url).is_err());
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(proxy.connection_count("test-plugin"), 0);
+        drop(listener);
+    }
+
+    #[test]
+    fn test_audit_log_redacts_query_secrets() {
+        let proxy = create_test_proxy(5, 100);
+        let url = format!("{}/?token=secret-token&room=lobby", spawn_echo_server(1));
+
+        let connection = proxy.connect("test-plugin", &url).unwrap();
+        connection.close();
+        assert_eq!(recv(&connection), WebSocketEvent::Closed(None));
+
+        let entries = proxy.audit_logger.lock().unwrap().read_audit_logs(None, None).unwrap();
+        assert!(!entries.is_empty());
+        assert!(entries.iter().all(|e| !e.resource.contains("secret-token")));
+        assert!(entries.iter().any(|e| e.resource.contains("room=lobby")));
+    }
+
+    #[test]
+    fn test_network_kill_switch() {
+        let mut proxy = create_test_proxy(5, 100);
+        let network_enabled = Arc::new(AtomicBool::new(true));
+        proxy.set_network_switch(Arc::clone(&network_enabled));
+        let url = spawn_echo_server(2);
+
+        let connection = proxy.connect("test-plugin", &url).unwrap();
+        network_enabled.store(false, Ordering::SeqCst);
+
+        assert!(matches!(
+            connection.send_text("hello"),
+            Err(PluginError::PermissionDenied(msg)) if msg == NETWORK_DISABLED
+        ));
+        assert_eq!(recv(&connection), WebSocketEvent::Closed(Some(NETWORK_DISABLED.to_string())));
+        assert!(proxy.connect("test-plugin", &url).is_err());
+
+        network_enabled.store(true, Ordering::SeqCst);
+        assert!(proxy.connect("test-plugin", &url).is_ok());
+    }
+
+    #[test]
+    fn test_outbound_rate_limit() {
+        let proxy = create_test_proxy(5, 2);
+        let url = spawn_echo_server(1);
+
+        let connection = proxy.connect("test-plugin", &url).unwrap();
+        assert!(connection.send_text("one").is_ok());
+        assert!(connection.send_text("two").is_ok());
+        assert!(connection.send_text("three").is_err());
+    }
+}
@@ -10,7 +10,9 @@
  */
 
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import { t } from '../core/i18n/i18nHelper';
+import { cancelMigration } from '../core/ipc/commands';
 
 interface MigrationStatus {
   is_migrated: boolean;
@@ -18,6 +20,24 @@ interface MigrationStatus {
   tauri_path: string;
   backup_path: string | null;
   migration_date: string | null;
+  migrated_categories: string[] | null;
+}
+
+interface MigrationProgress {
+  total_files: number;
+  copied_files: number;
+  current_file: string;
+  status: string;
+}
+
+interface MigrationWarning {
+  file_path: string;
+  reason: string;
+}
+
+interface MigrationResult {
+  message: string;
+  warnings: MigrationWarning[];
 }
 
 type WizardStep = 'check' | 'confirm' | 'progress' | 'complete' | 'error';
@@ -358,7 +378,13 @@ export class MigrationWizard {
         `;
 
       case 'progress':
-        return '';
+        return `
+          <div class="wizard-actions">
+            <button type="button" class="btn-secondary" data-action="cancel-migration">
+              ${t('common.cancel')}
+            </button>
+          </div>
+        `;
 
       case 'complete':
         return `
@@ -408,6 +434,18 @@ export class MigrationWizard {
     const migrateBtn = this.container.querySelector('[data-action="migrate"]');
     migrateBtn?.addEventListener('click', () => this.startMigration());
 
+    // Cancel button (progress → error, with the backend's cancellation message)
+    const cancelMigrationBtn = this.container.querySelector<HTMLButtonElement>('[data-action="cancel-migration"]');
+    cancelMigrationBtn?.addEventListener('click', async () => {
+      cancelMigrationBtn.disabled = true;
+      try {
+        await cancelMigration();
+      } catch (error) {
+        console.error('Failed to cancel migration:', error);
+        cancelMigrationBtn.disabled = false;
+      }
+    });
+
     // Restart button
     const restartBtn = this.container.querySelector('[data-action="restart"]');
     restartBtn?.addEventListener('click', () => this.restartApp());
@@ -432,11 +470,18 @@ export class MigrationWizard {
     this.currentStep = 'progress';
     this.render();
 
+    const unlisten = await listen<MigrationProgress>('migration://progress', (event) => {
+      this.updateProgress(event.payload);
+    });
+
     try {
       // Execute migration
-      const result = await invoke<string>('migrate_from_electron');
+      const result = await invoke<MigrationResult>('migrate_from_electron');
 
-      console.log('Migration result:', result);
+      console.log('Migration result:', result.message);
+      for (const warning of result.warnings) {
+        console.warn(`Migration warning: ${warning.file_path}: ${warning.reason}`);
+      }
 
       this.currentStep = 'complete';
       this.render();
@@ -445,9 +490,28 @@ export class MigrationWizard {
       this.errorMessage = (error as Error).message;
       this.currentStep = 'error';
       this.render();
+    } finally {
+      unlisten();
     }
   }
 
+  /**
+   * Update the progress bar from a migration progress event
+   */
+  private updateProgress(progress: MigrationProgress): void {
+    const percent = progress.total_files > 0
+      ? Math.min(100, Math.round((progress.copied_files / progress.total_files) * 100))
+      : 0;
+
+    const bar = this.container.querySelector<HTMLElement>('[data-progress-bar]');
+    const text = this.container.querySelector<HTMLElement>('[data-progress-text]');
+    const log = this.container.querySelector<HTMLElement>('[data-progress-log]');
+
+    if (bar) bar.style.width = `${percent}%`;
+    if (text) text.textContent = `${percent}%`;
+    if (log) log.textContent = `${progress.status}: ${progress.current_file}`;
+  }
+
   /**
    * Restart application
    */
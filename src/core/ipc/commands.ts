@@ -1,6 +1,22 @@
 // TypeScript wrappers for Tauri IPC commands
 import { invoke } from '@tauri-apps/api/core';
-import type { Agent, Group, Topic, GlobalSettings, Attachment } from '@core/models';
+import type { Agent, Group, Topic, Message, MessageMetadata, GlobalSettings, Attachment } from '@core/models';
+
+/**
+ * Error the conversation, agent, group and canvas commands reject with. `code` is stable:
+ * 'NOT_FOUND', 'VALIDATION', 'IO_ERROR', 'CONFLICT', 'INVALID_STATE', 'PERMISSION_DENIED', or
+ * 'ERROR' for errors that don't have a specific code yet.
+ */
+export interface CommandError {
+  code: string;
+  message: string;
+  details?: unknown;
+}
+
+/** Whether a command rejected because the stored copy changed since it was read */
+export function isConflictError(error: unknown): boolean {
+  return typeof error === 'object' && error !== null && (error as CommandError).code === 'CONFLICT';
+}
 
 /**
  * Conversation (Topic) Commands
@@ -10,18 +26,145 @@ export async function readConversation(topicId: string): Promise<Topic> {
   return await invoke<Topic>('read_conversation', { topicId });
 }
 
-export async function writeConversation(topic: Topic): Promise<void> {
-  await invoke('write_conversation', { topic });
+/**
+ * Pass the `updated_at` the topic had when it was read to reject overwriting a newer
+ * copy (rejects with code 'CONFLICT'); `force` overwrites regardless.
+ */
+export async function writeConversation(
+  topic: Topic,
+  expectedUpdatedAt?: string,
+  force?: boolean
+): Promise<void> {
+  await invoke('write_conversation', { topic, expectedUpdatedAt, force });
+}
+
+export async function appendMessage(
+  topicId: string,
+  ownerType: 'agent' | 'group',
+  message: Message
+): Promise<void> {
+  await invoke('append_message', { topicId, ownerType, message });
+}
+
+/**
+ * Append a chunk of a streaming reply to a message appended with `is_streaming: true`.
+ * Only the chunk is written, not the whole topic.
+ */
+export async function appendToStreamingMessage(
+  topicId: string,
+  ownerType: 'agent' | 'group',
+  messageId: string,
+  delta: string
+): Promise<void> {
+  await invoke('append_to_streaming_message', { topicId, ownerType, messageId, delta });
+}
+
+/** Mark a streaming message complete; resolves to the final message */
+export async function finalizeStreamingMessage(
+  topicId: string,
+  messageId: string,
+  metadata?: MessageMetadata
+): Promise<Message> {
+  return await invoke<Message>('finalize_streaming_message', { topicId, messageId, metadata });
+}
+
+/** Replace a message's content; with keepHistory the old content is kept in its edit history */
+export async function editMessage(
+  topicId: string,
+  ownerType: 'agent' | 'group',
+  messageId: string,
+  newContent: string,
+  keepHistory?: boolean
+): Promise<Message> {
+  return await invoke<Message>('edit_message', { topicId, ownerType, messageId, newContent, keepHistory });
+}
+
+/** Remove a single message from a topic */
+export async function deleteMessage(topicId: string, ownerType: 'agent' | 'group', messageId: string): Promise<void> {
+  await invoke('delete_message', { topicId, ownerType, messageId });
+}
+
+/** Fork a conversation at a message; resolves to the new topic's id */
+export async function forkConversation(
+  topicId: string,
+  ownerType: 'agent' | 'group',
+  atMessageId: string,
+  newTitle: string
+): Promise<string> {
+  return await invoke<string>('fork_conversation', { topicId, ownerType, atMessageId, newTitle });
+}
+
+export interface ConversationImport {
+  topic_id: string;
+  warnings: string[];
+}
+
+export async function importConversation(
+  json: unknown,
+  targetOwnerId: string,
+  targetOwnerType: 'agent' | 'group'
+): Promise<ConversationImport> {
+  return await invoke<ConversationImport>('import_conversation', { json, targetOwnerId, targetOwnerType });
 }
 
 export async function deleteConversation(topicId: string, ownerType: 'agent' | 'group'): Promise<void> {
   await invoke('delete_conversation', { topicId, ownerType });
 }
 
+export interface TopicFilter {
+  /** Topics last updated before this RFC 3339 timestamp */
+  older_than?: string;
+  /** Topics without any messages */
+  empty?: boolean;
+  /** Topics whose title contains this text (case-insensitive) */
+  title_contains?: string;
+  /** Also select pinned topics, which are otherwise always kept */
+  include_pinned?: boolean;
+}
+
+export interface TopicBulkDelete {
+  /** Topics moved to the trash (or, for a dry run, that would be) */
+  topic_ids: string[];
+  count: number;
+  dry_run: boolean;
+}
+
+/** Move an owner's topics matching the filter to the trash; `dryRun` only reports what would be deleted */
+export async function deleteTopicsMatching(
+  ownerId: string,
+  ownerType: 'agent' | 'group',
+  filter: TopicFilter,
+  dryRun?: boolean
+): Promise<TopicBulkDelete> {
+  return await invoke<TopicBulkDelete>('delete_topics_matching', { ownerId, ownerType, filter, dryRun });
+}
+
 export async function listTopics(ownerId: string, ownerType: 'agent' | 'group'): Promise<Topic[]> {
   return await invoke<Topic[]>('list_topics', { ownerId, ownerType });
 }
 
+export interface TopicStats {
+  message_count: number;
+  user_messages: number;
+  agent_messages: number;
+  total_characters: number;
+  messages_with_attachments: number;
+  tool_calls: number;
+  time_span_secs: number | null;
+}
+
+export async function getTopicStats(topicId: string): Promise<TopicStats> {
+  return await invoke<TopicStats>('get_topic_stats', { topicId });
+}
+
+export async function setTopicPinned(
+  topicId: string,
+  ownerType: 'agent' | 'group',
+  pinned: boolean
+): Promise<void> {
+  await invoke('set_topic_pinned', { topicId, ownerType, pinned });
+}
+
 /**
  * Agent Commands
  */
@@ -30,8 +173,17 @@ export async function readAgent(agentId: string): Promise<Agent> {
   return await invoke<Agent>('read_agent', { agentId });
 }
 
-export async function writeAgent(agent: Agent): Promise<void> {
-  await invoke('write_agent', { agent });
+/**
+ * Pass the `updated_at` the agent had when it was read to reject overwriting a newer
+ * copy (rejects with code 'CONFLICT'); `force` overwrites regardless. Resolves to the
+ * stored agent, whose `updated_at` is stamped by the backend.
+ */
+export async function writeAgent(
+  agent: Agent,
+  expectedUpdatedAt?: string,
+  force?: boolean
+): Promise<Agent> {
+  return await invoke<Agent>('write_agent', { agent, expectedUpdatedAt, force });
 }
 
 export async function deleteAgent(agentId: string): Promise<void> {
@@ -42,6 +194,14 @@ export async function listAgents(): Promise<Agent[]> {
   return await invoke<Agent[]>('list_agents');
 }
 
+export async function listAgentsByTag(tag: string): Promise<Agent[]> {
+  return await invoke<Agent[]>('list_agents_by_tag', { tag });
+}
+
+export async function listAllTags(): Promise<string[]> {
+  return await invoke<string[]>('list_all_tags');
+}
+
 /**
  * Group Commands
  */
@@ -50,8 +210,17 @@ export async function readGroup(groupId: string): Promise<Group> {
   return await invoke<Group>('read_group', { groupId });
 }
 
-export async function writeGroup(group: Group): Promise<void> {
-  await invoke('write_group', { group });
+/**
+ * Pass the `updated_at` the group had when it was read to reject overwriting a newer
+ * copy (rejects with code 'CONFLICT'); `force` overwrites regardless. Resolves to the
+ * stored group, whose `updated_at` is stamped by the backend.
+ */
+export async function writeGroup(
+  group: Group,
+  expectedUpdatedAt?: string,
+  force?: boolean
+): Promise<Group> {
+  return await invoke<Group>('write_group', { group, expectedUpdatedAt, force });
 }
 
 export async function deleteGroup(groupId: string): Promise<void> {
@@ -62,6 +231,68 @@ export async function listGroups(): Promise<Group[]> {
   return await invoke<Group[]>('list_groups');
 }
 
+/**
+ * Recent Activity Commands
+ */
+
+export interface ActivityItem {
+  kind: 'topic' | 'agent' | 'group';
+  id: string;
+  /** Topic title, or agent/group name */
+  title: string;
+  /** Owning agent or group of a topic */
+  owner_id: string | null;
+  /** Topic updated_at, or agent/group created_at */
+  timestamp: string;
+}
+
+/** Recently updated topics and recently created agents/groups, newest first */
+export async function getRecentActivity(limit: number): Promise<ActivityItem[]> {
+  return await invoke<ActivityItem[]>('get_recent_activity', { limit });
+}
+
+/**
+ * Search Commands
+ */
+
+export interface SearchOptions {
+  /** Treat the query as a regular expression instead of literal text */
+  use_regex?: boolean;
+  case_sensitive?: boolean;
+  /** Only messages from this sender */
+  sender?: 'user' | 'agent' | 'system' | 'tool';
+  /** Only messages at or after this RFC 3339 timestamp */
+  since?: string;
+  /** Only messages at or before this RFC 3339 timestamp */
+  until?: string;
+  /** Maximum number of hits (50 if unset) */
+  limit?: number;
+}
+
+export interface SearchHit {
+  topic_id: string;
+  topic_title: string;
+  owner_id: string;
+  owner_type: 'agent' | 'group';
+  message_id: string;
+  /** Excerpt of the message content around the first match */
+  snippet: string;
+  /** Match position in the message content (JavaScript string indices) */
+  match_start: number;
+  match_end: number;
+  timestamp: string;
+}
+
+/** Messages matching the query (literal and case-insensitive unless options say otherwise), newest first */
+export async function searchConversations(query: string, options?: SearchOptions): Promise<SearchHit[]> {
+  return await invoke<SearchHit[]>('search_conversations', { query, options });
+}
+
+/** Rebuild the search index from the topic files; returns the number of topics indexed */
+export async function rebuildSearchIndex(): Promise<number> {
+  return await invoke<number>('rebuild_search_index');
+}
+
 /**
  * Settings Commands
  */
@@ -102,8 +333,9 @@ export async function closeWindow(): Promise<void> {
  * Attachment Commands
  */
 
-export async function saveAttachment(attachment: Attachment, fileData: Uint8Array): Promise<string> {
-  return await invoke<string>('save_attachment', {
+/** Save an attachment's data; returns the attachment as stored, with its final path and size */
+export async function saveAttachment(attachment: Attachment, fileData: Uint8Array): Promise<Attachment> {
+  return await invoke<Attachment>('save_attachment', {
     attachment,
     fileData: Array.from(fileData)
   });
@@ -118,21 +350,81 @@ export async function deleteAttachment(filePath: string): Promise<void> {
   await invoke('delete_attachment', { filePath });
 }
 
+export async function findOrphanedAttachments(): Promise<string[]> {
+  return await invoke<string[]>('find_orphaned_attachments');
+}
+
+/** Deletes attachments no conversation references; with the default dryRun only lists them */
+export async function cleanupOrphanedAttachments(dryRun: boolean = true): Promise<string[]> {
+  return await invoke<string[]>('cleanup_orphaned_attachments', { dryRun });
+}
+
+/**
+ * Backup Commands
+ */
+
+export interface BackupManifest {
+  app_version: string;
+  created_at: string;
+  file_count: number;
+}
+
+/** Zips the whole AppData directory (minus .trash and temp files); returns the archive path */
+export async function createBackup(outputPath: string): Promise<string> {
+  return await invoke<string>('create_backup', { outputPath });
+}
+
+/** Restoring into a non-empty AppData fails unless overwrite is true */
+export async function restoreBackup(archivePath: string, overwrite: boolean = false): Promise<BackupManifest> {
+  return await invoke<BackupManifest>('restore_backup', { archivePath, overwrite });
+}
+
+/**
+ * Maintenance Commands
+ */
+
+export interface CompactReport {
+  temp_files_removed: number;
+  trash_items_removed: number;
+  audit_logs_removed: number;
+  empty_dirs_removed: number;
+  bytes_reclaimed: number;
+  /** AppData-relative paths of everything removed */
+  removed: string[];
+  dry_run: boolean;
+}
+
+/** Removes stray temp files, expired trash and audit logs, and empty directories; dryRun only reports them */
+export async function compactData(dryRun: boolean = false): Promise<CompactReport> {
+  return await invoke<CompactReport>('compact_data', { dryRun });
+}
+
+/**
+ * Migration Commands
+ */
+
+/** Asks the running Electron data migration to stop; resolves to false if none is running */
+export async function cancelMigration(): Promise<boolean> {
+  return await invoke<boolean>('cancel_migration');
+}
+
 /**
- * Error handling wrapper for IPC commands
+ * Error handling wrapper for IPC commands. Commands reject with a `CommandError` object,
+ * a plain string (older commands) or an `Error`; `code` is only set for `CommandError`s.
  */
 export async function safeInvoke<T>(
   command: string,
   args?: Record<string, unknown>
-): Promise<{ success: boolean; data?: T; error?: string }> {
+): Promise<{ success: boolean; data?: T; error?: string; code?: string }> {
   try {
     const data = await invoke<T>(command, args);
     return { success: true, data };
   } catch (error) {
-    return {
-      success: false,
-      error: error instanceof Error ? error.message : String(error)
-    };
+    if (typeof error === 'object' && error !== null && 'message' in error) {
+      const { message, code } = error as Partial<CommandError>;
+      return { success: false, error: String(message), code };
+    }
+    return { success: false, error: String(error) };
   }
 }
 
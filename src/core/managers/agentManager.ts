@@ -52,7 +52,7 @@
  * ```
  */
 
-import { readAgent, writeAgent, deleteAgent, listAgents } from '../ipc/commands';
+import { readAgent, writeAgent, deleteAgent, listAgents, isConflictError } from '../ipc/commands';
 import { Agent, validateAgent } from '../models/agent';
 
 export interface CreateAgentOptions {
@@ -125,8 +125,14 @@ export class AgentManager {
           console.log(`[AgentManager] Syncing ${localStorageAgents.length} localStorage agents to Tauri backend...`);
           for (const agent of localStorageAgents) {
             try {
-              await writeAgent(agent);
+              const saved = await writeAgent(agent, agent.updated_at);
+              this.agents.set(agent.id, saved);
             } catch (error) {
+              if (isConflictError(error)) {
+                // The backend copy was edited after this one was cached; it wins
+                this.agents.set(agent.id, await readAgent(agent.id));
+                continue;
+              }
               console.warn(`[AgentManager] Failed to sync agent ${agent.id} to Tauri:`, error);
             }
           }
@@ -223,13 +229,13 @@ export class AgentManager {
     this.agents.set(agent.id, agent);
 
     // Persist to backend
-    await this.persistAgent(agent);
+    const savedAgent = await this.persistAgent(agent);
 
     // Dispatch event
-    this.dispatchAgentEvent('agent-created', agent);
+    this.dispatchAgentEvent('agent-created', savedAgent);
 
     console.log(`[AgentManager] Created agent: ${agent.id} (${agent.name})`);
-    return { ...agent };
+    return { ...savedAgent };
   }
 
   /**
@@ -298,13 +304,20 @@ export class AgentManager {
     this.agents.set(agentId, updatedAgent);
 
     // Persist to backend
-    await this.persistAgent(updatedAgent);
+    let savedAgent: Agent;
+    try {
+      savedAgent = await this.persistAgent(updatedAgent);
+    } catch (error) {
+      // Rejected as a conflict: keep the cache matching what is stored
+      this.agents.set(agentId, agent);
+      throw error;
+    }
 
     // Dispatch event
-    this.dispatchAgentEvent('agent-updated', updatedAgent);
+    this.dispatchAgentEvent('agent-updated', savedAgent);
 
     console.log(`[AgentManager] Updated agent: ${agentId}`);
-    return { ...updatedAgent };
+    return { ...savedAgent };
   }
 
   /**
@@ -545,14 +558,19 @@ export class AgentManager {
   /**
    * Persist agent to backend
    */
-  private async persistAgent(agent: Agent): Promise<void> {
+  private async persistAgent(agent: Agent): Promise<Agent> {
     const isTauri = typeof window !== 'undefined' && '__TAURI__' in window;
 
     if (isTauri) {
       try {
-        await writeAgent(agent);
+        const saved = await writeAgent(agent, agent.updated_at);
+        this.agents.set(agent.id, saved);
         console.log(`[AgentManager] Saved agent to Tauri backend: ${agent.id}`);
+        return saved;
       } catch (error) {
+        if (isConflictError(error)) {
+          throw error;
+        }
         console.warn('[AgentManager] Tauri save failed, falling back to localStorage:', error);
         this.saveToLocalStorage();
       }
@@ -560,6 +578,7 @@ export class AgentManager {
       // Web debug mirror mode - use localStorage only
       this.saveToLocalStorage();
     }
+    return agent;
   }
 
   /**
@@ -72,6 +72,8 @@ export class ChatManager {
    */
   async sendMessage(options: SendMessageOptions): Promise<Message> {
     const { agent, topic, userMessage, groupContext, streaming, onStreamStart, onStreamChunk, onStreamEnd, onError } = options;
+    // The topic as last read; saving over a copy changed since then is rejected as a conflict
+    const expectedUpdatedAt = topic.updated_at;
 
     // Determine if streaming is enabled (priority: options.streaming > agent.streaming > default true)
     const enableStreaming = streaming !== undefined ? streaming : (agent.streaming !== undefined ? agent.streaming : true);
@@ -207,7 +209,7 @@ export class ChatManager {
       topic.updated_at = new Date().toISOString();
 
       // Auto-save conversation
-      await this.saveConversation(topic);
+      await this.saveConversation(topic, expectedUpdatedAt);
 
       return agentMessage;
 
@@ -222,7 +224,7 @@ export class ChatManager {
       onError?.(error instanceof Error ? error : new Error('Unknown error'));
 
       // Still save even on error
-      await this.saveConversation(topic);
+      await this.saveConversation(topic, expectedUpdatedAt);
 
       return agentMessage;
     } finally {
@@ -358,11 +360,12 @@ export class ChatManager {
   /**
    * Save conversation to disk
    * Dual persistence: Try Tauri backend first, fallback to localStorage
+   * Pass the `updated_at` the topic had when it was read to detect a concurrent edit.
    */
-  async saveConversation(topic: Topic): Promise<void> {
+  async saveConversation(topic: Topic, expectedUpdatedAt?: string): Promise<void> {
     // Try Tauri backend first (will fail in browser mode)
     try {
-      await writeConversation(topic);
+      await writeConversation(topic, expectedUpdatedAt);
       console.log('[ChatManager] Conversation saved to Tauri backend:', topic.id);
     } catch (error) {
       console.warn('[ChatManager] Tauri save failed (expected in browser mode):', error);
@@ -54,7 +54,7 @@
  * ```
  */
 
-import { readGroup, writeGroup, deleteGroup, listGroups } from '../ipc/commands';
+import { readGroup, writeGroup, deleteGroup, listGroups, isConflictError } from '../ipc/commands';
 import { Group, validateGroup } from '../models/group';
 
 export interface CreateGroupOptions {
@@ -125,8 +125,14 @@ export class GroupManager {
           console.log(`[GroupManager] Syncing ${localStorageGroups.length} localStorage groups to Tauri backend...`);
           for (const group of localStorageGroups) {
             try {
-              await writeGroup(group);
+              const saved = await writeGroup(group, group.updated_at);
+              this.groups.set(group.id, saved);
             } catch (error) {
+              if (isConflictError(error)) {
+                // The backend copy was edited after this one was cached; it wins
+                this.groups.set(group.id, await readGroup(group.id));
+                continue;
+              }
               console.warn(`[GroupManager] Failed to sync group ${group.id} to Tauri:`, error);
             }
           }
@@ -222,13 +228,13 @@ export class GroupManager {
     this.groups.set(group.id, group);
 
     // Persist to backend
-    await this.persistGroup(group);
+    const savedGroup = await this.persistGroup(group);
 
     // Dispatch event
-    this.dispatchGroupEvent('group-created', group);
+    this.dispatchGroupEvent('group-created', savedGroup);
 
     console.log(`[GroupManager] Created group: ${group.id} (${group.name})`);
-    return { ...group };
+    return { ...savedGroup };
   }
 
   /**
@@ -297,13 +303,20 @@ export class GroupManager {
     this.groups.set(groupId, updatedGroup);
 
     // Persist to backend
-    await this.persistGroup(updatedGroup);
+    let savedGroup: Group;
+    try {
+      savedGroup = await this.persistGroup(updatedGroup);
+    } catch (error) {
+      // Rejected as a conflict: keep the cache matching what is stored
+      this.groups.set(groupId, group);
+      throw error;
+    }
 
     // Dispatch event
-    this.dispatchGroupEvent('group-updated', updatedGroup);
+    this.dispatchGroupEvent('group-updated', savedGroup);
 
     console.log(`[GroupManager] Updated group: ${groupId}`);
-    return { ...updatedGroup };
+    return { ...savedGroup };
   }
 
   /**
@@ -524,14 +537,19 @@ export class GroupManager {
   /**
    * Persist group to backend
    */
-  private async persistGroup(group: Group): Promise<void> {
+  private async persistGroup(group: Group): Promise<Group> {
     const isTauri = typeof window !== 'undefined' && '__TAURI__' in window;
 
     if (isTauri) {
       try {
-        await writeGroup(group);
+        const saved = await writeGroup(group, group.updated_at);
+        this.groups.set(group.id, saved);
         console.log(`[GroupManager] Saved group to Tauri backend: ${group.id}`);
+        return saved;
       } catch (error) {
+        if (isConflictError(error)) {
+          throw error;
+        }
         console.warn('[GroupManager] Tauri save failed, falling back to localStorage:', error);
         this.saveToLocalStorage();
       }
@@ -539,6 +557,7 @@ export class GroupManager {
       // Web debug mirror mode - use localStorage only
       this.saveToLocalStorage();
     }
+    return group;
   }
 
   /**
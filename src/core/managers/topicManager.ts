@@ -116,12 +116,13 @@ export class TopicManager {
       const topic = await readConversation(topicId);
 
       // Update title
+      const expectedUpdatedAt = topic.updated_at;
       topic.title = newTitle.trim();
       topic.updated_at = new Date().toISOString();
 
       // Save (dual persistence: Tauri + localStorage)
       try {
-        await writeConversation(topic);
+        await writeConversation(topic, expectedUpdatedAt);
         console.log('[TopicManager] Topic renamed in Tauri backend:', topicId);
       } catch (error) {
         console.warn('[TopicManager] Tauri save failed (expected in browser mode):', error);
@@ -196,12 +197,13 @@ export class TopicManager {
       return; // Title unchanged
     }
 
+    const expectedUpdatedAt = topic.updated_at;
     topic.title = newTitle;
     topic.updated_at = new Date().toISOString();
 
     // Save (dual persistence: Tauri + localStorage)
     try {
-      await writeConversation(topic);
+      await writeConversation(topic, expectedUpdatedAt);
       console.log('[TopicManager] Title auto-updated in Tauri backend:', topic.id);
     } catch (error) {
       console.warn('[TopicManager] Tauri save failed (expected in browser mode):', error);
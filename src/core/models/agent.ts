@@ -10,6 +10,8 @@ export interface Agent {
   max_output_tokens: number;         // 最大输出令牌数
   streaming?: boolean;               // 是否启用流式回答 (默认 true)
   created_at: string;                // ISO 8601 时间戳
+  updated_at?: string;               // 最后修改时间 (ISO 8601), 用于写入冲突检测
+  tags?: string[];                   // 分类标签 (每个 1-30 字符)
 }
 
 /**
@@ -37,6 +39,12 @@ export function validateAgent(agent: Agent): string | null {
   if (agent.max_output_tokens < 1) {
     return 'Agent max_output_tokens must be positive';
   }
+  if (agent.tags?.some(tag => tag.trim().length === 0 || tag.length > 30)) {
+    return 'Agent tags must be 1-30 characters';
+  }
+  if (agent.max_output_tokens > agent.context_token_limit) {
+    return `Agent max_output_tokens (${agent.max_output_tokens}) must not exceed context_token_limit (${agent.context_token_limit})`;
+  }
   // Validate ISO 8601 timestamp
   if (!agent.created_at || isNaN(Date.parse(agent.created_at))) {
     return 'Agent created_at must be a valid ISO 8601 timestamp';
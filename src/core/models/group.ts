@@ -11,6 +11,7 @@ export interface Group {
   speaking_rules: string;            // Agent 响应规则
   streaming?: boolean;               // 是否启用流式回答 (默认 true)
   created_at: string;                // ISO 8601 时间戳
+  updated_at?: string;               // 最后修改时间 (ISO 8601), 用于写入冲突检测
 }
 
 /**
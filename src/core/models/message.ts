@@ -1,7 +1,7 @@
 // Message data model
 import { Attachment } from './attachment';
 
-export type MessageSender = 'user' | 'agent';
+export type MessageSender = 'user' | 'agent' | 'system' | 'tool';
 
 /**
  * Message initialization state (CORE-012F)
@@ -21,16 +21,22 @@ export interface ToolCall {
   result?: string;
 }
 
+export interface MessageEdit {
+  content: string;                   // 编辑前的内容
+  edited_at: string;                 // 被替换的时间 (ISO 8601)
+}
+
 export interface MessageMetadata {
   tokens?: number;                   // 令牌计数
   model_used?: string;               // 生成响应的模型
   latency_ms?: number;               // 响应延迟
   tool_calls?: ToolCall[];           // VCP 工具调用
+  edit_history?: MessageEdit[];      // 编辑历史（最早的在前）
 }
 
 export interface Message {
   id: string;                        // 唯一标识符 (UUID)
-  sender: MessageSender;             // 'user' 或 'agent'
+  sender: MessageSender;             // 'user' | 'agent' | 'system' | 'tool'
   sender_id?: string;                // Agent ID (用于多 agent 群组)
   sender_name?: string;              // 显示名称
   content: string;                   // 消息文本内容
@@ -48,8 +54,8 @@ export function validateMessage(message: Message): string | null {
   if (!message.id || message.id.length === 0) {
     return 'Message ID is required';
   }
-  if (message.sender !== 'user' && message.sender !== 'agent') {
-    return 'Message sender must be "user" or "agent"';
+  if (!['user', 'agent', 'system', 'tool'].includes(message.sender)) {
+    return 'Message sender must be "user", "agent", "system", or "tool"';
   }
   if (!message.content || message.content.length === 0) {
     return 'Message content is required';
@@ -31,6 +31,14 @@ export interface StreamingPreferences {
   buffer_size: number;              // 预缓冲chunk数量
 }
 
+export type AttachmentFileType = 'image' | 'document' | 'pdf' | 'audio' | 'video' | 'other';
+
+export interface AttachmentPolicy {
+  allowed_types: AttachmentFileType[];
+  max_size_bytes: number;                 // 未单独设置的类型的上限 (字节)
+  max_size_by_type: Partial<Record<AttachmentFileType, number>>; // 按类型的上限 (字节)
+}
+
 export interface GlobalSettings {
   backend_url: string;               // VCPToolBox URL
   api_key: string;                   // Bearer 令牌
@@ -44,6 +52,22 @@ export interface GlobalSettings {
   window_preferences: WindowPreferences;
   streaming_preferences: StreamingPreferences; // CORE-012G: Streaming settings
   keyboard_shortcuts: KeyboardShortcut[];
+  known_models?: string[];           // 已知模型 ID 白名单 (为空则不校验)
+  notifications_enabled?: boolean;   // 是否弹出系统通知 (默认 true)
+  do_not_disturb?: boolean;          // 免打扰: 仅记录通知, 不弹出
+  enforce_plugin_signatures?: boolean; // 仅安装已签名的插件
+  trusted_plugin_keys?: string[];    // 受信任的 Ed25519 公钥 (hex)
+  enforce_plugin_integrity?: boolean; // 拒绝启用安装后被修改过的插件
+  per_plugin_audit_logs?: boolean;   // 每个插件额外写入独立的审计日志
+  http_proxy?: string | null;        // 插件 HTTP 请求代理 (可选)
+  https_proxy?: string | null;       // 插件 HTTPS 请求代理 (可选)
+  no_proxy?: string[];               // 直连域名 (支持 *.example.com)
+  plugin_registry_url?: string | null; // 插件更新检查使用的注册表索引地址 (可选)
+  default_plugin_permissions?: string[]; // 新安装插件默认授予的权限 (如 storage.read:plugin-data/{id}/*)
+  plugins_directory?: string | null; // 插件安装目录 (为空则使用 AppData/plugins)
+  attachment_policy?: AttachmentPolicy; // 附件类型与大小限制
+  strip_image_metadata?: boolean;    // 保存图片附件时移除 EXIF/GPS 等元数据 (默认 true)
+  trash_retention_days?: number;     // 回收站保留天数 (默认 30)
 }
 
 /**
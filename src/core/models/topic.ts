@@ -11,6 +11,8 @@ export interface Topic {
   messages: Message[];               // 消息数组
   created_at: string;                // ISO 8601 时间戳
   updated_at: string;                // ISO 8601 时间戳
+  pinned?: boolean;                  // 置顶 (默认 false)
+  forked_from?: string;              // 分叉来源话题 ID
 }
 
 /**
@@ -39,6 +39,8 @@ export interface HttpResponse {
   status: number;
   headers: Record<string, string>;
   body: string;
+  /// 'base64' when the body is not valid UTF-8 (e.g. images or raw compressed data)
+  body_encoding: 'text' | 'base64';
 }
 
 /**
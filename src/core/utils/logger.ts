@@ -109,6 +109,21 @@ export class Logger {
     forwardToRust('error', message, { source: this.source, data });
   }
 
+  /**
+   * Log a structured event (written to the log file as a JSON line)
+   */
+  public event(name: string, fields: Record<string, unknown> = {}, level: LogLevel = 'info'): void {
+    console.debug(`[${this.source || 'Frontend'}] event:`, name, fields);
+
+    if (!isTauriEnv()) {
+      return;
+    }
+
+    invoke('log_event', { level, event: name, fields }).catch((error) => {
+      console.error('[Logger] Failed to forward event to Rust:', error);
+    });
+  }
+
   /**
    * Log with custom level
    */
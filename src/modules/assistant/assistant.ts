@@ -503,6 +503,7 @@ async function simulateStreamingResponse(message: Message) {
 
   // Update topic
   if (state.activeTopic) {
+    const expectedUpdatedAt = state.activeTopic.updated_at;
     state.activeTopic.updated_at = new Date().toISOString();
 
     // Auto-generate title from first user message if still "New Conversation"
@@ -518,7 +519,7 @@ async function simulateStreamingResponse(message: Message) {
 
     // Save conversation
     try {
-      await writeConversation(state.activeTopic);
+      await writeConversation(state.activeTopic, expectedUpdatedAt);
       renderTopicsList(); // Update sidebar
     } catch (error) {
       console.error('[Assistant] Failed to save conversation:', error);
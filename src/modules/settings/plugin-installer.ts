@@ -4,6 +4,9 @@
 import { invoke } from '@tauri-apps/api/core';
 import { open } from '@tauri-apps/plugin-dialog';
 
+/** Manifest permission: "type:scope" (suffix "?" for optional) or a structured entry */
+type PermissionEntry = string | { permission: string; optional?: boolean };
+
 interface PluginManifest {
   manifestVersion: string;
   name: string;
@@ -13,7 +16,7 @@ interface PluginManifest {
   author?: string;
   main: string;
   activationEvents: string[];
-  permissions: string[];
+  permissions: PermissionEntry[];
   contributes?: {
     commands?: Array<{
       commandIdentifier: string;
@@ -28,6 +31,10 @@ interface PluginManifest {
     vcp: string;
   };
   dependencies?: Record<string, string>;
+  icon?: string;
+  homepage?: string;
+  repository?: string;
+  license?: string;
 }
 
 interface ParsedPlugin {
@@ -289,7 +296,11 @@ export class PluginInstaller {
     }
 
     // Permissions list (will be fully implemented in PLUGIN-073)
-    this.displayPermissions(manifest.permissions);
+    this.displayPermissions(
+      manifest.permissions.map((entry) =>
+        typeof entry === 'string' ? entry.replace(/\?$/, '') : entry.permission
+      )
+    );
 
     // Contributions (commands, views, etc.)
     this.displayContributions(manifest);
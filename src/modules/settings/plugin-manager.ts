@@ -68,6 +68,7 @@ export enum PluginState {
   Activated = 'Activated',
   Running = 'Running',
   Deactivated = 'Deactivated',
+  Crashed = 'Crashed',
   Error = 'Error'
 }
 
@@ -194,7 +195,7 @@ export class PluginManagerUI {
    */
   private renderPluginCard(plugin: PluginInfo): string {
     const { metadata, state, enabled, error_message } = plugin;
-    const isError = state === PluginState.Error;
+    const isError = state === PluginState.Error || state === PluginState.Crashed;
     const isRunning = state === PluginState.Running || state === PluginState.Activated;
 
     const commandCount = metadata.contributes?.commands?.length || 0;
@@ -280,7 +281,7 @@ export class PluginManagerUI {
    */
   private getStateClass(state: PluginState, enabled: boolean): string {
     if (!enabled) return 'state-disabled';
-    if (state === PluginState.Error) return 'state-error';
+    if (state === PluginState.Error || state === PluginState.Crashed) return 'state-error';
     if (state === PluginState.Running || state === PluginState.Activated) return 'state-running';
     return 'state-inactive';
   }
@@ -291,6 +292,7 @@ export class PluginManagerUI {
   private getStateLabel(state: PluginState, enabled: boolean): string {
     if (!enabled) return 'Disabled';
     if (state === PluginState.Error) return 'Error';
+    if (state === PluginState.Crashed) return 'Crashed';
     if (state === PluginState.Running) return 'Running';
     if (state === PluginState.Activated) return 'Active';
     return 'Inactive';